@@ -0,0 +1,57 @@
+use openfeature_contrib_common::HttpError;
+use thiserror::Error;
+
+/// Errors raised while writing audit records.
+#[derive(Debug, Error)]
+pub enum AuditError {
+    /// Writing to the audit file failed.
+    #[error("failed to write the audit file: {0}")]
+    Io(#[from] std::io::Error),
+
+    /// A configured header has an invalid name or value.
+    #[error("invalid header {0}")]
+    InvalidHeader(String),
+
+    /// The request to the audit endpoint failed.
+    #[error("request to the audit endpoint failed: {0}")]
+    Http(#[from] HttpError),
+
+    /// A custom sink failed.
+    #[error("{0}")]
+    Other(Box<dyn std::error::Error + Send + Sync>),
+
+    /// A custom sink rejected the batch for good, so writing it again cannot succeed.
+    #[error("{0}")]
+    Rejected(Box<dyn std::error::Error + Send + Sync>),
+}
+
+impl From<reqwest::Error> for AuditError {
+    fn from(error: reqwest::Error) -> Self {
+        Self::Http(error.into())
+    }
+}
+
+impl AuditError {
+    /// Whether writing the batch again may succeed. Batches failing with other errors are
+    /// dropped.
+    pub fn is_retryable(&self) -> bool {
+        match self {
+            Self::Io(_) | Self::Other(_) => true,
+            Self::InvalidHeader(_) | Self::Rejected(_) => false,
+            Self::Http(e) => e.is_retryable(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn only_rejected_batches_and_invalid_headers_are_not_retried() {
+        assert!(!AuditError::InvalidHeader("x".to_string()).is_retryable());
+        assert!(!AuditError::Rejected("invalid record".into()).is_retryable());
+        assert!(AuditError::Io(std::io::ErrorKind::StorageFull.into()).is_retryable());
+        assert!(AuditError::Other("broker unavailable".into()).is_retryable());
+    }
+}
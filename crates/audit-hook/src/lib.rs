@@ -0,0 +1,378 @@
+//! Audit trail hook for OpenFeature.
+//!
+//! [`AuditHook`] writes an immutable [`AuditRecord`] per flag evaluation, telling who evaluated
+//! which flag, when, and with which result, for environments that must keep a trail of their
+//! decisions.
+//!
+//! Records are written by a background task, in batches, to an [`AuditSink`]:
+//!
+//! - [`FileSink`] appends JSON lines to a file rotated by size.
+//! - [`HttpSink`] posts JSON arrays to an HTTP endpoint.
+//! - Other destinations, e.g. a Kafka topic, implement [`AuditSink`].
+//!
+//! Batches that fail with a retryable error are written again with an exponential backoff; those
+//! the sink rejects for good, e.g. with a 4xx status, are dropped. Once [`AuditOptions::capacity`]
+//! records are pending, evaluations wait for the sink to catch up, up to
+//! [`AuditOptions::enqueue_timeout`]. Dropped records are counted by [`AuditHook::dropped`] and
+//! leave gaps in the sequence numbers.
+//!
+//! # Example
+//!
+//! ```rust
+//! use std::sync::{Arc, Mutex};
+//!
+//! use async_trait::async_trait;
+//! use open_feature::{EvaluationContext, OpenFeature};
+//! use open_feature_audit_hook::{AuditError, AuditHook, AuditOptions, AuditRecord, AuditSink};
+//! use open_feature_in_memory::InMemoryProvider;
+//!
+//! #[derive(Clone, Default)]
+//! struct MemorySink(Arc<Mutex<Vec<AuditRecord>>>);
+//!
+//! #[async_trait]
+//! impl AuditSink for MemorySink {
+//!     async fn write(&self, records: &[AuditRecord]) -> Result<(), AuditError> {
+//!         self.0.lock().unwrap().extend_from_slice(records);
+//!         Ok(())
+//!     }
+//! }
+//!
+//! #[tokio::main]
+//! async fn main() {
+//!     let provider = InMemoryProvider::new();
+//!     provider.set_flag("new-checkout", true, None, None);
+//!
+//!     let sink = MemorySink::default();
+//!     let hook = AuditHook::new(sink.clone(), AuditOptions::default());
+//!
+//!     let mut api = OpenFeature::singleton_mut().await;
+//!     api.set_provider(provider).await;
+//!     api.add_hook(hook.clone()).await;
+//!     let client = api.create_client();
+//!
+//!     let context = EvaluationContext::default().with_targeting_key("user-1");
+//!     client.get_bool_value("new-checkout", Some(&context), None).await.unwrap();
+//!
+//!     hook.flush().await;
+//!     let records = sink.0.lock().unwrap();
+//!     assert_eq!(records[0].flag_key, "new-checkout");
+//!     assert_eq!(records[0].targeting_key.as_deref(), Some("user-1"));
+//!     assert_eq!(records[0].value, Some(true.into()));
+//! }
+//! ```
+
+mod error;
+mod record;
+mod sink;
+
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
+use std::thread::{self, ThreadId};
+use std::time::Duration;
+
+use async_trait::async_trait;
+use open_feature::{
+    EvaluationContext, EvaluationDetails, EvaluationError, EvaluationReason, Hook, HookContext,
+    HookHints, Value,
+};
+use openfeature_contrib_common::{
+    error_code_name, fields_to_json, value_to_json, Backoff, DateTimeFormat,
+};
+use time::OffsetDateTime;
+use tokio::sync::mpsc::error::SendTimeoutError;
+use tokio::sync::{mpsc, oneshot};
+use tokio::task;
+use tokio::time::MissedTickBehavior;
+use tracing::{error, warn};
+
+pub use openfeature_contrib_common::BackoffPolicy;
+
+pub use crate::error::AuditError;
+pub use crate::record::AuditRecord;
+pub use crate::sink::{AuditSink, FileRotation, FileSink, HttpSink, HttpSinkOptions};
+
+/// Lower bound of the flush interval and of the delays between writes of a failed batch, so
+/// that zero does not make the writer spin.
+const MIN_DELAY: Duration = Duration::from_millis(10);
+
+/// Configuration of the [`AuditHook`].
+#[derive(Debug, Clone)]
+pub struct AuditOptions {
+    /// Number of records written together.
+    pub batch_size: usize,
+    /// Longest time a record waits for its batch to fill up. Clamped to at least 10ms.
+    pub flush_interval: Duration,
+    /// Number of pending records beyond which evaluations wait for the sink.
+    pub capacity: usize,
+    /// Longest time an evaluation waits for the sink while `capacity` records are pending,
+    /// before its record is dropped. `None` waits as long as it takes.
+    pub enqueue_timeout: Option<Duration>,
+    /// Delays between attempts to write a failed batch, of at least 10ms.
+    pub retry_backoff: BackoffPolicy,
+    /// Whether records include the custom fields of the evaluation context, which may hold
+    /// personal data.
+    pub include_context: bool,
+}
+
+impl Default for AuditOptions {
+    fn default() -> Self {
+        Self {
+            batch_size: 100,
+            flush_interval: Duration::from_secs(1),
+            capacity: 10_000,
+            enqueue_timeout: Some(Duration::from_secs(1)),
+            retry_backoff: BackoffPolicy::default(),
+            include_context: false,
+        }
+    }
+}
+
+/// Task, or thread outside of a Tokio task, running an evaluation.
+///
+/// Hooks are called sequentially from the evaluating task, so the error reported in `error` is
+/// found again in `finally`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+enum Evaluator {
+    Task(task::Id),
+    Thread(ThreadId),
+}
+
+impl Evaluator {
+    fn current() -> Self {
+        match task::try_id() {
+            Some(id) => Self::Task(id),
+            None => Self::Thread(thread::current().id()),
+        }
+    }
+}
+
+enum Command {
+    Record(Box<AuditRecord>),
+    Flush(oneshot::Sender<()>),
+}
+
+/// OpenFeature hook writing an [`AuditRecord`] per evaluation to an [`AuditSink`].
+///
+/// Clones of the hook share their trail. The background task writing the records stops once all
+/// clones are dropped and the pending records are written; call [`AuditHook::flush`] before the
+/// application exits, since hooks registered with the API are never dropped.
+#[derive(Clone)]
+pub struct AuditHook {
+    inner: Arc<Inner>,
+}
+
+struct Inner {
+    sender: mpsc::Sender<Command>,
+    sequence: AtomicU64,
+    include_context: bool,
+    enqueue_timeout: Option<Duration>,
+    dropped: Arc<AtomicU64>,
+    errors: Mutex<HashMap<(Evaluator, String), EvaluationError>>,
+}
+
+impl AuditHook {
+    /// Creates the hook, starting the task writing records to `sink`.
+    ///
+    /// Must be called within a Tokio runtime with the time driver enabled.
+    pub fn new(sink: impl AuditSink, options: AuditOptions) -> Self {
+        let (sender, receiver) = mpsc::channel(options.capacity.max(1));
+        let dropped = Arc::new(AtomicU64::new(0));
+        tokio::spawn(write_records(
+            sink,
+            receiver,
+            options.clone(),
+            dropped.clone(),
+        ));
+
+        Self {
+            inner: Arc::new(Inner {
+                sender,
+                sequence: AtomicU64::new(0),
+                include_context: options.include_context,
+                enqueue_timeout: options.enqueue_timeout,
+                dropped,
+                errors: Mutex::default(),
+            }),
+        }
+    }
+
+    /// Waits until the records of the evaluations completed so far are written.
+    pub async fn flush(&self) {
+        let (done, written) = oneshot::channel();
+        if self.inner.sender.send(Command::Flush(done)).await.is_ok() {
+            let _ = written.await;
+        }
+    }
+
+    /// Number of records dropped so far, because the queue stayed full or the sink rejected
+    /// their batch.
+    pub fn dropped(&self) -> u64 {
+        self.inner.dropped.load(Ordering::Relaxed)
+    }
+
+    fn errors(&self) -> MutexGuard<'_, HashMap<(Evaluator, String), EvaluationError>> {
+        self.inner
+            .errors
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+#[async_trait]
+impl Hook for AuditHook {
+    async fn before<'a>(
+        &self,
+        _: &HookContext<'a>,
+        _: Option<&'a HookHints>,
+    ) -> Result<Option<EvaluationContext>, EvaluationError> {
+        Ok(None)
+    }
+
+    async fn after<'a>(
+        &self,
+        _: &HookContext<'a>,
+        _: &EvaluationDetails<Value>,
+        _: Option<&'a HookHints>,
+    ) -> Result<(), EvaluationError> {
+        Ok(())
+    }
+
+    async fn error<'a>(
+        &self,
+        context: &HookContext<'a>,
+        error: &EvaluationError,
+        _: Option<&'a HookHints>,
+    ) {
+        self.errors().insert(
+            (Evaluator::current(), context.flag_key.to_string()),
+            error.clone(),
+        );
+    }
+
+    async fn finally<'a>(
+        &self,
+        context: &HookContext<'a>,
+        details: &EvaluationDetails<Value>,
+        _: Option<&'a HookHints>,
+    ) {
+        let error = self
+            .errors()
+            .remove(&(Evaluator::current(), context.flag_key.to_string()));
+        let failed = error.is_some() || details.reason == Some(EvaluationReason::Error);
+
+        let record = AuditRecord {
+            sequence: self.inner.sequence.fetch_add(1, Ordering::Relaxed),
+            timestamp: OffsetDateTime::now_utc(),
+            domain: context.client_metadata.name.clone(),
+            provider_name: context.provider_metadata.name.clone(),
+            flag_key: context.flag_key.to_string(),
+            flag_type: record::type_name(&context.flag_type),
+            targeting_key: context.evaluation_context.targeting_key.clone(),
+            context: self
+                .inner
+                .include_context
+                .then(|| fields_to_json(context.evaluation_context, DateTimeFormat::Rfc3339)),
+            default_value: context.default_value.as_ref().map(value_to_json),
+            value: (!failed).then(|| value_to_json(&details.value)),
+            variant: details.variant.clone(),
+            reason: details.reason.as_ref().map(ToString::to_string),
+            error_code: error
+                .as_ref()
+                .map(|error| error_code_name(&error.code).to_string()),
+            error_message: error.and_then(|error| error.message),
+        };
+
+        // Waits while the channel is full, slowing evaluations down to the pace of the sink.
+        let command = Command::Record(Box::new(record));
+        let sent = match self.inner.enqueue_timeout {
+            Some(timeout) => self
+                .inner
+                .sender
+                .send_timeout(command, timeout)
+                .await
+                .map_err(|e| match e {
+                    SendTimeoutError::Timeout(_) => "the audit queue stayed full",
+                    SendTimeoutError::Closed(_) => "the audit trail is closed",
+                }),
+            None => {
+                (self.inner.sender.send(command).await).map_err(|_| "the audit trail is closed")
+            }
+        };
+        if let Err(reason) = sent {
+            self.inner.dropped.fetch_add(1, Ordering::Relaxed);
+            warn!("Dropped the audit record of {}: {reason}", context.flag_key);
+        }
+    }
+}
+
+async fn write_records(
+    sink: impl AuditSink,
+    mut receiver: mpsc::Receiver<Command>,
+    options: AuditOptions,
+    dropped: Arc<AtomicU64>,
+) {
+    let batch_size = options.batch_size.max(1);
+    let mut batch = Vec::with_capacity(batch_size);
+    let mut backoff = options.retry_backoff.backoff();
+    let mut interval = tokio::time::interval(options.flush_interval.max(MIN_DELAY));
+    interval.set_missed_tick_behavior(MissedTickBehavior::Delay);
+
+    loop {
+        tokio::select! {
+            command = receiver.recv() => match command {
+                Some(Command::Record(record)) => {
+                    batch.push(*record);
+                    if batch.len() >= batch_size {
+                        write_batch(&sink, &mut batch, &mut backoff, &dropped).await;
+                    }
+                }
+                Some(Command::Flush(done)) => {
+                    write_batch(&sink, &mut batch, &mut backoff, &dropped).await;
+                    let _ = done.send(());
+                }
+                None => {
+                    write_batch(&sink, &mut batch, &mut backoff, &dropped).await;
+                    return;
+                }
+            },
+            _ = interval.tick() => write_batch(&sink, &mut batch, &mut backoff, &dropped).await,
+        }
+    }
+}
+
+/// Writes `batch`, retrying until the sink accepts it or rejects it for good.
+async fn write_batch(
+    sink: &impl AuditSink,
+    batch: &mut Vec<AuditRecord>,
+    backoff: &mut Backoff,
+    dropped: &AtomicU64,
+) {
+    if batch.is_empty() {
+        return;
+    }
+    loop {
+        match sink.write(batch).await {
+            Ok(()) => {
+                backoff.reset();
+                batch.clear();
+                return;
+            }
+            Err(e) if e.is_retryable() => {
+                let delay = backoff.next_delay().max(MIN_DELAY);
+                warn!(
+                    "Failed to write {} audit records, retrying in {delay:?}: {e}",
+                    batch.len()
+                );
+                tokio::time::sleep(delay).await;
+            }
+            Err(e) => {
+                error!("Dropped {} audit records: {e}", batch.len());
+                dropped.fetch_add(batch.len() as u64, Ordering::Relaxed);
+                backoff.reset();
+                batch.clear();
+                return;
+            }
+        }
+    }
+}
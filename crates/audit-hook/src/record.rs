@@ -0,0 +1,61 @@
+use open_feature::Type;
+use serde::Serialize;
+use serde_json::{Map, Value};
+use time::OffsetDateTime;
+
+/// Immutable record of a flag evaluation: who evaluated what, when, and with which result.
+///
+/// Records serialize to JSON objects with camelCase keys, the timestamp in RFC 3339:
+///
+/// ```json
+/// {"sequence":0,"timestamp":"2024-05-01T12:00:00Z","domain":"","providerName":"in-memory",
+///  "flagKey":"new-checkout","flagType":"boolean","targetingKey":"user-1","defaultValue":false,
+///  "value":true,"variant":"on","reason":"STATIC","errorCode":null,"errorMessage":null}
+/// ```
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AuditRecord {
+    /// Position of the record in the trail of its hook, starting at 0, so gaps reveal lost
+    /// records.
+    pub sequence: u64,
+    /// End of the evaluation.
+    #[serde(with = "time::serde::rfc3339")]
+    pub timestamp: OffsetDateTime,
+    /// Name of the client, empty for the default client.
+    pub domain: String,
+    /// Name of the provider.
+    pub provider_name: String,
+    /// Key of the evaluated flag.
+    pub flag_key: String,
+    /// Requested type of the flag.
+    pub flag_type: &'static str,
+    /// Targeting key of the evaluation context.
+    pub targeting_key: Option<String>,
+    /// Custom fields of the evaluation context, when
+    /// [`AuditOptions::include_context`](crate::AuditOptions::include_context) is set.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub context: Option<Map<String, Value>>,
+    /// Default value passed to the evaluation.
+    pub default_value: Option<Value>,
+    /// Value of a successful evaluation.
+    pub value: Option<Value>,
+    /// Variant of a successful evaluation.
+    pub variant: Option<String>,
+    /// Reason of the evaluation, `ERROR` when it failed.
+    pub reason: Option<String>,
+    /// Code of a failed evaluation.
+    pub error_code: Option<String>,
+    /// Message of a failed evaluation.
+    pub error_message: Option<String>,
+}
+
+pub(crate) fn type_name(flag_type: &Type) -> &'static str {
+    match flag_type {
+        Type::Bool => "boolean",
+        Type::Int => "integer",
+        Type::Float => "float",
+        Type::String => "string",
+        Type::Array => "array",
+        Type::Struct => "object",
+    }
+}
@@ -0,0 +1,204 @@
+use std::collections::HashMap;
+use std::ffi::OsString;
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, Write};
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex, PoisonError};
+use std::time::Duration;
+
+use async_trait::async_trait;
+use openfeature_contrib_common::check_status;
+use reqwest::header::{HeaderMap, HeaderName, HeaderValue};
+use reqwest::Client;
+
+use crate::error::AuditError;
+use crate::record::AuditRecord;
+
+/// Destination of audit records, e.g. a file, an HTTP endpoint or a Kafka topic.
+///
+/// `write` receives the records in batches, in the order of their sequence numbers. A batch that
+/// fails with a [retryable](AuditError::is_retryable) error is written again after a delay, so
+/// writes should be idempotent or reject the batch as a whole; return
+/// [`AuditError::Rejected`] for batches that can never be written.
+#[async_trait]
+pub trait AuditSink: Send + Sync + 'static {
+    /// Writes a batch of records.
+    async fn write(&self, records: &[AuditRecord]) -> Result<(), AuditError>;
+}
+
+/// Rotation of the files written by a [`FileSink`].
+#[derive(Debug, Clone)]
+pub struct FileRotation {
+    /// Size in bytes beyond which the file is rotated.
+    pub max_size: u64,
+    /// Number of rotated files kept, named after the file with the suffixes `.1`, the most
+    /// recent, to `.{max_files}`.
+    pub max_files: usize,
+}
+
+impl Default for FileRotation {
+    fn default() -> Self {
+        Self {
+            max_size: 10 * 1024 * 1024,
+            max_files: 5,
+        }
+    }
+}
+
+/// Sink appending records to a file as JSON lines, rotating it by size.
+///
+/// Every batch is synced to disk before the write succeeds.
+#[derive(Debug, Clone)]
+pub struct FileSink {
+    file: Arc<Mutex<RotatingFile>>,
+}
+
+#[derive(Debug)]
+struct RotatingFile {
+    path: PathBuf,
+    rotation: FileRotation,
+    file: Option<(File, u64)>,
+}
+
+impl FileSink {
+    /// Appends records to the file at `path`, created if missing.
+    pub fn new(path: impl Into<PathBuf>, rotation: FileRotation) -> Self {
+        Self {
+            file: Arc::new(Mutex::new(RotatingFile {
+                path: path.into(),
+                rotation,
+                file: None,
+            })),
+        }
+    }
+}
+
+#[async_trait]
+impl AuditSink for FileSink {
+    async fn write(&self, records: &[AuditRecord]) -> Result<(), AuditError> {
+        let mut lines = Vec::new();
+        for record in records {
+            serde_json::to_writer(&mut lines, record).map_err(io::Error::from)?;
+            lines.push(b'\n');
+        }
+
+        let file = self.file.clone();
+        tokio::task::spawn_blocking(move || {
+            file.lock()
+                .unwrap_or_else(PoisonError::into_inner)
+                .append(&lines)
+        })
+        .await
+        .map_err(io::Error::other)??;
+        Ok(())
+    }
+}
+
+impl RotatingFile {
+    fn append(&mut self, lines: &[u8]) -> io::Result<()> {
+        let (mut file, mut size) = match self.file.take() {
+            Some(file) => file,
+            None => self.open()?,
+        };
+        if size > 0 && size + lines.len() as u64 > self.rotation.max_size {
+            drop(file);
+            self.rotate()?;
+            (file, size) = self.open()?;
+        }
+
+        file.write_all(lines)?;
+        file.sync_data()?;
+        self.file = Some((file, size + lines.len() as u64));
+        Ok(())
+    }
+
+    fn open(&self) -> io::Result<(File, u64)> {
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)?;
+        let size = file.metadata()?.len();
+        Ok((file, size))
+    }
+
+    fn rotate(&self) -> io::Result<()> {
+        if self.rotation.max_files == 0 {
+            return fs::remove_file(&self.path);
+        }
+        for index in (1..self.rotation.max_files).rev() {
+            match fs::rename(self.rotated(index), self.rotated(index + 1)) {
+                Err(e) if e.kind() != io::ErrorKind::NotFound => return Err(e),
+                _ => {}
+            }
+        }
+        fs::rename(&self.path, self.rotated(1))
+    }
+
+    fn rotated(&self, index: usize) -> PathBuf {
+        let mut path = OsString::from(&self.path);
+        path.push(format!(".{index}"));
+        path.into()
+    }
+}
+
+/// Configuration of the [`HttpSink`].
+#[derive(Debug, Clone)]
+pub struct HttpSinkOptions {
+    /// URL the batches are posted to.
+    pub url: String,
+    /// Headers sent with every request, e.g. `Authorization`.
+    pub headers: HashMap<String, String>,
+    /// Timeout of requests.
+    pub timeout: Duration,
+}
+
+impl Default for HttpSinkOptions {
+    fn default() -> Self {
+        Self {
+            url: String::new(),
+            headers: HashMap::new(),
+            timeout: Duration::from_secs(10),
+        }
+    }
+}
+
+/// Sink posting batches of records to an HTTP endpoint as JSON arrays.
+///
+/// Responses with a status other than 2xx fail the write.
+#[derive(Debug, Clone)]
+pub struct HttpSink {
+    http: Client,
+    url: String,
+}
+
+impl HttpSink {
+    /// Creates the sink.
+    pub fn new(options: HttpSinkOptions) -> Result<Self, AuditError> {
+        let mut headers = HeaderMap::new();
+        for (name, value) in &options.headers {
+            let invalid = || AuditError::InvalidHeader(name.clone());
+            headers.insert(
+                HeaderName::try_from(name).map_err(|_| invalid())?,
+                HeaderValue::from_str(value).map_err(|_| invalid())?,
+            );
+        }
+        let http = Client::builder()
+            .default_headers(headers)
+            .timeout(options.timeout)
+            .build()?;
+
+        Ok(Self {
+            http,
+            url: options.url,
+        })
+    }
+}
+
+#[async_trait]
+impl AuditSink for HttpSink {
+    async fn write(&self, records: &[AuditRecord]) -> Result<(), AuditError> {
+        let response = self.http.post(&self.url).json(records).send().await?;
+        check_status(response)?;
+        Ok(())
+    }
+}
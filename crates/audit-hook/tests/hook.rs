@@ -0,0 +1,287 @@
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex, PoisonError};
+use std::time::Duration;
+
+use async_trait::async_trait;
+use open_feature::{Client, OpenFeature};
+use open_feature_audit_hook::{
+    AuditError, AuditHook, AuditOptions, AuditRecord, AuditSink, BackoffPolicy, HttpSink,
+    HttpSinkOptions,
+};
+use open_feature_in_memory::InMemoryProvider;
+use wiremock::matchers::method;
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+/// Sink failing its first `failures` writes.
+#[derive(Clone, Default)]
+struct FlakySink {
+    records: Arc<Mutex<Vec<AuditRecord>>>,
+    attempts: Arc<AtomicUsize>,
+    failures: usize,
+    retryable: bool,
+}
+
+impl FlakySink {
+    fn failing(failures: usize, retryable: bool) -> Self {
+        Self {
+            failures,
+            retryable,
+            ..Default::default()
+        }
+    }
+
+    fn records(&self) -> Vec<AuditRecord> {
+        self.records
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clone()
+    }
+
+    fn attempts(&self) -> usize {
+        self.attempts.load(Ordering::SeqCst)
+    }
+}
+
+#[async_trait]
+impl AuditSink for FlakySink {
+    async fn write(&self, records: &[AuditRecord]) -> Result<(), AuditError> {
+        if self.attempts.fetch_add(1, Ordering::SeqCst) < self.failures {
+            return Err(if self.retryable {
+                AuditError::Other("sink unavailable".into())
+            } else {
+                AuditError::Rejected("invalid records".into())
+            });
+        }
+        self.records
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .extend_from_slice(records);
+        Ok(())
+    }
+}
+
+/// Sink that never completes a write.
+struct StuckSink;
+
+#[async_trait]
+impl AuditSink for StuckSink {
+    async fn write(&self, _: &[AuditRecord]) -> Result<(), AuditError> {
+        std::future::pending().await
+    }
+}
+
+fn options() -> AuditOptions {
+    AuditOptions {
+        retry_backoff: BackoffPolicy {
+            initial: Duration::ZERO,
+            max: Duration::ZERO,
+            ..Default::default()
+        },
+        ..Default::default()
+    }
+}
+
+/// Creates a client of its own provider, so tests do not share flags or hooks.
+async fn client(domain: &str, hook: &AuditHook) -> Client {
+    let provider = InMemoryProvider::new();
+    provider.set_flag("new-checkout", true, Some("on"), None);
+    let mut api = OpenFeature::singleton_mut().await;
+    api.set_named_provider(domain, provider).await;
+    api.create_named_client(domain).with_hook(hook.clone())
+}
+
+#[tokio::test]
+async fn records_successful_and_failed_evaluations() {
+    let sink = FlakySink::default();
+    let hook = AuditHook::new(sink.clone(), options());
+    let client = client("records", &hook).await;
+
+    client
+        .get_bool_value("new-checkout", None, None)
+        .await
+        .unwrap();
+    client
+        .get_bool_value("missing", None, None)
+        .await
+        .unwrap_err();
+    hook.flush().await;
+
+    let records = sink.records();
+    assert_eq!(records.len(), 2);
+    assert_eq!(records[0].sequence, 0);
+    assert_eq!(records[0].flag_key, "new-checkout");
+    assert_eq!(records[0].value, Some(true.into()));
+    assert_eq!(records[0].variant.as_deref(), Some("on"));
+    assert_eq!(records[1].sequence, 1);
+    assert_eq!(records[1].value, None);
+    assert_eq!(records[1].error_code.as_deref(), Some("FLAG_NOT_FOUND"));
+}
+
+#[tokio::test]
+async fn retries_retryable_failures() {
+    let sink = FlakySink::failing(3, true);
+    let hook = AuditHook::new(sink.clone(), options());
+    let client = client("retries", &hook).await;
+
+    client
+        .get_bool_value("new-checkout", None, None)
+        .await
+        .unwrap();
+    hook.flush().await;
+
+    assert_eq!(sink.attempts(), 4);
+    assert_eq!(sink.records().len(), 1);
+    assert_eq!(hook.dropped(), 0);
+}
+
+#[tokio::test]
+async fn drops_batches_the_sink_rejects() {
+    let sink = FlakySink::failing(1, false);
+    let hook = AuditHook::new(sink.clone(), options());
+    let client = client("rejects", &hook).await;
+
+    client
+        .get_bool_value("new-checkout", None, None)
+        .await
+        .unwrap();
+    hook.flush().await;
+    client
+        .get_bool_value("new-checkout", None, None)
+        .await
+        .unwrap();
+    hook.flush().await;
+
+    assert_eq!(sink.attempts(), 2);
+    assert_eq!(hook.dropped(), 1);
+    let records = sink.records();
+    assert_eq!(records.len(), 1);
+    assert_eq!(records[0].sequence, 1);
+}
+
+#[tokio::test(start_paused = true)]
+async fn zero_backoff_does_not_spin() {
+    let sink = FlakySink::failing(usize::MAX, true);
+    let hook = AuditHook::new(sink.clone(), options());
+    let client = client("spin", &hook).await;
+
+    client
+        .get_bool_value("new-checkout", None, None)
+        .await
+        .unwrap();
+    tokio::time::sleep(Duration::from_secs(1)).await;
+
+    // One attempt per 10ms at most, besides the first.
+    assert!(sink.attempts() <= 101, "{} attempts", sink.attempts());
+    assert!(sink.records().is_empty());
+}
+
+#[tokio::test]
+async fn drops_records_once_the_queue_stays_full() {
+    let hook = AuditHook::new(
+        StuckSink,
+        AuditOptions {
+            batch_size: 1,
+            capacity: 1,
+            enqueue_timeout: Some(Duration::from_millis(50)),
+            ..options()
+        },
+    );
+    let client = client("overflow", &hook).await;
+
+    tokio::time::timeout(Duration::from_secs(5), async {
+        for _ in 0..4 {
+            client
+                .get_bool_value("new-checkout", None, None)
+                .await
+                .unwrap();
+        }
+    })
+    .await
+    .expect("evaluations waited for the stuck sink");
+
+    // The writer holds one record and the queue another.
+    assert_eq!(hook.dropped(), 2);
+}
+
+#[tokio::test]
+async fn a_zero_flush_interval_keeps_writing() {
+    let sink = FlakySink::default();
+    let hook = AuditHook::new(
+        sink.clone(),
+        AuditOptions {
+            flush_interval: Duration::ZERO,
+            ..options()
+        },
+    );
+    let client = client("zero-interval", &hook).await;
+
+    for _ in 0..2 {
+        client
+            .get_bool_value("new-checkout", None, None)
+            .await
+            .unwrap();
+        tokio::time::sleep(Duration::from_millis(50)).await;
+    }
+
+    assert_eq!(sink.records().len(), 2);
+    assert_eq!(hook.dropped(), 0);
+}
+
+#[tokio::test]
+async fn http_sink_drops_rejected_batches() {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .respond_with(ResponseTemplate::new(400))
+        .expect(1)
+        .mount(&server)
+        .await;
+    let sink = HttpSink::new(HttpSinkOptions {
+        url: server.uri(),
+        ..Default::default()
+    })
+    .unwrap();
+    let hook = AuditHook::new(sink, options());
+    let client = client("http-rejected", &hook).await;
+
+    client
+        .get_bool_value("new-checkout", None, None)
+        .await
+        .unwrap();
+    hook.flush().await;
+
+    assert_eq!(hook.dropped(), 1);
+}
+
+#[tokio::test]
+async fn http_sink_retries_unavailable_endpoints() {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .respond_with(ResponseTemplate::new(503))
+        .up_to_n_times(2)
+        .mount(&server)
+        .await;
+    Mock::given(method("POST"))
+        .respond_with(ResponseTemplate::new(204))
+        .expect(1)
+        .mount(&server)
+        .await;
+    let sink = HttpSink::new(HttpSinkOptions {
+        url: server.uri(),
+        ..Default::default()
+    })
+    .unwrap();
+    let hook = AuditHook::new(sink, options());
+    let client = client("http-unavailable", &hook).await;
+
+    client
+        .get_bool_value("new-checkout", None, None)
+        .await
+        .unwrap();
+    hook.flush().await;
+
+    assert_eq!(hook.dropped(), 0);
+    let requests = server.received_requests().await.unwrap();
+    assert_eq!(requests.len(), 3);
+    let records: serde_json::Value = requests[2].body_json().unwrap();
+    assert_eq!(records[0]["flagKey"], "new-checkout");
+}
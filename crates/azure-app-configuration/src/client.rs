@@ -0,0 +1,180 @@
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use chrono::Utc;
+use hmac::{Hmac, Mac};
+use openfeature_contrib_common::check_status;
+use reqwest::header::{HeaderMap, HeaderValue, ACCEPT, AUTHORIZATION};
+use reqwest::{Client, StatusCode, Url};
+use serde::Deserialize;
+use sha2::{Digest, Sha256};
+
+use crate::AzureAppConfigurationError;
+
+const API_VERSION: &str = "1.0";
+const FEATURE_FLAG_PREFIX: &str = ".appconfig.featureflag/";
+/// The label filter selecting key-values without a label.
+const NULL_LABEL: &str = "\0";
+
+/// Credentials parsed from an App Configuration connection string
+/// (`Endpoint=...;Id=...;Secret=...`).
+#[derive(Clone)]
+pub(crate) struct ConnectionString {
+    endpoint: Url,
+    id: String,
+    secret: Vec<u8>,
+}
+
+impl ConnectionString {
+    pub(crate) fn parse(value: &str) -> Result<Self, AzureAppConfigurationError> {
+        let mut endpoint = None;
+        let mut id = None;
+        let mut secret = None;
+        for part in value.split(';').filter(|part| !part.is_empty()) {
+            match part.split_once('=') {
+                Some(("Endpoint", value)) => endpoint = Some(value),
+                Some(("Id", value)) => id = Some(value),
+                Some(("Secret", value)) => secret = Some(value),
+                _ => {}
+            }
+        }
+
+        let invalid = |reason: &str| AzureAppConfigurationError::ConnectionString(reason.into());
+        let endpoint = Url::parse(endpoint.ok_or_else(|| invalid("missing Endpoint"))?)
+            .map_err(|e| invalid(&format!("invalid Endpoint: {e}")))?;
+        if endpoint.cannot_be_a_base() {
+            return Err(invalid("Endpoint must be an http(s) url"));
+        }
+        Ok(Self {
+            endpoint,
+            id: id.ok_or_else(|| invalid("missing Id"))?.to_string(),
+            secret: STANDARD
+                .decode(secret.ok_or_else(|| invalid("missing Secret"))?)
+                .map_err(|e| invalid(&format!("invalid Secret: {e}")))?,
+        })
+    }
+}
+
+/// A key-value as returned by the App Configuration REST API.
+#[derive(Debug, Deserialize)]
+pub(crate) struct KeyValue {
+    pub(crate) key: String,
+    #[serde(default)]
+    pub(crate) value: Option<String>,
+    #[serde(default)]
+    pub(crate) etag: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct KeyValuePage {
+    items: Vec<KeyValue>,
+    #[serde(rename = "@nextLink", default)]
+    next_link: Option<String>,
+}
+
+/// Minimal App Configuration REST client using HMAC-SHA256 request signing.
+pub(crate) struct AppConfigurationClient {
+    http: Client,
+    credentials: ConnectionString,
+    label: String,
+}
+
+impl AppConfigurationClient {
+    pub(crate) fn new(credentials: ConnectionString, label: Option<&str>, http: Client) -> Self {
+        Self {
+            http,
+            credentials,
+            label: label.unwrap_or(NULL_LABEL).to_string(),
+        }
+    }
+
+    /// Lists all feature flag key-values for the configured label, following pagination.
+    pub(crate) async fn list_feature_flags(
+        &self,
+    ) -> Result<Vec<KeyValue>, AzureAppConfigurationError> {
+        let mut url = self.credentials.endpoint.join("kv")?;
+        url.query_pairs_mut()
+            .append_pair("key", &format!("{FEATURE_FLAG_PREFIX}*"))
+            .append_pair("label", &self.label)
+            .append_pair("api-version", API_VERSION);
+
+        let mut items = Vec::new();
+        let mut next = Some(url);
+        while let Some(url) = next.take() {
+            let response = self.get(&url).await?;
+            let page: KeyValuePage = check_status(response)?.json().await?;
+            items.extend(page.items);
+            if let Some(link) = page.next_link {
+                next = Some(self.credentials.endpoint.join(&link)?);
+            }
+        }
+
+        Ok(items)
+    }
+
+    /// Fetches a single key-value, returning `None` if it does not exist.
+    pub(crate) async fn get_key_value(
+        &self,
+        key: &str,
+    ) -> Result<Option<KeyValue>, AzureAppConfigurationError> {
+        let mut url = self.credentials.endpoint.clone();
+        if let Ok(mut segments) = url.path_segments_mut() {
+            segments.pop_if_empty().extend(["kv", key]);
+        }
+        url.query_pairs_mut()
+            .append_pair("label", &self.label)
+            .append_pair("api-version", API_VERSION);
+
+        let response = self.get(&url).await?;
+        if response.status() == StatusCode::NOT_FOUND {
+            return Ok(None);
+        }
+        Ok(Some(check_status(response)?.json().await?))
+    }
+
+    async fn get(&self, url: &Url) -> Result<reqwest::Response, AzureAppConfigurationError> {
+        let headers = self.sign("GET", url, b"");
+        Ok(self.http.get(url.clone()).headers(headers).send().await?)
+    }
+
+    /// Builds the `x-ms-date`, `x-ms-content-sha256` and `Authorization` headers as described in
+    /// <https://learn.microsoft.com/azure/azure-app-configuration/rest-api-authentication-hmac>.
+    fn sign(&self, method: &str, url: &Url, body: &[u8]) -> HeaderMap {
+        let date = Utc::now().format("%a, %d %b %Y %H:%M:%S GMT").to_string();
+        let content_hash = STANDARD.encode(Sha256::digest(body));
+        let host = match url.port() {
+            Some(port) => format!("{}:{port}", url.host_str().unwrap_or_default()),
+            None => url.host_str().unwrap_or_default().to_string(),
+        };
+        let path_and_query = match url.query() {
+            Some(query) => format!("{}?{query}", url.path()),
+            None => url.path().to_string(),
+        };
+
+        let string_to_sign = format!("{method}\n{path_and_query}\n{date};{host};{content_hash}");
+        let mut mac = Hmac::<Sha256>::new_from_slice(&self.credentials.secret)
+            .expect("HMAC accepts keys of any length");
+        mac.update(string_to_sign.as_bytes());
+        let signature = STANDARD.encode(mac.finalize().into_bytes());
+
+        let mut headers = HeaderMap::new();
+        let mut insert = |name: &'static str, value: String| {
+            if let Ok(value) = HeaderValue::from_str(&value) {
+                headers.insert(name, value);
+            }
+        };
+        insert("x-ms-date", date);
+        insert("x-ms-content-sha256", content_hash);
+        insert(
+            AUTHORIZATION.as_str(),
+            format!(
+                "HMAC-SHA256 Credential={}&SignedHeaders=x-ms-date;host;x-ms-content-sha256&Signature={signature}",
+                self.credentials.id
+            ),
+        );
+        headers.insert(
+            ACCEPT,
+            HeaderValue::from_static("application/vnd.microsoft.appconfig.kvset+json, application/vnd.microsoft.appconfig.kv+json, application/problem+json"),
+        );
+        headers
+    }
+}
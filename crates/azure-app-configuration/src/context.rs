@@ -0,0 +1,30 @@
+use open_feature::{EvaluationContext, EvaluationContextFieldValue};
+
+use crate::feature_flag::TargetingContext;
+
+/// Builds the targeting context of an [`EvaluationContext`].
+///
+/// The targeting key is the user id. Groups are read from `groups_field`, a comma-separated
+/// string custom field.
+pub(crate) fn to_targeting_context<'a>(
+    context: &'a EvaluationContext,
+    groups_field: &str,
+) -> TargetingContext<'a> {
+    let groups = context
+        .custom_fields
+        .get(groups_field)
+        .and_then(EvaluationContextFieldValue::as_str)
+        .map(|groups| {
+            groups
+                .split(',')
+                .map(str::trim)
+                .filter(|group| !group.is_empty())
+                .collect()
+        })
+        .unwrap_or_default();
+
+    TargetingContext {
+        user_id: context.targeting_key.as_deref(),
+        groups,
+    }
+}
@@ -0,0 +1,36 @@
+use openfeature_contrib_common::HttpError;
+use thiserror::Error;
+
+/// Errors raised while creating or refreshing an
+/// [`AzureAppConfigurationProvider`](crate::AzureAppConfigurationProvider).
+#[derive(Debug, Error)]
+pub enum AzureAppConfigurationError {
+    /// The connection string is missing a component or contains an invalid value.
+    #[error("invalid connection string: {0}")]
+    ConnectionString(String),
+
+    /// A request URL could not be built from the configured endpoint.
+    #[error("invalid App Configuration url: {0}")]
+    Url(#[from] url::ParseError),
+
+    /// The HTTP request to App Configuration failed.
+    #[error("request to App Configuration failed: {0}")]
+    Http(#[from] HttpError),
+}
+
+impl From<reqwest::Error> for AzureAppConfigurationError {
+    fn from(error: reqwest::Error) -> Self {
+        Self::Http(error.into())
+    }
+}
+
+impl AzureAppConfigurationError {
+    /// Whether the request may succeed when sent again. Authentication failures and invalid
+    /// options are not retryable.
+    pub fn is_retryable(&self) -> bool {
+        match self {
+            Self::ConnectionString(_) | Self::Url(_) => false,
+            Self::Http(e) => e.is_retryable(),
+        }
+    }
+}
@@ -0,0 +1,617 @@
+//! The [feature management schema](https://github.com/microsoft/FeatureManagement/tree/main/Schema)
+//! stored in `.appconfig.featureflag/*` key-values, and its client-side evaluation.
+//!
+//! Targeting and allocation bucketing follow `Microsoft.FeatureManagement`: the context id is
+//! hashed with SHA-256 and the first four bytes, read as a little-endian `u32`, are scaled to a
+//! percentage. The same user therefore lands in the same bucket as in the .NET, Java, Python
+//! and JavaScript libraries.
+
+use chrono::{DateTime, Utc};
+use open_feature::EvaluationReason;
+use rand::Rng;
+use serde::Deserialize;
+use sha2::{Digest, Sha256};
+
+const PERCENTAGE_FILTER: &str = "Percentage";
+const TARGETING_FILTER: &str = "Targeting";
+const TIME_WINDOW_FILTER: &str = "TimeWindow";
+
+#[derive(Debug, Clone, Deserialize)]
+pub(crate) struct FeatureFlag {
+    pub(crate) id: String,
+    #[serde(default)]
+    pub(crate) enabled: bool,
+    #[serde(default)]
+    pub(crate) conditions: Option<Conditions>,
+    #[serde(default)]
+    pub(crate) variants: Vec<Variant>,
+    #[serde(default)]
+    pub(crate) allocation: Option<Allocation>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub(crate) struct Conditions {
+    #[serde(default)]
+    requirement_type: RequirementType,
+    #[serde(default)]
+    client_filters: Vec<ClientFilter>,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+enum RequirementType {
+    #[default]
+    Any,
+    All,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct ClientFilter {
+    name: String,
+    #[serde(default)]
+    parameters: serde_json::Value,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub(crate) struct Variant {
+    pub(crate) name: String,
+    #[serde(default)]
+    pub(crate) configuration_value: Option<serde_json::Value>,
+    #[serde(default)]
+    status_override: StatusOverride,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+enum StatusOverride {
+    #[default]
+    None,
+    Enabled,
+    Disabled,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+pub(crate) struct Allocation {
+    #[serde(default)]
+    default_when_disabled: Option<String>,
+    #[serde(default)]
+    default_when_enabled: Option<String>,
+    #[serde(default)]
+    user: Vec<UserAllocation>,
+    #[serde(default)]
+    group: Vec<GroupAllocation>,
+    #[serde(default)]
+    percentile: Vec<PercentileAllocation>,
+    #[serde(default)]
+    seed: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct UserAllocation {
+    variant: String,
+    #[serde(default)]
+    users: Vec<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct GroupAllocation {
+    variant: String,
+    #[serde(default)]
+    groups: Vec<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct PercentileAllocation {
+    variant: String,
+    from: f64,
+    to: f64,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct TargetingParameters {
+    audience: Audience,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct Audience {
+    #[serde(default)]
+    users: Vec<String>,
+    #[serde(default)]
+    groups: Vec<GroupRollout>,
+    #[serde(default)]
+    default_rollout_percentage: f64,
+    #[serde(default)]
+    exclusion: Exclusion,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct GroupRollout {
+    name: String,
+    rollout_percentage: f64,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct Exclusion {
+    #[serde(default)]
+    users: Vec<String>,
+    #[serde(default)]
+    groups: Vec<String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct PercentageParameters {
+    value: f64,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct TimeWindowParameters {
+    #[serde(default)]
+    start: Option<String>,
+    #[serde(default)]
+    end: Option<String>,
+}
+
+/// The subject of an evaluation, as used by the targeting filter and allocations.
+#[derive(Debug, Default)]
+pub(crate) struct TargetingContext<'a> {
+    pub(crate) user_id: Option<&'a str>,
+    pub(crate) groups: Vec<&'a str>,
+}
+
+/// Outcome of evaluating a [`FeatureFlag`].
+#[derive(Debug)]
+pub(crate) struct Evaluation<'a> {
+    pub(crate) enabled: bool,
+    pub(crate) variant: Option<&'a Variant>,
+    pub(crate) reason: EvaluationReason,
+}
+
+impl FeatureFlag {
+    pub(crate) fn evaluate(&self, context: &TargetingContext<'_>) -> Evaluation<'_> {
+        let (enabled, reason) = self.evaluate_enabled(context);
+        if self.variants.is_empty() {
+            return Evaluation {
+                enabled,
+                variant: None,
+                reason,
+            };
+        }
+
+        let default_allocation = Allocation::default();
+        let allocation = self.allocation.as_ref().unwrap_or(&default_allocation);
+        let (variant_name, reason) = if enabled {
+            match self.allocate(allocation, context) {
+                Some((name, reason)) => (Some(name), reason),
+                None => (
+                    allocation.default_when_enabled.as_deref(),
+                    EvaluationReason::Default,
+                ),
+            }
+        } else {
+            (allocation.default_when_disabled.as_deref(), reason)
+        };
+
+        let variant =
+            variant_name.and_then(|name| self.variants.iter().find(|variant| variant.name == name));
+        let enabled = match variant.map(|variant| variant.status_override) {
+            Some(StatusOverride::Enabled) => true,
+            Some(StatusOverride::Disabled) => false,
+            _ => enabled,
+        };
+
+        Evaluation {
+            enabled,
+            variant,
+            reason,
+        }
+    }
+
+    fn evaluate_enabled(&self, context: &TargetingContext<'_>) -> (bool, EvaluationReason) {
+        if !self.enabled {
+            return (false, EvaluationReason::Disabled);
+        }
+
+        let Some(conditions) = self
+            .conditions
+            .as_ref()
+            .filter(|conditions| !conditions.client_filters.is_empty())
+        else {
+            return (true, EvaluationReason::Static);
+        };
+
+        let mut results = conditions
+            .client_filters
+            .iter()
+            .map(|filter| self.evaluate_filter(filter, context));
+        let enabled = match conditions.requirement_type {
+            RequirementType::Any => results.any(|matched| matched),
+            RequirementType::All => results.all(|matched| matched),
+        };
+
+        if enabled {
+            (true, EvaluationReason::TargetingMatch)
+        } else {
+            (false, EvaluationReason::Default)
+        }
+    }
+
+    fn evaluate_filter(&self, filter: &ClientFilter, context: &TargetingContext<'_>) -> bool {
+        let name = filter
+            .name
+            .strip_prefix("Microsoft.")
+            .unwrap_or(&filter.name);
+        match name {
+            PERCENTAGE_FILTER => {
+                parameters::<PercentageParameters>(filter).is_some_and(|parameters| {
+                    rand::thread_rng().gen_range(0.0..100.0) < parameters.value
+                })
+            }
+            TIME_WINDOW_FILTER => parameters::<TimeWindowParameters>(filter)
+                .is_some_and(|parameters| in_time_window(&parameters, Utc::now())),
+            TARGETING_FILTER => parameters::<TargetingParameters>(filter)
+                .is_some_and(|parameters| self.is_targeted(&parameters.audience, context)),
+            _ => {
+                tracing::warn!(
+                    "Unsupported feature filter {} on feature flag {}",
+                    filter.name,
+                    self.id
+                );
+                false
+            }
+        }
+    }
+
+    fn is_targeted(&self, audience: &Audience, context: &TargetingContext<'_>) -> bool {
+        let user_id = context.user_id.unwrap_or_default();
+        let in_groups = |groups: &[String]| {
+            groups
+                .iter()
+                .any(|group| context.groups.iter().any(|g| g.eq_ignore_ascii_case(group)))
+        };
+
+        if audience.exclusion.users.iter().any(|user| user == user_id)
+            || in_groups(&audience.exclusion.groups)
+        {
+            return false;
+        }
+
+        if context.user_id.is_some() && audience.users.iter().any(|user| user == user_id) {
+            return true;
+        }
+
+        for group in &audience.groups {
+            if context
+                .groups
+                .iter()
+                .any(|g| g.eq_ignore_ascii_case(&group.name))
+            {
+                let context_id = format!("{user_id}\n{}\n{}", self.id, group.name);
+                if bucket(&context_id) < group.rollout_percentage {
+                    return true;
+                }
+            }
+        }
+
+        bucket(&format!("{user_id}\n{}", self.id)) < audience.default_rollout_percentage
+    }
+
+    fn allocate<'a>(
+        &self,
+        allocation: &'a Allocation,
+        context: &TargetingContext<'_>,
+    ) -> Option<(&'a str, EvaluationReason)> {
+        if let Some(user_id) = context.user_id {
+            if let Some(user) = allocation
+                .user
+                .iter()
+                .find(|user| user.users.iter().any(|u| u == user_id))
+            {
+                return Some((&user.variant, EvaluationReason::TargetingMatch));
+            }
+        }
+
+        if let Some(group) = allocation.group.iter().find(|group| {
+            group
+                .groups
+                .iter()
+                .any(|name| context.groups.iter().any(|g| g.eq_ignore_ascii_case(name)))
+        }) {
+            return Some((&group.variant, EvaluationReason::TargetingMatch));
+        }
+
+        if !allocation.percentile.is_empty() {
+            let seed = allocation
+                .seed
+                .clone()
+                .unwrap_or_else(|| format!("allocation\n{}", self.id));
+            let value = bucket(&format!("{}\n{seed}", context.user_id.unwrap_or_default()));
+            if let Some(percentile) = allocation.percentile.iter().find(|percentile| {
+                (value >= percentile.from && value < percentile.to)
+                    || (percentile.to == 100.0 && value == 100.0)
+            }) {
+                return Some((&percentile.variant, EvaluationReason::Split));
+            }
+        }
+
+        None
+    }
+}
+
+fn parameters<T: serde::de::DeserializeOwned>(filter: &ClientFilter) -> Option<T> {
+    serde_json::from_value(filter.parameters.clone())
+        .map_err(|e| tracing::warn!("Invalid parameters for filter {}: {e}", filter.name))
+        .ok()
+}
+
+fn in_time_window(parameters: &TimeWindowParameters, now: DateTime<Utc>) -> bool {
+    let start = parameters.start.as_deref().and_then(parse_time);
+    let end = parameters.end.as_deref().and_then(parse_time);
+    start.map_or(true, |start| now >= start) && end.map_or(true, |end| now < end)
+}
+
+/// Parses RFC 2822 (the format written by the Azure portal) or RFC 3339 timestamps.
+fn parse_time(value: &str) -> Option<DateTime<Utc>> {
+    DateTime::parse_from_rfc2822(value)
+        .or_else(|_| DateTime::parse_from_rfc3339(value))
+        .map(|time| time.with_timezone(&Utc))
+        .ok()
+}
+
+/// Maps a context id onto `[0, 100]`.
+fn bucket(context_id: &str) -> f64 {
+    let hash = Sha256::digest(context_id.as_bytes());
+    let marker = u32::from_le_bytes([hash[0], hash[1], hash[2], hash[3]]);
+    f64::from(marker) / f64::from(u32::MAX) * 100.0
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn flag(value: serde_json::Value) -> FeatureFlag {
+        serde_json::from_value(value).expect("invalid feature flag")
+    }
+
+    fn user<'a>(user_id: &'a str, groups: &[&'a str]) -> TargetingContext<'a> {
+        TargetingContext {
+            user_id: Some(user_id),
+            groups: groups.to_vec(),
+        }
+    }
+
+    fn targeting(audience: serde_json::Value) -> FeatureFlag {
+        flag(json!({
+            "id": "beta",
+            "enabled": true,
+            "conditions": {
+                "client_filters": [
+                    {"name": "Microsoft.Targeting", "parameters": {"Audience": audience}}
+                ]
+            }
+        }))
+    }
+
+    fn time_window(start: Option<&str>, end: Option<&str>) -> TimeWindowParameters {
+        TimeWindowParameters {
+            start: start.map(str::to_string),
+            end: end.map(str::to_string),
+        }
+    }
+
+    #[test]
+    fn buckets_match_the_feature_management_libraries() {
+        assert!((bucket("alice\nbeta") - 61.403_687_801_538_9).abs() < 1e-9);
+        assert!((bucket("bob\nbeta") - 54.634_565_523_507_675).abs() < 1e-9);
+        assert!((bucket("alice\nbeta\nring1") - 15.609_383_051_192_71).abs() < 1e-9);
+        assert!((bucket("bob\nallocation\nbeta") - 7.451_346_681_325_545).abs() < 1e-9);
+    }
+
+    #[test]
+    fn users_roll_out_by_default_percentage() {
+        // alice buckets at 61.4 and bob at 54.6.
+        let flag = targeting(json!({"DefaultRolloutPercentage": 60}));
+
+        assert!(flag.evaluate(&user("bob", &[])).enabled);
+        assert!(!flag.evaluate(&user("alice", &[])).enabled);
+    }
+
+    #[test]
+    fn listed_users_are_targeted() {
+        let flag = targeting(json!({"Users": ["alice"], "DefaultRolloutPercentage": 0}));
+
+        let evaluation = flag.evaluate(&user("alice", &[]));
+        assert!(evaluation.enabled);
+        assert_eq!(evaluation.reason, EvaluationReason::TargetingMatch);
+        assert!(!flag.evaluate(&user("bob", &[])).enabled);
+    }
+
+    #[test]
+    fn groups_roll_out_by_their_percentage() {
+        // In ring1, bob buckets at 3.9, alice at 15.6 and carol at 92.8.
+        let flag = targeting(json!({
+            "Groups": [{"Name": "ring1", "RolloutPercentage": 20}],
+            "DefaultRolloutPercentage": 0
+        }));
+
+        assert!(flag.evaluate(&user("bob", &["RING1"])).enabled);
+        assert!(flag.evaluate(&user("alice", &["ring1"])).enabled);
+        assert!(!flag.evaluate(&user("carol", &["ring1"])).enabled);
+        assert!(!flag.evaluate(&user("alice", &["ring2"])).enabled);
+    }
+
+    #[test]
+    fn exclusions_override_targeting() {
+        let flag = targeting(json!({
+            "Users": ["alice", "bob"],
+            "DefaultRolloutPercentage": 100,
+            "Exclusion": {"Users": ["alice"], "Groups": ["Blocked"]}
+        }));
+
+        assert!(!flag.evaluate(&user("alice", &[])).enabled);
+        assert!(!flag.evaluate(&user("bob", &["blocked"])).enabled);
+        assert!(flag.evaluate(&user("bob", &[])).enabled);
+    }
+
+    #[test]
+    fn requirement_type_all_needs_every_filter() {
+        let filters = json!([
+            {"name": "Microsoft.Targeting", "parameters": {"Audience": {"Users": ["alice"]}}},
+            {"name": "Microsoft.TimeWindow", "parameters": {"End": "Mon, 01 Jan 2001 00:00:00 GMT"}}
+        ]);
+        let any = flag(json!({
+            "id": "beta",
+            "enabled": true,
+            "conditions": {"client_filters": filters}
+        }));
+        let all = flag(json!({
+            "id": "beta",
+            "enabled": true,
+            "conditions": {"requirement_type": "All", "client_filters": filters}
+        }));
+
+        assert!(any.evaluate(&user("alice", &[])).enabled);
+        assert!(!all.evaluate(&user("alice", &[])).enabled);
+    }
+
+    #[test]
+    fn disabled_flags_are_off_whatever_the_filters() {
+        let flag = flag(json!({"id": "beta", "enabled": false}));
+
+        let evaluation = flag.evaluate(&user("alice", &[]));
+        assert!(!evaluation.enabled);
+        assert_eq!(evaluation.reason, EvaluationReason::Disabled);
+    }
+
+    #[test]
+    fn time_windows_parse_rfc_2822_and_rfc_3339() {
+        let now = DateTime::parse_from_rfc3339("2024-06-01T12:00:00Z")
+            .unwrap()
+            .with_timezone(&Utc);
+
+        assert!(in_time_window(
+            &time_window(
+                Some("Sat, 01 Jun 2024 11:00:00 GMT"),
+                Some("2024-06-01T13:00:00+00:00")
+            ),
+            now
+        ));
+        assert!(!in_time_window(
+            &time_window(Some("2024-06-01T13:00:00+00:30"), None),
+            now
+        ));
+        assert!(!in_time_window(
+            &time_window(None, Some("Sat, 01 Jun 2024 12:00:00 +0000")),
+            now
+        ));
+        assert!(in_time_window(&time_window(None, None), now));
+        assert_eq!(parse_time("June 1st"), None);
+    }
+
+    fn variant_flag(allocation: serde_json::Value) -> FeatureFlag {
+        flag(json!({
+            "id": "beta",
+            "enabled": true,
+            "variants": [
+                {"name": "control", "configuration_value": "blue"},
+                {"name": "treatment", "configuration_value": "green"},
+                {"name": "off", "status_override": "Disabled"}
+            ],
+            "allocation": allocation
+        }))
+    }
+
+    fn variant<'a>(flag: &'a FeatureFlag, context: &TargetingContext<'_>) -> Option<&'a str> {
+        flag.evaluate(context)
+            .variant
+            .map(|variant| variant.name.as_str())
+    }
+
+    #[test]
+    fn users_and_groups_are_allocated_before_percentiles() {
+        let flag = variant_flag(json!({
+            "user": [{"variant": "treatment", "users": ["alice"]}],
+            "group": [{"variant": "control", "groups": ["Ring1"]}],
+            "percentile": [{"variant": "off", "from": 0, "to": 100}]
+        }));
+
+        assert_eq!(
+            variant(&flag, &user("alice", &["ring1"])),
+            Some("treatment")
+        );
+        assert_eq!(variant(&flag, &user("bob", &["ring1"])), Some("control"));
+        assert_eq!(variant(&flag, &user("bob", &[])), Some("off"));
+    }
+
+    #[test]
+    fn percentiles_allocate_by_bucket() {
+        // bob buckets at 7.5, carol at 20.8, dave at 25.3 and alice at 72.0.
+        let flag = variant_flag(json!({
+            "percentile": [
+                {"variant": "control", "from": 0, "to": 25},
+                {"variant": "treatment", "from": 25, "to": 100}
+            ]
+        }));
+
+        assert_eq!(variant(&flag, &user("bob", &[])), Some("control"));
+        assert_eq!(variant(&flag, &user("carol", &[])), Some("control"));
+        assert_eq!(variant(&flag, &user("dave", &[])), Some("treatment"));
+        let evaluation = flag.evaluate(&user("alice", &[]));
+        assert_eq!(evaluation.reason, EvaluationReason::Split);
+    }
+
+    #[test]
+    fn seeds_replace_the_default_allocation_hash() {
+        // With the seed, bob buckets at 28.7 and alice at 78.0.
+        let flag = variant_flag(json!({
+            "seed": "seed",
+            "percentile": [
+                {"variant": "control", "from": 0, "to": 50},
+                {"variant": "treatment", "from": 50, "to": 100}
+            ]
+        }));
+
+        assert_eq!(variant(&flag, &user("bob", &[])), Some("control"));
+        assert_eq!(variant(&flag, &user("alice", &[])), Some("treatment"));
+    }
+
+    #[test]
+    fn unallocated_users_get_the_default_variants() {
+        let allocation = json!({
+            "default_when_enabled": "control",
+            "default_when_disabled": "treatment",
+            "percentile": [{"variant": "treatment", "from": 0, "to": 1}]
+        });
+        let enabled = variant_flag(allocation.clone());
+        let mut disabled = variant_flag(allocation);
+        disabled.enabled = false;
+
+        let evaluation = enabled.evaluate(&user("alice", &[]));
+        assert_eq!(evaluation.variant.map(|v| v.name.as_str()), Some("control"));
+        assert_eq!(evaluation.reason, EvaluationReason::Default);
+        let evaluation = disabled.evaluate(&user("alice", &[]));
+        assert_eq!(
+            evaluation.variant.map(|v| v.name.as_str()),
+            Some("treatment")
+        );
+        assert_eq!(evaluation.reason, EvaluationReason::Disabled);
+    }
+
+    #[test]
+    fn status_overrides_replace_the_flag_state() {
+        let flag = variant_flag(json!({
+            "user": [{"variant": "off", "users": ["alice"]}],
+            "default_when_enabled": "control"
+        }));
+
+        let evaluation = flag.evaluate(&user("alice", &[]));
+        assert_eq!(evaluation.variant.map(|v| v.name.as_str()), Some("off"));
+        assert!(!evaluation.enabled);
+        assert!(flag.evaluate(&user("bob", &[])).enabled);
+    }
+}
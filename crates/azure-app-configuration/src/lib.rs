@@ -0,0 +1,354 @@
+//! [Azure App Configuration](https://learn.microsoft.com/azure/azure-app-configuration/)
+//! feature flag provider for OpenFeature.
+//!
+//! The provider loads the `.appconfig.featureflag/*` key-values of a store through the App
+//! Configuration REST API, authenticating with the HMAC-SHA256 scheme of an access key
+//! connection string, and evaluates them locally following the
+//! [Microsoft feature management](https://github.com/microsoft/FeatureManagement) schema:
+//! the `Targeting`, `Percentage` and `TimeWindow` filters and variant allocation.
+//!
+//! Flags are refreshed in the background. When a sentinel key is configured, the flags are only
+//! reloaded after the sentinel's ETag changed, which keeps the number of requests against the
+//! store low.
+//!
+//! # Context mapping
+//!
+//! The targeting key is used as the targeting user id. Targeting groups are read from a
+//! comma-separated string custom field, `groups` by default.
+//!
+//! # Value mapping
+//!
+//! Boolean flags resolve to the feature's enabled state. String, integer, float and struct flags
+//! resolve to the configuration value of the allocated variant, and the variant name is reported
+//! in [`ResolutionDetails::variant`].
+//!
+//! # Example
+//!
+//! ```rust,no_run
+//! use open_feature::provider::FeatureProvider;
+//! use open_feature::EvaluationContext;
+//! use open_feature_azure_app_configuration::{
+//!     AzureAppConfigurationOptions, AzureAppConfigurationProvider,
+//! };
+//!
+//! #[tokio::main]
+//! async fn main() {
+//!     let provider = AzureAppConfigurationProvider::new(AzureAppConfigurationOptions {
+//!         connection_string: std::env::var("APP_CONFIGURATION_CONNECTION_STRING").unwrap(),
+//!         sentinel_key: Some("sentinel".to_string()),
+//!         ..Default::default()
+//!     })
+//!     .await
+//!     .expect("Failed to create Azure App Configuration provider");
+//!
+//!     let context = EvaluationContext::default()
+//!         .with_targeting_key("user-123")
+//!         .with_custom_field("groups", "beta-testers");
+//!     let details = provider.resolve_bool_value("new-checkout", &context).await;
+//!     println!("{details:?}");
+//! }
+//! ```
+
+mod client;
+mod context;
+mod error;
+mod feature_flag;
+
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+
+use async_trait::async_trait;
+use open_feature::provider::{FeatureProvider, ProviderMetadata, ResolutionDetails};
+use open_feature::{
+    EvaluationContext, EvaluationError, EvaluationErrorCode, EvaluationResult, StructValue, Value,
+};
+use tokio::sync::RwLock;
+use tokio::task::JoinHandle;
+use tracing::{debug, error, warn};
+
+use crate::client::{AppConfigurationClient, ConnectionString};
+use crate::context::to_targeting_context;
+pub use crate::error::AzureAppConfigurationError;
+use crate::feature_flag::FeatureFlag;
+
+/// Configuration of the [`AzureAppConfigurationProvider`].
+#[derive(Debug, Clone)]
+pub struct AzureAppConfigurationOptions {
+    /// Access key connection string of the store (`Endpoint=...;Id=...;Secret=...`).
+    pub connection_string: String,
+    /// Label of the feature flags to load. `None` loads flags without a label.
+    pub label: Option<String>,
+    /// Key whose ETag is watched to decide whether the flags must be reloaded. Without a
+    /// sentinel, all flags are reloaded on every refresh.
+    pub sentinel_key: Option<String>,
+    /// Interval between refreshes.
+    pub refresh_interval: Duration,
+    /// Custom field holding the comma-separated targeting groups.
+    pub groups_field: String,
+    /// Timeout of requests to App Configuration.
+    pub request_timeout: Duration,
+}
+
+impl Default for AzureAppConfigurationOptions {
+    fn default() -> Self {
+        Self {
+            connection_string: String::new(),
+            label: None,
+            sentinel_key: None,
+            refresh_interval: Duration::from_secs(30),
+            groups_field: "groups".to_string(),
+            request_timeout: Duration::from_secs(10),
+        }
+    }
+}
+
+type Flags = Arc<RwLock<HashMap<String, FeatureFlag>>>;
+
+/// OpenFeature provider evaluating Azure App Configuration feature flags locally.
+pub struct AzureAppConfigurationProvider {
+    metadata: ProviderMetadata,
+    groups_field: String,
+    flags: Flags,
+    refresh: JoinHandle<()>,
+}
+
+impl AzureAppConfigurationProvider {
+    /// Creates the provider, loading the feature flags and starting the background refresh.
+    pub async fn new(
+        options: AzureAppConfigurationOptions,
+    ) -> Result<Self, AzureAppConfigurationError> {
+        let credentials = ConnectionString::parse(&options.connection_string)?;
+        let http = reqwest::Client::builder()
+            .timeout(options.request_timeout)
+            .build()?;
+        let client = AppConfigurationClient::new(credentials, options.label.as_deref(), http);
+
+        let sentinel_etag = match &options.sentinel_key {
+            Some(key) => client
+                .get_key_value(key)
+                .await?
+                .and_then(|sentinel| sentinel.etag),
+            None => None,
+        };
+        let flags = Arc::new(RwLock::new(load_flags(&client).await?));
+        let refresh = spawn_refresh(
+            client,
+            flags.clone(),
+            options.sentinel_key,
+            sentinel_etag,
+            options.refresh_interval,
+        );
+
+        Ok(Self {
+            metadata: ProviderMetadata::new("azure-app-configuration"),
+            groups_field: options.groups_field,
+            flags,
+            refresh,
+        })
+    }
+
+    async fn resolve_variant<T>(
+        &self,
+        flag_key: &str,
+        context: &EvaluationContext,
+        parse: impl FnOnce(&serde_json::Value) -> EvaluationResult<T>,
+    ) -> EvaluationResult<ResolutionDetails<T>> {
+        let flags = self.flags.read().await;
+        let flag = find_flag(&flags, flag_key)?;
+        if flag.variants.is_empty() {
+            return Err(type_mismatch(format!(
+                "Flag {flag_key} has no variants and can only be resolved as a boolean"
+            )));
+        }
+
+        let evaluation = flag.evaluate(&to_targeting_context(context, &self.groups_field));
+        let variant = evaluation.variant.ok_or_else(|| {
+            EvaluationError::builder()
+                .code(EvaluationErrorCode::General(
+                    "No variant allocated".to_string(),
+                ))
+                .message(format!("No variant of flag {flag_key} was allocated"))
+                .build()
+        })?;
+        let value = variant
+            .configuration_value
+            .as_ref()
+            .unwrap_or(&serde_json::Value::Null);
+
+        Ok(ResolutionDetails {
+            value: parse(value)?,
+            variant: Some(variant.name.clone()),
+            reason: Some(evaluation.reason),
+            flag_metadata: None,
+        })
+    }
+}
+
+impl Drop for AzureAppConfigurationProvider {
+    fn drop(&mut self) {
+        self.refresh.abort();
+    }
+}
+
+#[async_trait]
+impl FeatureProvider for AzureAppConfigurationProvider {
+    fn metadata(&self) -> &ProviderMetadata {
+        &self.metadata
+    }
+
+    async fn resolve_bool_value(
+        &self,
+        flag_key: &str,
+        context: &EvaluationContext,
+    ) -> EvaluationResult<ResolutionDetails<bool>> {
+        let flags = self.flags.read().await;
+        let evaluation = find_flag(&flags, flag_key)?
+            .evaluate(&to_targeting_context(context, &self.groups_field));
+
+        Ok(ResolutionDetails {
+            value: evaluation.enabled,
+            variant: evaluation.variant.map(|variant| variant.name.clone()),
+            reason: Some(evaluation.reason),
+            flag_metadata: None,
+        })
+    }
+
+    async fn resolve_int_value(
+        &self,
+        flag_key: &str,
+        context: &EvaluationContext,
+    ) -> EvaluationResult<ResolutionDetails<i64>> {
+        self.resolve_variant(flag_key, context, |value| {
+            value
+                .as_i64()
+                .ok_or_else(|| type_mismatch(format!("Value of flag {flag_key} is not an integer")))
+        })
+        .await
+    }
+
+    async fn resolve_float_value(
+        &self,
+        flag_key: &str,
+        context: &EvaluationContext,
+    ) -> EvaluationResult<ResolutionDetails<f64>> {
+        self.resolve_variant(flag_key, context, |value| {
+            value
+                .as_f64()
+                .ok_or_else(|| type_mismatch(format!("Value of flag {flag_key} is not a number")))
+        })
+        .await
+    }
+
+    async fn resolve_string_value(
+        &self,
+        flag_key: &str,
+        context: &EvaluationContext,
+    ) -> EvaluationResult<ResolutionDetails<String>> {
+        self.resolve_variant(flag_key, context, |value| {
+            value
+                .as_str()
+                .map(str::to_string)
+                .ok_or_else(|| type_mismatch(format!("Value of flag {flag_key} is not a string")))
+        })
+        .await
+    }
+
+    async fn resolve_struct_value(
+        &self,
+        flag_key: &str,
+        context: &EvaluationContext,
+    ) -> EvaluationResult<ResolutionDetails<StructValue>> {
+        self.resolve_variant(flag_key, context, |value| {
+            match Value::try_from(value.clone())? {
+                Value::Struct(value) => Ok(value),
+                _ => Err(type_mismatch(format!(
+                    "Value of flag {flag_key} is not a JSON object"
+                ))),
+            }
+        })
+        .await
+    }
+}
+
+async fn load_flags(
+    client: &AppConfigurationClient,
+) -> Result<HashMap<String, FeatureFlag>, AzureAppConfigurationError> {
+    let flags = client
+        .list_feature_flags()
+        .await?
+        .into_iter()
+        .filter_map(|key_value| {
+            let value = key_value.value?;
+            serde_json::from_str::<FeatureFlag>(&value)
+                .map_err(|e| warn!("Skipping invalid feature flag {}: {e}", key_value.key))
+                .ok()
+        })
+        .map(|flag| (flag.id.clone(), flag))
+        .collect();
+
+    Ok(flags)
+}
+
+fn spawn_refresh(
+    client: AppConfigurationClient,
+    flags: Flags,
+    sentinel_key: Option<String>,
+    mut sentinel_etag: Option<String>,
+    interval: Duration,
+) -> JoinHandle<()> {
+    tokio::spawn(async move {
+        loop {
+            tokio::time::sleep(interval).await;
+            let etag = match &sentinel_key {
+                Some(key) => match client.get_key_value(key).await {
+                    Ok(sentinel) => sentinel.and_then(|sentinel| sentinel.etag),
+                    Err(e) if e.is_retryable() => {
+                        warn!("Failed to check App Configuration sentinel {key}: {e}");
+                        continue;
+                    }
+                    Err(e) => {
+                        error!("Failed to check App Configuration sentinel {key}: {e}");
+                        continue;
+                    }
+                },
+                None => None,
+            };
+            if sentinel_key.is_some() && etag == sentinel_etag {
+                continue;
+            }
+
+            match load_flags(&client).await {
+                Ok(loaded) => {
+                    debug!("Refreshed {} App Configuration feature flags", loaded.len());
+                    *flags.write().await = loaded;
+                    // Only remember the ETag once the reload succeeded, so failures are retried.
+                    sentinel_etag = etag;
+                }
+                Err(e) if e.is_retryable() => {
+                    warn!("Failed to refresh App Configuration feature flags: {e}")
+                }
+                Err(e) => error!("Failed to refresh App Configuration feature flags: {e}"),
+            }
+        }
+    })
+}
+
+fn find_flag<'a>(
+    flags: &'a HashMap<String, FeatureFlag>,
+    flag_key: &str,
+) -> EvaluationResult<&'a FeatureFlag> {
+    flags.get(flag_key).ok_or_else(|| {
+        EvaluationError::builder()
+            .code(EvaluationErrorCode::FlagNotFound)
+            .message(format!("Flag {flag_key} not found"))
+            .build()
+    })
+}
+
+fn type_mismatch(message: String) -> EvaluationError {
+    EvaluationError::builder()
+        .code(EvaluationErrorCode::TypeMismatch)
+        .message(message)
+        .build()
+}
@@ -0,0 +1,205 @@
+use std::collections::HashSet;
+
+use openfeature_contrib_common::HttpError;
+use reqwest::header::{HeaderMap, HeaderValue, AUTHORIZATION};
+use reqwest::Client;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use tracing::debug;
+
+use crate::event::Event;
+use crate::feature::{int64, Cache, Feature};
+use crate::{BucketeerError, BucketeerOptions};
+
+const SDK_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct GetFeatureFlagsRequest<'a> {
+    tag: &'a str,
+    feature_flags_id: &'a str,
+    requested_at: String,
+    sdk_version: &'static str,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct GetFeatureFlagsResponse {
+    #[serde(default)]
+    feature_flags_id: String,
+    #[serde(default)]
+    features: Vec<Feature>,
+    #[serde(default)]
+    archived_feature_flag_ids: Vec<String>,
+    #[serde(default, deserialize_with = "int64")]
+    requested_at: i64,
+    #[serde(default)]
+    force_update: bool,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct GetSegmentUsersRequest {
+    segment_ids: Vec<String>,
+    requested_at: String,
+    sdk_version: &'static str,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct GetSegmentUsersResponse {
+    #[serde(default)]
+    segment_users: Vec<SegmentUsers>,
+    #[serde(default)]
+    deleted_segment_ids: Vec<String>,
+    #[serde(default, deserialize_with = "int64")]
+    requested_at: i64,
+    #[serde(default)]
+    force_update: bool,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SegmentUsers {
+    segment_id: String,
+    #[serde(default)]
+    users: Vec<SegmentUser>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SegmentUser {
+    user_id: String,
+    #[serde(default)]
+    state: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+struct RegisterEventsRequest<'a> {
+    events: &'a [Event],
+}
+
+/// Thin HTTP client for the Bucketeer API gateway.
+pub(crate) struct BucketeerClient {
+    http: Client,
+    url: String,
+    tag: String,
+}
+
+impl BucketeerClient {
+    pub(crate) fn new(options: &BucketeerOptions) -> Result<Self, BucketeerError> {
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            AUTHORIZATION,
+            HeaderValue::from_str(&options.api_key).map_err(|_| BucketeerError::InvalidApiKey)?,
+        );
+        let http = Client::builder()
+            .default_headers(headers)
+            .timeout(options.request_timeout)
+            .build()?;
+
+        Ok(Self {
+            http,
+            url: options.api_endpoint.trim_end_matches('/').to_string(),
+            tag: options.tag.clone(),
+        })
+    }
+
+    /// Brings the cached features and segment users up to date.
+    pub(crate) async fn refresh(&self, cache: &mut Cache) -> Result<(), BucketeerError> {
+        let response: GetFeatureFlagsResponse = self
+            .post(
+                "get_feature_flags",
+                &GetFeatureFlagsRequest {
+                    tag: &self.tag,
+                    feature_flags_id: &cache.feature_flags_id,
+                    requested_at: cache.requested_at.to_string(),
+                    sdk_version: SDK_VERSION,
+                },
+            )
+            .await?;
+        if response.force_update {
+            cache.features.clear();
+        }
+        for id in &response.archived_feature_flag_ids {
+            cache.features.remove(id);
+        }
+        debug!("Received {} Bucketeer features", response.features.len());
+        cache.features.extend(
+            response
+                .features
+                .into_iter()
+                .map(|feature| (feature.id.clone(), feature)),
+        );
+        cache.feature_flags_id = response.feature_flags_id;
+        cache.requested_at = response.requested_at;
+
+        let segment_ids = cache.segment_ids();
+        if segment_ids.is_empty() {
+            cache.segments.clear();
+            return Ok(());
+        }
+        let response: GetSegmentUsersResponse = self
+            .post(
+                "get_segment_users",
+                &GetSegmentUsersRequest {
+                    segment_ids: segment_ids.iter().cloned().collect(),
+                    requested_at: cache.segments_requested_at.to_string(),
+                    sdk_version: SDK_VERSION,
+                },
+            )
+            .await?;
+        if response.force_update {
+            cache.segments.clear();
+        }
+        for id in &response.deleted_segment_ids {
+            cache.segments.remove(id);
+        }
+        for segment in response.segment_users {
+            let users: HashSet<String> = segment
+                .users
+                .into_iter()
+                .filter(|user| {
+                    user.state
+                        .as_deref()
+                        .map_or(true, |state| state == "INCLUDED")
+                })
+                .map(|user| user.user_id)
+                .collect();
+            cache.segments.insert(segment.segment_id, users);
+        }
+        cache.segments.retain(|id, _| segment_ids.contains(id));
+        cache.segments_requested_at = response.requested_at;
+
+        Ok(())
+    }
+
+    pub(crate) async fn register_events(&self, events: &[Event]) -> Result<(), BucketeerError> {
+        let url = format!("{}/register_events", self.url);
+        let response = self
+            .http
+            .post(&url)
+            .json(&RegisterEventsRequest { events })
+            .send()
+            .await?;
+
+        match response.status() {
+            status if status.is_success() => Ok(()),
+            _ => Err(HttpError::status_of(&response).into()),
+        }
+    }
+
+    async fn post<T: DeserializeOwned>(
+        &self,
+        path: &str,
+        body: &impl Serialize,
+    ) -> Result<T, BucketeerError> {
+        let url = format!("{}/{path}", self.url);
+        let response = self.http.post(&url).json(body).send().await?;
+
+        match response.status() {
+            status if status.is_success() => Ok(response.json().await?),
+            _ => Err(HttpError::status_of(&response).into()),
+        }
+    }
+}
@@ -0,0 +1,18 @@
+use open_feature::EvaluationContext;
+use openfeature_contrib_common::{fields_to_strings, DateTimeFormat};
+
+use crate::feature::User;
+
+/// Converts an [`EvaluationContext`] into a Bucketeer user.
+///
+/// The targeting key is the user id. Custom fields become user attributes, which Bucketeer
+/// stores as strings: date-times are sent as Unix seconds so `BEFORE`/`AFTER` clauses apply.
+/// `StructValue` fields are sent as JSON, other struct fields are skipped.
+pub(crate) fn to_user(context: &EvaluationContext) -> Option<User<'_>> {
+    let data = fields_to_strings(context, DateTimeFormat::UnixSeconds);
+
+    Some(User {
+        id: context.targeting_key.as_deref()?,
+        data,
+    })
+}
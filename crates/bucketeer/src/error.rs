@@ -0,0 +1,35 @@
+use openfeature_contrib_common::HttpError;
+use thiserror::Error;
+
+/// Errors raised while creating or refreshing a [`BucketeerProvider`](crate::BucketeerProvider).
+#[derive(Debug, Error)]
+pub enum BucketeerError {
+    /// An interval of the options is zero.
+    #[error("Bucketeer {0} must not be zero")]
+    ZeroInterval(&'static str),
+
+    /// The API key cannot be sent as a header value.
+    #[error("invalid Bucketeer API key")]
+    InvalidApiKey,
+
+    /// The HTTP request to Bucketeer failed.
+    #[error("request to Bucketeer failed: {0}")]
+    Http(#[from] HttpError),
+}
+
+impl From<reqwest::Error> for BucketeerError {
+    fn from(error: reqwest::Error) -> Self {
+        Self::Http(error.into())
+    }
+}
+
+impl BucketeerError {
+    /// Whether the request may succeed when sent again. Requests Bucketeer rejects, e.g. for an
+    /// unknown API key, fail the same way every time.
+    pub fn is_retryable(&self) -> bool {
+        match self {
+            Self::ZeroInterval(_) | Self::InvalidApiKey => false,
+            Self::Http(e) => e.is_retryable(),
+        }
+    }
+}
@@ -0,0 +1,236 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+
+use serde::Serialize;
+use time::OffsetDateTime;
+use tokio::sync::mpsc::{self, UnboundedReceiver, UnboundedSender};
+use tokio::task::JoinHandle;
+use tracing::warn;
+
+use crate::client::BucketeerClient;
+use crate::feature::Evaluation;
+
+const SDK_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+/// Event type of goal events in the `register_events` API.
+const GOAL_EVENT: u8 = 1;
+/// Event type of evaluation events in the `register_events` API.
+const EVALUATION_EVENT: u8 = 2;
+
+/// An event as sent to the `register_events` API.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct Event {
+    id: String,
+    event: serde_json::Value,
+    environment_namespace: &'static str,
+    #[serde(rename = "type")]
+    kind: u8,
+}
+
+#[derive(Debug, Serialize)]
+pub(crate) struct EventUser<'a> {
+    pub(crate) id: &'a str,
+    pub(crate) data: &'a HashMap<String, String>,
+}
+
+impl Event {
+    pub(crate) fn evaluation(user: &EventUser<'_>, tag: &str, evaluation: &Evaluation<'_>) -> Self {
+        Self::new(
+            EVALUATION_EVENT,
+            serde_json::json!({
+                "@type": "type.googleapis.com/bucketeer.event.client.EvaluationEvent",
+                "timestamp": OffsetDateTime::now_utc().unix_timestamp(),
+                "featureId": evaluation.feature.id,
+                "featureVersion": evaluation.feature.version,
+                "userId": user.id,
+                "variationId": evaluation.variation.id,
+                "user": user,
+                "reason": { "type": evaluation.reason.as_str() },
+                "tag": tag,
+                "sdkVersion": SDK_VERSION,
+            }),
+        )
+    }
+
+    pub(crate) fn goal(user: &EventUser<'_>, tag: &str, goal_id: &str, value: f64) -> Self {
+        Self::new(
+            GOAL_EVENT,
+            serde_json::json!({
+                "@type": "type.googleapis.com/bucketeer.event.client.GoalEvent",
+                "timestamp": OffsetDateTime::now_utc().unix_timestamp(),
+                "goalId": goal_id,
+                "userId": user.id,
+                "value": value,
+                "user": user,
+                "tag": tag,
+                "sdkVersion": SDK_VERSION,
+            }),
+        )
+    }
+
+    fn new(kind: u8, event: serde_json::Value) -> Self {
+        Self {
+            id: uuid::Uuid::new_v4().to_string(),
+            event,
+            environment_namespace: "",
+            kind,
+        }
+    }
+}
+
+/// Queues events and registers them in batches.
+pub(crate) fn spawn_event_sender(
+    client: Arc<BucketeerClient>,
+    flush_interval: Duration,
+    batch_size: usize,
+) -> (UnboundedSender<Event>, JoinHandle<()>) {
+    let (sender, receiver) = mpsc::unbounded_channel();
+    let task = tokio::spawn(send_events(client, receiver, flush_interval, batch_size));
+    (sender, task)
+}
+
+async fn send_events(
+    client: Arc<BucketeerClient>,
+    mut receiver: UnboundedReceiver<Event>,
+    flush_interval: Duration,
+    batch_size: usize,
+) {
+    let mut events = Vec::with_capacity(batch_size);
+    let mut interval = tokio::time::interval(flush_interval);
+    loop {
+        let closed = tokio::select! {
+            event = receiver.recv() => match event {
+                Some(event) => {
+                    events.push(event);
+                    if events.len() < batch_size {
+                        continue;
+                    }
+                    false
+                }
+                None => true,
+            },
+            _ = interval.tick() => false,
+        };
+
+        if !events.is_empty() {
+            if let Err(e) = client.register_events(&events).await {
+                warn!("Failed to register {} Bucketeer events: {e}", events.len());
+            }
+            events.clear();
+        }
+        if closed {
+            return;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use wiremock::matchers::{header, method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    use super::*;
+    use crate::BucketeerOptions;
+
+    fn client(server: &MockServer) -> Arc<BucketeerClient> {
+        let options = BucketeerOptions {
+            api_endpoint: server.uri(),
+            api_key: "server-api-key".to_string(),
+            tag: "backend".to_string(),
+            ..Default::default()
+        };
+        Arc::new(BucketeerClient::new(&options).unwrap())
+    }
+
+    fn goal(goal_id: &str) -> Event {
+        let data = HashMap::from([("plan".to_string(), "premium".to_string())]);
+        let user = EventUser {
+            id: "user-1",
+            data: &data,
+        };
+        Event::goal(&user, "backend", goal_id, 1.0)
+    }
+
+    /// The goal ids of the events of each registered batch.
+    async fn batches(server: &MockServer) -> Vec<Vec<String>> {
+        let requests = server.received_requests().await.unwrap();
+        requests
+            .iter()
+            .map(|request| {
+                let body: serde_json::Value = request.body_json().unwrap();
+                body["events"]
+                    .as_array()
+                    .unwrap()
+                    .iter()
+                    .map(|event| event["event"]["goalId"].as_str().unwrap().to_string())
+                    .collect()
+            })
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn events_are_registered_in_batches_and_flushed_on_close() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/register_events"))
+            .and(header("authorization", "server-api-key"))
+            .respond_with(ResponseTemplate::new(200))
+            .expect(2)
+            .mount(&server)
+            .await;
+
+        let (sender, task) = spawn_event_sender(client(&server), Duration::from_secs(3600), 2);
+        for goal_id in ["a", "b", "c"] {
+            sender.send(goal(goal_id)).unwrap();
+        }
+        drop(sender);
+        task.await.unwrap();
+
+        assert_eq!(batches(&server).await, vec![vec!["a", "b"], vec!["c"]]);
+    }
+
+    #[tokio::test]
+    async fn events_are_flushed_every_interval() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/register_events"))
+            .respond_with(ResponseTemplate::new(200))
+            .mount(&server)
+            .await;
+
+        let (sender, task) = spawn_event_sender(client(&server), Duration::from_millis(50), 100);
+        sender.send(goal("a")).unwrap();
+        tokio::time::timeout(Duration::from_secs(5), async {
+            while server.received_requests().await.unwrap().is_empty() {
+                tokio::time::sleep(Duration::from_millis(10)).await;
+            }
+        })
+        .await
+        .expect("the event was not flushed");
+        task.abort();
+        drop(sender);
+
+        assert_eq!(batches(&server).await, vec![vec!["a"]]);
+    }
+
+    #[tokio::test]
+    async fn failed_batches_are_dropped() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/register_events"))
+            .respond_with(ResponseTemplate::new(500))
+            .expect(2)
+            .mount(&server)
+            .await;
+
+        let (sender, task) = spawn_event_sender(client(&server), Duration::from_secs(3600), 1);
+        sender.send(goal("a")).unwrap();
+        sender.send(goal("b")).unwrap();
+        drop(sender);
+        task.await.unwrap();
+
+        assert_eq!(batches(&server).await, vec![vec!["a"], vec!["b"]]);
+    }
+}
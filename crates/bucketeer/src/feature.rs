@@ -0,0 +1,704 @@
+//! The feature flag cache of a Bucketeer server SDK and its local evaluation.
+//!
+//! Features are the proto-JSON `Feature` messages returned by the `get_feature_flags` API.
+//! Evaluation follows Bucketeer's evaluator: a disabled feature serves its off variation, then
+//! prerequisites, individual targets, rules and finally the default strategy are considered.
+//! Rollouts bucket users with the MD5 hash of `"{feature}-{user}{sampling seed}"`, like every
+//! Bucketeer SDK.
+
+use std::cmp::Ordering;
+use std::collections::{HashMap, HashSet};
+
+use md5::{Digest, Md5};
+use open_feature::EvaluationReason;
+use serde::{Deserialize, Deserializer};
+
+/// Limits prerequisite and `FEATURE_FLAG` clause chains, guarding against cycles.
+const MAX_DEPTH: usize = 10;
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct Feature {
+    pub(crate) id: String,
+    #[serde(default)]
+    pub(crate) version: i32,
+    #[serde(default)]
+    enabled: bool,
+    #[serde(default)]
+    off_variation: String,
+    #[serde(default)]
+    pub(crate) variation_type: VariationType,
+    #[serde(default)]
+    variations: Vec<Variation>,
+    #[serde(default)]
+    targets: Vec<Target>,
+    #[serde(default)]
+    rules: Vec<Rule>,
+    #[serde(default)]
+    default_strategy: Option<Strategy>,
+    #[serde(default)]
+    prerequisites: Vec<Prerequisite>,
+    #[serde(default)]
+    sampling_seed: String,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "UPPERCASE")]
+pub(crate) enum VariationType {
+    #[default]
+    String,
+    Boolean,
+    Number,
+    Json,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub(crate) struct Variation {
+    pub(crate) id: String,
+    #[serde(default)]
+    pub(crate) value: String,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct Target {
+    variation: String,
+    #[serde(default)]
+    users: Vec<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct Rule {
+    #[serde(default)]
+    id: String,
+    strategy: Strategy,
+    #[serde(default)]
+    clauses: Vec<Clause>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Strategy {
+    #[serde(rename = "type", default)]
+    kind: StrategyType,
+    #[serde(default)]
+    fixed_strategy: Option<FixedStrategy>,
+    #[serde(default)]
+    rollout_strategy: Option<RolloutStrategy>,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "UPPERCASE")]
+enum StrategyType {
+    #[default]
+    Fixed,
+    Rollout,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct FixedStrategy {
+    variation: String,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct RolloutStrategy {
+    #[serde(default)]
+    variations: Vec<WeightedVariation>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct WeightedVariation {
+    variation: String,
+    #[serde(default)]
+    weight: i32,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Prerequisite {
+    feature_id: String,
+    variation_id: String,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct Clause {
+    #[serde(default)]
+    attribute: String,
+    #[serde(default)]
+    operator: Operator,
+    #[serde(default)]
+    values: Vec<String>,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+enum Operator {
+    #[default]
+    Equals,
+    In,
+    EndsWith,
+    StartsWith,
+    Segment,
+    Greater,
+    GreaterOrEqual,
+    Less,
+    LessOrEqual,
+    Before,
+    After,
+    FeatureFlag,
+    PartiallyMatch,
+    #[serde(other)]
+    Unknown,
+}
+
+/// The user an evaluation is made for.
+#[derive(Debug)]
+pub(crate) struct User<'a> {
+    pub(crate) id: &'a str,
+    pub(crate) data: HashMap<String, String>,
+}
+
+/// Why a variation was served, as reported to Bucketeer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Reason {
+    Target,
+    Rule,
+    Default,
+    OffVariation,
+    Prerequisite,
+}
+
+impl Reason {
+    /// The `Reason.Type` name of the Bucketeer event API.
+    pub(crate) fn as_str(self) -> &'static str {
+        match self {
+            Reason::Target => "TARGET",
+            Reason::Rule => "RULE",
+            Reason::Default => "DEFAULT",
+            Reason::OffVariation => "OFF_VARIATION",
+            Reason::Prerequisite => "PREREQUISITE",
+        }
+    }
+}
+
+/// Outcome of evaluating a [`Feature`].
+#[derive(Debug)]
+pub(crate) struct Evaluation<'a> {
+    pub(crate) feature: &'a Feature,
+    pub(crate) variation: &'a Variation,
+    pub(crate) reason: Reason,
+    pub(crate) rule_id: Option<&'a str>,
+    rollout: bool,
+}
+
+impl Evaluation<'_> {
+    pub(crate) fn evaluation_reason(&self) -> EvaluationReason {
+        match self.reason {
+            _ if self.rollout => EvaluationReason::Split,
+            Reason::Target | Reason::Rule => EvaluationReason::TargetingMatch,
+            Reason::Default => EvaluationReason::Default,
+            Reason::OffVariation => EvaluationReason::Disabled,
+            Reason::Prerequisite => EvaluationReason::Other("PREREQUISITE".to_string()),
+        }
+    }
+}
+
+/// Why a feature could not be evaluated.
+#[derive(Debug)]
+pub(crate) enum EvaluationFailure {
+    NotFound(String),
+    MissingVariation(String),
+}
+
+/// The cached features and segment users of an environment.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct Cache {
+    pub(crate) features: HashMap<String, Feature>,
+    /// Included user ids, keyed by segment id.
+    pub(crate) segments: HashMap<String, HashSet<String>>,
+    pub(crate) feature_flags_id: String,
+    pub(crate) requested_at: i64,
+    pub(crate) segments_requested_at: i64,
+}
+
+impl Cache {
+    /// Ids of the segments referenced by `SEGMENT` clauses.
+    pub(crate) fn segment_ids(&self) -> HashSet<String> {
+        self.features
+            .values()
+            .flat_map(|feature| &feature.rules)
+            .flat_map(|rule| &rule.clauses)
+            .filter(|clause| clause.operator == Operator::Segment)
+            .flat_map(|clause| clause.values.iter().cloned())
+            .collect()
+    }
+
+    pub(crate) fn evaluate<'a>(
+        &'a self,
+        feature_id: &str,
+        user: &User<'_>,
+    ) -> Result<Evaluation<'a>, EvaluationFailure> {
+        self.evaluate_at(feature_id, user, 0)
+    }
+
+    fn evaluate_at<'a>(
+        &'a self,
+        feature_id: &str,
+        user: &User<'_>,
+        depth: usize,
+    ) -> Result<Evaluation<'a>, EvaluationFailure> {
+        let feature = self
+            .features
+            .get(feature_id)
+            .filter(|_| depth <= MAX_DEPTH)
+            .ok_or_else(|| EvaluationFailure::NotFound(feature_id.to_string()))?;
+        let evaluation = |variation_id: &str, reason, rule_id, rollout| {
+            let variation = feature
+                .variations
+                .iter()
+                .find(|variation| variation.id == variation_id)
+                .ok_or_else(|| EvaluationFailure::MissingVariation(variation_id.to_string()))?;
+            Ok(Evaluation {
+                feature,
+                variation,
+                reason,
+                rule_id,
+                rollout,
+            })
+        };
+
+        if !feature.enabled {
+            return evaluation(&feature.off_variation, Reason::OffVariation, None, false);
+        }
+
+        for prerequisite in &feature.prerequisites {
+            let matched = self
+                .evaluate_at(&prerequisite.feature_id, user, depth + 1)
+                .is_ok_and(|evaluation| evaluation.variation.id == prerequisite.variation_id);
+            if !matched {
+                return evaluation(&feature.off_variation, Reason::Prerequisite, None, false);
+            }
+        }
+
+        if let Some(target) = feature
+            .targets
+            .iter()
+            .find(|target| target.users.iter().any(|id| id == user.id))
+        {
+            return evaluation(&target.variation, Reason::Target, None, false);
+        }
+
+        for rule in &feature.rules {
+            if rule
+                .clauses
+                .iter()
+                .all(|clause| self.matches(clause, user, depth))
+            {
+                let (variation, rollout) = feature.strategy_variation(&rule.strategy, user.id);
+                return evaluation(variation, Reason::Rule, Some(&rule.id), rollout);
+            }
+        }
+
+        match &feature.default_strategy {
+            Some(strategy) => {
+                let (variation, rollout) = feature.strategy_variation(strategy, user.id);
+                evaluation(variation, Reason::Default, None, rollout)
+            }
+            None => Err(EvaluationFailure::MissingVariation(String::new())),
+        }
+    }
+
+    fn matches(&self, clause: &Clause, user: &User<'_>, depth: usize) -> bool {
+        match clause.operator {
+            Operator::Segment => clause.values.iter().any(|segment| {
+                self.segments
+                    .get(segment)
+                    .is_some_and(|users| users.contains(user.id))
+            }),
+            Operator::FeatureFlag => self
+                .evaluate_at(&clause.attribute, user, depth + 1)
+                .is_ok_and(|evaluation| clause.values.contains(&evaluation.variation.id)),
+            operator => user
+                .data
+                .get(&clause.attribute)
+                .is_some_and(|value| matches_value(operator, value, &clause.values)),
+        }
+    }
+}
+
+impl Feature {
+    /// Selects the variation of a strategy, reporting whether it was a rollout.
+    fn strategy_variation<'a>(&'a self, strategy: &'a Strategy, user_id: &str) -> (&'a str, bool) {
+        match (
+            strategy.kind,
+            &strategy.rollout_strategy,
+            &strategy.fixed_strategy,
+        ) {
+            (StrategyType::Rollout, Some(rollout), _) => {
+                let bucket = bucket(&self.id, user_id, &self.sampling_seed);
+                let mut sum = 0.0;
+                for variation in &rollout.variations {
+                    sum += f64::from(variation.weight) / 100_000.0;
+                    if bucket < sum {
+                        return (&variation.variation, true);
+                    }
+                }
+                ("", true)
+            }
+            (_, _, Some(fixed)) => (&fixed.variation, false),
+            _ => ("", false),
+        }
+    }
+}
+
+fn matches_value(operator: Operator, value: &str, expected: &[String]) -> bool {
+    let mut expected = expected.iter().map(String::as_str);
+    match operator {
+        Operator::Equals | Operator::In => expected.any(|expected| value == expected),
+        Operator::StartsWith => expected.any(|expected| value.starts_with(expected)),
+        Operator::EndsWith => expected.any(|expected| value.ends_with(expected)),
+        Operator::PartiallyMatch => expected.any(|expected| value.contains(expected)),
+        Operator::Greater => expected.any(|expected| compare(value, expected).is_gt()),
+        Operator::GreaterOrEqual => expected.any(|expected| compare(value, expected).is_ge()),
+        Operator::Less => expected.any(|expected| compare(value, expected).is_lt()),
+        Operator::LessOrEqual => expected.any(|expected| compare(value, expected).is_le()),
+        // Timestamps are compared as Unix seconds.
+        Operator::Before => expected.any(|expected| {
+            matches!((value.parse::<i64>(), expected.parse::<i64>()), (Ok(v), Ok(e)) if v < e)
+        }),
+        Operator::After => expected.any(|expected| {
+            matches!((value.parse::<i64>(), expected.parse::<i64>()), (Ok(v), Ok(e)) if v > e)
+        }),
+        Operator::Segment | Operator::FeatureFlag | Operator::Unknown => false,
+    }
+}
+
+/// Compares numerically when both sides are numbers, lexicographically otherwise.
+fn compare(value: &str, expected: &str) -> Ordering {
+    match (value.parse::<f64>(), expected.parse::<f64>()) {
+        (Ok(value), Ok(expected)) => value.partial_cmp(&expected).unwrap_or(Ordering::Equal),
+        _ => value.cmp(expected),
+    }
+}
+
+/// Maps a user onto `[0, 1)` for a feature.
+fn bucket(feature_id: &str, user_id: &str, sampling_seed: &str) -> f64 {
+    let hash = Md5::digest(format!("{feature_id}-{user_id}{sampling_seed}").as_bytes());
+    let mut prefix = [0; 8];
+    prefix.copy_from_slice(&hash[..8]);
+    u64::from_be_bytes(prefix) as f64 / u64::MAX as f64
+}
+
+/// Reads a proto-JSON `int64`, which may be encoded as a string.
+pub(crate) fn int64<'de, D: Deserializer<'de>>(deserializer: D) -> Result<i64, D::Error> {
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Int64 {
+        Number(i64),
+        String(String),
+    }
+
+    match Int64::deserialize(deserializer)? {
+        Int64::Number(value) => Ok(value),
+        Int64::String(value) => value.parse().map_err(serde::de::Error::custom),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn cache(features: serde_json::Value) -> Cache {
+        let features: Vec<Feature> = serde_json::from_value(features).expect("invalid features");
+        Cache {
+            features: features
+                .into_iter()
+                .map(|feature| (feature.id.clone(), feature))
+                .collect(),
+            ..Default::default()
+        }
+    }
+
+    /// A boolean feature serving `on` by default, with the given extra fields.
+    fn feature(id: &str, fields: serde_json::Value) -> serde_json::Value {
+        let mut feature = json!({
+            "id": id,
+            "version": 3,
+            "enabled": true,
+            "offVariation": "off",
+            "variationType": "BOOLEAN",
+            "variations": [{"id": "on", "value": "true"}, {"id": "off", "value": "false"}],
+            "defaultStrategy": {"type": "FIXED", "fixedStrategy": {"variation": "on"}}
+        });
+        let object = feature.as_object_mut().unwrap();
+        object.extend(fields.as_object().unwrap().clone());
+        feature
+    }
+
+    fn user<'a>(id: &'a str, data: &[(&str, &str)]) -> User<'a> {
+        User {
+            id,
+            data: data
+                .iter()
+                .map(|(key, value)| (key.to_string(), value.to_string()))
+                .collect(),
+        }
+    }
+
+    fn evaluate(cache: &Cache, feature_id: &str, user: &User<'_>) -> (String, Reason) {
+        let evaluation = cache
+            .evaluate(feature_id, user)
+            .unwrap_or_else(|e| panic!("evaluating {feature_id} failed: {e:?}"));
+        (evaluation.variation.id.clone(), evaluation.reason)
+    }
+
+    fn rule(clauses: serde_json::Value) -> serde_json::Value {
+        json!({"rules": [{
+            "id": "rule-1",
+            "strategy": {"type": "FIXED", "fixedStrategy": {"variation": "off"}},
+            "clauses": clauses
+        }]})
+    }
+
+    #[test]
+    fn buckets_match_the_bucketeer_sdks() {
+        assert!((bucket("feature", "user-1", "") - 0.841_714_577_856_368_2).abs() < 1e-12);
+        assert!((bucket("feature", "user-4", "") - 0.305_009_539_740_516_4).abs() < 1e-12);
+        assert!((bucket("feature", "user-2", "seed") - 0.122_355_722_213_948_64).abs() < 1e-12);
+    }
+
+    #[test]
+    fn disabled_features_serve_their_off_variation() {
+        let cache = cache(json!([feature("beta", json!({"enabled": false}))]));
+
+        assert_eq!(
+            evaluate(&cache, "beta", &user("user-1", &[])),
+            ("off".to_string(), Reason::OffVariation)
+        );
+    }
+
+    #[test]
+    fn unmet_prerequisites_serve_the_off_variation() {
+        let cache = cache(json!([
+            feature("parent", json!({})),
+            feature("disabled-parent", json!({"enabled": false})),
+            feature(
+                "child",
+                json!({
+                    "prerequisites": [{"featureId": "parent", "variationId": "on"}]
+                })
+            ),
+            feature(
+                "orphan",
+                json!({
+                    "prerequisites": [{"featureId": "disabled-parent", "variationId": "on"}]
+                })
+            ),
+        ]));
+        let user = user("user-1", &[]);
+
+        assert_eq!(
+            evaluate(&cache, "child", &user),
+            ("on".to_string(), Reason::Default)
+        );
+        assert_eq!(
+            evaluate(&cache, "orphan", &user),
+            ("off".to_string(), Reason::Prerequisite)
+        );
+    }
+
+    #[test]
+    fn prerequisite_cycles_are_cut_off() {
+        let cache = cache(json!([
+            feature(
+                "a",
+                json!({"prerequisites": [{"featureId": "b", "variationId": "on"}]})
+            ),
+            feature(
+                "b",
+                json!({"prerequisites": [{"featureId": "a", "variationId": "on"}]})
+            ),
+        ]));
+
+        assert_eq!(
+            evaluate(&cache, "a", &user("user-1", &[])),
+            ("off".to_string(), Reason::Prerequisite)
+        );
+    }
+
+    #[test]
+    fn targeted_users_get_their_variation() {
+        let cache = cache(json!([feature(
+            "beta",
+            json!({"targets": [{"variation": "off", "users": ["user-1"]}]})
+        )]));
+
+        assert_eq!(
+            evaluate(&cache, "beta", &user("user-1", &[])),
+            ("off".to_string(), Reason::Target)
+        );
+        assert_eq!(
+            evaluate(&cache, "beta", &user("user-2", &[])),
+            ("on".to_string(), Reason::Default)
+        );
+    }
+
+    #[test]
+    fn rules_match_when_every_clause_does() {
+        let cache = cache(json!([feature(
+            "beta",
+            rule(json!([
+                {"attribute": "plan", "operator": "IN", "values": ["premium", "gold"]},
+                {"attribute": "email", "operator": "ENDS_WITH", "values": ["@example.com"]},
+                {"attribute": "age", "operator": "GREATER_OR_EQUAL", "values": ["18"]}
+            ]))
+        )]));
+        let matching = user(
+            "user-1",
+            &[("plan", "gold"), ("email", "a@example.com"), ("age", "18")],
+        );
+        let minor = user(
+            "user-1",
+            &[("plan", "gold"), ("email", "a@example.com"), ("age", "9")],
+        );
+
+        let evaluation = cache.evaluate("beta", &matching).unwrap();
+        assert_eq!(evaluation.variation.id, "off");
+        assert_eq!(evaluation.reason, Reason::Rule);
+        assert_eq!(evaluation.rule_id, Some("rule-1"));
+        assert_eq!(
+            evaluate(&cache, "beta", &minor),
+            ("on".to_string(), Reason::Default)
+        );
+        assert_eq!(
+            evaluate(&cache, "beta", &user("user-1", &[])),
+            ("on".to_string(), Reason::Default)
+        );
+    }
+
+    #[test]
+    fn clause_operators_compare_like_bucketeer() {
+        let expected = |values: &[&str]| values.iter().map(|v| v.to_string()).collect::<Vec<_>>();
+
+        assert!(matches_value(Operator::Equals, "a", &expected(&["a"])));
+        assert!(matches_value(
+            Operator::StartsWith,
+            "abc",
+            &expected(&["ab"])
+        ));
+        assert!(matches_value(
+            Operator::PartiallyMatch,
+            "abc",
+            &expected(&["b"])
+        ));
+        // Numbers compare numerically, other values lexicographically.
+        assert!(matches_value(Operator::Greater, "10", &expected(&["9"])));
+        assert!(!matches_value(Operator::Greater, "10a", &expected(&["9"])));
+        assert!(matches_value(Operator::Less, "1.5", &expected(&["2"])));
+        assert!(matches_value(Operator::Before, "100", &expected(&["200"])));
+        assert!(!matches_value(Operator::After, "soon", &expected(&["200"])));
+        assert!(!matches_value(Operator::Unknown, "a", &expected(&["a"])));
+    }
+
+    #[test]
+    fn segment_clauses_match_included_users() {
+        let mut cache = cache(json!([feature(
+            "beta",
+            rule(json!([{"operator": "SEGMENT", "values": ["beta-testers"]}]))
+        )]));
+        cache.segments.insert(
+            "beta-testers".to_string(),
+            HashSet::from(["user-1".to_string()]),
+        );
+
+        assert_eq!(
+            cache.segment_ids(),
+            HashSet::from(["beta-testers".to_string()])
+        );
+        assert_eq!(
+            evaluate(&cache, "beta", &user("user-1", &[])),
+            ("off".to_string(), Reason::Rule)
+        );
+        assert_eq!(
+            evaluate(&cache, "beta", &user("user-2", &[])),
+            ("on".to_string(), Reason::Default)
+        );
+    }
+
+    #[test]
+    fn feature_flag_clauses_match_the_variation_of_another_feature() {
+        let cache = cache(json!([
+            feature(
+                "parent",
+                json!({"targets": [{"variation": "off", "users": ["user-1"]}]})
+            ),
+            feature(
+                "child",
+                rule(json!([
+                    {"attribute": "parent", "operator": "FEATURE_FLAG", "values": ["off"]}
+                ]))
+            ),
+        ]));
+
+        assert_eq!(
+            evaluate(&cache, "child", &user("user-1", &[])),
+            ("off".to_string(), Reason::Rule)
+        );
+        assert_eq!(
+            evaluate(&cache, "child", &user("user-2", &[])),
+            ("on".to_string(), Reason::Default)
+        );
+    }
+
+    #[test]
+    fn rollouts_split_users_by_weight() {
+        // Without a seed user-4 buckets at 0.31 and user-1 at 0.84; with it user-2 at 0.12
+        // and user-3 at 0.94.
+        let rollout = json!({"defaultStrategy": {
+            "type": "ROLLOUT",
+            "rolloutStrategy": {"variations": [
+                {"variation": "on", "weight": 50000},
+                {"variation": "off", "weight": 50000}
+            ]}
+        }});
+        let mut seeded = rollout.clone();
+        seeded["samplingSeed"] = json!("seed");
+        let seeded_cache = cache(json!([feature("feature", seeded)]));
+        let cache = cache(json!([feature("feature", rollout)]));
+
+        let evaluation = cache.evaluate("feature", &user("user-4", &[])).unwrap();
+        assert_eq!(evaluation.variation.id, "on");
+        assert_eq!(evaluation.evaluation_reason(), EvaluationReason::Split);
+        assert_eq!(
+            evaluate(&cache, "feature", &user("user-1", &[])).0,
+            "off".to_string()
+        );
+        assert_eq!(
+            evaluate(&seeded_cache, "feature", &user("user-2", &[])).0,
+            "on".to_string()
+        );
+        assert_eq!(
+            evaluate(&seeded_cache, "feature", &user("user-3", &[])).0,
+            "off".to_string()
+        );
+    }
+
+    #[test]
+    fn missing_features_and_variations_fail() {
+        let cache = cache(json!([feature(
+            "beta",
+            json!({"defaultStrategy": {"type": "FIXED", "fixedStrategy": {"variation": "gone"}}})
+        )]));
+        let user = user("user-1", &[]);
+
+        assert!(matches!(
+            cache.evaluate("missing", &user),
+            Err(EvaluationFailure::NotFound(id)) if id == "missing"
+        ));
+        assert!(matches!(
+            cache.evaluate("beta", &user),
+            Err(EvaluationFailure::MissingVariation(id)) if id == "gone"
+        ));
+    }
+}
@@ -0,0 +1,325 @@
+//! [Bucketeer](https://bucketeer.io/) provider for OpenFeature.
+//!
+//! The provider keeps a local cache of the features and segment users of an environment,
+//! polled from the Bucketeer API gateway, and evaluates flags locally with the same targeting
+//! rules and bucketing as Bucketeer's server SDKs. Every evaluation is reported to Bucketeer as
+//! an evaluation event, and goals can be tracked with [`BucketeerProvider::track`], so
+//! experiments keep their exposure and conversion data.
+//!
+//! # Context mapping
+//!
+//! The targeting key is the Bucketeer user id and is required. Custom fields become user
+//! attributes, used by rule clauses: numbers and booleans are formatted as strings and
+//! date-times as Unix seconds, `StructValue` fields as JSON. Other struct fields are skipped.
+//!
+//! # Example
+//!
+//! ```rust,no_run
+//! use open_feature::provider::FeatureProvider;
+//! use open_feature::EvaluationContext;
+//! use open_feature_bucketeer::{BucketeerOptions, BucketeerProvider};
+//!
+//! #[tokio::main]
+//! async fn main() {
+//!     let provider = BucketeerProvider::new(BucketeerOptions {
+//!         api_endpoint: "https://api.example.bucketeer.io".to_string(),
+//!         api_key: "server-api-key".to_string(),
+//!         tag: "backend".to_string(),
+//!         ..Default::default()
+//!     })
+//!     .await
+//!     .expect("Failed to create Bucketeer provider");
+//!
+//!     let context = EvaluationContext::default()
+//!         .with_targeting_key("user-123")
+//!         .with_custom_field("plan", "premium");
+//!     let details = provider.resolve_bool_value("new-checkout", &context).await;
+//!     println!("{details:?}");
+//!
+//!     provider.track("purchase", &context, 42.0);
+//! }
+//! ```
+
+mod client;
+mod context;
+mod error;
+mod event;
+mod feature;
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use async_trait::async_trait;
+use open_feature::provider::{FeatureProvider, ProviderMetadata, ResolutionDetails};
+use open_feature::{
+    EvaluationContext, EvaluationError, EvaluationErrorCode, EvaluationResult, FlagMetadata,
+    StructValue, Value,
+};
+use tokio::sync::mpsc::UnboundedSender;
+use tokio::sync::RwLock;
+use tokio::task::JoinHandle;
+use tracing::{error, warn};
+
+use crate::client::BucketeerClient;
+use crate::context::to_user;
+pub use crate::error::BucketeerError;
+use crate::event::{spawn_event_sender, Event, EventUser};
+use crate::feature::{Cache, EvaluationFailure, Variation, VariationType};
+
+/// Configuration of the [`BucketeerProvider`].
+#[derive(Debug, Clone)]
+pub struct BucketeerOptions {
+    /// Base URL of the Bucketeer API gateway.
+    pub api_endpoint: String,
+    /// Server-side API key of the environment.
+    pub api_key: String,
+    /// Tag of the features to load.
+    pub tag: String,
+    /// Interval between feature cache refreshes.
+    pub polling_interval: Duration,
+    /// Interval between event flushes.
+    pub event_flush_interval: Duration,
+    /// Number of queued events that triggers a flush.
+    pub event_batch_size: usize,
+    /// Timeout of requests to Bucketeer.
+    pub request_timeout: Duration,
+}
+
+impl Default for BucketeerOptions {
+    fn default() -> Self {
+        Self {
+            api_endpoint: String::new(),
+            api_key: String::new(),
+            tag: String::new(),
+            polling_interval: Duration::from_secs(60),
+            event_flush_interval: Duration::from_secs(10),
+            event_batch_size: 100,
+            request_timeout: Duration::from_secs(10),
+        }
+    }
+}
+
+/// OpenFeature provider evaluating Bucketeer features locally.
+pub struct BucketeerProvider {
+    metadata: ProviderMetadata,
+    tag: String,
+    cache: Arc<RwLock<Cache>>,
+    events: UnboundedSender<Event>,
+    refresh: JoinHandle<()>,
+}
+
+impl BucketeerProvider {
+    /// Creates the provider, loading the feature cache and starting the background tasks.
+    ///
+    /// Fails with [`BucketeerError::ZeroInterval`] when the polling or event flush interval is
+    /// zero.
+    pub async fn new(options: BucketeerOptions) -> Result<Self, BucketeerError> {
+        if options.polling_interval.is_zero() {
+            return Err(BucketeerError::ZeroInterval("polling interval"));
+        }
+        if options.event_flush_interval.is_zero() {
+            return Err(BucketeerError::ZeroInterval("event flush interval"));
+        }
+        let client = Arc::new(BucketeerClient::new(&options)?);
+        let mut cache = Cache::default();
+        client.refresh(&mut cache).await?;
+
+        let shared = Arc::new(RwLock::new(cache.clone()));
+        let refresh = spawn_refresh(
+            client.clone(),
+            cache,
+            shared.clone(),
+            options.polling_interval,
+        );
+        // The sender task flushes the remaining events and exits once the provider is dropped.
+        let (events, _) = spawn_event_sender(
+            client,
+            options.event_flush_interval,
+            options.event_batch_size.max(1),
+        );
+
+        Ok(Self {
+            metadata: ProviderMetadata::new("bucketeer"),
+            tag: options.tag,
+            cache: shared,
+            events,
+            refresh,
+        })
+    }
+
+    /// Reports that the user of `context` reached a goal, e.g. a conversion with its value.
+    pub fn track(&self, goal_id: &str, context: &EvaluationContext, value: f64) {
+        let Some(user) = to_user(context) else {
+            warn!("Cannot track Bucketeer goal {goal_id} without a targeting key");
+            return;
+        };
+        let user = EventUser {
+            id: user.id,
+            data: &user.data,
+        };
+        let _ = self
+            .events
+            .send(Event::goal(&user, &self.tag, goal_id, value));
+    }
+
+    async fn resolve<T>(
+        &self,
+        flag_key: &str,
+        context: &EvaluationContext,
+        variation_type: VariationType,
+        parse: impl FnOnce(&Variation) -> Option<T>,
+    ) -> EvaluationResult<ResolutionDetails<T>> {
+        let user = to_user(context).ok_or_else(|| {
+            EvaluationError::builder()
+                .code(EvaluationErrorCode::TargetingKeyMissing)
+                .message("Bucketeer requires a targeting key as user id")
+                .build()
+        })?;
+
+        let cache = self.cache.read().await;
+        let evaluation = cache.evaluate(flag_key, &user).map_err(|e| match e {
+            EvaluationFailure::NotFound(id) => EvaluationError::builder()
+                .code(EvaluationErrorCode::FlagNotFound)
+                .message(format!("Feature {id} not found"))
+                .build(),
+            EvaluationFailure::MissingVariation(id) => EvaluationError::builder()
+                .code(EvaluationErrorCode::General(
+                    "Missing variation".to_string(),
+                ))
+                .message(format!("Feature {flag_key} has no variation {id:?}"))
+                .build(),
+        })?;
+
+        if evaluation.feature.variation_type != variation_type {
+            return Err(type_mismatch(format!(
+                "Feature {flag_key} has {:?} variations",
+                evaluation.feature.variation_type
+            )));
+        }
+        let value = parse(evaluation.variation).ok_or_else(|| {
+            type_mismatch(format!(
+                "Variation {} of feature {flag_key} has another type",
+                evaluation.variation.id
+            ))
+        })?;
+
+        let event_user = EventUser {
+            id: user.id,
+            data: &user.data,
+        };
+        let _ = self
+            .events
+            .send(Event::evaluation(&event_user, &self.tag, &evaluation));
+
+        let mut flag_metadata =
+            FlagMetadata::default().with_value("version", i64::from(evaluation.feature.version));
+        if let Some(rule_id) = evaluation.rule_id {
+            flag_metadata.add_value("ruleId", rule_id);
+        }
+
+        Ok(ResolutionDetails {
+            value,
+            variant: Some(evaluation.variation.id.clone()),
+            reason: Some(evaluation.evaluation_reason()),
+            flag_metadata: Some(flag_metadata),
+        })
+    }
+}
+
+impl Drop for BucketeerProvider {
+    fn drop(&mut self) {
+        self.refresh.abort();
+    }
+}
+
+#[async_trait]
+impl FeatureProvider for BucketeerProvider {
+    fn metadata(&self) -> &ProviderMetadata {
+        &self.metadata
+    }
+
+    async fn resolve_bool_value(
+        &self,
+        flag_key: &str,
+        context: &EvaluationContext,
+    ) -> EvaluationResult<ResolutionDetails<bool>> {
+        self.resolve(flag_key, context, VariationType::Boolean, |variation| {
+            variation.value.parse().ok()
+        })
+        .await
+    }
+
+    async fn resolve_int_value(
+        &self,
+        flag_key: &str,
+        context: &EvaluationContext,
+    ) -> EvaluationResult<ResolutionDetails<i64>> {
+        self.resolve(flag_key, context, VariationType::Number, |variation| {
+            variation.value.parse().ok()
+        })
+        .await
+    }
+
+    async fn resolve_float_value(
+        &self,
+        flag_key: &str,
+        context: &EvaluationContext,
+    ) -> EvaluationResult<ResolutionDetails<f64>> {
+        self.resolve(flag_key, context, VariationType::Number, |variation| {
+            variation.value.parse().ok()
+        })
+        .await
+    }
+
+    async fn resolve_string_value(
+        &self,
+        flag_key: &str,
+        context: &EvaluationContext,
+    ) -> EvaluationResult<ResolutionDetails<String>> {
+        self.resolve(flag_key, context, VariationType::String, |variation| {
+            Some(variation.value.clone())
+        })
+        .await
+    }
+
+    async fn resolve_struct_value(
+        &self,
+        flag_key: &str,
+        context: &EvaluationContext,
+    ) -> EvaluationResult<ResolutionDetails<StructValue>> {
+        self.resolve(flag_key, context, VariationType::Json, |variation| {
+            let json = serde_json::from_str::<serde_json::Value>(&variation.value).ok()?;
+            match Value::try_from(json).ok()? {
+                Value::Struct(value) => Some(value),
+                _ => None,
+            }
+        })
+        .await
+    }
+}
+
+fn spawn_refresh(
+    client: Arc<BucketeerClient>,
+    mut cache: Cache,
+    shared: Arc<RwLock<Cache>>,
+    interval: Duration,
+) -> JoinHandle<()> {
+    tokio::spawn(async move {
+        loop {
+            tokio::time::sleep(interval).await;
+            // Refresh a private copy so evaluations are not blocked by the requests.
+            match client.refresh(&mut cache).await {
+                Ok(()) => *shared.write().await = cache.clone(),
+                Err(e) if e.is_retryable() => warn!("Failed to refresh Bucketeer features: {e}"),
+                Err(e) => error!("Failed to refresh Bucketeer features: {e}"),
+            }
+        }
+    })
+}
+
+fn type_mismatch(message: String) -> EvaluationError {
+    EvaluationError::builder()
+        .code(EvaluationErrorCode::TypeMismatch)
+        .message(message)
+        .build()
+}
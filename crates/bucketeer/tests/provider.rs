@@ -0,0 +1,201 @@
+use std::time::Duration;
+
+use open_feature::provider::FeatureProvider;
+use open_feature::{EvaluationContext, EvaluationErrorCode, EvaluationReason, StructValue};
+use open_feature_bucketeer::{BucketeerError, BucketeerOptions, BucketeerProvider};
+use openfeature_contrib_common::HttpError;
+use serde_json::json;
+use wiremock::matchers::{body_partial_json, method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+async fn server() -> MockServer {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/get_feature_flags"))
+        .and(body_partial_json(json!({"tag": "backend"})))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "featureFlagsId": "flags-1",
+            "requestedAt": "1700000000",
+            "features": [
+                {
+                    "id": "new-checkout",
+                    "version": 2,
+                    "enabled": true,
+                    "offVariation": "off",
+                    "variationType": "BOOLEAN",
+                    "variations": [{"id": "on", "value": "true"}, {"id": "off", "value": "false"}],
+                    "rules": [{
+                        "id": "beta-testers",
+                        "strategy": {"type": "FIXED", "fixedStrategy": {"variation": "on"}},
+                        "clauses": [{"operator": "SEGMENT", "values": ["beta"]}]
+                    }],
+                    "defaultStrategy": {"type": "FIXED", "fixedStrategy": {"variation": "off"}}
+                },
+                {
+                    "id": "banner",
+                    "enabled": true,
+                    "variationType": "JSON",
+                    "variations": [{"id": "blue", "value": "{\"color\": \"blue\"}"}],
+                    "defaultStrategy": {"type": "FIXED", "fixedStrategy": {"variation": "blue"}}
+                }
+            ]
+        })))
+        .mount(&server)
+        .await;
+    Mock::given(method("POST"))
+        .and(path("/get_segment_users"))
+        .and(body_partial_json(json!({"segmentIds": ["beta"]})))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "segmentUsers": [{
+                "segmentId": "beta",
+                "users": [
+                    {"userId": "user-1", "state": "INCLUDED"},
+                    {"userId": "user-2", "state": "EXCLUDED"}
+                ]
+            }],
+            "requestedAt": "1700000000"
+        })))
+        .mount(&server)
+        .await;
+    Mock::given(method("POST"))
+        .and(path("/register_events"))
+        .respond_with(ResponseTemplate::new(200))
+        .mount(&server)
+        .await;
+    server
+}
+
+fn options(server: &MockServer) -> BucketeerOptions {
+    BucketeerOptions {
+        api_endpoint: server.uri(),
+        api_key: "server-api-key".to_string(),
+        tag: "backend".to_string(),
+        ..Default::default()
+    }
+}
+
+fn user(id: &str) -> EvaluationContext {
+    EvaluationContext::default().with_targeting_key(id)
+}
+
+#[tokio::test]
+async fn features_are_evaluated_locally() {
+    let server = server().await;
+    let provider = BucketeerProvider::new(options(&server)).await.unwrap();
+
+    let details = provider
+        .resolve_bool_value("new-checkout", &user("user-1"))
+        .await
+        .unwrap();
+    assert!(details.value);
+    assert_eq!(details.variant.as_deref(), Some("on"));
+    assert_eq!(details.reason, Some(EvaluationReason::TargetingMatch));
+    let metadata = details.flag_metadata.unwrap();
+    assert_eq!(metadata.values.len(), 2);
+
+    let details = provider
+        .resolve_bool_value("new-checkout", &user("user-2"))
+        .await
+        .unwrap();
+    assert!(!details.value);
+    assert_eq!(details.reason, Some(EvaluationReason::Default));
+
+    let details = provider
+        .resolve_struct_value("banner", &user("user-1"))
+        .await
+        .unwrap();
+    assert_eq!(
+        details.value,
+        StructValue::default().with_field("color", "blue")
+    );
+}
+
+#[tokio::test]
+async fn evaluations_are_reported_as_events() {
+    let server = server().await;
+    let provider = BucketeerProvider::new(options(&server)).await.unwrap();
+
+    provider
+        .resolve_bool_value("new-checkout", &user("user-1"))
+        .await
+        .unwrap();
+    provider.track("purchase", &user("user-1"), 42.0);
+    drop(provider);
+
+    let events = tokio::time::timeout(Duration::from_secs(5), async {
+        loop {
+            let requests = server.received_requests().await.unwrap();
+            if let Some(request) = requests
+                .iter()
+                .find(|request| request.url.path() == "/register_events")
+            {
+                return request.body_json::<serde_json::Value>().unwrap()["events"].clone();
+            }
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+    })
+    .await
+    .expect("no events were registered");
+
+    assert_eq!(events[0]["type"], 2);
+    assert_eq!(events[0]["event"]["featureId"], "new-checkout");
+    assert_eq!(events[0]["event"]["variationId"], "on");
+    assert_eq!(events[0]["event"]["reason"]["type"], "RULE");
+    assert_eq!(events[1]["type"], 1);
+    assert_eq!(events[1]["event"]["goalId"], "purchase");
+    assert_eq!(events[1]["event"]["value"], 42.0);
+}
+
+#[tokio::test]
+async fn evaluation_errors_are_reported() {
+    let server = server().await;
+    let provider = BucketeerProvider::new(options(&server)).await.unwrap();
+
+    let error = provider
+        .resolve_bool_value("new-checkout", &EvaluationContext::default())
+        .await
+        .unwrap_err();
+    assert_eq!(error.code, EvaluationErrorCode::TargetingKeyMissing);
+    let error = provider
+        .resolve_bool_value("missing", &user("user-1"))
+        .await
+        .unwrap_err();
+    assert_eq!(error.code, EvaluationErrorCode::FlagNotFound);
+    let error = provider
+        .resolve_string_value("new-checkout", &user("user-1"))
+        .await
+        .unwrap_err();
+    assert_eq!(error.code, EvaluationErrorCode::TypeMismatch);
+}
+
+#[tokio::test]
+async fn zero_intervals_are_rejected() {
+    let server = server().await;
+
+    let result = BucketeerProvider::new(BucketeerOptions {
+        event_flush_interval: Duration::ZERO,
+        ..options(&server)
+    })
+    .await;
+    assert!(matches!(result, Err(BucketeerError::ZeroInterval(_))));
+    let result = BucketeerProvider::new(BucketeerOptions {
+        polling_interval: Duration::ZERO,
+        ..options(&server)
+    })
+    .await;
+    assert!(matches!(result, Err(BucketeerError::ZeroInterval(_))));
+}
+
+#[tokio::test]
+async fn failing_gateways_fail_creation() {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .respond_with(ResponseTemplate::new(401))
+        .mount(&server)
+        .await;
+
+    let result = BucketeerProvider::new(options(&server)).await;
+    assert!(
+        matches!(result, Err(BucketeerError::Http(HttpError::Status { status, .. })) if status == 401)
+    );
+}
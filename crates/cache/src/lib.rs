@@ -0,0 +1,331 @@
+//! Caching provider decorator for OpenFeature.
+//!
+//! [`CachedProvider`] wraps any provider and caches its successful resolutions per flag key, type
+//! and evaluation context. Cached values are served with the `CACHED` reason until their
+//! [`CacheOptions::ttl`] expires; errors are never cached, nor are evaluations whose context holds
+//! a field without a JSON representation. When the cache holds
+//! [`CacheOptions::max_entries`], the oldest entry, which expires first, is dropped.
+//! Expired entries that are never read again can also be swept periodically, see
+//! [`CacheOptions::sweep_interval`].
+//!
+//! Providers that learn about flag changes can keep the cache fresh through a
+//! [`CacheInvalidator`], e.g. from a task listening to their change notifications.
+//!
+//! # Example
+//!
+//! ```rust
+//! use open_feature::provider::FeatureProvider;
+//! use open_feature::{EvaluationContext, EvaluationReason};
+//! use open_feature_cache::{CacheOptions, CachedProvider};
+//! use open_feature_in_memory::InMemoryProvider;
+//!
+//! #[tokio::main]
+//! async fn main() {
+//!     let flags = InMemoryProvider::new();
+//!     flags.set_flag("new-checkout", false, None, None);
+//!
+//!     let provider = CachedProvider::new(flags.clone(), CacheOptions::default());
+//!     let invalidator = provider.invalidator();
+//!     let mut changes = flags.subscribe();
+//!     tokio::spawn(async move {
+//!         while let Ok(change) = changes.recv().await {
+//!             for flag_key in change.flag_keys {
+//!                 invalidator.invalidate(&flag_key);
+//!             }
+//!         }
+//!     });
+//!
+//!     let context = EvaluationContext::default().with_targeting_key("user-123");
+//!     provider.resolve_bool_value("new-checkout", &context).await.unwrap();
+//!     let details = provider.resolve_bool_value("new-checkout", &context).await.unwrap();
+//!     assert_eq!(details.reason, Some(EvaluationReason::Cached));
+//! }
+//! ```
+
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
+use std::time::Duration;
+
+use async_trait::async_trait;
+use open_feature::provider::{
+    FeatureProvider, ProviderMetadata, ProviderStatus, ResolutionDetails,
+};
+use open_feature::{EvaluationContext, EvaluationReason, EvaluationResult, HookWrapper};
+use openfeature_contrib_common::{
+    canonical_context, impl_resolve_methods, BoundedMap, FlagValue, ResolutionKey, StoredResolution,
+};
+use tokio::runtime::Handle;
+use tokio::task::JoinHandle;
+use tokio::time::Instant;
+use tracing::warn;
+
+/// Configuration of the [`CachedProvider`].
+#[derive(Debug, Clone)]
+pub struct CacheOptions {
+    /// How long a resolution is served from the cache. Resolutions cached with a TTL too long
+    /// to represent, such as [`Duration::MAX`], never expire.
+    pub ttl: Duration,
+    /// Maximum number of cached resolutions. 0 disables caching.
+    pub max_entries: usize,
+    /// Interval between sweeps of the expired entries by a background task. Without sweeps,
+    /// expired entries are only dropped when read again or when the cache is full. Ignored when
+    /// the provider is created outside a Tokio runtime.
+    pub sweep_interval: Option<Duration>,
+}
+
+impl Default for CacheOptions {
+    fn default() -> Self {
+        Self {
+            ttl: Duration::from_secs(60),
+            max_entries: 10_000,
+            sweep_interval: None,
+        }
+    }
+}
+
+/// Counts of the entries dropped from the cache so far, invalidations aside.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CacheStats {
+    /// Entries dropped after their TTL expired.
+    pub expired: u64,
+    /// Unexpired entries dropped to make room for new ones.
+    pub evicted: u64,
+}
+
+#[derive(Debug, Default)]
+struct Evictions {
+    expired: AtomicU64,
+    evicted: AtomicU64,
+}
+
+#[derive(Debug)]
+struct Entry {
+    resolution: StoredResolution,
+    /// `None` when the TTL overflows the clock.
+    expires: Option<Instant>,
+}
+
+impl Entry {
+    fn is_expired(&self, now: Instant) -> bool {
+        self.expires.is_some_and(|expires| expires <= now)
+    }
+}
+
+#[derive(Debug)]
+struct CacheState {
+    entries: BoundedMap<ResolutionKey, Entry>,
+    /// Bumped by every invalidation, so evaluations started before it are not cached.
+    generation: u64,
+}
+
+type Cache = Arc<Mutex<CacheState>>;
+
+fn lock(cache: &Cache) -> MutexGuard<'_, CacheState> {
+    cache.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+fn remove_expired(cache: &mut CacheState, evictions: &Evictions) {
+    let now = Instant::now();
+    let len = cache.entries.len();
+    cache.entries.retain(|_, entry| !entry.is_expired(now));
+    evictions
+        .expired
+        .fetch_add((len - cache.entries.len()) as u64, Ordering::Relaxed);
+}
+
+/// Handle invalidating the cache of a [`CachedProvider`].
+#[derive(Debug, Clone)]
+pub struct CacheInvalidator {
+    cache: Cache,
+}
+
+impl CacheInvalidator {
+    /// Drops the cached resolutions of a flag, for every type and context. Evaluations in
+    /// flight are not cached either, as they may have read the flag before its change.
+    pub fn invalidate(&self, flag_key: &str) {
+        let mut cache = lock(&self.cache);
+        cache.entries.retain(|key, _| key.flag_key != flag_key);
+        cache.generation += 1;
+    }
+
+    /// Drops every cached resolution, and does not cache the evaluations in flight.
+    pub fn invalidate_all(&self) {
+        let mut cache = lock(&self.cache);
+        cache.entries.clear();
+        cache.generation += 1;
+    }
+}
+
+/// OpenFeature provider caching the resolutions of a wrapped provider.
+///
+/// The provider reports the metadata, status and hooks of the wrapped provider.
+pub struct CachedProvider<P> {
+    provider: P,
+    options: CacheOptions,
+    cache: Cache,
+    evictions: Arc<Evictions>,
+    sweeping: Option<JoinHandle<()>>,
+}
+
+impl<P: FeatureProvider> CachedProvider<P> {
+    /// Wraps `provider` with an empty cache.
+    ///
+    /// The sweeps of [`CacheOptions::sweep_interval`] run on the current Tokio runtime, whose
+    /// time driver must be enabled. Outside a runtime, expired entries are only dropped lazily.
+    pub fn new(provider: P, options: CacheOptions) -> Self {
+        let cache = Arc::new(Mutex::new(CacheState {
+            entries: BoundedMap::new(options.max_entries),
+            generation: 0,
+        }));
+        let evictions = Arc::<Evictions>::default();
+        let sweeping = options
+            .sweep_interval
+            .filter(|interval| !interval.is_zero())
+            .and_then(|interval| match Handle::try_current() {
+                Ok(runtime) => Some(spawn_sweeping(
+                    &runtime,
+                    cache.clone(),
+                    evictions.clone(),
+                    interval,
+                )),
+                Err(e) => {
+                    warn!("Not sweeping the cache, expired entries are dropped lazily: {e}");
+                    None
+                }
+            });
+
+        Self {
+            provider,
+            options,
+            cache,
+            evictions,
+            sweeping,
+        }
+    }
+
+    /// Counts of the entries dropped from the cache so far.
+    pub fn stats(&self) -> CacheStats {
+        CacheStats {
+            expired: self.evictions.expired.load(Ordering::Relaxed),
+            evicted: self.evictions.evicted.load(Ordering::Relaxed),
+        }
+    }
+
+    /// Returns a handle invalidating this provider's cache, e.g. from another task.
+    pub fn invalidator(&self) -> CacheInvalidator {
+        CacheInvalidator {
+            cache: self.cache.clone(),
+        }
+    }
+
+    /// Drops the cached resolutions of a flag, for every type and context.
+    pub fn invalidate(&self, flag_key: &str) {
+        self.invalidator().invalidate(flag_key);
+    }
+
+    /// Drops every cached resolution.
+    pub fn invalidate_all(&self) {
+        self.invalidator().invalidate_all();
+    }
+
+    async fn resolve<T: FlagValue>(
+        &self,
+        flag_key: &str,
+        context: &EvaluationContext,
+    ) -> EvaluationResult<ResolutionDetails<T>> {
+        let evaluate = T::resolve(&self.provider, flag_key, context);
+        if self.options.max_entries == 0 || self.options.ttl.is_zero() {
+            return evaluate.await;
+        }
+
+        // Contexts without a canonical form share no cached resolutions.
+        let Some(context) = canonical_context(context) else {
+            return evaluate.await;
+        };
+        let key = ResolutionKey::new::<T>(flag_key, context);
+        let generation = {
+            let mut cache = lock(&self.cache);
+            match cache.entries.get(&key) {
+                Some(entry) if !entry.is_expired(Instant::now()) => {
+                    if let Some(details) = entry.resolution.details(EvaluationReason::Cached) {
+                        return Ok(details);
+                    }
+                }
+                Some(_) => {
+                    cache.entries.remove(&key);
+                    self.evictions.expired.fetch_add(1, Ordering::Relaxed);
+                }
+                None => {}
+            }
+            cache.generation
+        };
+
+        let details = evaluate.await?;
+        let entry = Entry {
+            resolution: StoredResolution::new(&details),
+            expires: Instant::now().checked_add(self.options.ttl),
+        };
+        self.insert(key, entry, generation);
+        Ok(details)
+    }
+
+    fn insert(&self, key: ResolutionKey, entry: Entry, generation: u64) {
+        let mut cache = lock(&self.cache);
+        if cache.generation != generation {
+            return;
+        }
+        // The TTL is the same for every entry, so the oldest one expires first.
+        if let Some((_, dropped)) = cache.entries.insert(key, entry) {
+            let counter = if dropped.is_expired(Instant::now()) {
+                &self.evictions.expired
+            } else {
+                &self.evictions.evicted
+            };
+            counter.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+}
+
+impl<P> Drop for CachedProvider<P> {
+    fn drop(&mut self) {
+        if let Some(sweeping) = &self.sweeping {
+            sweeping.abort();
+        }
+    }
+}
+
+fn spawn_sweeping(
+    runtime: &Handle,
+    cache: Cache,
+    evictions: Arc<Evictions>,
+    interval: Duration,
+) -> JoinHandle<()> {
+    runtime.spawn(async move {
+        loop {
+            tokio::time::sleep(interval).await;
+            remove_expired(&mut lock(&cache), &evictions);
+        }
+    })
+}
+
+#[async_trait]
+impl<P: FeatureProvider> FeatureProvider for CachedProvider<P> {
+    async fn initialize(&mut self, context: &EvaluationContext) {
+        self.provider.initialize(context).await;
+        self.invalidate_all();
+    }
+
+    fn status(&self) -> ProviderStatus {
+        self.provider.status()
+    }
+
+    fn metadata(&self) -> &ProviderMetadata {
+        self.provider.metadata()
+    }
+
+    fn hooks(&self) -> &[HookWrapper] {
+        self.provider.hooks()
+    }
+
+    impl_resolve_methods!();
+}
@@ -0,0 +1,280 @@
+use std::time::Duration;
+
+use async_trait::async_trait;
+use open_feature::provider::{FeatureProvider, ProviderMetadata, ResolutionDetails};
+use open_feature::{EvaluationContext, EvaluationReason, EvaluationResult, LoggingHook};
+use open_feature_cache::{CacheOptions, CacheStats, CachedProvider};
+use open_feature_in_memory::InMemoryProvider;
+use openfeature_contrib_common::{impl_resolve_methods, FlagValue};
+
+fn options(max_entries: usize, sweep_interval: Option<Duration>) -> CacheOptions {
+    CacheOptions {
+        ttl: Duration::from_secs(10),
+        max_entries,
+        sweep_interval,
+    }
+}
+
+fn user(targeting_key: &str) -> EvaluationContext {
+    EvaluationContext::default().with_targeting_key(targeting_key)
+}
+
+fn flags() -> InMemoryProvider {
+    let flags = InMemoryProvider::new();
+    flags.set_flag("enabled", true, Some("on"), None);
+    flags
+}
+
+async fn reason<P: FeatureProvider>(
+    provider: &CachedProvider<P>,
+    context: &EvaluationContext,
+) -> Option<EvaluationReason> {
+    provider
+        .resolve_bool_value("enabled", context)
+        .await
+        .unwrap()
+        .reason
+}
+
+#[tokio::test(start_paused = true)]
+async fn serves_cached_resolutions_until_the_ttl_expires() {
+    let flags = flags();
+    let provider = CachedProvider::new(flags.clone(), options(10, None));
+    let context = &user("user-1");
+
+    assert_eq!(
+        reason(&provider, context).await,
+        Some(EvaluationReason::Static)
+    );
+    flags.set_flag("enabled", false, Some("off"), None);
+    let details = provider
+        .resolve_bool_value("enabled", context)
+        .await
+        .unwrap();
+    assert!(details.value);
+    assert_eq!(details.variant.as_deref(), Some("on"));
+    assert_eq!(details.reason, Some(EvaluationReason::Cached));
+
+    tokio::time::advance(Duration::from_secs(11)).await;
+    let details = provider
+        .resolve_bool_value("enabled", context)
+        .await
+        .unwrap();
+    assert!(!details.value);
+    assert_eq!(details.reason, Some(EvaluationReason::Static));
+}
+
+/// Provider answering a second after reading its flags.
+struct Slow(InMemoryProvider);
+
+impl Slow {
+    async fn resolve<T: FlagValue>(
+        &self,
+        flag_key: &str,
+        context: &EvaluationContext,
+    ) -> EvaluationResult<ResolutionDetails<T>> {
+        let details = T::resolve(&self.0, flag_key, context).await;
+        tokio::time::sleep(Duration::from_secs(1)).await;
+        details
+    }
+}
+
+#[async_trait]
+impl FeatureProvider for Slow {
+    fn metadata(&self) -> &ProviderMetadata {
+        self.0.metadata()
+    }
+
+    impl_resolve_methods!();
+}
+
+#[tokio::test(start_paused = true)]
+async fn counts_expired_entries_read_again() {
+    let provider = CachedProvider::new(flags(), options(10, None));
+    reason(&provider, &user("user-1")).await;
+
+    tokio::time::advance(Duration::from_secs(10)).await;
+    assert_eq!(
+        reason(&provider, &user("user-1")).await,
+        Some(EvaluationReason::Static)
+    );
+    assert_eq!(
+        provider.stats(),
+        CacheStats {
+            expired: 1,
+            evicted: 0
+        }
+    );
+    assert_eq!(
+        reason(&provider, &user("user-1")).await,
+        Some(EvaluationReason::Cached)
+    );
+}
+
+#[tokio::test(start_paused = true)]
+async fn ttls_overflowing_the_clock_never_expire() {
+    let provider = CachedProvider::new(
+        flags(),
+        CacheOptions {
+            ttl: Duration::MAX,
+            ..options(10, Some(Duration::from_secs(3_600)))
+        },
+    );
+    reason(&provider, &user("user-1")).await;
+
+    tokio::time::sleep(Duration::from_secs(365 * 24 * 3_600)).await;
+    assert_eq!(
+        reason(&provider, &user("user-1")).await,
+        Some(EvaluationReason::Cached)
+    );
+    assert_eq!(provider.stats(), CacheStats::default());
+}
+
+#[tokio::test(start_paused = true)]
+async fn evaluations_in_flight_during_an_invalidation_are_not_cached() {
+    let flags = flags();
+    let provider = CachedProvider::new(Slow(flags.clone()), options(10, None));
+    let context = &user("user-1");
+
+    let (details, ()) = tokio::join!(provider.resolve_bool_value("enabled", context), async {
+        tokio::time::sleep(Duration::from_millis(500)).await;
+        flags.set_flag("enabled", false, Some("off"), None);
+        provider.invalidate("enabled");
+    });
+    assert!(details.unwrap().value);
+
+    let details = provider
+        .resolve_bool_value("enabled", context)
+        .await
+        .unwrap();
+    assert!(!details.value);
+    assert_eq!(details.reason, Some(EvaluationReason::Static));
+    assert_eq!(
+        reason(&provider, context).await,
+        Some(EvaluationReason::Cached)
+    );
+}
+
+#[tokio::test(start_paused = true)]
+async fn sweeps_expired_entries_that_are_never_read_again() {
+    let provider = CachedProvider::new(flags(), options(10, Some(Duration::from_secs(1))));
+    for targeting_key in ["user-1", "user-2", "user-3"] {
+        reason(&provider, &user(targeting_key)).await;
+    }
+
+    tokio::time::sleep(Duration::from_millis(9_500)).await;
+    assert_eq!(provider.stats(), CacheStats::default());
+    tokio::time::sleep(Duration::from_secs(1)).await;
+    assert_eq!(
+        provider.stats(),
+        CacheStats {
+            expired: 3,
+            evicted: 0
+        }
+    );
+}
+
+#[tokio::test(start_paused = true)]
+async fn keeps_expired_entries_without_sweeps() {
+    let provider = CachedProvider::new(flags(), options(10, None));
+    reason(&provider, &user("user-1")).await;
+
+    tokio::time::sleep(Duration::from_secs(60)).await;
+    assert_eq!(provider.stats(), CacheStats::default());
+}
+
+#[tokio::test(start_paused = true)]
+async fn evicts_the_oldest_entry_when_full() {
+    let provider = CachedProvider::new(flags(), options(2, None));
+    reason(&provider, &user("user-1")).await;
+    tokio::time::advance(Duration::from_secs(1)).await;
+    reason(&provider, &user("user-2")).await;
+    tokio::time::advance(Duration::from_secs(1)).await;
+    reason(&provider, &user("user-3")).await;
+
+    assert_eq!(
+        provider.stats(),
+        CacheStats {
+            expired: 0,
+            evicted: 1
+        }
+    );
+    assert_eq!(
+        reason(&provider, &user("user-2")).await,
+        Some(EvaluationReason::Cached)
+    );
+    assert_eq!(
+        reason(&provider, &user("user-3")).await,
+        Some(EvaluationReason::Cached)
+    );
+    assert_eq!(
+        reason(&provider, &user("user-1")).await,
+        Some(EvaluationReason::Static)
+    );
+    assert_eq!(provider.stats().evicted, 2);
+}
+
+#[tokio::test(start_paused = true)]
+async fn drops_expired_entries_before_evicting() {
+    let provider = CachedProvider::new(flags(), options(2, None));
+    reason(&provider, &user("user-1")).await;
+    tokio::time::advance(Duration::from_secs(9)).await;
+    reason(&provider, &user("user-2")).await;
+    tokio::time::advance(Duration::from_secs(2)).await;
+    reason(&provider, &user("user-3")).await;
+
+    assert_eq!(
+        provider.stats(),
+        CacheStats {
+            expired: 1,
+            evicted: 0
+        }
+    );
+    assert_eq!(
+        reason(&provider, &user("user-2")).await,
+        Some(EvaluationReason::Cached)
+    );
+}
+
+#[tokio::test(start_paused = true)]
+async fn aborts_sweeping_when_dropped() {
+    let metrics = tokio::runtime::Handle::current().metrics();
+    let provider = CachedProvider::new(flags(), options(10, Some(Duration::from_secs(1))));
+    assert_eq!(metrics.num_alive_tasks(), 1);
+
+    drop(provider);
+    tokio::time::timeout(Duration::from_secs(5), async {
+        while metrics.num_alive_tasks() > 0 {
+            tokio::task::yield_now().await;
+        }
+    })
+    .await
+    .unwrap();
+}
+
+#[test]
+fn falls_back_to_lazy_eviction_outside_a_runtime() {
+    let provider = CachedProvider::new(flags(), options(10, Some(Duration::from_secs(1))));
+
+    let runtime = tokio::runtime::Builder::new_current_thread()
+        .enable_time()
+        .start_paused(true)
+        .build()
+        .unwrap();
+    runtime.block_on(async {
+        reason(&provider, &user("user-1")).await;
+        tokio::time::sleep(Duration::from_secs(60)).await;
+        assert_eq!(provider.stats(), CacheStats::default());
+        assert_eq!(
+            reason(&provider, &user("user-1")).await,
+            Some(EvaluationReason::Static)
+        );
+    });
+}
+
+#[test]
+fn reports_the_hooks_of_the_wrapped_provider() {
+    let flags = InMemoryProvider::new().with_hook(LoggingHook::default());
+    let provider = CachedProvider::new(flags, options(10, None));
+    assert_eq!(provider.hooks().len(), 1);
+}
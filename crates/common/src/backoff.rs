@@ -0,0 +1,112 @@
+use std::time::Duration;
+
+use rand::Rng;
+
+/// Exponential backoff between retries of a failing operation, such as reconnecting a stream.
+///
+/// The delay starts at `initial` and is multiplied by `multiplier` after every retry until it
+/// reaches `max`. Each delay is randomized by `jitter`, so clients that failed together do not
+/// retry together.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BackoffPolicy {
+    /// Delay before the first retry.
+    pub initial: Duration,
+    /// Upper bound of the delay.
+    pub max: Duration,
+    /// Factor applied to the delay after every retry. Values below 1 are treated as 1.
+    pub multiplier: f64,
+    /// Fraction of every delay that is randomized, between 0 and 1. With 0.2, a delay of 10s is
+    /// spread over 8s to 12s.
+    pub jitter: f64,
+}
+
+impl Default for BackoffPolicy {
+    fn default() -> Self {
+        Self {
+            initial: Duration::from_secs(1),
+            max: Duration::from_secs(60),
+            multiplier: 2.0,
+            jitter: 0.2,
+        }
+    }
+}
+
+impl BackoffPolicy {
+    /// Starts a sequence of delays following this policy.
+    pub fn backoff(&self) -> Backoff {
+        Backoff::new(self.clone())
+    }
+}
+
+/// Delays of consecutive retries following a [`BackoffPolicy`].
+///
+/// The sequence never ends; [`reset`](Self::reset) it once the operation succeeds.
+///
+/// ```rust
+/// use std::time::Duration;
+///
+/// use openfeature_contrib_common::BackoffPolicy;
+///
+/// let mut backoff = BackoffPolicy {
+///     initial: Duration::from_secs(1),
+///     max: Duration::from_secs(5),
+///     multiplier: 2.0,
+///     jitter: 0.0,
+/// }
+/// .backoff();
+///
+/// let delays: Vec<u64> = backoff.by_ref().take(5).map(|delay| delay.as_secs()).collect();
+/// assert_eq!(delays, [1, 2, 4, 5, 5]);
+///
+/// backoff.reset();
+/// assert_eq!(backoff.next_delay(), Duration::from_secs(1));
+/// ```
+#[derive(Debug, Clone)]
+pub struct Backoff {
+    policy: BackoffPolicy,
+    current: Duration,
+}
+
+impl Backoff {
+    /// Starts a sequence of delays following `policy`.
+    pub fn new(policy: BackoffPolicy) -> Self {
+        let current = policy.initial.min(policy.max);
+        Self { policy, current }
+    }
+
+    /// Returns the delay before the next retry.
+    pub fn next_delay(&mut self) -> Duration {
+        let delay = self.current;
+        self.current = Duration::try_from_secs_f64(
+            self.current.as_secs_f64() * self.policy.multiplier.max(1.0),
+        )
+        .map_or(self.policy.max, |next| next.min(self.policy.max));
+        jittered(delay, self.policy.jitter).min(self.policy.max)
+    }
+
+    /// Starts the sequence over, after the operation succeeded.
+    pub fn reset(&mut self) {
+        self.current = self.policy.initial.min(self.policy.max);
+    }
+}
+
+impl Iterator for Backoff {
+    type Item = Duration;
+
+    fn next(&mut self) -> Option<Duration> {
+        Some(self.next_delay())
+    }
+}
+
+fn jittered(delay: Duration, jitter: f64) -> Duration {
+    let jitter = if jitter.is_nan() {
+        0.0
+    } else {
+        jitter.clamp(0.0, 1.0)
+    };
+    if jitter == 0.0 {
+        return delay;
+    }
+    let factor = rand::thread_rng().gen_range(1.0 - jitter..=1.0 + jitter);
+    Duration::try_from_secs_f64(delay.as_secs_f64() * factor).unwrap_or(delay)
+}
@@ -0,0 +1,183 @@
+use std::collections::{BTreeMap, HashMap};
+use std::hash::Hash;
+
+/// Map holding at most a given number of entries, dropping the oldest ones to make room.
+///
+/// Entries are ordered by their last insertion: inserting a key again replaces its value and
+/// makes it the newest entry. Lookups do not change the order. Inserting and removing take
+/// logarithmic time, so decorators can bound their [`StoredResolution`](crate::StoredResolution)s
+/// without scanning them.
+///
+/// ```rust
+/// use openfeature_contrib_common::BoundedMap;
+///
+/// let mut map = BoundedMap::new(2);
+/// assert_eq!(map.insert("a", 1), None);
+/// assert_eq!(map.insert("b", 2), None);
+/// assert_eq!(map.insert("a", 3), None);
+/// assert_eq!(map.insert("c", 4), Some(("b", 2)));
+/// assert_eq!(map.get(&"a"), Some(&3));
+/// ```
+#[derive(Debug, Clone)]
+pub struct BoundedMap<K, V> {
+    capacity: usize,
+    entries: HashMap<K, (u64, V)>,
+    order: BTreeMap<u64, K>,
+    next: u64,
+}
+
+impl<K: Eq + Hash + Clone, V> BoundedMap<K, V> {
+    /// Empty map holding at most `capacity` entries. A capacity of 0 holds none.
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            entries: HashMap::new(),
+            order: BTreeMap::new(),
+            next: 0,
+        }
+    }
+
+    /// Maximum number of entries.
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Number of entries.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Whether the map holds no entry.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Value of `key`.
+    pub fn get(&self, key: &K) -> Option<&V> {
+        self.entries.get(key).map(|(_, value)| value)
+    }
+
+    /// Inserts `value` as the newest entry, replacing the value of `key`.
+    ///
+    /// Returns the oldest entry when it was dropped to make room, or the given entry itself when
+    /// the capacity is 0.
+    pub fn insert(&mut self, key: K, value: V) -> Option<(K, V)> {
+        if self.capacity == 0 {
+            return Some((key, value));
+        }
+        let dropped = match self.entries.remove(&key) {
+            Some((position, _)) => {
+                self.order.remove(&position);
+                None
+            }
+            None if self.entries.len() >= self.capacity => self.pop_oldest(),
+            None => None,
+        };
+        let position = self.next;
+        self.next += 1;
+        self.order.insert(position, key.clone());
+        self.entries.insert(key, (position, value));
+        dropped
+    }
+
+    /// Removes `key`, returning its value.
+    pub fn remove(&mut self, key: &K) -> Option<V> {
+        let (position, value) = self.entries.remove(key)?;
+        self.order.remove(&position);
+        Some(value)
+    }
+
+    /// Oldest entry, the next one dropped to make room.
+    pub fn oldest(&self) -> Option<(&K, &V)> {
+        let (_, key) = self.order.first_key_value()?;
+        self.entries
+            .get_key_value(key)
+            .map(|(key, (_, value))| (key, value))
+    }
+
+    /// Removes the oldest entry.
+    pub fn pop_oldest(&mut self) -> Option<(K, V)> {
+        let (_, key) = self.order.pop_first()?;
+        let (_, value) = self.entries.remove(&key)?;
+        Some((key, value))
+    }
+
+    /// Keeps the entries for which `keep` returns true.
+    pub fn retain(&mut self, mut keep: impl FnMut(&K, &V) -> bool) {
+        let order = &mut self.order;
+        self.entries.retain(|key, (position, value)| {
+            let kept = keep(key, value);
+            if !kept {
+                order.remove(position);
+            }
+            kept
+        });
+    }
+
+    /// Removes every entry.
+    pub fn clear(&mut self) {
+        self.entries.clear();
+        self.order.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn drops_the_oldest_entries_once_full() {
+        let mut map = BoundedMap::new(3);
+        for key in 0..3 {
+            assert_eq!(map.insert(key, key * 10), None);
+        }
+        assert_eq!(map.oldest(), Some((&0, &0)));
+
+        assert_eq!(map.insert(3, 30), Some((0, 0)));
+        assert_eq!(map.insert(4, 40), Some((1, 10)));
+        assert_eq!(map.len(), 3);
+        assert_eq!(map.get(&0), None);
+        assert_eq!(map.get(&4), Some(&40));
+    }
+
+    #[test]
+    fn inserting_a_key_again_makes_it_the_newest() {
+        let mut map = BoundedMap::new(2);
+        map.insert("a", 1);
+        map.insert("b", 2);
+        assert_eq!(map.insert("a", 3), None);
+
+        assert_eq!(map.len(), 2);
+        assert_eq!(map.insert("c", 4), Some(("b", 2)));
+        assert_eq!(map.pop_oldest(), Some(("a", 3)));
+        assert_eq!(map.pop_oldest(), Some(("c", 4)));
+        assert_eq!(map.pop_oldest(), None);
+    }
+
+    #[test]
+    fn removed_entries_leave_the_order() {
+        let mut map = BoundedMap::new(3);
+        for key in 0..3 {
+            map.insert(key, ());
+        }
+        assert_eq!(map.remove(&0), Some(()));
+        map.retain(|key, _| *key != 1);
+
+        assert_eq!(map.len(), 1);
+        assert_eq!(map.oldest(), Some((&2, &())));
+        assert_eq!(map.insert(3, ()), None);
+        assert_eq!(map.insert(4, ()), None);
+        assert_eq!(map.insert(5, ()), Some((2, ())));
+
+        map.clear();
+        assert!(map.is_empty());
+        assert_eq!(map.oldest(), None);
+    }
+
+    #[test]
+    fn a_zero_capacity_holds_nothing() {
+        let mut map = BoundedMap::new(0);
+        assert_eq!(map.insert("a", 1), Some(("a", 1)));
+        assert!(map.is_empty());
+    }
+}
@@ -0,0 +1,150 @@
+use open_feature::EvaluationErrorCode;
+
+/// Error code of an evaluation whose request was answered with the HTTP `status`.
+///
+/// | Status             | Code                          |
+/// |--------------------|-------------------------------|
+/// | 400                | `INVALID_CONTEXT`             |
+/// | 401, 403           | `GENERAL` (`Unauthorized`)    |
+/// | 404                | `FLAG_NOT_FOUND`              |
+/// | 408, 504           | `GENERAL` (`Timeout`)         |
+/// | 429                | `GENERAL` (`Rate limited`)    |
+/// | 502, 503           | `GENERAL` (`Unavailable`)     |
+/// | others             | `GENERAL` (`HTTP {status}`)   |
+///
+/// ```rust
+/// use open_feature::EvaluationErrorCode;
+/// use openfeature_contrib_common::http_error_code;
+///
+/// assert_eq!(http_error_code(404), EvaluationErrorCode::FlagNotFound);
+/// assert_eq!(
+///     http_error_code(429),
+///     EvaluationErrorCode::General("Rate limited".to_string())
+/// );
+/// ```
+pub fn http_error_code(status: u16) -> EvaluationErrorCode {
+    match status {
+        400 => EvaluationErrorCode::InvalidContext,
+        401 | 403 => EvaluationErrorCode::General("Unauthorized".to_string()),
+        404 => EvaluationErrorCode::FlagNotFound,
+        408 | 504 => timeout_error_code(),
+        429 => rate_limited_error_code(),
+        502 | 503 => EvaluationErrorCode::General("Unavailable".to_string()),
+        status => EvaluationErrorCode::General(format!("HTTP {status}")),
+    }
+}
+
+/// Error code of an evaluation whose call failed with the gRPC status `code`, as numbered by
+/// the gRPC specification.
+///
+/// | Code                                            | Evaluation error code      |
+/// |-------------------------------------------------|----------------------------|
+/// | `INVALID_ARGUMENT` (3)                          | `INVALID_CONTEXT`          |
+/// | `DEADLINE_EXCEEDED` (4)                         | `GENERAL` (`Timeout`)      |
+/// | `NOT_FOUND` (5)                                 | `FLAG_NOT_FOUND`           |
+/// | `PERMISSION_DENIED` (7), `UNAUTHENTICATED` (16) | `GENERAL` (`Unauthorized`) |
+/// | `RESOURCE_EXHAUSTED` (8)                        | `GENERAL` (`Rate limited`) |
+/// | `UNAVAILABLE` (14)                              | `GENERAL` (`Unavailable`)  |
+/// | `DATA_LOSS` (15)                                | `PARSE_ERROR`              |
+/// | others                                          | `GENERAL` (`gRPC {code}`)  |
+///
+/// ```rust
+/// use openfeature_contrib_common::{grpc_error_code, http_error_code};
+///
+/// // Identical failures get identical codes over HTTP and gRPC.
+/// assert_eq!(grpc_error_code(5), http_error_code(404));
+/// assert_eq!(grpc_error_code(14), http_error_code(503));
+/// ```
+pub fn grpc_error_code(code: i32) -> EvaluationErrorCode {
+    match code {
+        3 => EvaluationErrorCode::InvalidContext,
+        4 => timeout_error_code(),
+        5 => EvaluationErrorCode::FlagNotFound,
+        7 | 16 => EvaluationErrorCode::General("Unauthorized".to_string()),
+        8 => rate_limited_error_code(),
+        14 => EvaluationErrorCode::General("Unavailable".to_string()),
+        15 => EvaluationErrorCode::ParseError,
+        code => EvaluationErrorCode::General(format!("gRPC {code}")),
+    }
+}
+
+/// Error code of an evaluation that did not complete in time, the same as for a timed out
+/// request.
+///
+/// ```rust
+/// use openfeature_contrib_common::{http_error_code, timeout_error_code};
+///
+/// assert_eq!(timeout_error_code(), http_error_code(504));
+/// ```
+pub fn timeout_error_code() -> EvaluationErrorCode {
+    EvaluationErrorCode::General("Timeout".to_string())
+}
+
+/// Error code of an evaluation refused by a rate limit, the same as for a rate limited request.
+pub fn rate_limited_error_code() -> EvaluationErrorCode {
+    EvaluationErrorCode::General("Rate limited".to_string())
+}
+
+/// Name of an error code as reported by hooks and telemetry: the specification name, and
+/// `GENERAL` for general errors whatever their message.
+///
+/// ```rust
+/// use open_feature::EvaluationErrorCode;
+/// use openfeature_contrib_common::{error_code_name, timeout_error_code};
+///
+/// assert_eq!(error_code_name(&EvaluationErrorCode::FlagNotFound), "FLAG_NOT_FOUND");
+/// assert_eq!(error_code_name(&timeout_error_code()), "GENERAL");
+/// ```
+pub fn error_code_name(code: &EvaluationErrorCode) -> &'static str {
+    match code {
+        EvaluationErrorCode::ProviderNotReady => "PROVIDER_NOT_READY",
+        EvaluationErrorCode::FlagNotFound => "FLAG_NOT_FOUND",
+        EvaluationErrorCode::ParseError => "PARSE_ERROR",
+        EvaluationErrorCode::TypeMismatch => "TYPE_MISMATCH",
+        EvaluationErrorCode::TargetingKeyMissing => "TARGETING_KEY_MISSING",
+        EvaluationErrorCode::InvalidContext => "INVALID_CONTEXT",
+        EvaluationErrorCode::General(_) => "GENERAL",
+    }
+}
+
+/// Whether a request answered with the HTTP `status` may succeed when retried: timeouts, rate
+/// limiting and unavailable servers.
+///
+/// ```rust
+/// use openfeature_contrib_common::is_retryable_http_status;
+///
+/// assert!(is_retryable_http_status(503));
+/// assert!(!is_retryable_http_status(404));
+/// ```
+pub fn is_retryable_http_status(status: u16) -> bool {
+    matches!(status, 408 | 429 | 500 | 502 | 503 | 504)
+}
+
+/// Whether a call that failed with the gRPC status `code` may succeed when retried: timeouts,
+/// exhausted resources, aborted calls and unavailable servers.
+pub fn is_retryable_grpc_code(code: i32) -> bool {
+    matches!(code, 4 | 8 | 10 | 14)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn error_code_names_match_the_display_of_specified_codes() {
+        for code in [
+            EvaluationErrorCode::ProviderNotReady,
+            EvaluationErrorCode::FlagNotFound,
+            EvaluationErrorCode::ParseError,
+            EvaluationErrorCode::TypeMismatch,
+            EvaluationErrorCode::TargetingKeyMissing,
+            EvaluationErrorCode::InvalidContext,
+        ] {
+            assert_eq!(error_code_name(&code), code.to_string());
+        }
+        assert_eq!(
+            error_code_name(&EvaluationErrorCode::General("Unavailable".to_string())),
+            "GENERAL"
+        );
+    }
+}
@@ -0,0 +1,196 @@
+use std::error::Error;
+use std::fmt;
+
+use open_feature::EvaluationErrorCode;
+use reqwest::{Response, StatusCode};
+
+use crate::{http_error_code, is_retryable_http_status, timeout_error_code};
+
+/// Failure of an HTTP request to a flag backend or an event endpoint.
+///
+/// Providers and hooks wrap it in their own error type, so the same failure gets the same
+/// [`error_code`](Self::error_code) and retry decision everywhere.
+#[derive(Debug)]
+pub enum HttpError {
+    /// The request could not be sent or its response could not be read or decoded.
+    Request(reqwest::Error),
+    /// The server answered with an unexpected status.
+    Status {
+        /// Status of the response.
+        status: StatusCode,
+        /// URL of the request.
+        url: String,
+    },
+}
+
+impl HttpError {
+    /// The unexpected status of `response`.
+    pub fn status_of(response: &Response) -> Self {
+        Self::Status {
+            status: response.status(),
+            url: response.url().to_string(),
+        }
+    }
+
+    /// Status of the response, if the server answered.
+    pub fn status(&self) -> Option<StatusCode> {
+        match self {
+            Self::Request(e) => e.status(),
+            Self::Status { status, .. } => Some(*status),
+        }
+    }
+
+    /// Whether sending the request again may succeed: connection failures, timeouts and the
+    /// statuses of [`is_retryable_http_status`]. Requests that could not be built and responses
+    /// that could not be decoded fail the same way every time.
+    pub fn is_retryable(&self) -> bool {
+        match self {
+            Self::Request(e) if e.is_builder() || e.is_decode() => false,
+            _ => self
+                .status()
+                .map_or(true, |status| is_retryable_http_status(status.as_u16())),
+        }
+    }
+
+    /// Error code of an evaluation that failed with this error, see [`http_error_code`].
+    /// Timeouts get the `Timeout` code, undecodable responses `PARSE_ERROR` and connection
+    /// failures the `Unavailable` code of a 503.
+    pub fn error_code(&self) -> EvaluationErrorCode {
+        match self {
+            Self::Request(e) if e.is_timeout() => timeout_error_code(),
+            Self::Request(e) if e.is_decode() => EvaluationErrorCode::ParseError,
+            Self::Request(e) if e.is_connect() => http_error_code(503),
+            _ => match self.status() {
+                Some(status) => http_error_code(status.as_u16()),
+                None => EvaluationErrorCode::General(self.to_string()),
+            },
+        }
+    }
+}
+
+impl fmt::Display for HttpError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Request(e) => e.fmt(f),
+            Self::Status { status, url } => write!(f, "unexpected status {status} from {url}"),
+        }
+    }
+}
+
+impl Error for HttpError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            Self::Request(e) => Some(e),
+            Self::Status { .. } => None,
+        }
+    }
+}
+
+impl From<reqwest::Error> for HttpError {
+    fn from(error: reqwest::Error) -> Self {
+        Self::Request(error)
+    }
+}
+
+/// Returns `response` if its status is a success, and its [`HttpError::Status`] otherwise.
+pub fn check_status(response: Response) -> Result<Response, HttpError> {
+    if response.status().is_success() {
+        Ok(response)
+    } else {
+        Err(HttpError::status_of(&response))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use wiremock::matchers::path;
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    use super::*;
+
+    async fn request(server: &MockServer, status: u16) -> Result<Response, HttpError> {
+        Mock::given(path(format!("/{status}")))
+            .respond_with(ResponseTemplate::new(status).set_body_string("{"))
+            .mount(server)
+            .await;
+        let response = reqwest::get(format!("{}/{status}", server.uri())).await?;
+        check_status(response)
+    }
+
+    #[tokio::test]
+    async fn classifies_statuses() {
+        let server = MockServer::start().await;
+        assert!(request(&server, 200).await.is_ok());
+        assert!(request(&server, 204).await.is_ok());
+
+        for status in [400, 401, 403, 404, 413, 422] {
+            let error = request(&server, status).await.unwrap_err();
+            assert!(!error.is_retryable(), "{status}");
+            assert_eq!(error.error_code(), http_error_code(status), "{status}");
+        }
+        for status in [408, 429, 500, 502, 503, 504] {
+            let error = request(&server, status).await.unwrap_err();
+            assert!(error.is_retryable(), "{status}");
+            assert_eq!(error.status().map(|status| status.as_u16()), Some(status));
+        }
+
+        let error = request(&server, 503).await.unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            format!(
+                "unexpected status 503 Service Unavailable from {}/503",
+                server.uri()
+            )
+        );
+    }
+
+    #[tokio::test]
+    async fn undecodable_responses_are_not_retryable() {
+        let server = MockServer::start().await;
+        let response = request(&server, 200).await.unwrap();
+        let error = HttpError::from(response.json::<Vec<u8>>().await.unwrap_err());
+
+        assert!(!error.is_retryable());
+        assert_eq!(error.error_code(), EvaluationErrorCode::ParseError);
+    }
+
+    #[tokio::test]
+    async fn timeouts_and_connection_failures_are_retryable() {
+        let server = MockServer::start().await;
+        Mock::given(path("/slow"))
+            .respond_with(ResponseTemplate::new(200).set_delay(Duration::from_secs(5)))
+            .mount(&server)
+            .await;
+        let error = reqwest::Client::new()
+            .get(format!("{}/slow", server.uri()))
+            .timeout(Duration::from_millis(50))
+            .send()
+            .await
+            .map(|_| ())
+            .map_err(HttpError::from)
+            .unwrap_err();
+        assert!(error.is_retryable());
+        assert_eq!(error.error_code(), timeout_error_code());
+
+        let port = std::net::TcpListener::bind("127.0.0.1:0")
+            .unwrap()
+            .local_addr()
+            .unwrap()
+            .port();
+        let error = HttpError::from(
+            reqwest::get(format!("http://127.0.0.1:{port}"))
+                .await
+                .unwrap_err(),
+        );
+        assert!(error.is_retryable());
+        assert_eq!(error.error_code(), http_error_code(503));
+    }
+
+    #[test]
+    fn invalid_requests_are_not_retryable() {
+        let error = reqwest::Client::new().get("not a url").build().unwrap_err();
+        assert!(!HttpError::from(error).is_retryable());
+    }
+}
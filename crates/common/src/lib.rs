@@ -0,0 +1,431 @@
+//! Shared utilities of the OpenFeature Rust contrib providers.
+//!
+//! # Context serialization
+//!
+//! Providers send evaluation context fields to their backend as strings or JSON values. The
+//! conversions of this crate are the canonical ones, so every provider handles the field types
+//! the same way:
+//!
+//! * booleans, integers and strings keep their value; floats that are not finite have no JSON
+//!   representation and are skipped in JSON;
+//! * date-times are formatted according to a [`DateTimeFormat`], RFC 3339 unless the backend
+//!   compares them as timestamps;
+//! * struct fields holding a [`StructValue`](open_feature::StructValue) are serialized
+//!   recursively, as JSON objects or their JSON text; other struct fields hold arbitrary Rust
+//!   values without a serialized form and are skipped.
+//!
+//! The targeting key is not a custom field: providers map it onto the attribute their backend
+//! expects.
+//!
+//! ```rust
+//! use openfeature_contrib_common::{fields_to_json, DateTimeFormat};
+//! use open_feature::EvaluationContext;
+//!
+//! let context = EvaluationContext::default()
+//!     .with_targeting_key("user-123")
+//!     .with_custom_field("plan", "premium")
+//!     .with_custom_field("seats", 5);
+//!
+//! let fields = fields_to_json(&context, DateTimeFormat::Rfc3339);
+//! assert_eq!(fields["plan"], "premium");
+//! assert_eq!(fields["seats"], 5);
+//! assert!(!fields.contains_key("targetingKey"));
+//! ```
+//!
+//! # Retries
+//!
+//! Providers that reconnect or retry after a failure wait according to a [`BackoffPolicy`]:
+//! exponentially growing delays with jitter, reset once the operation succeeds.
+//!
+//! ```rust
+//! use openfeature_contrib_common::BackoffPolicy;
+//!
+//! let mut backoff = BackoffPolicy::default().backoff();
+//! let first = backoff.next_delay();
+//! assert!(first <= BackoffPolicy::default().initial.mul_f64(1.2));
+//! ```
+//!
+//! # Error codes
+//!
+//! Providers map the failures of their backend requests with [`http_error_code`] and
+//! [`grpc_error_code`], so identical failure modes yield identical evaluation error codes
+//! whatever the provider and transport. [`is_retryable_http_status`] and
+//! [`is_retryable_grpc_code`] tell which failures may succeed when retried. With the `reqwest`
+//! feature, clients report failed requests as an `HttpError`, which applies both. Decorators
+//! failing evaluations on their own use [`timeout_error_code`] and [`rate_limited_error_code`].
+//! Hooks and telemetry report codes by their [`error_code_name`].
+//!
+//! # Decorators
+//!
+//! Provider decorators answering evaluations with resolutions stored earlier, such as caches,
+//! key them with a [`ResolutionKey`] and store them as a [`StoredResolution`]. The [`FlagValue`]
+//! trait converts the stored values back to the type of the evaluation, and evaluates flags of
+//! its type with a wrapped provider: decorators implement one generic `resolve` method and
+//! generate the `resolve_*_value` methods with [`impl_resolve_methods!`]. They bound the number
+//! of stored resolutions with a [`BoundedMap`], which drops the oldest ones first.
+
+mod backoff;
+mod bounded;
+mod errors;
+#[cfg(feature = "reqwest")]
+mod http;
+mod resolution;
+
+use std::collections::{BTreeMap, HashMap};
+
+use open_feature::{EvaluationContext, EvaluationContextFieldValue, StructValue};
+use serde_json::{Map, Number, Value};
+use time::format_description::well_known::Rfc3339;
+use time::OffsetDateTime;
+
+pub use crate::backoff::{Backoff, BackoffPolicy};
+pub use crate::bounded::BoundedMap;
+pub use crate::errors::{
+    error_code_name, grpc_error_code, http_error_code, is_retryable_grpc_code,
+    is_retryable_http_status, rate_limited_error_code, timeout_error_code,
+};
+#[cfg(feature = "reqwest")]
+pub use crate::http::{check_status, HttpError};
+pub use crate::resolution::{
+    FlagType, FlagValue, ResolutionFuture, ResolutionKey, StoredResolution,
+};
+
+#[doc(hidden)]
+pub mod __private {
+    pub use open_feature;
+}
+
+/// How date-time fields are serialized.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum DateTimeFormat {
+    /// RFC 3339 string, e.g. `2024-05-01T12:00:00Z`.
+    #[default]
+    Rfc3339,
+    /// Seconds since the Unix epoch.
+    UnixSeconds,
+    /// Milliseconds since the Unix epoch.
+    UnixMillis,
+}
+
+/// Formats a date-time as a string.
+///
+/// ```rust
+/// use openfeature_contrib_common::{format_date_time, DateTimeFormat};
+/// use time::macros::datetime;
+///
+/// let value = datetime!(2024-05-01 12:00 UTC);
+/// assert_eq!(
+///     format_date_time(&value, DateTimeFormat::Rfc3339).as_deref(),
+///     Some("2024-05-01T12:00:00Z")
+/// );
+/// assert_eq!(
+///     format_date_time(&value, DateTimeFormat::UnixMillis).as_deref(),
+///     Some("1714564800000")
+/// );
+/// ```
+pub fn format_date_time(value: &OffsetDateTime, format: DateTimeFormat) -> Option<String> {
+    match format {
+        DateTimeFormat::Rfc3339 => value.format(&Rfc3339).ok(),
+        DateTimeFormat::UnixSeconds => Some(value.unix_timestamp().to_string()),
+        DateTimeFormat::UnixMillis => Some(unix_millis(value)?.to_string()),
+    }
+}
+
+/// Converts a context field into a string.
+///
+/// Struct fields holding a [`StructValue`] become their JSON text; other struct fields have no
+/// string form and return `None`.
+///
+/// ```rust
+/// use openfeature_contrib_common::{field_to_string, DateTimeFormat};
+/// use open_feature::{EvaluationContextFieldValue, StructValue};
+///
+/// let value = EvaluationContextFieldValue::Float(0.5);
+/// assert_eq!(field_to_string(&value, DateTimeFormat::Rfc3339).as_deref(), Some("0.5"));
+///
+/// let value = EvaluationContextFieldValue::new_struct(StructValue::default().with_field("id", 7));
+/// assert_eq!(field_to_string(&value, DateTimeFormat::Rfc3339).as_deref(), Some(r#"{"id":7}"#));
+/// ```
+pub fn field_to_string(
+    value: &EvaluationContextFieldValue,
+    format: DateTimeFormat,
+) -> Option<String> {
+    match value {
+        EvaluationContextFieldValue::Bool(value) => Some(value.to_string()),
+        EvaluationContextFieldValue::Int(value) => Some(value.to_string()),
+        EvaluationContextFieldValue::Float(value) => Some(value.to_string()),
+        EvaluationContextFieldValue::String(value) => Some(value.clone()),
+        EvaluationContextFieldValue::DateTime(value) => format_date_time(value, format),
+        EvaluationContextFieldValue::Struct(value) => {
+            Some(struct_to_json(value.downcast_ref()?).to_string())
+        }
+    }
+}
+
+/// Converts a context field into a JSON value, or `None` for floats that are not finite.
+///
+/// Date-times formatted as Unix timestamps become JSON numbers. Struct fields holding a
+/// [`StructValue`] become JSON objects, see [`value_to_json`]; other struct fields have no JSON
+/// representation and return `None`.
+///
+/// ```rust
+/// use openfeature_contrib_common::{field_to_json, DateTimeFormat};
+/// use open_feature::EvaluationContextFieldValue;
+/// use serde_json::json;
+/// use time::macros::datetime;
+///
+/// let value = EvaluationContextFieldValue::DateTime(datetime!(2024-05-01 12:00 UTC));
+/// assert_eq!(
+///     field_to_json(&value, DateTimeFormat::UnixSeconds),
+///     Some(json!(1714564800))
+/// );
+/// assert_eq!(
+///     field_to_json(&EvaluationContextFieldValue::Float(f64::NAN), DateTimeFormat::Rfc3339),
+///     None
+/// );
+/// ```
+pub fn field_to_json(value: &EvaluationContextFieldValue, format: DateTimeFormat) -> Option<Value> {
+    Some(match value {
+        EvaluationContextFieldValue::Bool(value) => Value::Bool(*value),
+        EvaluationContextFieldValue::Int(value) => Value::Number((*value).into()),
+        EvaluationContextFieldValue::Float(value) => Value::Number(Number::from_f64(*value)?),
+        EvaluationContextFieldValue::String(value) => Value::String(value.clone()),
+        EvaluationContextFieldValue::DateTime(value) => match format {
+            DateTimeFormat::Rfc3339 => Value::String(value.format(&Rfc3339).ok()?),
+            DateTimeFormat::UnixSeconds => Value::Number(value.unix_timestamp().into()),
+            DateTimeFormat::UnixMillis => Value::Number(unix_millis(value)?.into()),
+        },
+        EvaluationContextFieldValue::Struct(value) => struct_to_json(value.downcast_ref()?),
+    })
+}
+
+/// Converts the custom fields of a context into strings, skipping fields without one.
+pub fn fields_to_strings(
+    context: &EvaluationContext,
+    format: DateTimeFormat,
+) -> HashMap<String, String> {
+    context
+        .custom_fields
+        .iter()
+        .filter_map(|(key, value)| Some((key.clone(), field_to_string(value, format)?)))
+        .collect()
+}
+
+/// Converts the custom fields of a context into a JSON object, skipping fields without a JSON
+/// representation.
+pub fn fields_to_json(context: &EvaluationContext, format: DateTimeFormat) -> Map<String, Value> {
+    context
+        .custom_fields
+        .iter()
+        .filter_map(|(key, value)| Some((key.clone(), field_to_json(value, format)?)))
+        .collect()
+}
+
+/// Converts a flag value into JSON. Floats that are not finite become `null`.
+///
+/// ```rust
+/// use openfeature_contrib_common::value_to_json;
+/// use open_feature::StructValue;
+/// use serde_json::json;
+///
+/// let value = StructValue::default().with_field("color", "blue").with_field("size", 3);
+/// assert_eq!(value_to_json(&value.into()), json!({"color": "blue", "size": 3}));
+/// ```
+pub fn value_to_json(value: &open_feature::Value) -> Value {
+    match value {
+        open_feature::Value::Bool(value) => Value::Bool(*value),
+        open_feature::Value::Int(value) => Value::Number((*value).into()),
+        open_feature::Value::Float(value) => {
+            Number::from_f64(*value).map_or(Value::Null, Value::Number)
+        }
+        open_feature::Value::String(value) => Value::String(value.clone()),
+        open_feature::Value::Array(values) => values.iter().map(value_to_json).collect(),
+        open_feature::Value::Struct(value) => struct_to_json(value),
+    }
+}
+
+fn struct_to_json(value: &StructValue) -> Value {
+    Value::Object(
+        value
+            .fields
+            .iter()
+            .map(|(key, value)| (key.clone(), value_to_json(value)))
+            .collect(),
+    )
+}
+
+/// Serializes the targeting key and custom fields of a context as canonical JSON, with the
+/// fields sorted by name, so equal contexts serialize identically. Useful as a cache key or as
+/// hash input.
+///
+/// Returns `None` when a field has no JSON representation, i.e. struct fields not holding a
+/// [`StructValue`] and floats that are not finite: skipping them would give contexts that differ
+/// in such fields the same key, so callers must not share results between them, e.g. bypass
+/// their cache.
+///
+/// ```rust
+/// use openfeature_contrib_common::canonical_context;
+/// use open_feature::{EvaluationContext, EvaluationContextFieldValue};
+///
+/// let context = EvaluationContext::default()
+///     .with_targeting_key("user-123")
+///     .with_custom_field("seats", 5)
+///     .with_custom_field("plan", "premium");
+/// assert_eq!(
+///     canonical_context(&context).as_deref(),
+///     Some(r#"{"fields":{"plan":"premium","seats":5},"targetingKey":"user-123"}"#)
+/// );
+///
+/// let opaque = context.with_custom_field("session", EvaluationContextFieldValue::new_struct(7));
+/// assert_eq!(canonical_context(&opaque), None);
+/// ```
+pub fn canonical_context(context: &EvaluationContext) -> Option<String> {
+    let fields = context
+        .custom_fields
+        .iter()
+        .map(|(key, value)| Some((key.clone(), field_to_json(value, DateTimeFormat::Rfc3339)?)))
+        .collect::<Option<BTreeMap<String, Value>>>()?;
+    let mut canonical = BTreeMap::new();
+    canonical.insert("fields", Value::from_iter(fields));
+    canonical.insert(
+        "targetingKey",
+        context
+            .targeting_key
+            .clone()
+            .map_or(Value::Null, Value::String),
+    );
+    Some(serde_json::to_string(&canonical).expect("JSON values always serialize"))
+}
+
+fn unix_millis(value: &OffsetDateTime) -> Option<i64> {
+    // Rounds down, so instants before the epoch keep their order.
+    i64::try_from(value.unix_timestamp_nanos().div_euclid(1_000_000)).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use open_feature::EvaluationContextFieldValue as Field;
+    use serde_json::json;
+    use time::macros::datetime;
+
+    use super::*;
+
+    #[test]
+    fn non_finite_floats_have_no_json_representation() {
+        for value in [f64::NAN, f64::INFINITY, f64::NEG_INFINITY] {
+            assert_eq!(
+                field_to_json(&Field::Float(value), DateTimeFormat::Rfc3339),
+                None
+            );
+            assert_eq!(
+                value_to_json(&open_feature::Value::Float(value)),
+                Value::Null
+            );
+        }
+
+        let context = EvaluationContext::default()
+            .with_custom_field("ratio", f64::NAN)
+            .with_custom_field("seats", 5);
+        assert_eq!(
+            Value::Object(fields_to_json(&context, DateTimeFormat::Rfc3339)),
+            json!({"seats": 5})
+        );
+    }
+
+    #[test]
+    fn millis_round_down_before_the_epoch() {
+        let value = Field::DateTime(datetime!(1969-12-31 23:59:59.9995 UTC));
+        assert_eq!(
+            field_to_json(&value, DateTimeFormat::UnixMillis),
+            Some(json!(-1))
+        );
+        assert_eq!(
+            field_to_string(&value, DateTimeFormat::UnixMillis).as_deref(),
+            Some("-1")
+        );
+    }
+
+    #[test]
+    fn millis_cover_the_whole_date_range() {
+        let max = Field::DateTime(datetime!(9999-12-31 23:59:59.999 UTC));
+        assert_eq!(
+            field_to_json(&max, DateTimeFormat::UnixMillis),
+            Some(json!(253_402_300_799_999_i64))
+        );
+        let min = Field::DateTime(datetime!(-9999-01-01 00:00 UTC));
+        assert_eq!(
+            field_to_json(&min, DateTimeFormat::UnixMillis),
+            Some(json!(-377_705_116_800_000_i64))
+        );
+    }
+
+    #[test]
+    fn nested_struct_fields_serialize_recursively() {
+        let address = StructValue::default()
+            .with_field("city", "Berlin")
+            .with_field("zip", 10115);
+        let value = StructValue::default()
+            .with_field("address", address)
+            .with_field(
+                "tags",
+                open_feature::Value::Array(vec!["a".into(), 1.5.into()]),
+            )
+            .with_field("ratio", f64::NAN);
+        let field = Field::new_struct(value);
+
+        let expected = json!({
+            "address": {"city": "Berlin", "zip": 10115},
+            "tags": ["a", 1.5],
+            "ratio": null,
+        });
+        assert_eq!(
+            field_to_json(&field, DateTimeFormat::Rfc3339),
+            Some(expected.clone())
+        );
+        let string = field_to_string(&field, DateTimeFormat::Rfc3339).unwrap();
+        assert_eq!(serde_json::from_str::<Value>(&string).unwrap(), expected);
+    }
+
+    #[test]
+    fn opaque_struct_fields_are_skipped() {
+        let field = Field::new_struct(42_u8);
+        assert_eq!(field_to_json(&field, DateTimeFormat::Rfc3339), None);
+        assert_eq!(field_to_string(&field, DateTimeFormat::Rfc3339), None);
+
+        let context = EvaluationContext::default()
+            .with_custom_field("opaque", field)
+            .with_custom_field("plan", "premium");
+        assert_eq!(
+            fields_to_strings(&context, DateTimeFormat::Rfc3339),
+            HashMap::from([("plan".to_string(), "premium".to_string())])
+        );
+    }
+
+    #[test]
+    fn contexts_differing_in_a_struct_field_have_different_keys() {
+        let context = |plan: &str| {
+            EvaluationContext::default()
+                .with_targeting_key("user-123")
+                .with_custom_field(
+                    "account",
+                    Field::new_struct(StructValue::default().with_field("plan", plan)),
+                )
+        };
+        let premium = canonical_context(&context("premium")).unwrap();
+        let free = canonical_context(&context("free")).unwrap();
+        assert_ne!(premium, free);
+        assert_eq!(canonical_context(&context("premium")).unwrap(), premium);
+    }
+
+    #[test]
+    fn contexts_with_fields_without_json_have_no_key() {
+        let context = EvaluationContext::default().with_targeting_key("user-123");
+        let opaque = context
+            .clone()
+            .with_custom_field("session", Field::new_struct(42_u8));
+        assert_eq!(canonical_context(&opaque), None);
+
+        let nan = context.with_custom_field("ratio", f64::NAN);
+        assert_eq!(canonical_context(&nan), None);
+    }
+}
@@ -0,0 +1,373 @@
+use std::future::Future;
+use std::pin::Pin;
+
+use open_feature::provider::{FeatureProvider, ResolutionDetails};
+use open_feature::{
+    EvaluationContext, EvaluationReason, EvaluationResult, FlagMetadata, StructValue, Value,
+};
+
+/// Future of an evaluation by a [`FeatureProvider`].
+pub type ResolutionFuture<'a, T> =
+    Pin<Box<dyn Future<Output = EvaluationResult<ResolutionDetails<T>>> + Send + 'a>>;
+
+/// Type of a flag evaluation.
+///
+/// Decorators storing resolutions key them by flag type, so a flag evaluated as several types
+/// keeps a resolution per type.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum FlagType {
+    /// Evaluated with `resolve_bool_value`.
+    Boolean,
+    /// Evaluated with `resolve_int_value`.
+    Integer,
+    /// Evaluated with `resolve_float_value`.
+    Float,
+    /// Evaluated with `resolve_string_value`.
+    String,
+    /// Evaluated with `resolve_struct_value`.
+    Object,
+}
+
+impl FlagType {
+    /// Name of the type, as reported by hooks and telemetry.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Boolean => "boolean",
+            Self::Integer => "integer",
+            Self::Float => "float",
+            Self::String => "string",
+            Self::Object => "object",
+        }
+    }
+}
+
+/// Value types a provider resolves flags to.
+pub trait FlagValue: Clone + Into<Value> {
+    /// Type of the evaluations resolving to this value type.
+    const FLAG_TYPE: FlagType;
+
+    /// Converts a stored value back, returning `None` when it holds another type.
+    fn from_value(value: Value) -> Option<Self>;
+
+    /// Evaluates `flag_key` as this type with `provider`, e.g. with `resolve_bool_value` for
+    /// `bool`, so decorators handle every type with one generic method.
+    fn resolve<'a, P: FeatureProvider + ?Sized>(
+        provider: &'a P,
+        flag_key: &'a str,
+        context: &'a EvaluationContext,
+    ) -> ResolutionFuture<'a, Self>;
+}
+
+impl FlagValue for bool {
+    const FLAG_TYPE: FlagType = FlagType::Boolean;
+
+    fn from_value(value: Value) -> Option<Self> {
+        value.as_bool()
+    }
+
+    fn resolve<'a, P: FeatureProvider + ?Sized>(
+        provider: &'a P,
+        flag_key: &'a str,
+        context: &'a EvaluationContext,
+    ) -> ResolutionFuture<'a, Self> {
+        provider.resolve_bool_value(flag_key, context)
+    }
+}
+
+impl FlagValue for i64 {
+    const FLAG_TYPE: FlagType = FlagType::Integer;
+
+    fn from_value(value: Value) -> Option<Self> {
+        value.as_i64()
+    }
+
+    fn resolve<'a, P: FeatureProvider + ?Sized>(
+        provider: &'a P,
+        flag_key: &'a str,
+        context: &'a EvaluationContext,
+    ) -> ResolutionFuture<'a, Self> {
+        provider.resolve_int_value(flag_key, context)
+    }
+}
+
+impl FlagValue for f64 {
+    const FLAG_TYPE: FlagType = FlagType::Float;
+
+    fn from_value(value: Value) -> Option<Self> {
+        value.as_f64()
+    }
+
+    fn resolve<'a, P: FeatureProvider + ?Sized>(
+        provider: &'a P,
+        flag_key: &'a str,
+        context: &'a EvaluationContext,
+    ) -> ResolutionFuture<'a, Self> {
+        provider.resolve_float_value(flag_key, context)
+    }
+}
+
+impl FlagValue for String {
+    const FLAG_TYPE: FlagType = FlagType::String;
+
+    fn from_value(value: Value) -> Option<Self> {
+        match value {
+            Value::String(value) => Some(value),
+            _ => None,
+        }
+    }
+
+    fn resolve<'a, P: FeatureProvider + ?Sized>(
+        provider: &'a P,
+        flag_key: &'a str,
+        context: &'a EvaluationContext,
+    ) -> ResolutionFuture<'a, Self> {
+        provider.resolve_string_value(flag_key, context)
+    }
+}
+
+impl FlagValue for StructValue {
+    const FLAG_TYPE: FlagType = FlagType::Object;
+
+    fn from_value(value: Value) -> Option<Self> {
+        match value {
+            Value::Struct(value) => Some(value),
+            _ => None,
+        }
+    }
+
+    fn resolve<'a, P: FeatureProvider + ?Sized>(
+        provider: &'a P,
+        flag_key: &'a str,
+        context: &'a EvaluationContext,
+    ) -> ResolutionFuture<'a, Self> {
+        provider.resolve_struct_value(flag_key, context)
+    }
+}
+
+/// Implements the `resolve_*_value` methods of a [`FeatureProvider`] by calling a generic
+/// method of the implementing type for the type of the evaluation. The method is named
+/// `resolve` unless another name is passed, and has the signature of `resolve` below.
+///
+/// The macro is invoked inside the `#[async_trait]` implementation of the trait. Decorators
+/// evaluate with the wrapped provider through [`FlagValue::resolve`].
+///
+/// ```rust
+/// use async_trait::async_trait;
+/// use open_feature::provider::{FeatureProvider, ProviderMetadata, ResolutionDetails};
+/// use open_feature::{EvaluationContext, EvaluationReason, EvaluationResult};
+/// use open_feature_in_memory::InMemoryProvider;
+/// use openfeature_contrib_common::{impl_resolve_methods, FlagValue};
+///
+/// /// Reports every evaluation as cached.
+/// struct Cached<P>(P);
+///
+/// impl<P: FeatureProvider> Cached<P> {
+///     async fn resolve<T: FlagValue>(
+///         &self,
+///         flag_key: &str,
+///         context: &EvaluationContext,
+///     ) -> EvaluationResult<ResolutionDetails<T>> {
+///         let details = T::resolve(&self.0, flag_key, context).await?;
+///         Ok(ResolutionDetails {
+///             reason: Some(EvaluationReason::Cached),
+///             ..details
+///         })
+///     }
+/// }
+///
+/// #[async_trait]
+/// impl<P: FeatureProvider> FeatureProvider for Cached<P> {
+///     fn metadata(&self) -> &ProviderMetadata {
+///         self.0.metadata()
+///     }
+///
+///     impl_resolve_methods!();
+/// }
+///
+/// # #[tokio::main]
+/// # async fn main() {
+/// let flags = InMemoryProvider::new();
+/// flags.set_flag("limit", 25, None, None);
+/// let details = Cached(flags)
+///     .resolve_int_value("limit", &EvaluationContext::default())
+///     .await
+///     .unwrap();
+/// assert_eq!(details.reason, Some(EvaluationReason::Cached));
+/// # }
+/// ```
+#[macro_export]
+macro_rules! impl_resolve_methods {
+    () => {
+        $crate::impl_resolve_methods!(resolve);
+    };
+    ($resolve:ident) => {
+        $crate::impl_resolve_methods!(@method $resolve, resolve_bool_value, bool);
+        $crate::impl_resolve_methods!(@method $resolve, resolve_int_value, i64);
+        $crate::impl_resolve_methods!(@method $resolve, resolve_float_value, f64);
+        $crate::impl_resolve_methods!(@method $resolve, resolve_string_value, ::std::string::String);
+        $crate::impl_resolve_methods!(
+            @method $resolve,
+            resolve_struct_value,
+            $crate::__private::open_feature::StructValue
+        );
+    };
+    // The signature `#[async_trait]` gives the methods of the trait, as it leaves the items
+    // of macro invocations untouched.
+    (@method $resolve:ident, $method:ident, $type:ty) => {
+        fn $method<'life0, 'life1, 'life2, 'async_trait>(
+            &'life0 self,
+            flag_key: &'life1 str,
+            context: &'life2 $crate::__private::open_feature::EvaluationContext,
+        ) -> $crate::ResolutionFuture<'async_trait, $type>
+        where
+            'life0: 'async_trait,
+            'life1: 'async_trait,
+            'life2: 'async_trait,
+            Self: 'async_trait,
+        {
+            ::std::boxed::Box::pin(self.$resolve::<$type>(flag_key, context))
+        }
+    };
+}
+
+/// Key of a stored resolution: the flag, the type it was evaluated as and the part of the
+/// evaluation context it was resolved for.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ResolutionKey<C = String> {
+    /// Key of the flag.
+    pub flag_key: String,
+    /// Type the flag was evaluated as.
+    pub flag_type: FlagType,
+    /// Context of the evaluation, e.g. its [`canonical_context`](crate::canonical_context).
+    pub context: C,
+}
+
+impl<C> ResolutionKey<C> {
+    /// Key of the evaluations of `flag_key` as `T` for `context`.
+    pub fn new<T: FlagValue>(flag_key: &str, context: C) -> Self {
+        Self {
+            flag_key: flag_key.to_string(),
+            flag_type: T::FLAG_TYPE,
+            context,
+        }
+    }
+}
+
+/// Resolution stored by a decorator to answer later evaluations, e.g. from a cache.
+///
+/// ```rust
+/// use open_feature::provider::ResolutionDetails;
+/// use open_feature::EvaluationReason;
+/// use openfeature_contrib_common::StoredResolution;
+///
+/// let stored = StoredResolution::new(&ResolutionDetails::<i64>::new(25));
+/// let details = stored.details::<i64>(EvaluationReason::Cached).unwrap();
+/// assert_eq!(details.value, 25);
+/// assert_eq!(details.reason, Some(EvaluationReason::Cached));
+/// assert!(stored.details::<bool>(EvaluationReason::Cached).is_none());
+/// ```
+#[derive(Debug, Clone)]
+pub struct StoredResolution {
+    value: Value,
+    variant: Option<String>,
+    flag_metadata: Option<FlagMetadata>,
+}
+
+impl StoredResolution {
+    /// Stores the value, variant and flag metadata of a resolution.
+    pub fn new<T: FlagValue>(details: &ResolutionDetails<T>) -> Self {
+        Self {
+            value: details.value.clone().into(),
+            variant: details.variant.clone(),
+            flag_metadata: details.flag_metadata.clone(),
+        }
+    }
+
+    /// Stores a bare value, without variant or flag metadata.
+    pub fn from_value(value: Value) -> Self {
+        Self {
+            value,
+            variant: None,
+            flag_metadata: None,
+        }
+    }
+
+    /// The stored resolution with `reason`, or `None` when its value is not a `T`.
+    pub fn details<T: FlagValue>(&self, reason: EvaluationReason) -> Option<ResolutionDetails<T>> {
+        Some(ResolutionDetails {
+            value: T::from_value(self.value.clone())?,
+            variant: self.variant.clone(),
+            reason: Some(reason),
+            flag_metadata: self.flag_metadata.clone(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn roundtrip<T: FlagValue>(value: T) -> Option<T> {
+        StoredResolution::new(&ResolutionDetails::<T>::new(value))
+            .details(EvaluationReason::Cached)
+            .map(|details| details.value)
+    }
+
+    #[test]
+    fn values_of_every_type_roundtrip() {
+        assert_eq!(roundtrip(true), Some(true));
+        assert_eq!(roundtrip(25_i64), Some(25));
+        assert_eq!(roundtrip(0.5), Some(0.5));
+        assert_eq!(roundtrip("dark".to_string()), Some("dark".to_string()));
+        let value = StructValue::default().with_field("color", "blue");
+        assert_eq!(roundtrip(value.clone()), Some(value));
+    }
+
+    #[test]
+    fn values_of_another_type_are_not_converted() {
+        let stored = StoredResolution::from_value(Value::String("on".to_string()));
+        assert!(stored.details::<bool>(EvaluationReason::Cached).is_none());
+        assert!(stored
+            .details::<StructValue>(EvaluationReason::Cached)
+            .is_none());
+        assert_eq!(
+            stored
+                .details::<String>(EvaluationReason::Cached)
+                .unwrap()
+                .value,
+            "on"
+        );
+    }
+
+    #[test]
+    fn keys_tell_flag_types_apart() {
+        assert_ne!(
+            ResolutionKey::new::<bool>("enabled", "user-1"),
+            ResolutionKey::new::<String>("enabled", "user-1")
+        );
+        assert_eq!(
+            ResolutionKey::new::<i64>("limit", "user-1").flag_type,
+            FlagType::Integer
+        );
+    }
+
+    #[test]
+    fn stored_resolutions_keep_the_variant_and_metadata() {
+        let details = ResolutionDetails {
+            value: 10_i64,
+            variant: Some("ten".to_string()),
+            reason: Some(EvaluationReason::TargetingMatch),
+            flag_metadata: Some(FlagMetadata::default().with_value("team", "checkout")),
+        };
+
+        let details = StoredResolution::new(&details)
+            .details::<i64>(EvaluationReason::Cached)
+            .unwrap();
+        assert_eq!(details.variant.as_deref(), Some("ten"));
+        assert_eq!(details.reason, Some(EvaluationReason::Cached));
+        assert_eq!(
+            details.flag_metadata,
+            Some(FlagMetadata::default().with_value("team", "checkout"))
+        );
+    }
+}
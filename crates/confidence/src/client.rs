@@ -0,0 +1,149 @@
+use openfeature_contrib_common::HttpError;
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use time::format_description::well_known::Rfc3339;
+use time::OffsetDateTime;
+
+use crate::ConfidenceError;
+
+const SDK_ID: &str = "SDK_ID_RUST_PROVIDER";
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ResolveRequest<'a> {
+    client_secret: &'a str,
+    evaluation_context: &'a Map<String, Value>,
+    flags: [String; 1],
+    apply: bool,
+    sdk: Sdk,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ApplyRequest<'a> {
+    client_secret: &'a str,
+    resolve_token: &'a str,
+    flags: [AppliedFlag<'a>; 1],
+    send_time: String,
+    sdk: Sdk,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct AppliedFlag<'a> {
+    flag: &'a str,
+    apply_time: String,
+}
+
+#[derive(Debug, Serialize)]
+struct Sdk {
+    id: &'static str,
+    version: &'static str,
+}
+
+impl Default for Sdk {
+    fn default() -> Self {
+        Self {
+            id: SDK_ID,
+            version: env!("CARGO_PKG_VERSION"),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct ResolveResponse {
+    #[serde(default)]
+    pub(crate) resolved_flags: Vec<ResolvedFlag>,
+    #[serde(default)]
+    pub(crate) resolve_token: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct ResolvedFlag {
+    pub(crate) flag: String,
+    #[serde(default)]
+    pub(crate) variant: String,
+    #[serde(default)]
+    pub(crate) value: Option<Map<String, Value>>,
+    #[serde(default)]
+    pub(crate) reason: String,
+}
+
+/// Thin HTTP client for the Confidence resolver API (`/v1/flags:resolve` and `/v1/flags:apply`).
+#[derive(Clone)]
+pub(crate) struct ConfidenceClient {
+    http: Client,
+    url: String,
+    client_secret: String,
+}
+
+impl ConfidenceClient {
+    pub(crate) fn new(http: Client, url: &str, client_secret: String) -> Self {
+        Self {
+            http,
+            url: url.trim_end_matches('/').to_string(),
+            client_secret,
+        }
+    }
+
+    /// Resolves a single flag (`flags/<name>`) without applying it.
+    pub(crate) async fn resolve(
+        &self,
+        flag: String,
+        evaluation_context: &Map<String, Value>,
+    ) -> Result<ResolveResponse, ConfidenceError> {
+        let url = format!("{}/v1/flags:resolve", self.url);
+        let response = self
+            .http
+            .post(&url)
+            .json(&ResolveRequest {
+                client_secret: &self.client_secret,
+                evaluation_context,
+                flags: [flag],
+                apply: false,
+                sdk: Sdk::default(),
+            })
+            .send()
+            .await?;
+
+        match response.status() {
+            status if status.is_success() => Ok(response.json().await?),
+            _ => Err(HttpError::status_of(&response).into()),
+        }
+    }
+
+    /// Reports that the value of `flag` resolved with `resolve_token` was used.
+    pub(crate) async fn apply(
+        &self,
+        resolve_token: &str,
+        flag: &str,
+    ) -> Result<(), ConfidenceError> {
+        let url = format!("{}/v1/flags:apply", self.url);
+        let now = OffsetDateTime::now_utc()
+            .format(&Rfc3339)
+            .unwrap_or_default();
+        let response = self
+            .http
+            .post(&url)
+            .json(&ApplyRequest {
+                client_secret: &self.client_secret,
+                resolve_token,
+                flags: [AppliedFlag {
+                    flag,
+                    apply_time: now.clone(),
+                }],
+                send_time: now,
+                sdk: Sdk::default(),
+            })
+            .send()
+            .await?;
+
+        match response.status() {
+            status if status.is_success() => Ok(()),
+            _ => Err(HttpError::status_of(&response).into()),
+        }
+    }
+}
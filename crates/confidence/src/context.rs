@@ -0,0 +1,42 @@
+use open_feature::EvaluationContext;
+use openfeature_contrib_common::{fields_to_json, DateTimeFormat};
+use serde_json::{Map, Value};
+
+/// Field of the Confidence evaluation context holding the targeting key.
+const TARGETING_KEY: &str = "targeting_key";
+
+/// Serializes an [`EvaluationContext`] into the Confidence evaluation context.
+///
+/// The targeting key is sent as `targeting_key`, custom fields are sent under their own name
+/// with date-times formatted as RFC 3339 and `StructValue` fields as JSON objects. Other struct
+/// fields are skipped.
+pub(crate) fn to_evaluation_context(context: &EvaluationContext) -> Map<String, Value> {
+    let mut fields = fields_to_json(context, DateTimeFormat::Rfc3339);
+    if let Some(targeting_key) = &context.targeting_key {
+        fields.insert(
+            TARGETING_KEY.to_string(),
+            Value::String(targeting_key.clone()),
+        );
+    }
+    fields
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn targeting_key_is_sent_with_the_custom_fields() {
+        let context = EvaluationContext::default()
+            .with_targeting_key("user-1")
+            .with_custom_field("country", "SE")
+            .with_custom_field("age", 30);
+
+        assert_eq!(
+            Value::Object(to_evaluation_context(&context)),
+            json!({"targeting_key": "user-1", "country": "SE", "age": 30})
+        );
+    }
+}
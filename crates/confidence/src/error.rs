@@ -0,0 +1,16 @@
+use openfeature_contrib_common::HttpError;
+use thiserror::Error;
+
+/// Errors raised by the Confidence resolver client.
+#[derive(Debug, Error)]
+pub enum ConfidenceError {
+    /// The HTTP request to Confidence failed.
+    #[error("request to Confidence failed: {0}")]
+    Http(#[from] HttpError),
+}
+
+impl From<reqwest::Error> for ConfidenceError {
+    fn from(error: reqwest::Error) -> Self {
+        Self::Http(error.into())
+    }
+}
@@ -0,0 +1,325 @@
+//! [Confidence](https://confidence.spotify.com/) provider for OpenFeature.
+//!
+//! The provider resolves flags remotely with the Confidence
+//! [resolver API](https://confidence.spotify.com/docs/api/flags). Confidence flags always hold a
+//! struct value; single properties are addressed with dot-separated flag keys, e.g.
+//! `checkout.button.color` resolves the `checkout` flag and reads `button.color` from its value.
+//!
+//! Flags are resolved without being applied, and an apply (exposure) event is sent in the
+//! background once a value has been handed out, so Confidence only counts visitors who actually
+//! saw a variant.
+//!
+//! # Context mapping
+//!
+//! The targeting key is sent as `targeting_key` and custom fields under their own name
+//! (date-times as RFC 3339, `StructValue` fields as objects). Other struct fields are skipped.
+//!
+//! # Example
+//!
+//! ```rust,no_run
+//! use open_feature::provider::FeatureProvider;
+//! use open_feature::EvaluationContext;
+//! use open_feature_confidence::{ConfidenceOptions, ConfidenceProvider};
+//!
+//! #[tokio::main]
+//! async fn main() {
+//!     let provider = ConfidenceProvider::new(ConfidenceOptions {
+//!         client_secret: "client-secret".to_string(),
+//!         ..Default::default()
+//!     })
+//!     .expect("Failed to create Confidence provider");
+//!
+//!     let context = EvaluationContext::default()
+//!         .with_targeting_key("user-123")
+//!         .with_custom_field("country", "SE");
+//!     let details = provider
+//!         .resolve_string_value("checkout.button.color", &context)
+//!         .await;
+//!     println!("{details:?}");
+//! }
+//! ```
+
+mod client;
+mod context;
+mod error;
+
+use std::time::Duration;
+
+use async_trait::async_trait;
+use open_feature::provider::{FeatureProvider, ProviderMetadata, ResolutionDetails};
+use open_feature::{
+    EvaluationContext, EvaluationError, EvaluationErrorCode, EvaluationReason, EvaluationResult,
+    StructValue, Value,
+};
+use reqwest::StatusCode;
+use tracing::warn;
+
+use crate::client::ConfidenceClient;
+use crate::context::to_evaluation_context;
+pub use crate::error::ConfidenceError;
+
+/// Configuration of the [`ConfidenceProvider`].
+#[derive(Debug, Clone)]
+pub struct ConfidenceOptions {
+    /// Client secret of a Confidence backend client.
+    pub client_secret: String,
+    /// Base URL of the Confidence resolver.
+    pub resolver_url: String,
+    /// Timeout applied to every resolve request.
+    pub request_timeout: Duration,
+    /// Whether apply (exposure) events are sent for resolved flags.
+    pub apply: bool,
+}
+
+impl Default for ConfidenceOptions {
+    fn default() -> Self {
+        Self {
+            client_secret: String::new(),
+            resolver_url: "https://resolver.confidence.dev".to_string(),
+            request_timeout: Duration::from_secs(5),
+            apply: true,
+        }
+    }
+}
+
+/// OpenFeature provider backed by the Confidence resolver API.
+pub struct ConfidenceProvider {
+    metadata: ProviderMetadata,
+    client: ConfidenceClient,
+    apply: bool,
+}
+
+impl ConfidenceProvider {
+    /// Creates the provider. No request is made until the first evaluation.
+    pub fn new(options: ConfidenceOptions) -> Result<Self, ConfidenceError> {
+        let http = reqwest::Client::builder()
+            .timeout(options.request_timeout)
+            .build()?;
+
+        Ok(Self {
+            metadata: ProviderMetadata::new("confidence"),
+            client: ConfidenceClient::new(http, &options.resolver_url, options.client_secret),
+            apply: options.apply,
+        })
+    }
+
+    async fn resolve<T>(
+        &self,
+        flag_key: &str,
+        context: &EvaluationContext,
+        convert: impl FnOnce(serde_json::Value) -> Option<T>,
+    ) -> EvaluationResult<ResolutionDetails<T>> {
+        let (flag_name, path) = match flag_key.split_once('.') {
+            Some((flag_name, path)) => (flag_name, Some(path)),
+            None => (flag_key, None),
+        };
+        let flag = format!("flags/{flag_name}");
+
+        let response = self
+            .client
+            .resolve(flag.clone(), &to_evaluation_context(context))
+            .await
+            .map_err(|e| {
+                let ConfidenceError::Http(http) = &e;
+                if http.status() == Some(StatusCode::NOT_FOUND) {
+                    return flag_not_found(flag_name);
+                }
+                EvaluationError::builder()
+                    .code(http.error_code())
+                    .message(format!("Confidence resolve failed: {e}"))
+                    .build()
+            })?;
+        let resolved = response
+            .resolved_flags
+            .into_iter()
+            .find(|resolved| resolved.flag == flag)
+            .ok_or_else(|| flag_not_found(flag_name))?;
+
+        let reason = match resolved.reason.as_str() {
+            "RESOLVE_REASON_MATCH" => EvaluationReason::TargetingMatch,
+            "RESOLVE_REASON_NO_SEGMENT_MATCH" | "RESOLVE_REASON_NO_TREATMENT_MATCH" => {
+                return Err(general_error(format!(
+                    "No variant of flag {flag_name} matched the context"
+                )))
+            }
+            "RESOLVE_REASON_TARGETING_KEY_ERROR" => {
+                return Err(EvaluationError::builder()
+                    .code(EvaluationErrorCode::TargetingKeyMissing)
+                    .message(format!("Invalid targeting key for flag {flag_name}"))
+                    .build())
+            }
+            "RESOLVE_REASON_FLAG_ARCHIVED" => {
+                return Err(general_error(format!("Flag {flag_name} is archived")))
+            }
+            reason => {
+                return Err(general_error(format!(
+                    "Confidence could not resolve flag {flag_name}: {reason}"
+                )))
+            }
+        };
+
+        let mut value = serde_json::Value::Object(resolved.value.unwrap_or_default());
+        for property in path.into_iter().flat_map(|path| path.split('.')) {
+            value = match value {
+                serde_json::Value::Object(mut fields) => {
+                    fields.remove(property).ok_or_else(|| {
+                        type_mismatch(format!("Flag {flag_name} has no property {property}"))
+                    })?
+                }
+                _ => {
+                    return Err(type_mismatch(format!(
+                        "Property path {flag_key} does not point into a struct"
+                    )))
+                }
+            };
+        }
+        if value.is_null() {
+            return Err(general_error(format!("Value of {flag_key} is not set")));
+        }
+        let value = convert(value)
+            .ok_or_else(|| type_mismatch(format!("Value of {flag_key} has another type")))?;
+
+        if self.apply {
+            let client = self.client.clone();
+            tokio::spawn(async move {
+                if let Err(e) = client.apply(&response.resolve_token, &flag).await {
+                    warn!("Failed to apply Confidence flag {flag}: {e}");
+                }
+            });
+        }
+
+        Ok(ResolutionDetails {
+            value,
+            variant: Some(resolved.variant),
+            reason: Some(reason),
+            flag_metadata: None,
+        })
+    }
+}
+
+#[async_trait]
+impl FeatureProvider for ConfidenceProvider {
+    fn metadata(&self) -> &ProviderMetadata {
+        &self.metadata
+    }
+
+    async fn resolve_bool_value(
+        &self,
+        flag_key: &str,
+        context: &EvaluationContext,
+    ) -> EvaluationResult<ResolutionDetails<bool>> {
+        self.resolve(flag_key, context, |value| value.as_bool())
+            .await
+    }
+
+    async fn resolve_int_value(
+        &self,
+        flag_key: &str,
+        context: &EvaluationContext,
+    ) -> EvaluationResult<ResolutionDetails<i64>> {
+        self.resolve(flag_key, context, |value| {
+            // Struct values travel as protobuf JSON, where every number may be a double.
+            value.as_i64().or_else(|| {
+                value
+                    .as_f64()
+                    .filter(|value| value.fract() == 0.0)
+                    .map(|value| value as i64)
+            })
+        })
+        .await
+    }
+
+    async fn resolve_float_value(
+        &self,
+        flag_key: &str,
+        context: &EvaluationContext,
+    ) -> EvaluationResult<ResolutionDetails<f64>> {
+        self.resolve(flag_key, context, |value| value.as_f64())
+            .await
+    }
+
+    async fn resolve_string_value(
+        &self,
+        flag_key: &str,
+        context: &EvaluationContext,
+    ) -> EvaluationResult<ResolutionDetails<String>> {
+        self.resolve(flag_key, context, |value| match value {
+            serde_json::Value::String(value) => Some(value),
+            _ => None,
+        })
+        .await
+    }
+
+    async fn resolve_struct_value(
+        &self,
+        flag_key: &str,
+        context: &EvaluationContext,
+    ) -> EvaluationResult<ResolutionDetails<StructValue>> {
+        self.resolve(flag_key, context, |value| {
+            match Value::try_from(without_nulls(value)) {
+                Ok(Value::Struct(value)) => Some(value),
+                _ => None,
+            }
+        })
+        .await
+    }
+}
+
+/// Drops unset (`null`) properties, which have no OpenFeature representation.
+fn without_nulls(value: serde_json::Value) -> serde_json::Value {
+    match value {
+        serde_json::Value::Object(fields) => serde_json::Value::Object(
+            fields
+                .into_iter()
+                .filter(|(_, value)| !value.is_null())
+                .map(|(key, value)| (key, without_nulls(value)))
+                .collect(),
+        ),
+        serde_json::Value::Array(values) => {
+            serde_json::Value::Array(values.into_iter().map(without_nulls).collect())
+        }
+        value => value,
+    }
+}
+
+fn flag_not_found(flag_name: &str) -> EvaluationError {
+    EvaluationError::builder()
+        .code(EvaluationErrorCode::FlagNotFound)
+        .message(format!("Flag {flag_name} not found"))
+        .build()
+}
+
+fn type_mismatch(message: String) -> EvaluationError {
+    EvaluationError::builder()
+        .code(EvaluationErrorCode::TypeMismatch)
+        .message(message)
+        .build()
+}
+
+fn general_error(message: String) -> EvaluationError {
+    EvaluationError::builder()
+        .code(EvaluationErrorCode::General(message.clone()))
+        .message(message)
+        .build()
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn unset_properties_are_dropped_at_every_level() {
+        let value = json!({
+            "color": "blue",
+            "size": null,
+            "button": {"label": null, "sizes": [{"width": 1, "height": null}]}
+        });
+
+        assert_eq!(
+            without_nulls(value),
+            json!({"color": "blue", "button": {"sizes": [{"width": 1}]}})
+        );
+    }
+}
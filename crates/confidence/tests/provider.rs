@@ -0,0 +1,251 @@
+use std::time::Duration;
+
+use open_feature::provider::FeatureProvider;
+use open_feature::{EvaluationContext, EvaluationErrorCode, EvaluationReason, StructValue};
+use open_feature_confidence::{ConfidenceOptions, ConfidenceProvider};
+use serde_json::json;
+use wiremock::matchers::{body_partial_json, method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+fn resolved(reason: &str, value: serde_json::Value) -> ResponseTemplate {
+    ResponseTemplate::new(200).set_body_json(json!({
+        "resolvedFlags": [{
+            "flag": "flags/checkout",
+            "variant": "flags/checkout/variants/treatment",
+            "value": value,
+            "reason": reason
+        }],
+        "resolveToken": "token-1"
+    }))
+}
+
+fn checkout() -> ResponseTemplate {
+    resolved(
+        "RESOLVE_REASON_MATCH",
+        json!({
+            "enabled": true,
+            "max-items": 25.0,
+            "ratio": 0.5,
+            "unset": null,
+            "button": {"color": "blue", "label": null}
+        }),
+    )
+}
+
+async fn server(response: ResponseTemplate) -> MockServer {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/v1/flags:resolve"))
+        .and(body_partial_json(json!({
+            "clientSecret": "secret",
+            "evaluationContext": {"targeting_key": "user-1", "country": "SE"},
+            "flags": ["flags/checkout"],
+            "apply": false
+        })))
+        .respond_with(response)
+        .mount(&server)
+        .await;
+    Mock::given(method("POST"))
+        .and(path("/v1/flags:apply"))
+        .respond_with(ResponseTemplate::new(200))
+        .mount(&server)
+        .await;
+    server
+}
+
+fn provider(server: &MockServer, apply: bool) -> ConfidenceProvider {
+    ConfidenceProvider::new(ConfidenceOptions {
+        client_secret: "secret".to_string(),
+        resolver_url: server.uri(),
+        apply,
+        ..Default::default()
+    })
+    .unwrap()
+}
+
+fn user() -> EvaluationContext {
+    EvaluationContext::default()
+        .with_targeting_key("user-1")
+        .with_custom_field("country", "SE")
+}
+
+async fn apply_requests(server: &MockServer) -> Vec<serde_json::Value> {
+    server
+        .received_requests()
+        .await
+        .unwrap()
+        .iter()
+        .filter(|request| request.url.path() == "/v1/flags:apply")
+        .map(|request| request.body_json().unwrap())
+        .collect()
+}
+
+#[tokio::test]
+async fn properties_are_addressed_with_dotted_keys() {
+    let server = server(checkout()).await;
+    let provider = provider(&server, false);
+
+    let details = provider
+        .resolve_string_value("checkout.button.color", &user())
+        .await
+        .unwrap();
+    assert_eq!(details.value, "blue");
+    assert_eq!(
+        details.variant.as_deref(),
+        Some("flags/checkout/variants/treatment")
+    );
+    assert_eq!(details.reason, Some(EvaluationReason::TargetingMatch));
+    assert!(
+        provider
+            .resolve_bool_value("checkout.enabled", &user())
+            .await
+            .unwrap()
+            .value
+    );
+    assert_eq!(
+        provider
+            .resolve_int_value("checkout.max-items", &user())
+            .await
+            .unwrap()
+            .value,
+        25
+    );
+    assert_eq!(
+        provider
+            .resolve_float_value("checkout.ratio", &user())
+            .await
+            .unwrap()
+            .value,
+        0.5
+    );
+}
+
+#[tokio::test]
+async fn whole_flags_resolve_as_structs_without_unset_properties() {
+    let server = server(checkout()).await;
+
+    let details = provider(&server, false)
+        .resolve_struct_value("checkout.button", &user())
+        .await
+        .unwrap();
+    assert_eq!(
+        details.value,
+        StructValue::default().with_field("color", "blue")
+    );
+}
+
+#[tokio::test]
+async fn resolved_values_are_applied() {
+    let server = server(checkout()).await;
+
+    provider(&server, true)
+        .resolve_bool_value("checkout.enabled", &user())
+        .await
+        .unwrap();
+    let applied = tokio::time::timeout(Duration::from_secs(5), async {
+        loop {
+            if let Some(request) = apply_requests(&server).await.pop() {
+                return request;
+            }
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+    })
+    .await
+    .expect("the flag was not applied");
+
+    assert_eq!(applied["clientSecret"], "secret");
+    assert_eq!(applied["resolveToken"], "token-1");
+    assert_eq!(applied["flags"][0]["flag"], "flags/checkout");
+}
+
+#[tokio::test]
+async fn failed_resolutions_are_not_applied() {
+    let server = server(checkout()).await;
+    let provider = provider(&server, true);
+
+    provider
+        .resolve_string_value("checkout.enabled", &user())
+        .await
+        .unwrap_err();
+    tokio::time::sleep(Duration::from_millis(50)).await;
+
+    assert!(apply_requests(&server).await.is_empty());
+}
+
+#[tokio::test]
+async fn mismatched_properties_fail() {
+    let server = server(checkout()).await;
+    let provider = provider(&server, false);
+
+    for (flag_key, code) in [
+        ("checkout.missing", EvaluationErrorCode::TypeMismatch),
+        ("checkout.enabled.nested", EvaluationErrorCode::TypeMismatch),
+        ("checkout.ratio", EvaluationErrorCode::TypeMismatch),
+        (
+            "checkout.unset",
+            EvaluationErrorCode::General("Value of checkout.unset is not set".to_string()),
+        ),
+    ] {
+        let error = provider
+            .resolve_int_value(flag_key, &user())
+            .await
+            .unwrap_err();
+        assert_eq!(error.code, code, "{flag_key}");
+    }
+}
+
+#[tokio::test]
+async fn resolve_reasons_map_to_errors() {
+    for (reason, code) in [
+        (
+            "RESOLVE_REASON_NO_SEGMENT_MATCH",
+            EvaluationErrorCode::General(
+                "No variant of flag checkout matched the context".to_string(),
+            ),
+        ),
+        (
+            "RESOLVE_REASON_TARGETING_KEY_ERROR",
+            EvaluationErrorCode::TargetingKeyMissing,
+        ),
+        (
+            "RESOLVE_REASON_FLAG_ARCHIVED",
+            EvaluationErrorCode::General("Flag checkout is archived".to_string()),
+        ),
+    ] {
+        let server = server(resolved(reason, json!({}))).await;
+
+        let error = provider(&server, false)
+            .resolve_struct_value("checkout", &user())
+            .await
+            .unwrap_err();
+        assert_eq!(error.code, code, "{reason}");
+    }
+}
+
+#[tokio::test]
+async fn resolver_errors_map_to_error_codes() {
+    for (status, code) in [
+        (404, EvaluationErrorCode::FlagNotFound),
+        (
+            401,
+            EvaluationErrorCode::General("Unauthorized".to_string()),
+        ),
+        (400, EvaluationErrorCode::InvalidContext),
+    ] {
+        let server = server(ResponseTemplate::new(status)).await;
+
+        let error = provider(&server, false)
+            .resolve_struct_value("checkout", &user())
+            .await
+            .unwrap_err();
+        assert_eq!(error.code, code, "{status}");
+    }
+
+    let server =
+        server(ResponseTemplate::new(200).set_body_json(json!({"resolvedFlags": []}))).await;
+    let error = provider(&server, false)
+        .resolve_struct_value("checkout", &user())
+        .await
+        .unwrap_err();
+    assert_eq!(error.code, EvaluationErrorCode::FlagNotFound);
+}
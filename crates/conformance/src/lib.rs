@@ -0,0 +1,352 @@
+//! Conformance test kit for OpenFeature providers.
+//!
+//! [`provider_conformance_tests!`] generates the standard battery of provider tests for any
+//! [`FeatureProvider`]: typed resolution, reason propagation, `FLAG_NOT_FOUND`,
+//! `TYPE_MISMATCH` and provider metadata. The checks are also available as functions, e.g. to
+//! run them from hand-written tests.
+//!
+//! # Fixture
+//!
+//! The tests resolve a fixed set of flags, so the provider under test must serve the flags of
+//! [`fixture`], and must not serve [`MISSING_FLAG`]:
+//!
+//! | Flag                 | Value                 |
+//! |----------------------|-----------------------|
+//! | [`BOOL_FLAG`]        | `true`                |
+//! | [`INT_FLAG`]         | `42`                  |
+//! | [`FLOAT_FLAG`]       | `0.5`                 |
+//! | [`STRING_FLAG`]      | `"conformance"`       |
+//! | [`STRUCT_FLAG`]      | `{"color": "blue"}`   |
+//!
+//! Flags are resolved with the context of [`context`], whose targeting key is
+//! `conformance-user`.
+//!
+//! # Example
+//!
+//! In an integration test, e.g. `tests/conformance.rs`:
+//!
+//! ```rust
+//! use open_feature_conformance::{fixture, provider_conformance_tests};
+//! use open_feature_in_memory::InMemoryProvider;
+//!
+//! async fn setup() -> InMemoryProvider {
+//!     let provider = InMemoryProvider::new();
+//!     for (flag_key, value) in fixture() {
+//!         provider.set_flag(flag_key, value, None, None);
+//!     }
+//!     provider
+//! }
+//!
+//! provider_conformance_tests!(InMemoryProvider, setup);
+//! ```
+//!
+//! The setup function is awaited once per test, so every test starts from a fresh provider.
+//! The checks can also be run directly:
+//!
+//! ```rust
+//! use open_feature_conformance::{check_bool_value, check_flag_not_found, fixture};
+//! use open_feature_in_memory::InMemoryProvider;
+//!
+//! #[tokio::main]
+//! async fn main() {
+//!     let provider = InMemoryProvider::new();
+//!     for (flag_key, value) in fixture() {
+//!         provider.set_flag(flag_key, value, None, None);
+//!     }
+//!
+//!     check_bool_value(&provider).await;
+//!     check_flag_not_found(&provider).await;
+//! }
+//! ```
+
+use std::fmt::Debug;
+
+use open_feature::provider::{FeatureProvider, ResolutionDetails};
+use open_feature::{
+    EvaluationContext, EvaluationErrorCode, EvaluationReason, EvaluationResult, StructValue, Value,
+};
+
+/// Boolean flag of the fixture, serving `true`.
+pub const BOOL_FLAG: &str = "conformance-bool";
+/// Integer flag of the fixture, serving `42`.
+pub const INT_FLAG: &str = "conformance-int";
+/// Float flag of the fixture, serving `0.5`.
+pub const FLOAT_FLAG: &str = "conformance-float";
+/// String flag of the fixture, serving `"conformance"`.
+pub const STRING_FLAG: &str = "conformance-string";
+/// Struct flag of the fixture, serving `{"color": "blue"}`.
+pub const STRUCT_FLAG: &str = "conformance-struct";
+/// Flag the provider must not serve.
+pub const MISSING_FLAG: &str = "conformance-missing";
+
+/// The flags the provider under test must serve, with their values.
+pub fn fixture() -> Vec<(&'static str, Value)> {
+    vec![
+        (BOOL_FLAG, Value::Bool(true)),
+        (INT_FLAG, Value::Int(42)),
+        (FLOAT_FLAG, Value::Float(0.5)),
+        (STRING_FLAG, Value::String("conformance".to_string())),
+        (STRUCT_FLAG, Value::Struct(fixture_struct())),
+    ]
+}
+
+/// The evaluation context flags are resolved with.
+pub fn context() -> EvaluationContext {
+    EvaluationContext::default().with_targeting_key("conformance-user")
+}
+
+fn fixture_struct() -> StructValue {
+    StructValue::default().with_field("color", "blue")
+}
+
+/// Checks that the provider has a name.
+pub fn check_metadata<P: FeatureProvider>(provider: &P) {
+    assert!(
+        !provider.metadata().name.is_empty(),
+        "provider metadata has an empty name"
+    );
+}
+
+/// Checks that [`BOOL_FLAG`] resolves to its value with a reason.
+pub async fn check_bool_value<P: FeatureProvider>(provider: &P) {
+    let details = provider
+        .resolve_bool_value(BOOL_FLAG, &context())
+        .await
+        .unwrap_or_else(|e| panic!("resolving {BOOL_FLAG} failed: {e:?}"));
+    assert!(details.value, "{BOOL_FLAG} resolved to false");
+    check_reason(BOOL_FLAG, details.reason);
+}
+
+/// Checks that [`INT_FLAG`] resolves to its value with a reason.
+pub async fn check_int_value<P: FeatureProvider>(provider: &P) {
+    let details = provider
+        .resolve_int_value(INT_FLAG, &context())
+        .await
+        .unwrap_or_else(|e| panic!("resolving {INT_FLAG} failed: {e:?}"));
+    assert_eq!(details.value, 42, "{INT_FLAG} resolved to another value");
+    check_reason(INT_FLAG, details.reason);
+}
+
+/// Checks that [`FLOAT_FLAG`] resolves to its value with a reason.
+pub async fn check_float_value<P: FeatureProvider>(provider: &P) {
+    let details = provider
+        .resolve_float_value(FLOAT_FLAG, &context())
+        .await
+        .unwrap_or_else(|e| panic!("resolving {FLOAT_FLAG} failed: {e:?}"));
+    assert_eq!(details.value, 0.5, "{FLOAT_FLAG} resolved to another value");
+    check_reason(FLOAT_FLAG, details.reason);
+}
+
+/// Checks that [`STRING_FLAG`] resolves to its value with a reason.
+pub async fn check_string_value<P: FeatureProvider>(provider: &P) {
+    let details = provider
+        .resolve_string_value(STRING_FLAG, &context())
+        .await
+        .unwrap_or_else(|e| panic!("resolving {STRING_FLAG} failed: {e:?}"));
+    assert_eq!(
+        details.value, "conformance",
+        "{STRING_FLAG} resolved to another value"
+    );
+    check_reason(STRING_FLAG, details.reason);
+}
+
+/// Checks that [`STRUCT_FLAG`] resolves to its value with a reason.
+pub async fn check_struct_value<P: FeatureProvider>(provider: &P) {
+    let details = provider
+        .resolve_struct_value(STRUCT_FLAG, &context())
+        .await
+        .unwrap_or_else(|e| panic!("resolving {STRUCT_FLAG} failed: {e:?}"));
+    assert_eq!(
+        details.value,
+        fixture_struct(),
+        "{STRUCT_FLAG} resolved to another value"
+    );
+    check_reason(STRUCT_FLAG, details.reason);
+}
+
+/// Checks that resolving [`MISSING_FLAG`] fails with `FLAG_NOT_FOUND` for every type.
+pub async fn check_flag_not_found<P: FeatureProvider>(provider: &P) {
+    let context = context();
+    let code = EvaluationErrorCode::FlagNotFound;
+    let flag_key = MISSING_FLAG;
+    check_error(
+        provider.resolve_bool_value(flag_key, &context).await,
+        &code,
+        flag_key,
+        "boolean",
+    );
+    check_error(
+        provider.resolve_int_value(flag_key, &context).await,
+        &code,
+        flag_key,
+        "integer",
+    );
+    check_error(
+        provider.resolve_float_value(flag_key, &context).await,
+        &code,
+        flag_key,
+        "float",
+    );
+    check_error(
+        provider.resolve_string_value(flag_key, &context).await,
+        &code,
+        flag_key,
+        "string",
+    );
+    check_error(
+        provider.resolve_struct_value(flag_key, &context).await,
+        &code,
+        flag_key,
+        "struct",
+    );
+}
+
+/// Checks that resolving fixture flags as another type fails with `TYPE_MISMATCH`.
+///
+/// Integer flags resolved as floats are not checked, since providers may widen them.
+pub async fn check_type_mismatch<P: FeatureProvider>(provider: &P) {
+    let context = context();
+    let code = EvaluationErrorCode::TypeMismatch;
+    check_error(
+        provider.resolve_bool_value(STRING_FLAG, &context).await,
+        &code,
+        STRING_FLAG,
+        "boolean",
+    );
+    check_error(
+        provider.resolve_int_value(BOOL_FLAG, &context).await,
+        &code,
+        BOOL_FLAG,
+        "integer",
+    );
+    check_error(
+        provider.resolve_float_value(STRING_FLAG, &context).await,
+        &code,
+        STRING_FLAG,
+        "float",
+    );
+    check_error(
+        provider.resolve_string_value(INT_FLAG, &context).await,
+        &code,
+        INT_FLAG,
+        "string",
+    );
+    check_error(
+        provider.resolve_struct_value(STRING_FLAG, &context).await,
+        &code,
+        STRING_FLAG,
+        "struct",
+    );
+}
+
+fn check_error<T: Debug>(
+    result: EvaluationResult<ResolutionDetails<T>>,
+    code: &EvaluationErrorCode,
+    flag_key: &str,
+    kind: &str,
+) {
+    match result {
+        Ok(details) => {
+            panic!("{flag_key} resolved as {kind} to {details:?} instead of failing with {code}")
+        }
+        Err(e) => assert_eq!(
+            &e.code, code,
+            "resolving {flag_key} as {kind} failed with {} instead of {code}",
+            e.code
+        ),
+    }
+}
+
+fn check_reason(flag_key: &str, reason: Option<EvaluationReason>) {
+    match reason {
+        Some(EvaluationReason::Error) => panic!("{flag_key} resolved with the ERROR reason"),
+        Some(_) => {}
+        None => panic!("{flag_key} resolved without a reason"),
+    }
+}
+
+/// Generates the conformance tests for a provider.
+///
+/// `$provider` is the provider type and `$setup` an async function without arguments returning
+/// a provider serving the [`fixture`]. Every check becomes a `#[test]` function prefixed with
+/// `conformance_`, running on its own Tokio runtime; invoke the macro in separate modules to test
+/// several providers in one file.
+#[macro_export]
+macro_rules! provider_conformance_tests {
+    ($provider:ty, $setup:path) => {
+        #[test]
+        fn conformance_metadata() {
+            $crate::__private::runtime().block_on(async {
+                let provider: $provider = $setup().await;
+                $crate::check_metadata(&provider);
+            });
+        }
+
+        #[test]
+        fn conformance_bool_value() {
+            $crate::__private::runtime().block_on(async {
+                let provider: $provider = $setup().await;
+                $crate::check_bool_value(&provider).await;
+            });
+        }
+
+        #[test]
+        fn conformance_int_value() {
+            $crate::__private::runtime().block_on(async {
+                let provider: $provider = $setup().await;
+                $crate::check_int_value(&provider).await;
+            });
+        }
+
+        #[test]
+        fn conformance_float_value() {
+            $crate::__private::runtime().block_on(async {
+                let provider: $provider = $setup().await;
+                $crate::check_float_value(&provider).await;
+            });
+        }
+
+        #[test]
+        fn conformance_string_value() {
+            $crate::__private::runtime().block_on(async {
+                let provider: $provider = $setup().await;
+                $crate::check_string_value(&provider).await;
+            });
+        }
+
+        #[test]
+        fn conformance_struct_value() {
+            $crate::__private::runtime().block_on(async {
+                let provider: $provider = $setup().await;
+                $crate::check_struct_value(&provider).await;
+            });
+        }
+
+        #[test]
+        fn conformance_flag_not_found() {
+            $crate::__private::runtime().block_on(async {
+                let provider: $provider = $setup().await;
+                $crate::check_flag_not_found(&provider).await;
+            });
+        }
+
+        #[test]
+        fn conformance_type_mismatch() {
+            $crate::__private::runtime().block_on(async {
+                let provider: $provider = $setup().await;
+                $crate::check_type_mismatch(&provider).await;
+            });
+        }
+    };
+}
+
+#[doc(hidden)]
+pub mod __private {
+    /// Runtime of a generated test.
+    pub fn runtime() -> tokio::runtime::Runtime {
+        tokio::runtime::Builder::new_multi_thread()
+            .enable_all()
+            .build()
+            .expect("Failed to build the Tokio runtime")
+    }
+}
@@ -0,0 +1,132 @@
+//! Runs the conformance tests against the providers of this repository that need no remote
+//! service.
+
+use open_feature_conformance::{fixture, provider_conformance_tests};
+use open_feature_in_memory::InMemoryProvider;
+
+fn in_memory() -> InMemoryProvider {
+    let provider = InMemoryProvider::new();
+    for (flag_key, value) in fixture() {
+        provider.set_flag(flag_key, value, None, None);
+    }
+    provider
+}
+
+mod in_memory {
+    use super::*;
+
+    async fn setup() -> InMemoryProvider {
+        in_memory()
+    }
+
+    provider_conformance_tests!(InMemoryProvider, setup);
+}
+
+mod file {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use open_feature_file::{FileOptions, FileProvider};
+
+    use super::*;
+
+    static FILES: AtomicUsize = AtomicUsize::new(0);
+
+    async fn setup() -> FileProvider {
+        let path = std::env::temp_dir().join(format!(
+            "open-feature-conformance-{}-{}.yaml",
+            std::process::id(),
+            FILES.fetch_add(1, Ordering::Relaxed)
+        ));
+        let flags = "\
+conformance-bool: true
+conformance-int: 42
+conformance-float: 0.5
+conformance-string: conformance
+conformance-struct:
+  color: blue
+";
+        tokio::fs::write(&path, flags)
+            .await
+            .expect("Failed to write the flag file");
+        let provider = FileProvider::new(FileOptions {
+            path: path.clone(),
+            watch_interval: None,
+            ..Default::default()
+        })
+        .await
+        .expect("Failed to load the flag file");
+        tokio::fs::remove_file(&path)
+            .await
+            .expect("Failed to remove the flag file");
+        provider
+    }
+
+    provider_conformance_tests!(FileProvider, setup);
+}
+
+mod cache {
+    use open_feature_cache::{CacheOptions, CachedProvider};
+
+    use super::*;
+
+    async fn setup() -> CachedProvider<InMemoryProvider> {
+        CachedProvider::new(in_memory(), CacheOptions::default())
+    }
+
+    provider_conformance_tests!(CachedProvider<InMemoryProvider>, setup);
+}
+
+mod stale {
+    use open_feature_stale::{StaleOptions, StaleProvider};
+
+    use super::*;
+
+    async fn setup() -> StaleProvider<InMemoryProvider> {
+        StaleProvider::new(in_memory(), StaleOptions::default())
+    }
+
+    provider_conformance_tests!(StaleProvider<InMemoryProvider>, setup);
+}
+
+mod rate_limit {
+    use open_feature_rate_limit::{RateLimitOptions, RateLimitedProvider};
+
+    use super::*;
+
+    async fn setup() -> RateLimitedProvider<InMemoryProvider> {
+        RateLimitedProvider::new(in_memory(), RateLimitOptions::default()).unwrap()
+    }
+
+    provider_conformance_tests!(RateLimitedProvider<InMemoryProvider>, setup);
+}
+
+mod traffic_split {
+    use open_feature_traffic_split::{TrafficSplitOptions, TrafficSplitProvider};
+
+    use super::*;
+
+    async fn setup() -> TrafficSplitProvider<InMemoryProvider, InMemoryProvider> {
+        TrafficSplitProvider::new(
+            in_memory(),
+            in_memory(),
+            TrafficSplitOptions {
+                percentage: 50.0,
+                ..Default::default()
+            },
+        )
+    }
+
+    provider_conformance_tests!(TrafficSplitProvider<InMemoryProvider, InMemoryProvider>, setup);
+}
+
+mod fallback {
+    use open_feature_fallback::{FallbackOptions, FallbackProvider};
+
+    use super::*;
+
+    async fn setup() -> FallbackProvider<InMemoryProvider, InMemoryProvider> {
+        FallbackProvider::new(in_memory(), in_memory(), FallbackOptions::default())
+    }
+
+    provider_conformance_tests!(FallbackProvider<InMemoryProvider, InMemoryProvider>, setup);
+}
@@ -0,0 +1,31 @@
+use std::sync::Arc;
+
+use eppo::{AttributeValue, Attributes};
+use open_feature::{EvaluationContext, EvaluationContextFieldValue};
+use openfeature_contrib_common::{format_date_time, DateTimeFormat};
+
+/// Converts the custom fields of an [`EvaluationContext`] into Eppo subject attributes.
+///
+/// Numbers become numeric attributes, strings and booleans categorical ones and date-times are
+/// sent as RFC 3339 strings. Struct fields have no Eppo attribute type and are skipped.
+pub(crate) fn to_attributes(context: &EvaluationContext) -> Arc<Attributes> {
+    let attributes = context
+        .custom_fields
+        .iter()
+        .filter_map(|(key, value)| {
+            let value = match value {
+                EvaluationContextFieldValue::Bool(value) => AttributeValue::from(*value),
+                EvaluationContextFieldValue::Int(value) => AttributeValue::numeric(*value as f64),
+                EvaluationContextFieldValue::Float(value) => AttributeValue::numeric(*value),
+                EvaluationContextFieldValue::String(value) => AttributeValue::from(value.as_str()),
+                EvaluationContextFieldValue::DateTime(value) => {
+                    AttributeValue::from(format_date_time(value, DateTimeFormat::Rfc3339)?)
+                }
+                EvaluationContextFieldValue::Struct(_) => return None,
+            };
+            Some((key.as_str().into(), value))
+        })
+        .collect();
+
+    Arc::new(attributes)
+}
@@ -0,0 +1,13 @@
+use thiserror::Error;
+
+/// Errors raised while creating an [`EppoProvider`](crate::EppoProvider).
+#[derive(Debug, Error)]
+pub enum EppoError {
+    /// The Eppo SDK failed to start or fetch its configuration.
+    #[error("Eppo SDK error: {0}")]
+    Sdk(#[from] eppo::Error),
+
+    /// Waiting for the initial configuration was interrupted.
+    #[error("failed to wait for the Eppo configuration: {0}")]
+    Join(#[from] tokio::task::JoinError),
+}
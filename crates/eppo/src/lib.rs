@@ -0,0 +1,316 @@
+//! [Eppo](https://www.geteppo.com/) provider for OpenFeature.
+//!
+//! The provider wraps the official [Eppo Rust SDK](https://docs.rs/eppo), which downloads the
+//! universal flag configuration in a background poller thread and evaluates assignments
+//! locally. Assignment events are forwarded to the configured [`AssignmentLogger`], so
+//! experiment exposures keep flowing into the data warehouse when flags are evaluated through
+//! the OpenFeature API.
+//!
+//! # Context mapping
+//!
+//! The targeting key is used as the Eppo subject key and is required. Custom fields become
+//! subject attributes: numbers are numeric attributes, strings and booleans categorical
+//! attributes and date-times RFC 3339 strings.
+//!
+//! # Example
+//!
+//! ```rust,no_run
+//! use std::sync::Arc;
+//!
+//! use open_feature::provider::FeatureProvider;
+//! use open_feature::EvaluationContext;
+//! use open_feature_eppo::{AssignmentEvent, EppoOptions, EppoProvider};
+//!
+//! #[tokio::main]
+//! async fn main() {
+//!     let provider = EppoProvider::new(EppoOptions {
+//!         api_key: "sdk-key".to_string(),
+//!         assignment_logger: Some(Arc::new(|event: AssignmentEvent| {
+//!             println!("assigned {:?}", event.base.variation);
+//!         })),
+//!         ..Default::default()
+//!     })
+//!     .await
+//!     .expect("Failed to create Eppo provider");
+//!
+//!     let context = EvaluationContext::default()
+//!         .with_targeting_key("user-123")
+//!         .with_custom_field("country", "NL");
+//!     let details = provider.resolve_string_value("checkout-copy", &context).await;
+//!     println!("{details:?}");
+//! }
+//! ```
+
+mod context;
+mod error;
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use async_trait::async_trait;
+use eppo::{
+    AllocationEvaluationCode, Client, ClientConfig, EvaluationResultWithDetails,
+    FlagEvaluationCode, PollerThread,
+};
+use eppo_core::Str;
+use open_feature::provider::{
+    FeatureProvider, ProviderMetadata, ProviderStatus, ResolutionDetails,
+};
+use open_feature::{
+    EvaluationContext, EvaluationError, EvaluationErrorCode, EvaluationReason, EvaluationResult,
+    FlagMetadata, StructValue, Value,
+};
+use tracing::warn;
+
+use crate::context::to_attributes;
+pub use crate::error::EppoError;
+pub use eppo::{AssignmentEvent, AssignmentLogger};
+
+/// Configuration of the [`EppoProvider`].
+#[derive(Clone)]
+pub struct EppoOptions {
+    /// Eppo SDK key.
+    pub api_key: String,
+    /// Overrides the Eppo API base URL.
+    pub base_url: Option<String>,
+    /// How long [`EppoProvider::new`] waits for the first configuration. `None` returns
+    /// immediately; evaluations report `PROVIDER_NOT_READY` until the configuration arrives.
+    pub wait_for_configuration: Option<Duration>,
+    /// Receives an event for every assignment, e.g. to store experiment exposures.
+    pub assignment_logger: Option<Arc<dyn AssignmentLogger + Send + Sync>>,
+}
+
+impl Default for EppoOptions {
+    fn default() -> Self {
+        Self {
+            api_key: String::new(),
+            base_url: None,
+            wait_for_configuration: Some(Duration::from_secs(5)),
+            assignment_logger: None,
+        }
+    }
+}
+
+/// Forwards assignment events to a shared logger.
+struct SharedLogger(Arc<dyn AssignmentLogger + Send + Sync>);
+
+impl AssignmentLogger for SharedLogger {
+    fn log_assignment(&self, event: AssignmentEvent) {
+        self.0.log_assignment(event);
+    }
+}
+
+/// OpenFeature provider evaluating Eppo flags locally.
+pub struct EppoProvider {
+    metadata: ProviderMetadata,
+    client: Client<'static>,
+    poller: PollerThread,
+    /// Whether a configuration was fetched. The SDK never drops it once fetched.
+    ready: AtomicBool,
+}
+
+impl EppoProvider {
+    /// Creates the provider and starts the configuration poller.
+    pub async fn new(options: EppoOptions) -> Result<Self, EppoError> {
+        let mut config = ClientConfig::from_api_key(options.api_key);
+        if let Some(base_url) = options.base_url {
+            config = config.base_url(base_url);
+        }
+        if let Some(logger) = options.assignment_logger {
+            config = config.assignment_logger(SharedLogger(logger));
+        }
+
+        let mut client = config.to_client();
+        let mut poller = client.start_poller_thread()?;
+        let mut ready = false;
+        if let Some(timeout) = options.wait_for_configuration {
+            // The SDK blocks on its own runtime while waiting, which must not happen on ours.
+            poller = tokio::task::spawn_blocking(move || {
+                poller
+                    .wait_for_configuration_timeout(timeout)
+                    .map(|_| poller)
+            })
+            .await??;
+            ready = true;
+        }
+
+        Ok(Self {
+            metadata: ProviderMetadata::new("eppo"),
+            client,
+            poller,
+            ready: AtomicBool::new(ready),
+        })
+    }
+
+    fn resolve<T, U>(
+        &self,
+        flag_key: &str,
+        context: &EvaluationContext,
+        evaluate: impl FnOnce(
+            &Client<'static>,
+            &str,
+            &Str,
+            &Arc<eppo::Attributes>,
+        ) -> EvaluationResultWithDetails<T>,
+        convert: impl FnOnce(T) -> EvaluationResult<U>,
+    ) -> EvaluationResult<ResolutionDetails<U>> {
+        let subject_key = context.targeting_key.as_deref().ok_or_else(|| {
+            EvaluationError::builder()
+                .code(EvaluationErrorCode::TargetingKeyMissing)
+                .message("Eppo requires a targeting key as subject key")
+                .build()
+        })?;
+
+        let result = evaluate(
+            &self.client,
+            flag_key,
+            &subject_key.into(),
+            &to_attributes(context),
+        );
+        let details = result.evaluation_details;
+        let value = match (result.variation, details.flag_evaluation_code) {
+            (Some(value), _) => value,
+            (None, code) => {
+                return Err(EvaluationError::builder()
+                    .code(error_code(code))
+                    .message(details.flag_evaluation_description.clone())
+                    .build())
+            }
+        };
+
+        let mut flag_metadata = FlagMetadata::default();
+        let mut reason = EvaluationReason::TargetingMatch;
+        if let Some(allocation) = details.allocations.iter().find(|allocation| {
+            allocation.allocation_evaluation_code == AllocationEvaluationCode::Match
+        }) {
+            flag_metadata.add_value("allocationKey", allocation.key.to_string());
+            // Allocations without rules serve every subject, like the default allocation.
+            if allocation.evaluated_rules.is_empty() {
+                reason = EvaluationReason::Default;
+            }
+        }
+
+        Ok(ResolutionDetails {
+            value: convert(value)?,
+            variant: details.variation_key.as_ref().map(ToString::to_string),
+            reason: Some(reason),
+            flag_metadata: Some(flag_metadata),
+        })
+    }
+}
+
+impl Drop for EppoProvider {
+    fn drop(&mut self) {
+        self.poller.stop();
+    }
+}
+
+#[async_trait]
+impl FeatureProvider for EppoProvider {
+    fn metadata(&self) -> &ProviderMetadata {
+        &self.metadata
+    }
+
+    fn status(&self) -> ProviderStatus {
+        if self.ready.load(Ordering::Relaxed) {
+            return ProviderStatus::Ready;
+        }
+        // Evaluations only report a missing configuration until the first one is fetched.
+        let details = self
+            .client
+            .get_assignment_details("", &Str::from(""), &Arc::default())
+            .evaluation_details;
+        if details.flag_evaluation_code == Some(FlagEvaluationCode::ConfigurationMissing) {
+            return ProviderStatus::NotReady;
+        }
+        self.ready.store(true, Ordering::Relaxed);
+        ProviderStatus::Ready
+    }
+
+    async fn resolve_bool_value(
+        &self,
+        flag_key: &str,
+        context: &EvaluationContext,
+    ) -> EvaluationResult<ResolutionDetails<bool>> {
+        self.resolve(
+            flag_key,
+            context,
+            Client::get_boolean_assignment_details,
+            Ok,
+        )
+    }
+
+    async fn resolve_int_value(
+        &self,
+        flag_key: &str,
+        context: &EvaluationContext,
+    ) -> EvaluationResult<ResolutionDetails<i64>> {
+        self.resolve(
+            flag_key,
+            context,
+            Client::get_integer_assignment_details,
+            Ok,
+        )
+    }
+
+    async fn resolve_float_value(
+        &self,
+        flag_key: &str,
+        context: &EvaluationContext,
+    ) -> EvaluationResult<ResolutionDetails<f64>> {
+        self.resolve(
+            flag_key,
+            context,
+            Client::get_numeric_assignment_details,
+            Ok,
+        )
+    }
+
+    async fn resolve_string_value(
+        &self,
+        flag_key: &str,
+        context: &EvaluationContext,
+    ) -> EvaluationResult<ResolutionDetails<String>> {
+        self.resolve(
+            flag_key,
+            context,
+            Client::get_string_assignment_details,
+            |value| Ok(value.to_string()),
+        )
+    }
+
+    async fn resolve_struct_value(
+        &self,
+        flag_key: &str,
+        context: &EvaluationContext,
+    ) -> EvaluationResult<ResolutionDetails<StructValue>> {
+        self.resolve(
+            flag_key,
+            context,
+            Client::get_json_assignment_details,
+            |json| match Value::try_from(json.as_ref())? {
+                Value::Struct(value) => Ok(value),
+                _ => Err(EvaluationError::builder()
+                    .code(EvaluationErrorCode::TypeMismatch)
+                    .message(format!("JSON value of {flag_key} is not an object"))
+                    .build()),
+            },
+        )
+    }
+}
+
+fn error_code(code: Option<FlagEvaluationCode>) -> EvaluationErrorCode {
+    match code {
+        Some(FlagEvaluationCode::ConfigurationMissing) => EvaluationErrorCode::ProviderNotReady,
+        Some(FlagEvaluationCode::FlagUnrecognizedOrDisabled) => EvaluationErrorCode::FlagNotFound,
+        Some(FlagEvaluationCode::TypeMismatch) => EvaluationErrorCode::TypeMismatch,
+        Some(FlagEvaluationCode::UnexpectedConfigurationError) => EvaluationErrorCode::ParseError,
+        // No allocation serves a value, so the application's default applies.
+        Some(FlagEvaluationCode::DefaultAllocationNull) => EvaluationErrorCode::FlagNotFound,
+        Some(FlagEvaluationCode::Match) | None => {
+            warn!("Eppo returned no variation without a failure code");
+            EvaluationErrorCode::General("No variation".to_string())
+        }
+    }
+}
@@ -0,0 +1,244 @@
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use open_feature::provider::{FeatureProvider, ProviderStatus};
+use open_feature::{EvaluationContext, EvaluationErrorCode, EvaluationReason};
+use open_feature_eppo::{AssignmentEvent, EppoOptions, EppoProvider};
+use serde_json::json;
+use wiremock::matchers::{method, path, query_param};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+/// Universal flag configuration with a targeted flag and flags served to every subject.
+fn configuration() -> serde_json::Value {
+    let everyone = |key: &str, variation: &str| {
+        json!({
+            "key": key,
+            "splits": [{"variationKey": variation, "shards": []}],
+            "doLog": true
+        })
+    };
+    json!({
+        "createdAt": "2024-07-18T00:00:00Z",
+        "format": "SERVER",
+        "environment": {"name": "test"},
+        "flags": {
+            "new-checkout": {
+                "key": "new-checkout",
+                "enabled": true,
+                "variationType": "BOOLEAN",
+                "variations": {"on": {"key": "on", "value": true}},
+                "allocations": [{
+                    "key": "dutch-users",
+                    "rules": [{"conditions": [
+                        {"attribute": "country", "operator": "ONE_OF", "value": ["NL"]}
+                    ]}],
+                    "splits": [{"variationKey": "on", "shards": []}],
+                    "doLog": true
+                }],
+                "totalShards": 10000
+            },
+            "checkout-copy": {
+                "key": "checkout-copy",
+                "enabled": true,
+                "variationType": "STRING",
+                "variations": {"short": {"key": "short", "value": "Buy"}},
+                "allocations": [everyone("default", "short")],
+                "totalShards": 10000
+            },
+            "limit": {
+                "key": "limit",
+                "enabled": true,
+                "variationType": "INTEGER",
+                "variations": {"low": {"key": "low", "value": 25}},
+                "allocations": [everyone("default", "low")],
+                "totalShards": 10000
+            },
+            "layout": {
+                "key": "layout",
+                "enabled": true,
+                "variationType": "JSON",
+                "variations": {"grid": {"key": "grid", "value": "{\"columns\": 2}"}},
+                "allocations": [everyone("default", "grid")],
+                "totalShards": 10000
+            }
+        }
+    })
+}
+
+async fn server(response: ResponseTemplate) -> MockServer {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/flag-config/v1/config"))
+        .and(query_param("apiKey", "sdk-key"))
+        .respond_with(response)
+        .mount(&server)
+        .await;
+    server
+}
+
+fn options(server: &MockServer) -> EppoOptions {
+    EppoOptions {
+        api_key: "sdk-key".to_string(),
+        base_url: Some(server.uri()),
+        ..Default::default()
+    }
+}
+
+fn user(country: &str) -> EvaluationContext {
+    EvaluationContext::default()
+        .with_targeting_key("user-1")
+        .with_custom_field("country", country)
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn resolves_targeted_flags() {
+    let server = server(ResponseTemplate::new(200).set_body_json(configuration())).await;
+    let provider = EppoProvider::new(options(&server)).await.unwrap();
+    assert_eq!(provider.status(), ProviderStatus::Ready);
+
+    let details = provider
+        .resolve_bool_value("new-checkout", &user("NL"))
+        .await
+        .unwrap();
+    assert!(details.value);
+    assert_eq!(details.variant.as_deref(), Some("on"));
+    assert_eq!(details.reason, Some(EvaluationReason::TargetingMatch));
+    assert_eq!(
+        details
+            .flag_metadata
+            .unwrap()
+            .values
+            .get("allocationKey")
+            .cloned(),
+        Some("dutch-users".into())
+    );
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn allocations_serving_every_subject_resolve_with_the_default_reason() {
+    let server = server(ResponseTemplate::new(200).set_body_json(configuration())).await;
+    let provider = EppoProvider::new(options(&server)).await.unwrap();
+    let context = user("DE");
+
+    let details = provider
+        .resolve_string_value("checkout-copy", &context)
+        .await
+        .unwrap();
+    assert_eq!(details.value, "Buy");
+    assert_eq!(details.reason, Some(EvaluationReason::Default));
+    assert_eq!(
+        provider
+            .resolve_int_value("limit", &context)
+            .await
+            .unwrap()
+            .value,
+        25
+    );
+    let layout = provider
+        .resolve_struct_value("layout", &context)
+        .await
+        .unwrap()
+        .value;
+    assert_eq!(
+        layout.fields.get("columns").and_then(|v| v.as_i64()),
+        Some(2)
+    );
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn subjects_matching_no_allocation_get_the_default_value() {
+    let server = server(ResponseTemplate::new(200).set_body_json(configuration())).await;
+    let provider = EppoProvider::new(options(&server)).await.unwrap();
+
+    let error = provider
+        .resolve_bool_value("new-checkout", &user("DE"))
+        .await
+        .unwrap_err();
+    assert_eq!(error.code, EvaluationErrorCode::FlagNotFound);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn maps_evaluation_failures_to_error_codes() {
+    let server = server(ResponseTemplate::new(200).set_body_json(configuration())).await;
+    let provider = EppoProvider::new(options(&server)).await.unwrap();
+    let context = user("NL");
+
+    let missing = provider
+        .resolve_bool_value("missing", &context)
+        .await
+        .unwrap_err();
+    assert_eq!(missing.code, EvaluationErrorCode::FlagNotFound);
+    let mismatch = provider
+        .resolve_string_value("new-checkout", &context)
+        .await
+        .unwrap_err();
+    assert_eq!(mismatch.code, EvaluationErrorCode::TypeMismatch);
+    let anonymous = provider
+        .resolve_bool_value("new-checkout", &EvaluationContext::default())
+        .await
+        .unwrap_err();
+    assert_eq!(anonymous.code, EvaluationErrorCode::TargetingKeyMissing);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn is_not_ready_until_a_configuration_is_fetched() {
+    let server = server(ResponseTemplate::new(500)).await;
+    let provider = EppoProvider::new(EppoOptions {
+        wait_for_configuration: None,
+        ..options(&server)
+    })
+    .await
+    .unwrap();
+
+    assert_eq!(provider.status(), ProviderStatus::NotReady);
+    let error = provider
+        .resolve_bool_value("new-checkout", &user("NL"))
+        .await
+        .unwrap_err();
+    assert_eq!(error.code, EvaluationErrorCode::ProviderNotReady);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn becomes_ready_once_a_configuration_is_fetched() {
+    let server = server(ResponseTemplate::new(200).set_body_json(configuration())).await;
+    let provider = EppoProvider::new(EppoOptions {
+        wait_for_configuration: None,
+        ..options(&server)
+    })
+    .await
+    .unwrap();
+
+    tokio::time::timeout(Duration::from_secs(5), async {
+        while provider.status() != ProviderStatus::Ready {
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+    })
+    .await
+    .unwrap();
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn logs_assignments() {
+    let server = server(ResponseTemplate::new(200).set_body_json(configuration())).await;
+    let events = Arc::new(Mutex::new(Vec::new()));
+    let logged = events.clone();
+    let provider = EppoProvider::new(EppoOptions {
+        assignment_logger: Some(Arc::new(move |event: AssignmentEvent| {
+            logged.lock().unwrap().push(event);
+        })),
+        ..options(&server)
+    })
+    .await
+    .unwrap();
+
+    provider
+        .resolve_bool_value("new-checkout", &user("NL"))
+        .await
+        .unwrap();
+
+    let events = events.lock().unwrap();
+    assert_eq!(events.len(), 1);
+    assert_eq!(&*events[0].base.allocation, "dutch-users");
+    assert_eq!(&*events[0].base.variation, "on");
+    assert_eq!(&*events[0].subject, "user-1");
+}
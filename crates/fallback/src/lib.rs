@@ -0,0 +1,241 @@
+//! Fallback provider combinator for OpenFeature.
+//!
+//! [`FallbackProvider`] wraps a primary and a secondary provider. Evaluations go to the primary;
+//! when it fails, the secondary answers instead. Once the primary failed
+//! [`FallbackOptions::failure_threshold`] times in a row, the provider fails over: evaluations go
+//! straight to the secondary, and every [`FallbackOptions::probe_interval`] one evaluation probes
+//! the primary. The first successful probe switches back. Both transitions are announced to the
+//! receivers of [`FallbackProvider::subscribe`].
+//!
+//! Only errors that indicate an unhealthy provider count as failures: `PROVIDER_NOT_READY`,
+//! `PARSE_ERROR` and `GENERAL`. Errors about the flag or the context, such as `FLAG_NOT_FOUND`
+//! or `TYPE_MISMATCH`, are returned as-is.
+//!
+//! # Example
+//!
+//! ```rust
+//! use open_feature::provider::{FeatureProvider, NoOpProvider};
+//! use open_feature::EvaluationContext;
+//! use open_feature_fallback::{FallbackEvent, FallbackOptions, FallbackProvider};
+//! use open_feature_in_memory::InMemoryProvider;
+//!
+//! #[tokio::main]
+//! async fn main() {
+//!     let local = InMemoryProvider::new();
+//!     local.set_flag("new-checkout", true, None, None);
+//!
+//!     // The no-op provider fails every evaluation with `PROVIDER_NOT_READY`.
+//!     let provider = FallbackProvider::new(
+//!         NoOpProvider::default(),
+//!         local,
+//!         FallbackOptions {
+//!             failure_threshold: 1,
+//!             ..Default::default()
+//!         },
+//!     );
+//!     let mut events = provider.subscribe();
+//!
+//!     let details = provider
+//!         .resolve_bool_value("new-checkout", &EvaluationContext::default())
+//!         .await
+//!         .unwrap();
+//!     assert!(details.value);
+//!     assert!(provider.is_failed_over());
+//!     assert_eq!(events.recv().await.unwrap(), FallbackEvent::FailedOver);
+//! }
+//! ```
+
+use std::sync::{Mutex, PoisonError};
+use std::time::Duration;
+
+use async_trait::async_trait;
+use open_feature::provider::{
+    FeatureProvider, ProviderMetadata, ProviderStatus, ResolutionDetails,
+};
+use open_feature::{EvaluationContext, EvaluationErrorCode, EvaluationResult, HookWrapper};
+use openfeature_contrib_common::{impl_resolve_methods, FlagValue};
+use tokio::sync::broadcast;
+use tokio::time::Instant;
+use tracing::{info, warn};
+
+/// Configuration of the [`FallbackProvider`].
+#[derive(Debug, Clone)]
+pub struct FallbackOptions {
+    /// Number of consecutive primary failures after which the provider fails over.
+    pub failure_threshold: u32,
+    /// Interval between probes of the primary while failed over.
+    pub probe_interval: Duration,
+}
+
+impl Default for FallbackOptions {
+    fn default() -> Self {
+        Self {
+            failure_threshold: 3,
+            probe_interval: Duration::from_secs(30),
+        }
+    }
+}
+
+/// A switch between the primary and the secondary provider.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FallbackEvent {
+    /// The primary failed repeatedly; evaluations go to the secondary.
+    FailedOver,
+    /// A probe of the primary succeeded; evaluations go to the primary again.
+    Recovered,
+}
+
+#[derive(Debug)]
+struct Health {
+    consecutive_failures: u32,
+    failed_over: bool,
+    last_probe: Instant,
+}
+
+enum Route {
+    Primary,
+    Secondary,
+}
+
+/// OpenFeature provider falling back from a primary to a secondary provider.
+///
+/// The provider runs the hooks of both providers.
+pub struct FallbackProvider<P, S> {
+    metadata: ProviderMetadata,
+    primary: P,
+    secondary: S,
+    hooks: Vec<HookWrapper>,
+    options: FallbackOptions,
+    health: Mutex<Health>,
+    events: broadcast::Sender<FallbackEvent>,
+}
+
+impl<P: FeatureProvider, S: FeatureProvider> FallbackProvider<P, S> {
+    /// Creates the provider, starting with the primary.
+    pub fn new(primary: P, secondary: S, options: FallbackOptions) -> Self {
+        let hooks = [primary.hooks(), secondary.hooks()].concat();
+        Self {
+            metadata: ProviderMetadata::new("fallback"),
+            hooks,
+            primary,
+            secondary,
+            options,
+            health: Mutex::new(Health {
+                consecutive_failures: 0,
+                failed_over: false,
+                last_probe: Instant::now(),
+            }),
+            events: broadcast::channel(16).0,
+        }
+    }
+
+    /// Returns a receiver of the switches made from now on.
+    pub fn subscribe(&self) -> broadcast::Receiver<FallbackEvent> {
+        self.events.subscribe()
+    }
+
+    /// Whether evaluations currently go to the secondary.
+    pub fn is_failed_over(&self) -> bool {
+        self.health().failed_over
+    }
+
+    fn health(&self) -> std::sync::MutexGuard<'_, Health> {
+        self.health.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    fn route(&self) -> Route {
+        let mut health = self.health();
+        if !health.failed_over {
+            return Route::Primary;
+        }
+        if health.last_probe.elapsed() < self.options.probe_interval {
+            return Route::Secondary;
+        }
+        health.last_probe = Instant::now();
+        Route::Primary
+    }
+
+    fn record_success(&self) {
+        let mut health = self.health();
+        health.consecutive_failures = 0;
+        if health.failed_over {
+            health.failed_over = false;
+            info!("Primary provider recovered, switching back");
+            let _ = self.events.send(FallbackEvent::Recovered);
+        }
+    }
+
+    fn record_failure(&self) {
+        let mut health = self.health();
+        if health.failed_over {
+            return;
+        }
+        health.consecutive_failures += 1;
+        if health.consecutive_failures >= self.options.failure_threshold.max(1) {
+            health.failed_over = true;
+            health.last_probe = Instant::now();
+            warn!(
+                "Primary provider failed {} times, failing over",
+                health.consecutive_failures
+            );
+            let _ = self.events.send(FallbackEvent::FailedOver);
+        }
+    }
+
+    async fn resolve<T: FlagValue>(
+        &self,
+        flag_key: &str,
+        context: &EvaluationContext,
+    ) -> EvaluationResult<ResolutionDetails<T>> {
+        if let Route::Secondary = self.route() {
+            return T::resolve(&self.secondary, flag_key, context).await;
+        }
+
+        match T::resolve(&self.primary, flag_key, context).await {
+            Err(e) if is_failure(&e.code) => {
+                self.record_failure();
+                T::resolve(&self.secondary, flag_key, context).await
+            }
+            result => {
+                self.record_success();
+                result
+            }
+        }
+    }
+}
+
+#[async_trait]
+impl<P: FeatureProvider, S: FeatureProvider> FeatureProvider for FallbackProvider<P, S> {
+    async fn initialize(&mut self, context: &EvaluationContext) {
+        self.primary.initialize(context).await;
+        self.secondary.initialize(context).await;
+    }
+
+    fn status(&self) -> ProviderStatus {
+        if self.is_failed_over() {
+            self.secondary.status()
+        } else {
+            self.primary.status()
+        }
+    }
+
+    fn metadata(&self) -> &ProviderMetadata {
+        &self.metadata
+    }
+
+    fn hooks(&self) -> &[HookWrapper] {
+        &self.hooks
+    }
+
+    impl_resolve_methods!();
+}
+
+/// Whether an error indicates an unhealthy provider rather than a problem with the flag.
+fn is_failure(code: &EvaluationErrorCode) -> bool {
+    matches!(
+        code,
+        EvaluationErrorCode::ProviderNotReady
+            | EvaluationErrorCode::ParseError
+            | EvaluationErrorCode::General(_)
+    )
+}
@@ -0,0 +1,164 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use async_trait::async_trait;
+use open_feature::provider::{FeatureProvider, ProviderMetadata, ResolutionDetails};
+use open_feature::{
+    EvaluationContext, EvaluationError, EvaluationErrorCode, EvaluationResult, LoggingHook,
+};
+use open_feature_fallback::{FallbackEvent, FallbackOptions, FallbackProvider};
+use open_feature_in_memory::InMemoryProvider;
+use openfeature_contrib_common::{impl_resolve_methods, FlagValue};
+use tokio::sync::broadcast::error::TryRecvError;
+
+/// Provider failing with `PROVIDER_NOT_READY` while unhealthy.
+struct Flaky {
+    flags: InMemoryProvider,
+    healthy: Arc<AtomicBool>,
+}
+
+impl Flaky {
+    async fn resolve<T: FlagValue>(
+        &self,
+        flag_key: &str,
+        context: &EvaluationContext,
+    ) -> EvaluationResult<ResolutionDetails<T>> {
+        if !self.healthy.load(Ordering::Relaxed) {
+            return Err(EvaluationError::builder()
+                .code(EvaluationErrorCode::ProviderNotReady)
+                .build());
+        }
+        T::resolve(&self.flags, flag_key, context).await
+    }
+}
+
+#[async_trait]
+impl FeatureProvider for Flaky {
+    fn metadata(&self) -> &ProviderMetadata {
+        self.flags.metadata()
+    }
+
+    impl_resolve_methods!();
+}
+
+fn flags(variant: &str) -> InMemoryProvider {
+    let flags = InMemoryProvider::new();
+    flags.set_flag("enabled", true, Some(variant), None);
+    flags
+}
+
+/// Provider falling back from a flaky primary after 3 failures, with its health switch.
+fn fallback() -> (FallbackProvider<Flaky, InMemoryProvider>, Arc<AtomicBool>) {
+    let healthy = Arc::new(AtomicBool::new(true));
+    let primary = Flaky {
+        flags: flags("primary"),
+        healthy: healthy.clone(),
+    };
+    let options = FallbackOptions {
+        failure_threshold: 3,
+        probe_interval: Duration::from_secs(10),
+    };
+    (
+        FallbackProvider::new(primary, flags("secondary"), options),
+        healthy,
+    )
+}
+
+/// Variant of an evaluation of `enabled`, telling which provider answered.
+async fn variant<P: FeatureProvider>(provider: &P) -> String {
+    provider
+        .resolve_bool_value("enabled", &EvaluationContext::default())
+        .await
+        .unwrap()
+        .variant
+        .unwrap()
+}
+
+#[tokio::test(start_paused = true)]
+async fn fails_over_after_the_failure_threshold() {
+    let (provider, healthy) = fallback();
+    let mut events = provider.subscribe();
+    assert_eq!(variant(&provider).await, "primary");
+
+    healthy.store(false, Ordering::Relaxed);
+    for _ in 0..2 {
+        assert_eq!(variant(&provider).await, "secondary");
+        assert!(!provider.is_failed_over());
+    }
+    assert_eq!(events.try_recv().unwrap_err(), TryRecvError::Empty);
+
+    assert_eq!(variant(&provider).await, "secondary");
+    assert!(provider.is_failed_over());
+    assert_eq!(events.try_recv().unwrap(), FallbackEvent::FailedOver);
+}
+
+#[tokio::test(start_paused = true)]
+async fn successes_reset_the_failure_count() {
+    let (provider, healthy) = fallback();
+    for _ in 0..3 {
+        healthy.store(false, Ordering::Relaxed);
+        variant(&provider).await;
+        variant(&provider).await;
+        healthy.store(true, Ordering::Relaxed);
+        assert_eq!(variant(&provider).await, "primary");
+    }
+    assert!(!provider.is_failed_over());
+}
+
+#[tokio::test(start_paused = true)]
+async fn flag_errors_are_not_failures() {
+    let (provider, _) = fallback();
+    let context = EvaluationContext::default();
+    for _ in 0..5 {
+        let error = provider
+            .resolve_bool_value("missing", &context)
+            .await
+            .unwrap_err();
+        assert_eq!(error.code, EvaluationErrorCode::FlagNotFound);
+    }
+    assert!(!provider.is_failed_over());
+}
+
+#[tokio::test(start_paused = true)]
+async fn probes_the_primary_every_interval_and_switches_back() {
+    let (provider, healthy) = fallback();
+    let mut events = provider.subscribe();
+    healthy.store(false, Ordering::Relaxed);
+    for _ in 0..3 {
+        variant(&provider).await;
+    }
+    assert_eq!(events.try_recv().unwrap(), FallbackEvent::FailedOver);
+
+    // Failed over, the primary is not evaluated until the probe interval elapsed.
+    healthy.store(true, Ordering::Relaxed);
+    tokio::time::advance(Duration::from_secs(9)).await;
+    assert_eq!(variant(&provider).await, "secondary");
+
+    // A failed probe keeps the provider failed over for another interval.
+    healthy.store(false, Ordering::Relaxed);
+    tokio::time::advance(Duration::from_secs(1)).await;
+    assert_eq!(variant(&provider).await, "secondary");
+    assert!(provider.is_failed_over());
+    healthy.store(true, Ordering::Relaxed);
+    assert_eq!(variant(&provider).await, "secondary");
+
+    tokio::time::advance(Duration::from_secs(10)).await;
+    assert_eq!(variant(&provider).await, "primary");
+    assert!(!provider.is_failed_over());
+    assert_eq!(events.try_recv().unwrap(), FallbackEvent::Recovered);
+    assert_eq!(variant(&provider).await, "primary");
+    assert_eq!(events.try_recv().unwrap_err(), TryRecvError::Empty);
+}
+
+#[test]
+fn reports_the_hooks_of_both_providers() {
+    let provider = FallbackProvider::new(
+        InMemoryProvider::new().with_hook(LoggingHook::default()),
+        InMemoryProvider::new()
+            .with_hook(LoggingHook::default())
+            .with_hook(LoggingHook::default()),
+        FallbackOptions::default(),
+    );
+    assert_eq!(provider.hooks().len(), 3);
+}
@@ -0,0 +1,53 @@
+use std::collections::HashMap;
+
+use open_feature::EvaluationContext;
+use openfeature_contrib_common::{fields_to_strings, DateTimeFormat};
+
+/// FeatureHub attribute holding the user key.
+pub(crate) const USER_KEY: &str = "userkey";
+
+/// Converts an [`EvaluationContext`] into FeatureHub client context attributes.
+///
+/// The targeting key becomes `userkey`; custom fields are used under their own name, formatted
+/// as strings (date-times as RFC 3339, `StructValue` fields as JSON). Other struct fields are
+/// skipped.
+pub(crate) fn to_attributes(context: &EvaluationContext) -> HashMap<String, String> {
+    let mut attributes = fields_to_strings(context, DateTimeFormat::Rfc3339);
+    if let Some(targeting_key) = &context.targeting_key {
+        attributes.insert(USER_KEY.to_string(), targeting_key.clone());
+    }
+    attributes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn targeting_key_becomes_the_user_key() {
+        let context = EvaluationContext::default()
+            .with_targeting_key("user-1")
+            .with_custom_field("country", "new_zealand")
+            .with_custom_field("version", 2)
+            .with_custom_field("beta", true);
+
+        assert_eq!(
+            to_attributes(&context),
+            HashMap::from([
+                (USER_KEY.to_string(), "user-1".to_string()),
+                ("country".to_string(), "new_zealand".to_string()),
+                ("version".to_string(), "2".to_string()),
+                ("beta".to_string(), "true".to_string()),
+            ])
+        );
+    }
+
+    #[test]
+    fn targeting_key_takes_precedence_over_a_user_key_field() {
+        let context = EvaluationContext::default()
+            .with_targeting_key("user-1")
+            .with_custom_field(USER_KEY, "user-2");
+
+        assert_eq!(to_attributes(&context)[USER_KEY], "user-1");
+    }
+}
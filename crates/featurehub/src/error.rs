@@ -0,0 +1,17 @@
+use thiserror::Error;
+
+/// Errors raised while creating a [`FeatureHubProvider`](crate::FeatureHubProvider).
+#[derive(Debug, Error)]
+pub enum FeatureHubError {
+    /// The HTTP client could not be created.
+    #[error("failed to create the HTTP client: {0}")]
+    Http(#[from] reqwest::Error),
+
+    /// FeatureHub rejected the API key.
+    #[error("FeatureHub rejected the API key")]
+    Failed,
+
+    /// No feature state arrived within [`FeatureHubOptions::wait_for_features`](crate::FeatureHubOptions::wait_for_features).
+    #[error("timed out waiting for the first FeatureHub feature states")]
+    Timeout,
+}
@@ -0,0 +1,504 @@
+//! Feature states delivered by the FeatureHub Edge and their client-side evaluation.
+//!
+//! Client-evaluated API keys receive the rollout strategies of every feature, which are applied
+//! here the way the official SDKs do: percentage rollouts hash the percentage key and the feature
+//! id with 32-bit MurmurHash3 onto `[0, 1_000_000)`, so a user gets the same value as with any
+//! other FeatureHub SDK.
+
+use std::cmp::Ordering;
+use std::collections::HashMap;
+use std::io::Cursor;
+use std::net::IpAddr;
+
+use open_feature::EvaluationReason;
+use regex::Regex;
+use serde::Deserialize;
+
+use crate::context::USER_KEY;
+
+/// Fallback attribute used as percentage key when there is no user key.
+const SESSION: &str = "session";
+
+#[derive(Debug, Clone, Deserialize)]
+pub(crate) struct FeatureState {
+    pub(crate) id: String,
+    pub(crate) key: String,
+    #[serde(default)]
+    pub(crate) version: Option<i64>,
+    #[serde(rename = "type", default)]
+    pub(crate) kind: Option<FeatureValueType>,
+    #[serde(default)]
+    pub(crate) value: serde_json::Value,
+    #[serde(default)]
+    strategies: Vec<RolloutStrategy>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "UPPERCASE")]
+pub(crate) enum FeatureValueType {
+    Boolean,
+    String,
+    Number,
+    Json,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct RolloutStrategy {
+    #[serde(default)]
+    id: Option<String>,
+    #[serde(default)]
+    value: serde_json::Value,
+    #[serde(default)]
+    percentage: Option<u32>,
+    #[serde(default)]
+    percentage_attributes: Vec<String>,
+    #[serde(default)]
+    attributes: Vec<StrategyAttribute>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct StrategyAttribute {
+    conditional: Conditional,
+    field_name: String,
+    #[serde(default)]
+    values: Vec<serde_json::Value>,
+    #[serde(rename = "type")]
+    kind: AttributeType,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+enum Conditional {
+    Equals,
+    EndsWith,
+    StartsWith,
+    Greater,
+    GreaterEquals,
+    Less,
+    LessEquals,
+    NotEquals,
+    Includes,
+    Excludes,
+    Regex,
+    #[serde(other)]
+    Unknown,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+enum AttributeType {
+    String,
+    SemanticVersion,
+    Number,
+    Date,
+    Datetime,
+    Boolean,
+    IpAddress,
+    #[serde(other)]
+    Unknown,
+}
+
+/// Outcome of evaluating a [`FeatureState`].
+#[derive(Debug)]
+pub(crate) struct Evaluation<'a> {
+    pub(crate) value: &'a serde_json::Value,
+    pub(crate) strategy_id: Option<&'a str>,
+    pub(crate) reason: EvaluationReason,
+}
+
+impl FeatureState {
+    pub(crate) fn evaluate(&self, attributes: &HashMap<String, String>) -> Evaluation<'_> {
+        let default_reason = if self.strategies.is_empty() {
+            EvaluationReason::Static
+        } else {
+            EvaluationReason::Default
+        };
+        let default_percentage_key = attributes.get(USER_KEY).or_else(|| attributes.get(SESSION));
+
+        // Percentage strategies without attributes stack, keyed by percentage key.
+        let mut base_percentage: HashMap<String, u32> = HashMap::new();
+        for strategy in &self.strategies {
+            let percentage = strategy.percentage.unwrap_or_default();
+            let percentage_key = if strategy.percentage_attributes.is_empty() {
+                default_percentage_key.cloned()
+            } else {
+                Some(
+                    strategy
+                        .percentage_attributes
+                        .iter()
+                        .map(|name| attributes.get(name).map_or("<none>", String::as_str))
+                        .collect::<Vec<_>>()
+                        .join("$"),
+                )
+            };
+
+            match percentage_key.filter(|_| percentage != 0) {
+                Some(percentage_key) => {
+                    let base = base_percentage.entry(percentage_key.clone()).or_default();
+                    let user_percentage = client_percentage(&percentage_key, &self.id);
+                    let threshold = if strategy.attributes.is_empty() {
+                        *base
+                    } else {
+                        0
+                    };
+                    if user_percentage <= threshold.saturating_add(percentage)
+                        && strategy.matches(attributes)
+                    {
+                        return strategy.evaluation(EvaluationReason::Split);
+                    }
+                    if strategy.attributes.is_empty() {
+                        *base = base.saturating_add(percentage);
+                    }
+                }
+                None if !strategy.attributes.is_empty() && strategy.matches(attributes) => {
+                    return strategy.evaluation(EvaluationReason::TargetingMatch);
+                }
+                None => {}
+            }
+        }
+
+        Evaluation {
+            value: &self.value,
+            strategy_id: None,
+            reason: default_reason,
+        }
+    }
+}
+
+impl RolloutStrategy {
+    fn matches(&self, attributes: &HashMap<String, String>) -> bool {
+        self.attributes.iter().all(|attribute| {
+            attributes
+                .get(&attribute.field_name)
+                .is_some_and(|value| attribute.matches(value))
+        })
+    }
+
+    fn evaluation(&self, reason: EvaluationReason) -> Evaluation<'_> {
+        Evaluation {
+            value: &self.value,
+            strategy_id: self.id.as_deref(),
+            reason,
+        }
+    }
+}
+
+impl StrategyAttribute {
+    fn matches(&self, value: &str) -> bool {
+        let mut expected = self.values.iter().map(|expected| match expected {
+            serde_json::Value::String(expected) => expected.clone(),
+            expected => expected.to_string(),
+        });
+
+        match self.conditional {
+            Conditional::NotEquals => !expected.any(|expected| self.equals(value, &expected)),
+            Conditional::Excludes => !expected.any(|expected| self.includes(value, &expected)),
+            Conditional::Equals => expected.any(|expected| self.equals(value, &expected)),
+            Conditional::Includes => expected.any(|expected| self.includes(value, &expected)),
+            Conditional::StartsWith => expected.any(|expected| value.starts_with(&expected)),
+            Conditional::EndsWith => expected.any(|expected| value.ends_with(&expected)),
+            Conditional::Regex => expected
+                .any(|expected| Regex::new(&expected).is_ok_and(|regex| regex.is_match(value))),
+            Conditional::Greater => self.compares(value, expected, Ordering::is_gt),
+            Conditional::GreaterEquals => self.compares(value, expected, Ordering::is_ge),
+            Conditional::Less => self.compares(value, expected, Ordering::is_lt),
+            Conditional::LessEquals => self.compares(value, expected, Ordering::is_le),
+            Conditional::Unknown => false,
+        }
+    }
+
+    fn equals(&self, value: &str, expected: &str) -> bool {
+        match self.kind {
+            AttributeType::IpAddress => ip_matches(value, expected),
+            _ => self.compare(value, expected) == Some(Ordering::Equal),
+        }
+    }
+
+    fn includes(&self, value: &str, expected: &str) -> bool {
+        match self.kind {
+            AttributeType::String => value.contains(expected),
+            _ => self.equals(value, expected),
+        }
+    }
+
+    fn compares(
+        &self,
+        value: &str,
+        mut expected: impl Iterator<Item = String>,
+        predicate: fn(Ordering) -> bool,
+    ) -> bool {
+        expected.any(|expected| self.compare(value, &expected).is_some_and(predicate))
+    }
+
+    fn compare(&self, value: &str, expected: &str) -> Option<Ordering> {
+        match self.kind {
+            AttributeType::Number => value
+                .parse::<f64>()
+                .ok()?
+                .partial_cmp(&expected.parse::<f64>().ok()?),
+            AttributeType::Boolean => Some(
+                value
+                    .eq_ignore_ascii_case("true")
+                    .cmp(&expected.eq_ignore_ascii_case("true")),
+            ),
+            AttributeType::SemanticVersion => Some(compare_versions(value, expected)),
+            // ISO 8601 dates and date-times order lexicographically.
+            AttributeType::String
+            | AttributeType::Date
+            | AttributeType::Datetime
+            | AttributeType::IpAddress => Some(value.cmp(expected)),
+            AttributeType::Unknown => None,
+        }
+    }
+}
+
+/// Compares dot-separated versions component by component, numerically where possible.
+fn compare_versions(value: &str, expected: &str) -> Ordering {
+    let parts = |version: &str| {
+        version
+            .split(['.', '-', '+'])
+            .map(str::to_string)
+            .collect::<Vec<_>>()
+    };
+    let (value, expected) = (parts(value), parts(expected));
+    for i in 0..value.len().max(expected.len()) {
+        let a = value.get(i).map_or("0", String::as_str);
+        let b = expected.get(i).map_or("0", String::as_str);
+        let ordering = match (a.parse::<u64>(), b.parse::<u64>()) {
+            (Ok(a), Ok(b)) => a.cmp(&b),
+            _ => a.cmp(b),
+        };
+        if ordering.is_ne() {
+            return ordering;
+        }
+    }
+    Ordering::Equal
+}
+
+/// Matches an IP address against an address or a CIDR block.
+fn ip_matches(value: &str, expected: &str) -> bool {
+    let Ok(address) = value.parse::<IpAddr>() else {
+        return false;
+    };
+    let Some((network, prefix)) = expected.split_once('/') else {
+        return expected
+            .parse::<IpAddr>()
+            .is_ok_and(|expected| expected == address);
+    };
+    let (Ok(network), Ok(prefix)) = (network.parse::<IpAddr>(), prefix.parse::<u32>()) else {
+        return false;
+    };
+
+    match (address, network) {
+        (IpAddr::V4(address), IpAddr::V4(network)) if prefix <= 32 => {
+            let mask = u32::MAX.checked_shl(32 - prefix).unwrap_or(0);
+            u32::from(address) & mask == u32::from(network) & mask
+        }
+        (IpAddr::V6(address), IpAddr::V6(network)) if prefix <= 128 => {
+            let mask = u128::MAX.checked_shl(128 - prefix).unwrap_or(0);
+            u128::from(address) & mask == u128::from(network) & mask
+        }
+        _ => false,
+    }
+}
+
+/// Maps a percentage key onto `[0, 1_000_000)`, FeatureHub's percentage resolution.
+fn client_percentage(percentage_key: &str, feature_id: &str) -> u32 {
+    let input = format!("{percentage_key}{feature_id}");
+    let hash = murmur3::murmur3_32(&mut Cursor::new(input.as_bytes()), 0)
+        .expect("reading from memory cannot fail");
+    (f64::from(hash) / 2f64.powi(32) * 1_000_000.0) as u32
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn feature(strategies: serde_json::Value) -> FeatureState {
+        serde_json::from_value(json!({
+            "id": "feature-1",
+            "key": "NEW_CHECKOUT",
+            "version": 1,
+            "type": "STRING",
+            "value": "default",
+            "strategies": strategies
+        }))
+        .expect("invalid feature state")
+    }
+
+    fn attributes(attributes: &[(&str, &str)]) -> HashMap<String, String> {
+        attributes
+            .iter()
+            .map(|(name, value)| (name.to_string(), value.to_string()))
+            .collect()
+    }
+
+    fn evaluate(feature: &FeatureState, attributes: &[(&str, &str)]) -> serde_json::Value {
+        feature
+            .evaluate(&self::attributes(attributes))
+            .value
+            .clone()
+    }
+
+    fn attribute(conditional: &str, kind: &str, values: serde_json::Value) -> StrategyAttribute {
+        serde_json::from_value(json!({
+            "conditional": conditional,
+            "fieldName": "field",
+            "type": kind,
+            "values": values
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn percentages_match_the_featurehub_sdks() {
+        assert_eq!(client_percentage("user-1", "feature-1"), 508_438);
+        assert_eq!(client_percentage("user-2", "feature-1"), 225_843);
+        assert_eq!(client_percentage("nz$ios", "feature-1"), 565_571);
+    }
+
+    #[test]
+    fn features_without_strategies_are_static() {
+        let feature = feature(json!([]));
+        let evaluation = feature.evaluate(&attributes(&[("userkey", "user-1")]));
+
+        assert_eq!(evaluation.value, "default");
+        assert_eq!(evaluation.reason, EvaluationReason::Static);
+        assert_eq!(evaluation.strategy_id, None);
+    }
+
+    #[test]
+    fn percentage_strategies_stack() {
+        // user-2 is at 22.6%, user-4 at 35.5% and user-8 at 66.8%.
+        let feature = feature(json!([
+            {"id": "a", "value": "a", "percentage": 300_000},
+            {"id": "b", "value": "b", "percentage": 300_000}
+        ]));
+
+        let evaluation = feature.evaluate(&attributes(&[("userkey", "user-2")]));
+        assert_eq!(evaluation.value, "a");
+        assert_eq!(evaluation.strategy_id, Some("a"));
+        assert_eq!(evaluation.reason, EvaluationReason::Split);
+        assert_eq!(evaluate(&feature, &[("userkey", "user-4")]), "b");
+        let evaluation = feature.evaluate(&attributes(&[("userkey", "user-8")]));
+        assert_eq!(evaluation.value, "default");
+        assert_eq!(evaluation.reason, EvaluationReason::Default);
+    }
+
+    #[test]
+    fn sessions_stand_in_for_user_keys() {
+        let feature = feature(json!([{"value": "a", "percentage": 300_000}]));
+
+        assert_eq!(evaluate(&feature, &[("session", "user-2")]), "a");
+        assert_eq!(evaluate(&feature, &[]), "default");
+    }
+
+    #[test]
+    fn percentage_attributes_replace_the_percentage_key() {
+        // nz$ios is at 56.6% and au$ios at 28.9%.
+        let feature = feature(json!([{
+            "value": "a",
+            "percentage": 300_000,
+            "percentageAttributes": ["country", "platform"]
+        }]));
+
+        assert_eq!(
+            evaluate(&feature, &[("country", "au"), ("platform", "ios")]),
+            "a"
+        );
+        assert_eq!(
+            evaluate(&feature, &[("country", "nz"), ("platform", "ios")]),
+            "default"
+        );
+    }
+
+    #[test]
+    fn attribute_strategies_target_matching_contexts() {
+        let feature = feature(json!([{
+            "id": "nz",
+            "value": "kiwi",
+            "attributes": [{
+                "conditional": "EQUALS",
+                "fieldName": "country",
+                "type": "STRING",
+                "values": ["new_zealand"]
+            }]
+        }]));
+
+        let evaluation = feature.evaluate(&attributes(&[("country", "new_zealand")]));
+        assert_eq!(evaluation.value, "kiwi");
+        assert_eq!(evaluation.reason, EvaluationReason::TargetingMatch);
+        assert_eq!(evaluate(&feature, &[("country", "australia")]), "default");
+        assert_eq!(evaluate(&feature, &[]), "default");
+    }
+
+    #[test]
+    fn percentages_with_attributes_do_not_stack() {
+        // user-2 is at 22.6% and user-4 at 35.5%.
+        let feature = feature(json!([
+            {"value": "a", "percentage": 300_000},
+            {
+                "value": "b",
+                "percentage": 300_000,
+                "attributes": [{
+                    "conditional": "EQUALS",
+                    "fieldName": "plan",
+                    "type": "STRING",
+                    "values": ["premium"]
+                }]
+            }
+        ]));
+
+        assert_eq!(
+            evaluate(&feature, &[("userkey", "user-4"), ("plan", "premium")]),
+            "default"
+        );
+        assert_eq!(evaluate(&feature, &[("userkey", "user-2")]), "a");
+    }
+
+    #[test]
+    fn conditionals_compare_by_attribute_type() {
+        assert!(attribute("STARTS_WITH", "STRING", json!(["ab"])).matches("abc"));
+        assert!(attribute("ENDS_WITH", "STRING", json!(["bc"])).matches("abc"));
+        assert!(attribute("INCLUDES", "STRING", json!(["b"])).matches("abc"));
+        assert!(!attribute("EXCLUDES", "STRING", json!(["b"])).matches("abc"));
+        assert!(attribute("NOT_EQUALS", "STRING", json!(["a", "b"])).matches("c"));
+        assert!(attribute("REGEX", "STRING", json!(["^a.c$"])).matches("abc"));
+        assert!(!attribute("REGEX", "STRING", json!(["("])).matches("abc"));
+        assert!(attribute("GREATER", "NUMBER", json!([9])).matches("10"));
+        assert!(!attribute("GREATER", "NUMBER", json!([9])).matches("ten"));
+        assert!(attribute("LESS_EQUALS", "NUMBER", json!(["2.5"])).matches("2.5"));
+        assert!(attribute("EQUALS", "BOOLEAN", json!([true])).matches("TRUE"));
+        assert!(attribute("LESS", "DATE", json!(["2024-06-01"])).matches("2024-05-31"));
+        assert!(!attribute("UNKNOWN", "STRING", json!(["a"])).matches("a"));
+    }
+
+    #[test]
+    fn semantic_versions_compare_numerically() {
+        assert!(attribute("GREATER", "SEMANTIC_VERSION", json!(["1.9.0"])).matches("1.10.0"));
+        assert!(attribute("EQUALS", "SEMANTIC_VERSION", json!(["2"])).matches("2.0.0"));
+        assert!(attribute("LESS", "SEMANTIC_VERSION", json!(["1.0.0-rc2"])).matches("1.0.0-rc1"));
+    }
+
+    #[test]
+    fn ip_addresses_match_addresses_and_cidr_blocks() {
+        let attribute = attribute(
+            "EQUALS",
+            "IP_ADDRESS",
+            json!(["10.0.0.0/8", "192.168.1.1", "2001:db8::/32"]),
+        );
+
+        assert!(attribute.matches("10.1.2.3"));
+        assert!(attribute.matches("192.168.1.1"));
+        assert!(attribute.matches("2001:db8::1"));
+        assert!(!attribute.matches("192.168.1.2"));
+        assert!(!attribute.matches("11.0.0.1"));
+        assert!(!attribute.matches("not an address"));
+        assert!(ip_matches("1.2.3.4", "0.0.0.0/0"));
+        assert!(!ip_matches("1.2.3.4", "1.2.3.4/33"));
+    }
+}
@@ -0,0 +1,315 @@
+//! [FeatureHub](https://www.featurehub.io/) provider for OpenFeature.
+//!
+//! The provider streams feature states from the FeatureHub Edge over server-sent events and
+//! evaluates them locally. It is meant for client-evaluated API keys (those containing `*`),
+//! for which the Edge delivers the rollout strategies of every feature; strategies are applied
+//! per evaluation with the attributes of the evaluation context.
+//!
+//! The provider reports `NOT_READY` until the first feature states arrive, `STALE` while the
+//! stream reconnects and `ERROR` when FeatureHub rejects the API key.
+//!
+//! # Context mapping
+//!
+//! The targeting key becomes the `userkey` attribute. Custom fields are used as attributes under
+//! their own name, e.g. `country`, `platform`, `version` or `session`; date-times are formatted as
+//! RFC 3339, `StructValue` fields as JSON and other struct fields are skipped.
+//!
+//! # Example
+//!
+//! ```rust,no_run
+//! use open_feature::provider::FeatureProvider;
+//! use open_feature::EvaluationContext;
+//! use open_feature_featurehub::{FeatureHubOptions, FeatureHubProvider};
+//!
+//! #[tokio::main]
+//! async fn main() {
+//!     let provider = FeatureHubProvider::new(FeatureHubOptions {
+//!         edge_url: "https://edge.example.com".to_string(),
+//!         api_key: "environment-id/client-eval-key*secret".to_string(),
+//!         ..Default::default()
+//!     })
+//!     .await
+//!     .expect("Failed to create FeatureHub provider");
+//!
+//!     let context = EvaluationContext::default()
+//!         .with_targeting_key("user-123")
+//!         .with_custom_field("country", "new_zealand");
+//!     let details = provider.resolve_bool_value("NEW_CHECKOUT", &context).await;
+//!     println!("{details:?}");
+//! }
+//! ```
+
+mod context;
+mod error;
+mod feature;
+mod stream;
+
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+
+use async_trait::async_trait;
+use open_feature::provider::{
+    FeatureProvider, ProviderMetadata, ProviderStatus, ResolutionDetails,
+};
+use open_feature::{
+    EvaluationContext, EvaluationError, EvaluationErrorCode, EvaluationResult, FlagMetadata,
+    StructValue, Value,
+};
+use tokio::sync::{watch, RwLock};
+use tokio::task::JoinHandle;
+
+pub use openfeature_contrib_common::BackoffPolicy;
+
+use crate::context::to_attributes;
+pub use crate::error::FeatureHubError;
+use crate::feature::FeatureValueType;
+use crate::stream::{spawn_stream, Features, StreamStatus};
+
+/// Configuration of the [`FeatureHubProvider`].
+#[derive(Debug, Clone)]
+pub struct FeatureHubOptions {
+    /// Base URL of the FeatureHub Edge.
+    pub edge_url: String,
+    /// Client-evaluated API key of the environment.
+    pub api_key: String,
+    /// How long [`FeatureHubProvider::new`] waits for the first feature states. `None` returns
+    /// immediately; evaluations report `PROVIDER_NOT_READY` until the feature states arrive.
+    pub wait_for_features: Option<Duration>,
+    /// Delays before reconnecting after the stream ended, growing while reconnecting fails.
+    pub reconnect_backoff: BackoffPolicy,
+}
+
+impl Default for FeatureHubOptions {
+    fn default() -> Self {
+        Self {
+            edge_url: "http://localhost:8085".to_string(),
+            api_key: String::new(),
+            wait_for_features: Some(Duration::from_secs(5)),
+            reconnect_backoff: BackoffPolicy::default(),
+        }
+    }
+}
+
+/// OpenFeature provider evaluating FeatureHub feature states locally.
+pub struct FeatureHubProvider {
+    metadata: ProviderMetadata,
+    features: Features,
+    status: watch::Receiver<StreamStatus>,
+    stream: JoinHandle<()>,
+}
+
+impl FeatureHubProvider {
+    /// Creates the provider and opens the feature stream.
+    pub async fn new(options: FeatureHubOptions) -> Result<Self, FeatureHubError> {
+        // Streams stay open indefinitely, so only connecting is subject to a timeout.
+        let http = reqwest::Client::builder()
+            .connect_timeout(Duration::from_secs(10))
+            .build()?;
+        let url = format!(
+            "{}/features/{}",
+            options.edge_url.trim_end_matches('/'),
+            options.api_key
+        );
+
+        let features = Arc::new(RwLock::new(HashMap::new()));
+        let (sender, mut status) = watch::channel(StreamStatus::NotReady);
+        let stream = spawn_stream(
+            http,
+            url,
+            features.clone(),
+            sender,
+            options.reconnect_backoff.backoff(),
+        );
+        let provider = Self {
+            metadata: ProviderMetadata::new("featurehub"),
+            features,
+            status: status.clone(),
+            stream,
+        };
+
+        if let Some(timeout) = options.wait_for_features {
+            let ready = tokio::time::timeout(
+                timeout,
+                status.wait_for(|status| *status != StreamStatus::NotReady),
+            )
+            .await
+            .map_err(|_| FeatureHubError::Timeout)?
+            .map(|status| *status);
+            // The stream may already have ended again after delivering the feature states.
+            if !matches!(ready, Ok(StreamStatus::Ready | StreamStatus::Stale)) {
+                return Err(FeatureHubError::Failed);
+            }
+        }
+
+        Ok(provider)
+    }
+
+    async fn resolve<T>(
+        &self,
+        flag_key: &str,
+        context: &EvaluationContext,
+        value_type: FeatureValueType,
+        convert: impl FnOnce(&serde_json::Value) -> EvaluationResult<T>,
+    ) -> EvaluationResult<ResolutionDetails<T>> {
+        if matches!(
+            *self.status.borrow(),
+            StreamStatus::NotReady | StreamStatus::Failed
+        ) {
+            return Err(EvaluationError::builder()
+                .code(EvaluationErrorCode::ProviderNotReady)
+                .message("No FeatureHub feature states received")
+                .build());
+        }
+
+        let features = self.features.read().await;
+        let feature = features.get(flag_key).ok_or_else(|| {
+            EvaluationError::builder()
+                .code(EvaluationErrorCode::FlagNotFound)
+                .message(format!("Feature {flag_key} not found"))
+                .build()
+        })?;
+        if let Some(kind) = feature.kind.filter(|kind| *kind != value_type) {
+            return Err(type_mismatch(format!(
+                "Feature {flag_key} is of type {kind:?}"
+            )));
+        }
+
+        let evaluation = feature.evaluate(&to_attributes(context));
+        if evaluation.value.is_null() {
+            return Err(EvaluationError::builder()
+                .code(EvaluationErrorCode::General("No value".to_string()))
+                .message(format!("Feature {flag_key} has no value"))
+                .build());
+        }
+
+        let mut flag_metadata = FlagMetadata::default();
+        if let Some(version) = feature.version {
+            flag_metadata.add_value("version", version);
+        }
+        if let Some(strategy_id) = evaluation.strategy_id {
+            flag_metadata.add_value("strategyId", strategy_id);
+        }
+
+        Ok(ResolutionDetails {
+            value: convert(evaluation.value)?,
+            variant: None,
+            reason: Some(evaluation.reason),
+            flag_metadata: Some(flag_metadata),
+        })
+    }
+}
+
+impl Drop for FeatureHubProvider {
+    fn drop(&mut self) {
+        self.stream.abort();
+    }
+}
+
+#[async_trait]
+impl FeatureProvider for FeatureHubProvider {
+    fn metadata(&self) -> &ProviderMetadata {
+        &self.metadata
+    }
+
+    fn status(&self) -> ProviderStatus {
+        match *self.status.borrow() {
+            StreamStatus::NotReady => ProviderStatus::NotReady,
+            StreamStatus::Ready => ProviderStatus::Ready,
+            StreamStatus::Stale => ProviderStatus::STALE,
+            StreamStatus::Failed => ProviderStatus::Error,
+        }
+    }
+
+    async fn resolve_bool_value(
+        &self,
+        flag_key: &str,
+        context: &EvaluationContext,
+    ) -> EvaluationResult<ResolutionDetails<bool>> {
+        self.resolve(flag_key, context, FeatureValueType::Boolean, |value| {
+            value
+                .as_bool()
+                .ok_or_else(|| type_mismatch(format!("Value of {flag_key} is not a boolean")))
+        })
+        .await
+    }
+
+    async fn resolve_int_value(
+        &self,
+        flag_key: &str,
+        context: &EvaluationContext,
+    ) -> EvaluationResult<ResolutionDetails<i64>> {
+        self.resolve(flag_key, context, FeatureValueType::Number, |value| {
+            value
+                .as_i64()
+                .or_else(|| {
+                    value
+                        .as_f64()
+                        .filter(|value| value.fract() == 0.0)
+                        .map(|value| value as i64)
+                })
+                .ok_or_else(|| type_mismatch(format!("Value of {flag_key} is not an integer")))
+        })
+        .await
+    }
+
+    async fn resolve_float_value(
+        &self,
+        flag_key: &str,
+        context: &EvaluationContext,
+    ) -> EvaluationResult<ResolutionDetails<f64>> {
+        self.resolve(flag_key, context, FeatureValueType::Number, |value| {
+            value
+                .as_f64()
+                .ok_or_else(|| type_mismatch(format!("Value of {flag_key} is not a number")))
+        })
+        .await
+    }
+
+    async fn resolve_string_value(
+        &self,
+        flag_key: &str,
+        context: &EvaluationContext,
+    ) -> EvaluationResult<ResolutionDetails<String>> {
+        self.resolve(flag_key, context, FeatureValueType::String, |value| {
+            value
+                .as_str()
+                .map(str::to_string)
+                .ok_or_else(|| type_mismatch(format!("Value of {flag_key} is not a string")))
+        })
+        .await
+    }
+
+    async fn resolve_struct_value(
+        &self,
+        flag_key: &str,
+        context: &EvaluationContext,
+    ) -> EvaluationResult<ResolutionDetails<StructValue>> {
+        self.resolve(flag_key, context, FeatureValueType::Json, |value| {
+            // JSON features carry their value as an encoded string.
+            let json = match value {
+                serde_json::Value::String(value) => serde_json::from_str(value).map_err(|e| {
+                    EvaluationError::builder()
+                        .code(EvaluationErrorCode::ParseError)
+                        .message(format!("Value of {flag_key} is not valid JSON: {e}"))
+                        .build()
+                })?,
+                value => value.clone(),
+            };
+            match Value::try_from(json)? {
+                Value::Struct(value) => Ok(value),
+                _ => Err(type_mismatch(format!(
+                    "Value of {flag_key} is not a JSON object"
+                ))),
+            }
+        })
+        .await
+    }
+}
+
+fn type_mismatch(message: String) -> EvaluationError {
+    EvaluationError::builder()
+        .code(EvaluationErrorCode::TypeMismatch)
+        .message(message)
+        .build()
+}
@@ -0,0 +1,316 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use openfeature_contrib_common::Backoff;
+use reqwest::header::ACCEPT;
+use reqwest::Client;
+use tokio::sync::{watch, RwLock};
+use tokio::task::JoinHandle;
+use tracing::{debug, warn};
+
+use crate::feature::FeatureState;
+
+pub(crate) type Features = Arc<RwLock<HashMap<String, FeatureState>>>;
+
+/// Connection state of the feature stream.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum StreamStatus {
+    /// No feature state has been received yet.
+    NotReady,
+    /// The feature states are up to date.
+    Ready,
+    /// The stream disconnected; the last known feature states are served.
+    Stale,
+    /// FeatureHub rejected the API key.
+    Failed,
+}
+
+/// Keeps an SSE connection to the Edge open, reconnecting whenever it ends. The delays grow
+/// until a connection delivers the feature states again.
+pub(crate) fn spawn_stream(
+    http: Client,
+    url: String,
+    features: Features,
+    status: watch::Sender<StreamStatus>,
+    mut backoff: Backoff,
+) -> JoinHandle<()> {
+    tokio::spawn(async move {
+        loop {
+            match stream(&http, &url, &features, &status).await {
+                Ok(()) => debug!("FeatureHub stream closed"),
+                Err(e) => {
+                    if e.status().is_some_and(|status| status.is_client_error()) {
+                        status.send_replace(StreamStatus::Failed);
+                    }
+                    warn!("FeatureHub stream failed: {e}");
+                }
+            }
+            let was_ready = status.send_if_modified(|status| {
+                let ready = *status == StreamStatus::Ready;
+                if ready {
+                    *status = StreamStatus::Stale;
+                }
+                ready
+            });
+            if was_ready {
+                backoff.reset();
+            }
+            tokio::time::sleep(backoff.next_delay()).await;
+        }
+    })
+}
+
+async fn stream(
+    http: &Client,
+    url: &str,
+    features: &Features,
+    status: &watch::Sender<StreamStatus>,
+) -> Result<(), reqwest::Error> {
+    let mut response = http
+        .get(url)
+        .header(ACCEPT, "text/event-stream")
+        .send()
+        .await?
+        .error_for_status()?;
+
+    let mut buffer = Vec::new();
+    let mut event = String::new();
+    let mut data = String::new();
+    while let Some(chunk) = response.chunk().await? {
+        buffer.extend_from_slice(&chunk);
+        while let Some(end) = buffer.iter().position(|byte| *byte == b'\n') {
+            let line = buffer.drain(..=end).collect::<Vec<_>>();
+            let line = String::from_utf8_lossy(&line);
+            let line = line.trim_end_matches(['\r', '\n']);
+
+            if line.is_empty() {
+                if !data.is_empty() && !dispatch(&event, &data, features, status).await {
+                    return Ok(());
+                }
+                event.clear();
+                data.clear();
+            } else if let Some(value) = line.strip_prefix("event:") {
+                event = value.trim_start().to_string();
+            } else if let Some(value) = line.strip_prefix("data:") {
+                if !data.is_empty() {
+                    data.push('\n');
+                }
+                data.push_str(value.trim_start());
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Applies a server-sent event, returning `false` when the connection must be closed.
+async fn dispatch(
+    event: &str,
+    data: &str,
+    features: &Features,
+    status: &watch::Sender<StreamStatus>,
+) -> bool {
+    match event {
+        "features" => match serde_json::from_str::<Vec<FeatureState>>(data) {
+            Ok(states) => {
+                debug!("Received {} FeatureHub feature states", states.len());
+                *features.write().await = states
+                    .into_iter()
+                    .map(|state| (state.key.clone(), state))
+                    .collect();
+                status.send_replace(StreamStatus::Ready);
+            }
+            Err(e) => warn!("Invalid FeatureHub feature states: {e}"),
+        },
+        "feature" => match serde_json::from_str::<FeatureState>(data) {
+            Ok(state) => {
+                let mut features = features.write().await;
+                let newer = features
+                    .get(&state.key)
+                    .map_or(true, |current| current.version <= state.version);
+                if newer {
+                    features.insert(state.key.clone(), state);
+                }
+            }
+            Err(e) => warn!("Invalid FeatureHub feature state: {e}"),
+        },
+        "delete_feature" => match serde_json::from_str::<FeatureState>(data) {
+            Ok(state) => {
+                features.write().await.remove(&state.key);
+            }
+            Err(e) => warn!("Invalid FeatureHub feature state: {e}"),
+        },
+        "failed" => {
+            warn!("FeatureHub refused the feature stream: {data}");
+            status.send_replace(StreamStatus::Failed);
+            return false;
+        }
+        "bye" => return false,
+        _ => {}
+    }
+
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use wiremock::matchers::{header, method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    use super::*;
+
+    async fn receive(body: &str) -> (HashMap<String, FeatureState>, StreamStatus) {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/features/key"))
+            .and(header("accept", "text/event-stream"))
+            .respond_with(ResponseTemplate::new(200).set_body_raw(body, "text/event-stream"))
+            .mount(&server)
+            .await;
+
+        let features = Features::default();
+        let (status, receiver) = watch::channel(StreamStatus::NotReady);
+        let url = format!("{}/features/key", server.uri());
+        stream(&Client::new(), &url, &features, &status)
+            .await
+            .expect("stream failed");
+
+        let features = features.read().await.clone();
+        let status = *receiver.borrow();
+        (features, status)
+    }
+
+    fn values(features: &HashMap<String, FeatureState>) -> Vec<(&str, &serde_json::Value)> {
+        let mut values = features
+            .values()
+            .map(|feature| (feature.key.as_str(), &feature.value))
+            .collect::<Vec<_>>();
+        values.sort_by_key(|(key, _)| *key);
+        values
+    }
+
+    #[tokio::test]
+    async fn replaces_the_features_with_the_initial_states() {
+        let (features, status) = receive(concat!(
+            "event: features\n",
+            "data: [{\"id\": \"1\", \"key\": \"A\", \"version\": 1, \"value\": true}]\n",
+            "\n",
+            "event: features\n",
+            "data: [{\"id\": \"2\", \"key\": \"B\", \"version\": 1, \"value\": false}]\n",
+            "\n",
+        ))
+        .await;
+
+        assert_eq!(status, StreamStatus::Ready);
+        assert_eq!(values(&features), [("B", &serde_json::json!(false))]);
+    }
+
+    #[tokio::test]
+    async fn joins_multi_line_data_and_accepts_crlf() {
+        let (features, status) = receive(concat!(
+            ": comment\r\n",
+            "event:features\r\n",
+            "data:[{\"id\": \"1\", \"key\": \"A\",\r\n",
+            "data: \"value\": \"on\"}]\r\n",
+            "\r\n",
+        ))
+        .await;
+
+        assert_eq!(status, StreamStatus::Ready);
+        assert_eq!(values(&features), [("A", &serde_json::json!("on"))]);
+    }
+
+    #[tokio::test]
+    async fn applies_only_newer_feature_updates() {
+        let (features, _) = receive(concat!(
+            "event: features\n",
+            "data: [{\"id\": \"1\", \"key\": \"A\", \"version\": 2, \"value\": \"v2\"}, ",
+            "{\"id\": \"2\", \"key\": \"B\", \"version\": 1, \"value\": \"v1\"}]\n",
+            "\n",
+            "event: feature\n",
+            "data: {\"id\": \"1\", \"key\": \"A\", \"version\": 1, \"value\": \"v1\"}\n",
+            "\n",
+            "event: feature\n",
+            "data: {\"id\": \"2\", \"key\": \"B\", \"version\": 3, \"value\": \"v3\"}\n",
+            "\n",
+            "event: feature\n",
+            "data: {\"id\": \"3\", \"key\": \"C\", \"version\": 1, \"value\": \"new\"}\n",
+            "\n",
+        ))
+        .await;
+
+        assert_eq!(
+            values(&features),
+            [
+                ("A", &serde_json::json!("v2")),
+                ("B", &serde_json::json!("v3")),
+                ("C", &serde_json::json!("new")),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn deletes_features() {
+        let (features, _) = receive(concat!(
+            "event: features\n",
+            "data: [{\"id\": \"1\", \"key\": \"A\"}, {\"id\": \"2\", \"key\": \"B\"}]\n",
+            "\n",
+            "event: delete_feature\n",
+            "data: {\"id\": \"1\", \"key\": \"A\"}\n",
+            "\n",
+        ))
+        .await;
+
+        assert_eq!(features.keys().collect::<Vec<_>>(), ["B"]);
+    }
+
+    #[tokio::test]
+    async fn ignores_invalid_and_unknown_events() {
+        let (features, status) = receive(concat!(
+            "event: features\n",
+            "data: not json\n",
+            "\n",
+            "event: ack\n",
+            "data: {}\n",
+            "\n",
+            "event: feature\n",
+            "\n",
+        ))
+        .await;
+
+        assert_eq!(status, StreamStatus::NotReady);
+        assert!(features.is_empty());
+    }
+
+    #[tokio::test]
+    async fn stops_at_bye() {
+        let (features, status) = receive(concat!(
+            "event: bye\n",
+            "data: {}\n",
+            "\n",
+            "event: features\n",
+            "data: [{\"id\": \"1\", \"key\": \"A\"}]\n",
+            "\n",
+        ))
+        .await;
+
+        assert_eq!(status, StreamStatus::NotReady);
+        assert!(features.is_empty());
+    }
+
+    #[tokio::test]
+    async fn fails_when_the_edge_refuses_the_stream() {
+        let (features, status) = receive(concat!(
+            "event: failed\n",
+            "data: {\"status\": \"unknown API key\"}\n",
+            "\n",
+            "event: features\n",
+            "data: [{\"id\": \"1\", \"key\": \"A\"}]\n",
+            "\n",
+        ))
+        .await;
+
+        assert_eq!(status, StreamStatus::Failed);
+        assert!(features.is_empty());
+    }
+}
@@ -0,0 +1,234 @@
+use std::time::Duration;
+
+use open_feature::provider::{FeatureProvider, ProviderStatus};
+use open_feature::{EvaluationContext, EvaluationErrorCode, EvaluationReason};
+use open_feature_featurehub::{
+    BackoffPolicy, FeatureHubError, FeatureHubOptions, FeatureHubProvider,
+};
+use serde_json::json;
+use wiremock::matchers::{method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+fn features() -> String {
+    let states = json!([
+        {
+            "id": "feature-1",
+            "key": "NEW_CHECKOUT",
+            "version": 3,
+            "type": "BOOLEAN",
+            "value": false,
+            "strategies": [{
+                "id": "nz",
+                "value": true,
+                "attributes": [{
+                    "conditional": "EQUALS",
+                    "fieldName": "country",
+                    "type": "STRING",
+                    "values": ["new_zealand"]
+                }]
+            }]
+        },
+        {"id": "feature-2", "key": "BANNER", "version": 1, "type": "STRING", "value": "hello"},
+        {"id": "feature-3", "key": "LIMIT", "version": 1, "type": "NUMBER", "value": 10},
+        {"id": "feature-4", "key": "RATIO", "version": 1, "type": "NUMBER", "value": 0.5},
+        {
+            "id": "feature-5",
+            "key": "THEME",
+            "version": 1,
+            "type": "JSON",
+            "value": "{\"color\": \"blue\"}"
+        },
+        {"id": "feature-6", "key": "UNSET", "version": 1, "type": "STRING", "value": null}
+    ]);
+    format!("event: features\ndata: {states}\n\n")
+}
+
+async fn server(response: ResponseTemplate) -> MockServer {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/features/env/key*secret"))
+        .respond_with(response)
+        .mount(&server)
+        .await;
+    server
+}
+
+fn options(server: &MockServer) -> FeatureHubOptions {
+    FeatureHubOptions {
+        edge_url: server.uri(),
+        api_key: "env/key*secret".to_string(),
+        wait_for_features: Some(Duration::from_secs(5)),
+        // Keep the provider stale once the stream ended.
+        reconnect_backoff: BackoffPolicy {
+            initial: Duration::from_secs(60),
+            max: Duration::from_secs(60),
+            ..Default::default()
+        },
+    }
+}
+
+async fn provider() -> (MockServer, FeatureHubProvider) {
+    let server =
+        server(ResponseTemplate::new(200).set_body_raw(features(), "text/event-stream")).await;
+    let provider = FeatureHubProvider::new(options(&server)).await.unwrap();
+    (server, provider)
+}
+
+#[tokio::test]
+async fn resolves_features_with_strategies() {
+    let (_server, provider) = provider().await;
+    let user = EvaluationContext::default()
+        .with_targeting_key("user-1")
+        .with_custom_field("country", "new_zealand");
+
+    let details = provider
+        .resolve_bool_value("NEW_CHECKOUT", &user)
+        .await
+        .unwrap();
+    assert!(details.value);
+    assert_eq!(details.reason, Some(EvaluationReason::TargetingMatch));
+    let metadata = details.flag_metadata.unwrap();
+    assert_eq!(metadata.values["version"], 3_i64.into());
+    assert_eq!(metadata.values["strategyId"], "nz".into());
+
+    let details = provider
+        .resolve_bool_value("NEW_CHECKOUT", &EvaluationContext::default())
+        .await
+        .unwrap();
+    assert!(!details.value);
+    assert_eq!(details.reason, Some(EvaluationReason::Default));
+}
+
+#[tokio::test]
+async fn resolves_every_feature_type() {
+    let (_server, provider) = provider().await;
+    let context = EvaluationContext::default();
+
+    let details = provider
+        .resolve_string_value("BANNER", &context)
+        .await
+        .unwrap();
+    assert_eq!(details.value, "hello");
+    assert_eq!(details.reason, Some(EvaluationReason::Static));
+    assert_eq!(
+        provider
+            .resolve_int_value("LIMIT", &context)
+            .await
+            .unwrap()
+            .value,
+        10
+    );
+    assert_eq!(
+        provider
+            .resolve_float_value("RATIO", &context)
+            .await
+            .unwrap()
+            .value,
+        0.5
+    );
+    let theme = provider
+        .resolve_struct_value("THEME", &context)
+        .await
+        .unwrap()
+        .value;
+    assert_eq!(theme.fields["color"], "blue".into());
+}
+
+#[tokio::test]
+async fn reports_evaluation_errors() {
+    let (_server, provider) = provider().await;
+    let context = EvaluationContext::default();
+
+    let error = provider
+        .resolve_bool_value("MISSING", &context)
+        .await
+        .unwrap_err();
+    assert_eq!(error.code, EvaluationErrorCode::FlagNotFound);
+    let error = provider
+        .resolve_bool_value("BANNER", &context)
+        .await
+        .unwrap_err();
+    assert_eq!(error.code, EvaluationErrorCode::TypeMismatch);
+    let error = provider
+        .resolve_int_value("RATIO", &context)
+        .await
+        .unwrap_err();
+    assert_eq!(error.code, EvaluationErrorCode::TypeMismatch);
+    let error = provider
+        .resolve_string_value("UNSET", &context)
+        .await
+        .unwrap_err();
+    assert_eq!(
+        error.code,
+        EvaluationErrorCode::General("No value".to_string())
+    );
+}
+
+#[tokio::test]
+async fn serves_the_last_features_while_reconnecting() {
+    let (_server, provider) = provider().await;
+
+    tokio::time::timeout(Duration::from_secs(5), async {
+        while provider.status() != ProviderStatus::STALE {
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+    })
+    .await
+    .expect("provider did not become stale");
+    let details = provider
+        .resolve_string_value("BANNER", &EvaluationContext::default())
+        .await
+        .unwrap();
+    assert_eq!(details.value, "hello");
+}
+
+#[tokio::test]
+async fn fails_when_the_api_key_is_rejected() {
+    let server = server(ResponseTemplate::new(401)).await;
+
+    let result = FeatureHubProvider::new(options(&server)).await;
+
+    assert!(matches!(result, Err(FeatureHubError::Failed)));
+}
+
+#[tokio::test]
+async fn reports_an_error_status_when_not_waiting() {
+    let server = server(ResponseTemplate::new(404)).await;
+    let provider = FeatureHubProvider::new(FeatureHubOptions {
+        wait_for_features: None,
+        ..options(&server)
+    })
+    .await
+    .unwrap();
+
+    tokio::time::timeout(Duration::from_secs(5), async {
+        while provider.status() != ProviderStatus::Error {
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+    })
+    .await
+    .expect("provider did not fail");
+    let error = provider
+        .resolve_bool_value("NEW_CHECKOUT", &EvaluationContext::default())
+        .await
+        .unwrap_err();
+    assert_eq!(error.code, EvaluationErrorCode::ProviderNotReady);
+}
+
+#[tokio::test]
+async fn times_out_waiting_for_features() {
+    let server = server(
+        ResponseTemplate::new(200)
+            .set_body_raw(features(), "text/event-stream")
+            .set_delay(Duration::from_secs(5)),
+    )
+    .await;
+
+    let result = FeatureHubProvider::new(FeatureHubOptions {
+        wait_for_features: Some(Duration::from_millis(100)),
+        ..options(&server)
+    })
+    .await;
+
+    assert!(matches!(result, Err(FeatureHubError::Timeout)));
+}
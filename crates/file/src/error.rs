@@ -0,0 +1,38 @@
+use std::path::PathBuf;
+
+use thiserror::Error;
+
+/// Errors raised while loading a flag file.
+#[derive(Debug, Error)]
+pub enum FileError {
+    /// The file could not be read.
+    #[error("failed to read {path}: {source}")]
+    Io {
+        /// Path of the file.
+        path: PathBuf,
+        /// Cause of the failure.
+        source: std::io::Error,
+    },
+
+    /// The extension of the file is not `.json`, `.yaml`, `.yml` or `.toml`.
+    #[error("unsupported flag file format: {0}")]
+    UnsupportedFormat(PathBuf),
+
+    /// The file is not valid for its format.
+    #[error("failed to parse {path}: {message}")]
+    Parse {
+        /// Path of the file.
+        path: PathBuf,
+        /// Description of the syntax error.
+        message: String,
+    },
+
+    /// The file does not hold a map of flags.
+    #[error("{path} is invalid: {message}")]
+    Invalid {
+        /// Path of the file.
+        path: PathBuf,
+        /// What is wrong with the content.
+        message: String,
+    },
+}
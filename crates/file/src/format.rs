@@ -0,0 +1,174 @@
+use std::collections::HashMap;
+use std::path::Path;
+
+use serde_json::{Map, Value};
+
+use crate::FileError;
+
+/// Top-level key holding the per-environment sections.
+pub(crate) const ENVIRONMENTS: &str = "environments";
+
+/// Parses a flag file according to its extension.
+///
+/// The flags of the selected `environment` section override the top-level flags; other sections
+/// are ignored.
+pub(crate) fn parse(
+    path: &Path,
+    content: &str,
+    environment: Option<&str>,
+) -> Result<HashMap<String, Value>, FileError> {
+    let parse_error = |message: String| FileError::Parse {
+        path: path.to_path_buf(),
+        message,
+    };
+    let invalid = |message: String| FileError::Invalid {
+        path: path.to_path_buf(),
+        message,
+    };
+
+    let document: Value = match path.extension().and_then(|extension| extension.to_str()) {
+        Some("json") => serde_json::from_str(content).map_err(|e| parse_error(e.to_string()))?,
+        Some("yaml" | "yml") => {
+            serde_yaml::from_str(content).map_err(|e| parse_error(e.to_string()))?
+        }
+        Some("toml") => toml::from_str(content).map_err(|e| parse_error(e.to_string()))?,
+        _ => return Err(FileError::UnsupportedFormat(path.to_path_buf())),
+    };
+    let mut flags = match document {
+        Value::Object(flags) => flags,
+        // An empty YAML file.
+        Value::Null => Map::new(),
+        _ => return Err(invalid("the document is not a map of flags".to_string())),
+    };
+
+    let mut sections = match flags.remove(ENVIRONMENTS) {
+        Some(Value::Object(sections)) => sections,
+        None => Map::new(),
+        Some(_) => return Err(invalid(format!("`{ENVIRONMENTS}` is not a map"))),
+    };
+    if let Some(environment) = environment {
+        match sections.remove(environment) {
+            Some(Value::Object(overrides)) => flags.extend(overrides),
+            Some(_) => {
+                return Err(invalid(format!(
+                    "environment section {environment} is not a map"
+                )))
+            }
+            None => {}
+        }
+    }
+
+    Ok(flags.into_iter().collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn parse_as(file: &str, content: &str, environment: Option<&str>) -> Value {
+        let flags = parse(Path::new(file), content, environment).unwrap();
+        Value::Object(flags.into_iter().collect())
+    }
+
+    #[test]
+    fn parses_every_format() {
+        let expected = json!({"enabled": true, "limit": 25, "banner": {"color": "blue"}});
+
+        assert_eq!(
+            parse_as(
+                "flags.json",
+                r#"{"enabled": true, "limit": 25, "banner": {"color": "blue"}}"#,
+                None
+            ),
+            expected
+        );
+        for file in ["flags.yaml", "flags.yml"] {
+            assert_eq!(
+                parse_as(
+                    file,
+                    "enabled: true\nlimit: 25\nbanner:\n  color: blue\n",
+                    None
+                ),
+                expected
+            );
+        }
+        assert_eq!(
+            parse_as(
+                "flags.toml",
+                "enabled = true\nlimit = 25\n\n[banner]\ncolor = \"blue\"\n",
+                None
+            ),
+            expected
+        );
+    }
+
+    #[test]
+    fn an_empty_yaml_file_has_no_flags() {
+        assert_eq!(parse_as("flags.yaml", "", None), json!({}));
+    }
+
+    #[test]
+    fn the_environment_section_overrides_top_level_flags() {
+        let content = r#"
+            enabled: false
+            limit: 25
+            environments:
+              production:
+                enabled: true
+              staging:
+                limit: 5
+        "#;
+
+        assert_eq!(
+            parse_as("flags.yaml", content, Some("production")),
+            json!({"enabled": true, "limit": 25})
+        );
+        assert_eq!(
+            parse_as("flags.yaml", content, Some("staging")),
+            json!({"enabled": false, "limit": 5})
+        );
+        assert_eq!(
+            parse_as("flags.yaml", content, Some("development")),
+            json!({"enabled": false, "limit": 25})
+        );
+        assert_eq!(
+            parse_as("flags.yaml", content, None),
+            json!({"enabled": false, "limit": 25})
+        );
+    }
+
+    #[test]
+    fn rejects_unsupported_and_invalid_files() {
+        let path = Path::new("flags.json");
+        assert!(matches!(
+            parse(Path::new("flags.ini"), "", None),
+            Err(FileError::UnsupportedFormat(_))
+        ));
+        assert!(matches!(
+            parse(Path::new("flags"), "", None),
+            Err(FileError::UnsupportedFormat(_))
+        ));
+        assert!(matches!(
+            parse(path, "{", None),
+            Err(FileError::Parse { .. })
+        ));
+        assert!(matches!(
+            parse(path, "[true]", None),
+            Err(FileError::Invalid { .. })
+        ));
+        assert!(matches!(
+            parse(path, r#"{"environments": true}"#, None),
+            Err(FileError::Invalid { .. })
+        ));
+        assert!(matches!(
+            parse(
+                path,
+                r#"{"environments": {"production": true}}"#,
+                Some("production")
+            ),
+            Err(FileError::Invalid { .. })
+        ));
+    }
+}
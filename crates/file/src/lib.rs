@@ -0,0 +1,277 @@
+//! Key-value file provider for OpenFeature.
+//!
+//! The provider serves static flags from a plain `key: value` file, for simple use cases that do
+//! not need targeting. Unlike flagd's file resolver it requires no schema: every top-level key
+//! is a flag and its value is the flag value.
+//!
+//! ```yaml
+//! new-checkout: false
+//! max-items: 25
+//! banner:
+//!   color: blue
+//!
+//! environments:
+//!   production:
+//!     new-checkout: true
+//! ```
+//!
+//! The format follows the file extension: `.json`, `.yaml`/`.yml` or `.toml`. Flags of the
+//! section of [`FileOptions::environment`] under `environments` override the top-level flags.
+//! The file is watched for changes unless [`FileOptions::watch_interval`] is `None` or zero.
+//!
+//! # Example
+//!
+//! ```rust,no_run
+//! use open_feature::provider::FeatureProvider;
+//! use open_feature::EvaluationContext;
+//! use open_feature_file::{FileOptions, FileProvider};
+//!
+//! #[tokio::main]
+//! async fn main() {
+//!     let provider = FileProvider::new(FileOptions {
+//!         path: "flags.yaml".into(),
+//!         environment: Some("production".to_string()),
+//!         ..Default::default()
+//!     })
+//!     .await
+//!     .expect("Failed to load flags");
+//!
+//!     let details = provider
+//!         .resolve_bool_value("new-checkout", &EvaluationContext::default())
+//!         .await;
+//!     println!("{details:?}");
+//! }
+//! ```
+
+mod error;
+mod format;
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::{Duration, SystemTime};
+
+use async_trait::async_trait;
+use open_feature::provider::{FeatureProvider, ProviderMetadata, ResolutionDetails};
+use open_feature::{
+    EvaluationContext, EvaluationError, EvaluationErrorCode, EvaluationReason, EvaluationResult,
+    StructValue, Value,
+};
+use tokio::sync::RwLock;
+use tokio::task::JoinHandle;
+use tracing::{debug, warn};
+
+pub use crate::error::FileError;
+use crate::format::parse;
+
+type Flags = Arc<RwLock<HashMap<String, serde_json::Value>>>;
+
+/// Configuration of the [`FileProvider`].
+#[derive(Debug, Clone)]
+pub struct FileOptions {
+    /// Path of the flag file.
+    pub path: PathBuf,
+    /// Section under `environments` whose flags override the top-level flags.
+    pub environment: Option<String>,
+    /// Interval between checks of the file for changes. `None` or zero loads the file only once.
+    pub watch_interval: Option<Duration>,
+}
+
+impl Default for FileOptions {
+    fn default() -> Self {
+        Self {
+            path: PathBuf::new(),
+            environment: None,
+            watch_interval: Some(Duration::from_secs(5)),
+        }
+    }
+}
+
+/// OpenFeature provider serving the flags of a key-value file.
+pub struct FileProvider {
+    metadata: ProviderMetadata,
+    flags: Flags,
+    watch: Option<JoinHandle<()>>,
+}
+
+impl FileProvider {
+    /// Creates the provider, loading the file and starting to watch it.
+    pub async fn new(options: FileOptions) -> Result<Self, FileError> {
+        let modified = modified(&options.path).await;
+        let flags = Arc::new(RwLock::new(
+            load(&options.path, options.environment.as_deref()).await?,
+        ));
+        let watch = options
+            .watch_interval
+            .filter(|interval| !interval.is_zero())
+            .map(|interval| {
+                spawn_watch(
+                    options.path,
+                    options.environment,
+                    modified,
+                    flags.clone(),
+                    interval,
+                )
+            });
+
+        Ok(Self {
+            metadata: ProviderMetadata::new("file"),
+            flags,
+            watch,
+        })
+    }
+
+    async fn resolve<T>(
+        &self,
+        flag_key: &str,
+        convert: impl FnOnce(&serde_json::Value) -> EvaluationResult<T>,
+    ) -> EvaluationResult<ResolutionDetails<T>> {
+        let flags = self.flags.read().await;
+        let value = flags.get(flag_key).ok_or_else(|| {
+            EvaluationError::builder()
+                .code(EvaluationErrorCode::FlagNotFound)
+                .message(format!("Flag {flag_key} not found"))
+                .build()
+        })?;
+
+        Ok(ResolutionDetails {
+            value: convert(value)?,
+            variant: None,
+            reason: Some(EvaluationReason::Static),
+            flag_metadata: None,
+        })
+    }
+}
+
+impl Drop for FileProvider {
+    fn drop(&mut self) {
+        if let Some(watch) = &self.watch {
+            watch.abort();
+        }
+    }
+}
+
+#[async_trait]
+impl FeatureProvider for FileProvider {
+    fn metadata(&self) -> &ProviderMetadata {
+        &self.metadata
+    }
+
+    async fn resolve_bool_value(
+        &self,
+        flag_key: &str,
+        _context: &EvaluationContext,
+    ) -> EvaluationResult<ResolutionDetails<bool>> {
+        self.resolve(flag_key, |value| {
+            value
+                .as_bool()
+                .ok_or_else(|| type_mismatch(format!("Value of {flag_key} is not a boolean")))
+        })
+        .await
+    }
+
+    async fn resolve_int_value(
+        &self,
+        flag_key: &str,
+        _context: &EvaluationContext,
+    ) -> EvaluationResult<ResolutionDetails<i64>> {
+        self.resolve(flag_key, |value| {
+            value
+                .as_i64()
+                .ok_or_else(|| type_mismatch(format!("Value of {flag_key} is not an integer")))
+        })
+        .await
+    }
+
+    async fn resolve_float_value(
+        &self,
+        flag_key: &str,
+        _context: &EvaluationContext,
+    ) -> EvaluationResult<ResolutionDetails<f64>> {
+        self.resolve(flag_key, |value| {
+            value
+                .as_f64()
+                .ok_or_else(|| type_mismatch(format!("Value of {flag_key} is not a number")))
+        })
+        .await
+    }
+
+    async fn resolve_string_value(
+        &self,
+        flag_key: &str,
+        _context: &EvaluationContext,
+    ) -> EvaluationResult<ResolutionDetails<String>> {
+        self.resolve(flag_key, |value| {
+            value
+                .as_str()
+                .map(str::to_string)
+                .ok_or_else(|| type_mismatch(format!("Value of {flag_key} is not a string")))
+        })
+        .await
+    }
+
+    async fn resolve_struct_value(
+        &self,
+        flag_key: &str,
+        _context: &EvaluationContext,
+    ) -> EvaluationResult<ResolutionDetails<StructValue>> {
+        self.resolve(flag_key, |value| match Value::try_from(value.clone())? {
+            Value::Struct(value) => Ok(value),
+            _ => Err(type_mismatch(format!("Value of {flag_key} is not a map"))),
+        })
+        .await
+    }
+}
+
+async fn load(
+    path: &Path,
+    environment: Option<&str>,
+) -> Result<HashMap<String, serde_json::Value>, FileError> {
+    let content = tokio::fs::read_to_string(path)
+        .await
+        .map_err(|source| FileError::Io {
+            path: path.to_path_buf(),
+            source,
+        })?;
+    parse(path, &content, environment)
+}
+
+async fn modified(path: &Path) -> Option<SystemTime> {
+    tokio::fs::metadata(path).await.ok()?.modified().ok()
+}
+
+/// Reloads the flags whenever the modification time of the file changes. Files that cannot be
+/// loaded are reported and the previous flags are kept.
+fn spawn_watch(
+    path: PathBuf,
+    environment: Option<String>,
+    mut last_modified: Option<SystemTime>,
+    flags: Flags,
+    interval: Duration,
+) -> JoinHandle<()> {
+    tokio::spawn(async move {
+        loop {
+            tokio::time::sleep(interval).await;
+            let modified = modified(&path).await;
+            if modified == last_modified {
+                continue;
+            }
+            last_modified = modified;
+
+            match load(&path, environment.as_deref()).await {
+                Ok(loaded) => {
+                    debug!("Reloaded {} flags from {}", loaded.len(), path.display());
+                    *flags.write().await = loaded;
+                }
+                Err(e) => warn!("Failed to reload flags: {e}"),
+            }
+        }
+    })
+}
+
+fn type_mismatch(message: String) -> EvaluationError {
+    EvaluationError::builder()
+        .code(EvaluationErrorCode::TypeMismatch)
+        .message(message)
+        .build()
+}
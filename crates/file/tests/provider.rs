@@ -0,0 +1,196 @@
+use std::path::PathBuf;
+use std::time::Duration;
+
+use open_feature::provider::FeatureProvider;
+use open_feature::{EvaluationContext, EvaluationErrorCode, EvaluationReason};
+use open_feature_file::{FileError, FileOptions, FileProvider};
+
+fn flag_file(name: &str, content: &str) -> PathBuf {
+    let path = std::env::temp_dir().join(format!("{}-{name}", std::process::id()));
+    std::fs::write(&path, content).unwrap();
+    path
+}
+
+/// Rewrites the file with a later modification time, whatever the timestamp resolution.
+fn rewrite(path: &PathBuf, content: &str) {
+    let modified = std::fs::metadata(path).unwrap().modified().unwrap();
+    std::fs::write(path, content).unwrap();
+    let file = std::fs::File::options().write(true).open(path).unwrap();
+    file.set_modified(modified + Duration::from_secs(1))
+        .unwrap();
+}
+
+fn options(path: PathBuf) -> FileOptions {
+    FileOptions {
+        path,
+        environment: None,
+        watch_interval: None,
+    }
+}
+
+#[tokio::test]
+async fn resolves_flags_of_every_type() {
+    let path = flag_file(
+        "flags.yaml",
+        "enabled: true\nlimit: 25\nratio: 0.5\ntheme: dark\nbanner:\n  color: blue\n",
+    );
+    let provider = FileProvider::new(options(path)).await.unwrap();
+    let context = &EvaluationContext::default();
+
+    let details = provider
+        .resolve_bool_value("enabled", context)
+        .await
+        .unwrap();
+    assert!(details.value);
+    assert_eq!(details.reason, Some(EvaluationReason::Static));
+    assert_eq!(
+        provider
+            .resolve_int_value("limit", context)
+            .await
+            .unwrap()
+            .value,
+        25
+    );
+    assert_eq!(
+        provider
+            .resolve_float_value("ratio", context)
+            .await
+            .unwrap()
+            .value,
+        0.5
+    );
+    assert_eq!(
+        provider
+            .resolve_string_value("theme", context)
+            .await
+            .unwrap()
+            .value,
+        "dark"
+    );
+    let banner = provider
+        .resolve_struct_value("banner", context)
+        .await
+        .unwrap()
+        .value;
+    assert_eq!(banner.fields["color"], "blue".into());
+}
+
+#[tokio::test]
+async fn reports_missing_and_mistyped_flags() {
+    let path = flag_file("errors.json", r#"{"enabled": true, "ratio": 0.5}"#);
+    let provider = FileProvider::new(options(path)).await.unwrap();
+    let context = &EvaluationContext::default();
+
+    let error = provider
+        .resolve_bool_value("missing", context)
+        .await
+        .unwrap_err();
+    assert_eq!(error.code, EvaluationErrorCode::FlagNotFound);
+    for error in [
+        provider
+            .resolve_int_value("ratio", context)
+            .await
+            .unwrap_err(),
+        provider
+            .resolve_string_value("enabled", context)
+            .await
+            .unwrap_err(),
+        provider
+            .resolve_struct_value("enabled", context)
+            .await
+            .unwrap_err(),
+    ] {
+        assert_eq!(error.code, EvaluationErrorCode::TypeMismatch);
+    }
+}
+
+#[tokio::test]
+async fn applies_the_environment_section() {
+    let path = flag_file(
+        "environments.toml",
+        "enabled = false\n\n[environments.production]\nenabled = true\n",
+    );
+    let provider = FileProvider::new(FileOptions {
+        environment: Some("production".to_string()),
+        ..options(path)
+    })
+    .await
+    .unwrap();
+    let context = &EvaluationContext::default();
+
+    assert!(
+        provider
+            .resolve_bool_value("enabled", context)
+            .await
+            .unwrap()
+            .value
+    );
+    let error = provider
+        .resolve_struct_value("environments", context)
+        .await
+        .unwrap_err();
+    assert_eq!(error.code, EvaluationErrorCode::FlagNotFound);
+}
+
+#[tokio::test]
+async fn fails_for_missing_files() {
+    let path = std::env::temp_dir().join(format!("{}-missing.yaml", std::process::id()));
+
+    let result = FileProvider::new(options(path)).await;
+
+    assert!(matches!(result, Err(FileError::Io { .. })));
+}
+
+#[tokio::test]
+async fn reloads_the_file_when_it_changes() {
+    let path = flag_file("watched.yaml", "enabled: false\n");
+    let provider = FileProvider::new(FileOptions {
+        watch_interval: Some(Duration::from_millis(10)),
+        ..options(path.clone())
+    })
+    .await
+    .unwrap();
+    let context = &EvaluationContext::default();
+
+    rewrite(&path, "enabled: [");
+    tokio::time::sleep(Duration::from_millis(100)).await;
+    let details = provider
+        .resolve_bool_value("enabled", context)
+        .await
+        .unwrap();
+    assert!(!details.value, "invalid files must keep the previous flags");
+
+    rewrite(&path, "enabled: true\n");
+    tokio::time::timeout(Duration::from_secs(5), async {
+        while !provider
+            .resolve_bool_value("enabled", context)
+            .await
+            .unwrap()
+            .value
+        {
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+    })
+    .await
+    .expect("file was not reloaded");
+}
+
+#[tokio::test]
+async fn a_zero_watch_interval_loads_the_file_once() {
+    let path = flag_file("unwatched.yaml", "enabled: false\n");
+    let provider = FileProvider::new(FileOptions {
+        watch_interval: Some(Duration::ZERO),
+        ..options(path.clone())
+    })
+    .await
+    .unwrap();
+
+    rewrite(&path, "enabled: true\n");
+    tokio::time::sleep(Duration::from_millis(100)).await;
+
+    let details = provider
+        .resolve_bool_value("enabled", &EvaluationContext::default())
+        .await
+        .unwrap();
+    assert!(!details.value);
+}
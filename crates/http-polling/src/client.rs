@@ -0,0 +1,72 @@
+use std::collections::HashMap;
+use std::time::Duration;
+
+use openfeature_contrib_common::HttpError;
+use reqwest::header::{HeaderMap, HeaderName, HeaderValue, ETAG, IF_NONE_MATCH};
+use reqwest::{Client, StatusCode};
+
+use crate::HttpPollingError;
+
+/// A flag document with its entity tag.
+#[derive(Default)]
+pub(crate) struct Document {
+    pub(crate) flags: HashMap<String, serde_json::Value>,
+    pub(crate) etag: Option<String>,
+}
+
+pub(crate) struct DocumentClient {
+    http: Client,
+    url: String,
+}
+
+impl DocumentClient {
+    pub(crate) fn new(
+        url: &str,
+        headers: &HashMap<String, String>,
+        timeout: Duration,
+    ) -> Result<Self, HttpPollingError> {
+        let mut default_headers = HeaderMap::new();
+        for (name, value) in headers {
+            let invalid = || HttpPollingError::InvalidHeader(name.clone());
+            default_headers.insert(
+                HeaderName::try_from(name).map_err(|_| invalid())?,
+                HeaderValue::from_str(value).map_err(|_| invalid())?,
+            );
+        }
+        let http = Client::builder()
+            .default_headers(default_headers)
+            .timeout(timeout)
+            .build()?;
+
+        Ok(Self {
+            http,
+            url: url.to_string(),
+        })
+    }
+
+    /// Fetches the flag document, or `None` if it still matches `etag`.
+    pub(crate) async fn fetch(
+        &self,
+        etag: Option<&str>,
+    ) -> Result<Option<Document>, HttpPollingError> {
+        let mut request = self.http.get(&self.url);
+        if let Some(etag) = etag {
+            request = request.header(IF_NONE_MATCH, etag);
+        }
+        let response = request.send().await?;
+
+        match response.status() {
+            StatusCode::NOT_MODIFIED => Ok(None),
+            status if status.is_success() => {
+                let etag = response
+                    .headers()
+                    .get(ETAG)
+                    .and_then(|etag| etag.to_str().ok())
+                    .map(str::to_string);
+                let flags = response.json().await?;
+                Ok(Some(Document { flags, etag }))
+            }
+            _ => Err(HttpError::status_of(&response).into()),
+        }
+    }
+}
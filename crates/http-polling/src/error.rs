@@ -0,0 +1,31 @@
+use openfeature_contrib_common::HttpError;
+use thiserror::Error;
+
+/// Errors raised while fetching the flag document.
+#[derive(Debug, Error)]
+pub enum HttpPollingError {
+    /// A configured header has an invalid name or value.
+    #[error("invalid header {0}")]
+    InvalidHeader(String),
+
+    /// The request failed or the response is not valid JSON.
+    #[error("request for the flag document failed: {0}")]
+    Http(#[from] HttpError),
+}
+
+impl From<reqwest::Error> for HttpPollingError {
+    fn from(error: reqwest::Error) -> Self {
+        Self::Http(error.into())
+    }
+}
+
+impl HttpPollingError {
+    /// Whether fetching the flag document again may succeed. Failed polls that cannot succeed
+    /// before the endpoint or the options change are logged as errors.
+    pub fn is_retryable(&self) -> bool {
+        match self {
+            Self::InvalidHeader(_) => false,
+            Self::Http(e) => e.is_retryable(),
+        }
+    }
+}
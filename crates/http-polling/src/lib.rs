@@ -0,0 +1,253 @@
+//! HTTP JSON polling provider for OpenFeature.
+//!
+//! The provider polls an HTTP(S) endpoint returning a flat JSON document of flag values and
+//! serves the latest document, a lightweight option for teams without a flag backend. Any static
+//! file host, object storage bucket or small service can serve the document:
+//!
+//! ```json
+//! {
+//!   "new-checkout": true,
+//!   "max-items": 25,
+//!   "theme": "dark",
+//!   "banner": { "color": "blue" }
+//! }
+//! ```
+//!
+//! Polls send the entity tag of the last document, so unchanged documents are not transferred
+//! again when the endpoint supports `ETag`s. Headers such as `Authorization` are configured with
+//! [`HttpPollingOptions::headers`].
+//!
+//! # Example
+//!
+//! ```rust,no_run
+//! use std::collections::HashMap;
+//!
+//! use open_feature::provider::FeatureProvider;
+//! use open_feature::EvaluationContext;
+//! use open_feature_http_polling::{HttpPollingOptions, HttpPollingProvider};
+//!
+//! #[tokio::main]
+//! async fn main() {
+//!     let provider = HttpPollingProvider::new(HttpPollingOptions {
+//!         url: "https://config.example.com/flags.json".to_string(),
+//!         headers: HashMap::from([(
+//!             "Authorization".to_string(),
+//!             "Bearer secret-token".to_string(),
+//!         )]),
+//!         ..Default::default()
+//!     })
+//!     .await
+//!     .expect("Failed to fetch flags");
+//!
+//!     let details = provider
+//!         .resolve_bool_value("new-checkout", &EvaluationContext::default())
+//!         .await;
+//!     println!("{details:?}");
+//! }
+//! ```
+
+mod client;
+mod error;
+
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+
+use async_trait::async_trait;
+use open_feature::provider::{FeatureProvider, ProviderMetadata, ResolutionDetails};
+use open_feature::{
+    EvaluationContext, EvaluationError, EvaluationErrorCode, EvaluationReason, EvaluationResult,
+    StructValue, Value,
+};
+use tokio::sync::RwLock;
+use tokio::task::JoinHandle;
+use tracing::{debug, error, warn};
+
+use crate::client::DocumentClient;
+pub use crate::error::HttpPollingError;
+
+type Flags = Arc<RwLock<HashMap<String, serde_json::Value>>>;
+
+/// Configuration of the [`HttpPollingProvider`].
+#[derive(Debug, Clone)]
+pub struct HttpPollingOptions {
+    /// URL of the flag document.
+    pub url: String,
+    /// Headers sent with every request, e.g. `Authorization`.
+    pub headers: HashMap<String, String>,
+    /// Interval between polls of the document.
+    pub polling_interval: Duration,
+    /// Timeout of requests for the document.
+    pub request_timeout: Duration,
+}
+
+impl Default for HttpPollingOptions {
+    fn default() -> Self {
+        Self {
+            url: String::new(),
+            headers: HashMap::new(),
+            polling_interval: Duration::from_secs(30),
+            request_timeout: Duration::from_secs(10),
+        }
+    }
+}
+
+/// OpenFeature provider serving flags from a polled JSON document.
+pub struct HttpPollingProvider {
+    metadata: ProviderMetadata,
+    flags: Flags,
+    polling: JoinHandle<()>,
+}
+
+impl HttpPollingProvider {
+    /// Creates the provider, fetching the document and starting to poll it.
+    pub async fn new(options: HttpPollingOptions) -> Result<Self, HttpPollingError> {
+        let client = DocumentClient::new(&options.url, &options.headers, options.request_timeout)?;
+        let document = client.fetch(None).await?.unwrap_or_default();
+
+        let flags = Arc::new(RwLock::new(document.flags));
+        let polling = spawn_polling(
+            client,
+            document.etag,
+            flags.clone(),
+            options.polling_interval,
+        );
+
+        Ok(Self {
+            metadata: ProviderMetadata::new("http-polling"),
+            flags,
+            polling,
+        })
+    }
+
+    async fn resolve<T>(
+        &self,
+        flag_key: &str,
+        convert: impl FnOnce(&serde_json::Value) -> EvaluationResult<T>,
+    ) -> EvaluationResult<ResolutionDetails<T>> {
+        let flags = self.flags.read().await;
+        let value = flags.get(flag_key).ok_or_else(|| {
+            EvaluationError::builder()
+                .code(EvaluationErrorCode::FlagNotFound)
+                .message(format!("Flag {flag_key} not found"))
+                .build()
+        })?;
+
+        Ok(ResolutionDetails {
+            value: convert(value)?,
+            variant: None,
+            reason: Some(EvaluationReason::Static),
+            flag_metadata: None,
+        })
+    }
+}
+
+impl Drop for HttpPollingProvider {
+    fn drop(&mut self) {
+        self.polling.abort();
+    }
+}
+
+#[async_trait]
+impl FeatureProvider for HttpPollingProvider {
+    fn metadata(&self) -> &ProviderMetadata {
+        &self.metadata
+    }
+
+    async fn resolve_bool_value(
+        &self,
+        flag_key: &str,
+        _context: &EvaluationContext,
+    ) -> EvaluationResult<ResolutionDetails<bool>> {
+        self.resolve(flag_key, |value| {
+            value
+                .as_bool()
+                .ok_or_else(|| type_mismatch(format!("Value of {flag_key} is not a boolean")))
+        })
+        .await
+    }
+
+    async fn resolve_int_value(
+        &self,
+        flag_key: &str,
+        _context: &EvaluationContext,
+    ) -> EvaluationResult<ResolutionDetails<i64>> {
+        self.resolve(flag_key, |value| {
+            value
+                .as_i64()
+                .ok_or_else(|| type_mismatch(format!("Value of {flag_key} is not an integer")))
+        })
+        .await
+    }
+
+    async fn resolve_float_value(
+        &self,
+        flag_key: &str,
+        _context: &EvaluationContext,
+    ) -> EvaluationResult<ResolutionDetails<f64>> {
+        self.resolve(flag_key, |value| {
+            value
+                .as_f64()
+                .ok_or_else(|| type_mismatch(format!("Value of {flag_key} is not a number")))
+        })
+        .await
+    }
+
+    async fn resolve_string_value(
+        &self,
+        flag_key: &str,
+        _context: &EvaluationContext,
+    ) -> EvaluationResult<ResolutionDetails<String>> {
+        self.resolve(flag_key, |value| {
+            value
+                .as_str()
+                .map(str::to_string)
+                .ok_or_else(|| type_mismatch(format!("Value of {flag_key} is not a string")))
+        })
+        .await
+    }
+
+    async fn resolve_struct_value(
+        &self,
+        flag_key: &str,
+        _context: &EvaluationContext,
+    ) -> EvaluationResult<ResolutionDetails<StructValue>> {
+        self.resolve(flag_key, |value| match Value::try_from(value.clone())? {
+            Value::Struct(value) => Ok(value),
+            _ => Err(type_mismatch(format!(
+                "Value of {flag_key} is not a JSON object"
+            ))),
+        })
+        .await
+    }
+}
+
+fn spawn_polling(
+    client: DocumentClient,
+    mut etag: Option<String>,
+    flags: Flags,
+    interval: Duration,
+) -> JoinHandle<()> {
+    tokio::spawn(async move {
+        loop {
+            tokio::time::sleep(interval).await;
+            match client.fetch(etag.as_deref()).await {
+                Ok(Some(document)) => {
+                    debug!("Fetched {} flags", document.flags.len());
+                    *flags.write().await = document.flags;
+                    etag = document.etag;
+                }
+                Ok(None) => debug!("Flag document not modified"),
+                Err(e) if e.is_retryable() => warn!("Failed to fetch flags: {e}"),
+                Err(e) => error!("Failed to fetch flags: {e}"),
+            }
+        }
+    })
+}
+
+fn type_mismatch(message: String) -> EvaluationError {
+    EvaluationError::builder()
+        .code(EvaluationErrorCode::TypeMismatch)
+        .message(message)
+        .build()
+}
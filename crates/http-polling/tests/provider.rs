@@ -0,0 +1,244 @@
+use std::collections::HashMap;
+use std::time::Duration;
+
+use open_feature::provider::FeatureProvider;
+use open_feature::{EvaluationContext, EvaluationErrorCode, EvaluationReason};
+use open_feature_http_polling::{HttpPollingError, HttpPollingOptions, HttpPollingProvider};
+use openfeature_contrib_common::HttpError;
+use serde_json::json;
+use wiremock::matchers::{header, method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+fn options(server: &MockServer, polling_interval: Duration) -> HttpPollingOptions {
+    HttpPollingOptions {
+        url: format!("{}/flags.json", server.uri()),
+        headers: HashMap::from([("Authorization".to_string(), "Bearer token".to_string())]),
+        polling_interval,
+        ..Default::default()
+    }
+}
+
+async fn wait_for_requests(server: &MockServer, count: usize) {
+    tokio::time::timeout(Duration::from_secs(5), async {
+        while server.received_requests().await.unwrap().len() < count {
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+    })
+    .await
+    .expect("the document was not polled");
+}
+
+#[tokio::test]
+async fn resolves_flags_of_every_type() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/flags.json"))
+        .and(header("Authorization", "Bearer token"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "enabled": true,
+            "limit": 25,
+            "ratio": 0.5,
+            "theme": "dark",
+            "banner": {"color": "blue"}
+        })))
+        .mount(&server)
+        .await;
+    let provider = HttpPollingProvider::new(options(&server, Duration::from_secs(60)))
+        .await
+        .unwrap();
+    let context = &EvaluationContext::default();
+
+    let details = provider
+        .resolve_bool_value("enabled", context)
+        .await
+        .unwrap();
+    assert!(details.value);
+    assert_eq!(details.reason, Some(EvaluationReason::Static));
+    assert_eq!(
+        provider
+            .resolve_int_value("limit", context)
+            .await
+            .unwrap()
+            .value,
+        25
+    );
+    assert_eq!(
+        provider
+            .resolve_float_value("ratio", context)
+            .await
+            .unwrap()
+            .value,
+        0.5
+    );
+    assert_eq!(
+        provider
+            .resolve_string_value("theme", context)
+            .await
+            .unwrap()
+            .value,
+        "dark"
+    );
+    let banner = provider
+        .resolve_struct_value("banner", context)
+        .await
+        .unwrap()
+        .value;
+    assert_eq!(banner.fields["color"], "blue".into());
+
+    let error = provider
+        .resolve_bool_value("missing", context)
+        .await
+        .unwrap_err();
+    assert_eq!(error.code, EvaluationErrorCode::FlagNotFound);
+    let error = provider
+        .resolve_int_value("ratio", context)
+        .await
+        .unwrap_err();
+    assert_eq!(error.code, EvaluationErrorCode::TypeMismatch);
+}
+
+#[tokio::test]
+async fn unmodified_documents_are_kept() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(header("if-none-match", "\"v1\""))
+        .respond_with(ResponseTemplate::new(304))
+        .with_priority(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .insert_header("etag", "\"v1\"")
+                .set_body_json(json!({"enabled": true})),
+        )
+        .expect(1)
+        .mount(&server)
+        .await;
+    let provider = HttpPollingProvider::new(options(&server, Duration::from_millis(10)))
+        .await
+        .unwrap();
+
+    wait_for_requests(&server, 3).await;
+
+    let details = provider
+        .resolve_bool_value("enabled", &EvaluationContext::default())
+        .await
+        .unwrap();
+    assert!(details.value);
+}
+
+#[tokio::test]
+async fn polls_replace_the_flags() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .insert_header("etag", "\"v1\"")
+                .set_body_json(json!({"enabled": false, "removed": true})),
+        )
+        .up_to_n_times(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(header("if-none-match", "\"v1\""))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .insert_header("etag", "\"v2\"")
+                .set_body_json(json!({"enabled": true})),
+        )
+        .up_to_n_times(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(header("if-none-match", "\"v2\""))
+        .respond_with(ResponseTemplate::new(304))
+        .mount(&server)
+        .await;
+    let provider = HttpPollingProvider::new(options(&server, Duration::from_millis(10)))
+        .await
+        .unwrap();
+    let context = &EvaluationContext::default();
+
+    wait_for_requests(&server, 3).await;
+
+    assert!(
+        provider
+            .resolve_bool_value("enabled", context)
+            .await
+            .unwrap()
+            .value
+    );
+    let error = provider
+        .resolve_bool_value("removed", context)
+        .await
+        .unwrap_err();
+    assert_eq!(error.code, EvaluationErrorCode::FlagNotFound);
+}
+
+#[tokio::test]
+async fn failed_polls_keep_the_flags() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({"enabled": true})))
+        .up_to_n_times(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .respond_with(ResponseTemplate::new(503))
+        .mount(&server)
+        .await;
+    let provider = HttpPollingProvider::new(options(&server, Duration::from_millis(10)))
+        .await
+        .unwrap();
+
+    wait_for_requests(&server, 3).await;
+
+    let details = provider
+        .resolve_bool_value("enabled", &EvaluationContext::default())
+        .await
+        .unwrap();
+    assert!(details.value);
+}
+
+#[tokio::test]
+async fn fails_when_the_first_fetch_fails() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .respond_with(ResponseTemplate::new(401))
+        .mount(&server)
+        .await;
+
+    let result = HttpPollingProvider::new(options(&server, Duration::from_secs(60))).await;
+
+    assert!(matches!(
+        result,
+        Err(HttpPollingError::Http(HttpError::Status { status, .. })) if status == 401
+    ));
+}
+
+#[tokio::test]
+async fn fails_for_invalid_documents() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .respond_with(ResponseTemplate::new(200).set_body_string("[true]"))
+        .mount(&server)
+        .await;
+
+    let result = HttpPollingProvider::new(options(&server, Duration::from_secs(60))).await;
+
+    assert!(matches!(result, Err(HttpPollingError::Http(_))));
+}
+
+#[tokio::test]
+async fn rejects_invalid_headers() {
+    let server = MockServer::start().await;
+
+    let result = HttpPollingProvider::new(HttpPollingOptions {
+        headers: HashMap::from([("Invalid Name".to_string(), "value".to_string())]),
+        ..options(&server, Duration::from_secs(60))
+    })
+    .await;
+
+    assert!(matches!(result, Err(HttpPollingError::InvalidHeader(name)) if name == "Invalid Name"));
+}
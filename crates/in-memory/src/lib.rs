@@ -0,0 +1,245 @@
+//! In-memory provider for OpenFeature, meant for application test suites.
+//!
+//! Flags are set and changed at any time through [`InMemoryProvider::set_flag`], so tests can
+//! flip flags deterministically without a flag backend. Per-context behaviour is simulated with
+//! targeting closures, and every mutation is announced to the receivers of
+//! [`InMemoryProvider::subscribe`].
+//!
+//! The provider is a cheap handle to shared state: keep a clone to mutate flags after handing
+//! the provider to the OpenFeature API.
+//!
+//! # Example
+//!
+//! ```rust
+//! use open_feature::provider::FeatureProvider;
+//! use open_feature::{EvaluationContext, EvaluationReason};
+//! use open_feature_in_memory::InMemoryProvider;
+//!
+//! #[tokio::main]
+//! async fn main() {
+//!     let provider = InMemoryProvider::new();
+//!     provider.set_flag("new-checkout", false, Some("off"), None);
+//!     provider.set_targeting("new-checkout", |context| {
+//!         (context.targeting_key.as_deref() == Some("beta-tester")).then(|| true.into())
+//!     });
+//!
+//!     let context = EvaluationContext::default().with_targeting_key("beta-tester");
+//!     let details = provider
+//!         .resolve_bool_value("new-checkout", &context)
+//!         .await
+//!         .unwrap();
+//!     assert!(details.value);
+//!     assert_eq!(details.reason, Some(EvaluationReason::TargetingMatch));
+//!
+//!     provider.set_flag("new-checkout", true, Some("on"), None);
+//!     let details = provider
+//!         .resolve_bool_value("new-checkout", &EvaluationContext::default())
+//!         .await
+//!         .unwrap();
+//!     assert!(details.value);
+//! }
+//! ```
+
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::{Arc, PoisonError, RwLock, RwLockWriteGuard};
+
+use async_trait::async_trait;
+use open_feature::provider::{FeatureProvider, ProviderMetadata, ResolutionDetails};
+use open_feature::{
+    EvaluationContext, EvaluationError, EvaluationErrorCode, EvaluationReason, EvaluationResult,
+    Hook, HookWrapper, Value,
+};
+use openfeature_contrib_common::{impl_resolve_methods, FlagValue};
+use tokio::sync::broadcast;
+
+/// Computes the value of a flag for an evaluation context; `None` serves the flag's value.
+type Targeting = Arc<dyn Fn(&EvaluationContext) -> Option<Value> + Send + Sync>;
+
+struct Flag {
+    value: Value,
+    variant: Option<String>,
+    reason: EvaluationReason,
+    targeting: Option<Targeting>,
+}
+
+impl fmt::Debug for Flag {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Flag")
+            .field("value", &self.value)
+            .field("variant", &self.variant)
+            .field("reason", &self.reason)
+            .field("targeting", &self.targeting.is_some())
+            .finish()
+    }
+}
+
+/// Announces flags that were set, retargeted or removed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FlagsChanged {
+    /// Keys of the changed flags.
+    pub flag_keys: Vec<String>,
+}
+
+/// OpenFeature provider serving flags set in memory.
+#[derive(Clone)]
+pub struct InMemoryProvider {
+    metadata: ProviderMetadata,
+    flags: Arc<RwLock<HashMap<String, Flag>>>,
+    changes: broadcast::Sender<FlagsChanged>,
+    hooks: Vec<HookWrapper>,
+}
+
+impl fmt::Debug for InMemoryProvider {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("InMemoryProvider")
+            .field("metadata", &self.metadata)
+            .field("flags", &self.flags)
+            .field("changes", &self.changes)
+            .field("hooks", &self.hooks.len())
+            .finish()
+    }
+}
+
+impl Default for InMemoryProvider {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl InMemoryProvider {
+    /// Creates a provider without flags.
+    pub fn new() -> Self {
+        Self {
+            metadata: ProviderMetadata::new("in-memory"),
+            flags: Arc::default(),
+            changes: broadcast::channel(64).0,
+            hooks: Vec::new(),
+        }
+    }
+
+    /// Adds a hook the provider reports from `hooks`, e.g. to test provider hooks.
+    pub fn with_hook(mut self, hook: impl Hook) -> Self {
+        self.hooks.push(HookWrapper::new(hook));
+        self
+    }
+
+    /// Sets the value of a flag, replacing any previous value and targeting. The reason
+    /// defaults to `STATIC`.
+    pub fn set_flag(
+        &self,
+        flag_key: impl Into<String>,
+        value: impl Into<Value>,
+        variant: Option<&str>,
+        reason: Option<EvaluationReason>,
+    ) {
+        let flag_key = flag_key.into();
+        let flag = Flag {
+            value: value.into(),
+            variant: variant.map(str::to_string),
+            reason: reason.unwrap_or(EvaluationReason::Static),
+            targeting: None,
+        };
+        self.write().insert(flag_key.clone(), flag);
+        self.announce(vec![flag_key]);
+    }
+
+    /// Sets the targeting of a flag set with [`set_flag`](Self::set_flag). Values returned by
+    /// `targeting` are served with the `TARGETING_MATCH` reason; when it returns `None`, the
+    /// flag's value is served.
+    ///
+    /// Returns `false` if the flag does not exist.
+    pub fn set_targeting<F>(&self, flag_key: &str, targeting: F) -> bool
+    where
+        F: Fn(&EvaluationContext) -> Option<Value> + Send + Sync + 'static,
+    {
+        match self.write().get_mut(flag_key) {
+            Some(flag) => flag.targeting = Some(Arc::new(targeting)),
+            None => return false,
+        }
+        self.announce(vec![flag_key.to_string()]);
+        true
+    }
+
+    /// Removes a flag, so evaluating it fails with `FLAG_NOT_FOUND`.
+    pub fn remove_flag(&self, flag_key: &str) {
+        if self.write().remove(flag_key).is_some() {
+            self.announce(vec![flag_key.to_string()]);
+        }
+    }
+
+    /// Removes every flag.
+    pub fn clear(&self) {
+        let flag_keys: Vec<String> = self.write().drain().map(|(key, _)| key).collect();
+        self.announce(flag_keys);
+    }
+
+    /// Returns a receiver of the flag changes made from now on.
+    pub fn subscribe(&self) -> broadcast::Receiver<FlagsChanged> {
+        self.changes.subscribe()
+    }
+
+    fn write(&self) -> RwLockWriteGuard<'_, HashMap<String, Flag>> {
+        self.flags.write().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    fn announce(&self, flag_keys: Vec<String>) {
+        if !flag_keys.is_empty() {
+            // Sending only fails when nobody is subscribed.
+            let _ = self.changes.send(FlagsChanged { flag_keys });
+        }
+    }
+
+    async fn resolve<T: FlagValue>(
+        &self,
+        flag_key: &str,
+        context: &EvaluationContext,
+    ) -> EvaluationResult<ResolutionDetails<T>> {
+        let (flag_value, variant, reason, targeting) = {
+            let flags = self.flags.read().unwrap_or_else(PoisonError::into_inner);
+            let flag = flags.get(flag_key).ok_or_else(|| {
+                EvaluationError::builder()
+                    .code(EvaluationErrorCode::FlagNotFound)
+                    .message(format!("Flag {flag_key} not found"))
+                    .build()
+            })?;
+            (
+                flag.value.clone(),
+                flag.variant.clone(),
+                flag.reason.clone(),
+                flag.targeting.clone(),
+            )
+        };
+        // Targeting runs without the lock held, so it may mutate flags itself.
+        let (value, variant, reason) = match targeting.and_then(|targeting| targeting(context)) {
+            Some(value) => (value, None, EvaluationReason::TargetingMatch),
+            None => (flag_value, variant, reason),
+        };
+
+        let value = T::from_value(value).ok_or_else(|| {
+            EvaluationError::builder()
+                .code(EvaluationErrorCode::TypeMismatch)
+                .message(format!("Value of {flag_key} has another type"))
+                .build()
+        })?;
+        Ok(ResolutionDetails {
+            value,
+            variant,
+            reason: Some(reason),
+            flag_metadata: None,
+        })
+    }
+}
+
+#[async_trait]
+impl FeatureProvider for InMemoryProvider {
+    fn metadata(&self) -> &ProviderMetadata {
+        &self.metadata
+    }
+
+    fn hooks(&self) -> &[HookWrapper] {
+        &self.hooks
+    }
+
+    impl_resolve_methods!();
+}
@@ -0,0 +1,187 @@
+use open_feature::provider::FeatureProvider;
+use open_feature::{
+    EvaluationContext, EvaluationErrorCode, EvaluationReason, LoggingHook, StructValue,
+};
+use open_feature_in_memory::{FlagsChanged, InMemoryProvider};
+use tokio::sync::broadcast::error::TryRecvError;
+
+fn changed(flag_keys: &[&str]) -> FlagsChanged {
+    FlagsChanged {
+        flag_keys: flag_keys.iter().map(ToString::to_string).collect(),
+    }
+}
+
+#[tokio::test]
+async fn resolves_flags_of_every_type() {
+    let provider = InMemoryProvider::new();
+    provider.set_flag("enabled", true, Some("on"), None);
+    provider.set_flag("limit", 25, None, None);
+    provider.set_flag("ratio", 0.5, None, Some(EvaluationReason::Default));
+    provider.set_flag("theme", "dark", None, None);
+    provider.set_flag(
+        "layout",
+        StructValue::default().with_field("columns", 2),
+        None,
+        None,
+    );
+    let context = EvaluationContext::default();
+
+    let details = provider
+        .resolve_bool_value("enabled", &context)
+        .await
+        .unwrap();
+    assert!(details.value);
+    assert_eq!(details.variant.as_deref(), Some("on"));
+    assert_eq!(details.reason, Some(EvaluationReason::Static));
+    assert_eq!(
+        provider
+            .resolve_int_value("limit", &context)
+            .await
+            .unwrap()
+            .value,
+        25
+    );
+    let ratio = provider
+        .resolve_float_value("ratio", &context)
+        .await
+        .unwrap();
+    assert_eq!(ratio.value, 0.5);
+    assert_eq!(ratio.reason, Some(EvaluationReason::Default));
+    assert_eq!(
+        provider
+            .resolve_string_value("theme", &context)
+            .await
+            .unwrap()
+            .value,
+        "dark"
+    );
+    let layout = provider
+        .resolve_struct_value("layout", &context)
+        .await
+        .unwrap()
+        .value;
+    assert_eq!(
+        layout
+            .fields
+            .get("columns")
+            .and_then(|value| value.as_i64()),
+        Some(2)
+    );
+}
+
+#[tokio::test]
+async fn fails_evaluations_of_missing_flags_and_other_types() {
+    let provider = InMemoryProvider::new();
+    provider.set_flag("limit", 25, None, None);
+    let context = EvaluationContext::default();
+
+    let error = provider
+        .resolve_int_value("missing", &context)
+        .await
+        .unwrap_err();
+    assert_eq!(error.code, EvaluationErrorCode::FlagNotFound);
+    let error = provider
+        .resolve_float_value("limit", &context)
+        .await
+        .unwrap_err();
+    assert_eq!(error.code, EvaluationErrorCode::TypeMismatch);
+}
+
+#[tokio::test]
+async fn removed_flags_are_not_found() {
+    let provider = InMemoryProvider::new();
+    provider.set_flag("enabled", true, None, None);
+    provider.set_flag("limit", 25, None, None);
+    let context = EvaluationContext::default();
+
+    provider.remove_flag("enabled");
+    let error = provider
+        .resolve_bool_value("enabled", &context)
+        .await
+        .unwrap_err();
+    assert_eq!(error.code, EvaluationErrorCode::FlagNotFound);
+    assert!(provider.resolve_int_value("limit", &context).await.is_ok());
+
+    provider.clear();
+    assert!(provider.resolve_int_value("limit", &context).await.is_err());
+}
+
+#[tokio::test]
+async fn targeting_overrides_the_value_per_context() {
+    let provider = InMemoryProvider::new();
+    provider.set_flag("enabled", false, Some("off"), None);
+    assert!(provider.set_targeting("enabled", |context| {
+        (context.targeting_key.as_deref() == Some("beta-tester")).then(|| true.into())
+    }));
+
+    let beta = EvaluationContext::default().with_targeting_key("beta-tester");
+    let details = provider.resolve_bool_value("enabled", &beta).await.unwrap();
+    assert!(details.value);
+    assert_eq!(details.variant, None);
+    assert_eq!(details.reason, Some(EvaluationReason::TargetingMatch));
+
+    let details = provider
+        .resolve_bool_value("enabled", &EvaluationContext::default())
+        .await
+        .unwrap();
+    assert!(!details.value);
+    assert_eq!(details.variant.as_deref(), Some("off"));
+
+    // Setting the flag again drops its targeting.
+    provider.set_flag("enabled", false, None, None);
+    assert!(
+        !provider
+            .resolve_bool_value("enabled", &beta)
+            .await
+            .unwrap()
+            .value
+    );
+}
+
+#[test]
+fn targeting_missing_flags_fails() {
+    let provider = InMemoryProvider::new();
+    let mut changes = provider.subscribe();
+
+    assert!(!provider.set_targeting("missing", |_| Some(true.into())));
+    assert_eq!(changes.try_recv(), Err(TryRecvError::Empty));
+}
+
+#[test]
+fn announces_flag_changes_to_subscribers() {
+    let provider = InMemoryProvider::new();
+    let mut changes = provider.subscribe();
+
+    provider.set_flag("enabled", true, None, None);
+    provider.set_flag("limit", 25, None, None);
+    assert!(provider.set_targeting("enabled", |_| None));
+    assert!(!provider.set_targeting("missing", |_| None));
+    provider.remove_flag("limit");
+    provider.remove_flag("missing");
+    provider.clear();
+    provider.clear();
+
+    assert_eq!(changes.try_recv().unwrap(), changed(&["enabled"]));
+    assert_eq!(changes.try_recv().unwrap(), changed(&["limit"]));
+    assert_eq!(changes.try_recv().unwrap(), changed(&["enabled"]));
+    assert_eq!(changes.try_recv().unwrap(), changed(&["limit"]));
+    assert_eq!(changes.try_recv().unwrap(), changed(&["enabled"]));
+    assert_eq!(changes.try_recv(), Err(TryRecvError::Empty));
+}
+
+#[test]
+fn clones_share_their_flags() {
+    let provider = InMemoryProvider::new();
+    let clone = provider.clone();
+    let mut changes = clone.subscribe();
+
+    provider.set_flag("enabled", true, None, None);
+    assert_eq!(changes.try_recv().unwrap(), changed(&["enabled"]));
+}
+
+#[test]
+fn reports_its_hooks() {
+    let provider = InMemoryProvider::new().with_hook(LoggingHook::default());
+    assert_eq!(provider.hooks().len(), 1);
+    assert_eq!(provider.metadata().name, "in-memory");
+}
@@ -0,0 +1,56 @@
+use async_trait::async_trait;
+use open_feature::{
+    EvaluationContext, EvaluationDetails, EvaluationError, Hook, HookContext, HookHints, Value,
+};
+
+use crate::schemas::{JsonSchemas, ViolationAction};
+
+/// OpenFeature hook validating resolved values against their JSON schema in its `after` stage.
+///
+/// With [`ViolationAction::Fail`], the hook fails its `after` stage, which runs the error hooks.
+/// The `open-feature` 0.3 client still returns the resolved value in that case: wrap the
+/// provider in a [`JsonSchemaProvider`](crate::JsonSchemaProvider) to replace invalid values by
+/// the application's defaults.
+#[derive(Debug, Clone)]
+pub struct JsonSchemaHook {
+    schemas: JsonSchemas,
+    action: ViolationAction,
+}
+
+impl JsonSchemaHook {
+    /// Creates the hook.
+    pub fn new(schemas: JsonSchemas, action: ViolationAction) -> Self {
+        Self { schemas, action }
+    }
+}
+
+#[async_trait]
+impl Hook for JsonSchemaHook {
+    async fn before<'a>(
+        &self,
+        _: &HookContext<'a>,
+        _: Option<&'a HookHints>,
+    ) -> Result<Option<EvaluationContext>, EvaluationError> {
+        Ok(None)
+    }
+
+    async fn after<'a>(
+        &self,
+        context: &HookContext<'a>,
+        details: &EvaluationDetails<Value>,
+        _: Option<&'a HookHints>,
+    ) -> Result<(), EvaluationError> {
+        self.schemas
+            .check(context.flag_key, &details.value, self.action)
+    }
+
+    async fn error<'a>(&self, _: &HookContext<'a>, _: &EvaluationError, _: Option<&'a HookHints>) {}
+
+    async fn finally<'a>(
+        &self,
+        _: &HookContext<'a>,
+        _: &EvaluationDetails<Value>,
+        _: Option<&'a HookHints>,
+    ) {
+    }
+}
@@ -0,0 +1,58 @@
+//! JSON schema validation hook for OpenFeature.
+//!
+//! Flags often carry structured payloads, or strings restricted to a few values. When a backend
+//! returns a payload the application cannot safely consume, validating it against a JSON schema
+//! registered at startup turns a crash or a silent misbehaviour into a failed evaluation, or at
+//! least a log line.
+//!
+//! Schemas are registered per flag key in [`JsonSchemas`] and apply to values of any type:
+//! structs are validated as JSON objects, strings as JSON strings. Flags without a schema are not
+//! validated. A [`ViolationAction`] chooses between failing evaluations of invalid values with
+//! `TYPE_MISMATCH` and only logging them.
+//!
+//! - [`JsonSchemaHook`] validates in the `after` stage of the evaluation.
+//! - [`JsonSchemaProvider`] wraps a provider and validates its resolutions. Since the
+//!   `open-feature` 0.3 client returns the resolved value even when an `after` hook fails, this is
+//!   the way to replace invalid values by the application's defaults.
+//!
+//! # Example
+//!
+//! ```rust
+//! use open_feature::provider::FeatureProvider;
+//! use open_feature::{EvaluationContext, EvaluationErrorCode, StructValue};
+//! use open_feature_in_memory::InMemoryProvider;
+//! use open_feature_json_schema_hook::{JsonSchemaProvider, JsonSchemas, ViolationAction};
+//! use serde_json::json;
+//!
+//! #[tokio::main]
+//! async fn main() {
+//!     let flags = InMemoryProvider::new();
+//!     flags.set_flag("banner", StructValue::default().with_field("color", 3), None, None);
+//!
+//!     let schemas = JsonSchemas::new()
+//!         .with_schema(
+//!             "banner",
+//!             &json!({
+//!                 "type": "object",
+//!                 "properties": {"color": {"type": "string"}},
+//!                 "required": ["color"]
+//!             }),
+//!         )
+//!         .expect("Invalid schema");
+//!     let provider = JsonSchemaProvider::new(flags, schemas, ViolationAction::Fail);
+//!
+//!     let error = provider
+//!         .resolve_struct_value("banner", &EvaluationContext::default())
+//!         .await
+//!         .unwrap_err();
+//!     assert_eq!(error.code, EvaluationErrorCode::TypeMismatch);
+//! }
+//! ```
+
+mod hook;
+mod provider;
+mod schemas;
+
+pub use crate::hook::JsonSchemaHook;
+pub use crate::provider::JsonSchemaProvider;
+pub use crate::schemas::{JsonSchemas, SchemaError, ViolationAction};
@@ -0,0 +1,62 @@
+use async_trait::async_trait;
+use open_feature::provider::{
+    FeatureProvider, ProviderMetadata, ProviderStatus, ResolutionDetails,
+};
+use open_feature::{EvaluationContext, EvaluationResult, HookWrapper};
+use openfeature_contrib_common::{impl_resolve_methods, FlagValue};
+
+use crate::schemas::{JsonSchemas, ViolationAction};
+
+/// OpenFeature provider validating the values of a wrapped provider against their JSON schema.
+///
+/// With [`ViolationAction::Fail`], evaluations of invalid values fail with `TYPE_MISMATCH`, so
+/// the application gets its default value. The provider reports the metadata, status and hooks
+/// of the wrapped provider.
+pub struct JsonSchemaProvider<P> {
+    provider: P,
+    schemas: JsonSchemas,
+    action: ViolationAction,
+}
+
+impl<P: FeatureProvider> JsonSchemaProvider<P> {
+    /// Wraps `provider`.
+    pub fn new(provider: P, schemas: JsonSchemas, action: ViolationAction) -> Self {
+        Self {
+            provider,
+            schemas,
+            action,
+        }
+    }
+
+    async fn resolve<T: FlagValue>(
+        &self,
+        flag_key: &str,
+        context: &EvaluationContext,
+    ) -> EvaluationResult<ResolutionDetails<T>> {
+        let details = T::resolve(&self.provider, flag_key, context).await?;
+        self.schemas
+            .check(flag_key, &details.value.clone().into(), self.action)?;
+        Ok(details)
+    }
+}
+
+#[async_trait]
+impl<P: FeatureProvider> FeatureProvider for JsonSchemaProvider<P> {
+    async fn initialize(&mut self, context: &EvaluationContext) {
+        self.provider.initialize(context).await;
+    }
+
+    fn status(&self) -> ProviderStatus {
+        self.provider.status()
+    }
+
+    fn metadata(&self) -> &ProviderMetadata {
+        self.provider.metadata()
+    }
+
+    fn hooks(&self) -> &[HookWrapper] {
+        self.provider.hooks()
+    }
+
+    impl_resolve_methods!();
+}
@@ -0,0 +1,103 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use jsonschema::Validator;
+use open_feature::{EvaluationError, EvaluationErrorCode, EvaluationResult, Value};
+use openfeature_contrib_common::value_to_json;
+use thiserror::Error;
+use tracing::warn;
+
+/// Error raised when registering a schema that is not a valid JSON schema.
+#[derive(Debug, Error)]
+#[error("invalid schema of {flag_key}: {message}")]
+pub struct SchemaError {
+    /// Key of the flag the schema was registered for.
+    pub flag_key: String,
+    /// Cause of the failure.
+    pub message: String,
+}
+
+/// What happens when a resolved value violates its schema.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ViolationAction {
+    /// The evaluation fails with `TYPE_MISMATCH`, so the application gets its default value.
+    #[default]
+    Fail,
+    /// The violation is logged and the value is used anyway.
+    Log,
+}
+
+/// JSON schemas of flag values, registered per flag key.
+///
+/// Flags without a schema are not validated.
+#[derive(Debug, Clone, Default)]
+pub struct JsonSchemas {
+    validators: HashMap<String, Arc<Validator>>,
+}
+
+impl JsonSchemas {
+    /// Creates an empty set of schemas.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registers the schema of a flag, replacing any previous one. The draft is detected from
+    /// the `$schema` keyword, defaulting to the latest one.
+    pub fn with_schema(
+        mut self,
+        flag_key: impl Into<String>,
+        schema: &serde_json::Value,
+    ) -> Result<Self, SchemaError> {
+        let flag_key = flag_key.into();
+        match jsonschema::validator_for(schema) {
+            Ok(validator) => {
+                self.validators.insert(flag_key, Arc::new(validator));
+                Ok(self)
+            }
+            Err(e) => Err(SchemaError {
+                flag_key,
+                message: e.to_string(),
+            }),
+        }
+    }
+
+    /// Validates a value of a flag, returning the first violation of its schema.
+    pub fn validate(&self, flag_key: &str, value: &Value) -> Result<(), String> {
+        let Some(validator) = self.validators.get(flag_key) else {
+            return Ok(());
+        };
+        let instance = value_to_json(value);
+        validator.validate(&instance).map_err(|e| {
+            let path = e.instance_path.to_string();
+            if path.is_empty() {
+                e.to_string()
+            } else {
+                format!("{e} at {path}")
+            }
+        })
+    }
+
+    /// Validates a value of a flag, applying `action` to violations.
+    pub(crate) fn check(
+        &self,
+        flag_key: &str,
+        value: &Value,
+        action: ViolationAction,
+    ) -> EvaluationResult<()> {
+        let Err(violation) = self.validate(flag_key, value) else {
+            return Ok(());
+        };
+        match action {
+            ViolationAction::Fail => Err(EvaluationError::builder()
+                .code(EvaluationErrorCode::TypeMismatch)
+                .message(format!(
+                    "Value of {flag_key} violates its schema: {violation}"
+                ))
+                .build()),
+            ViolationAction::Log => {
+                warn!("Value of {flag_key} violates its schema: {violation}");
+                Ok(())
+            }
+        }
+    }
+}
@@ -0,0 +1,168 @@
+use std::sync::{Arc, Mutex};
+
+use async_trait::async_trait;
+use open_feature::provider::FeatureProvider;
+use open_feature::{
+    Client, EvaluationContext, EvaluationDetails, EvaluationError, EvaluationErrorCode, Hook,
+    HookContext, HookHints, LoggingHook, OpenFeature, StructValue, Value,
+};
+use open_feature_in_memory::InMemoryProvider;
+use open_feature_json_schema_hook::{
+    JsonSchemaHook, JsonSchemaProvider, JsonSchemas, ViolationAction,
+};
+use serde_json::json;
+
+fn flags() -> InMemoryProvider {
+    let flags = InMemoryProvider::new();
+    flags.set_flag(
+        "banner",
+        StructValue::default().with_field("color", 3),
+        None,
+        None,
+    );
+    flags.set_flag("theme", "dark", None, None);
+    flags
+}
+
+fn schemas() -> JsonSchemas {
+    JsonSchemas::new()
+        .with_schema(
+            "banner",
+            &json!({
+                "type": "object",
+                "properties": {"color": {"type": "string"}},
+                "required": ["color"]
+            }),
+        )
+        .unwrap()
+        .with_schema("theme", &json!({"enum": ["light", "dark"]}))
+        .unwrap()
+}
+
+/// Hook recording the errors of the evaluations it surrounds.
+#[derive(Clone, Default)]
+struct Errors(Arc<Mutex<Vec<EvaluationError>>>);
+
+#[async_trait]
+impl Hook for Errors {
+    async fn before<'a>(
+        &self,
+        _: &HookContext<'a>,
+        _: Option<&'a HookHints>,
+    ) -> Result<Option<EvaluationContext>, EvaluationError> {
+        Ok(None)
+    }
+
+    async fn after<'a>(
+        &self,
+        _: &HookContext<'a>,
+        _: &EvaluationDetails<Value>,
+        _: Option<&'a HookHints>,
+    ) -> Result<(), EvaluationError> {
+        Ok(())
+    }
+
+    async fn error<'a>(
+        &self,
+        _: &HookContext<'a>,
+        error: &EvaluationError,
+        _: Option<&'a HookHints>,
+    ) {
+        self.0.lock().unwrap().push(error.clone());
+    }
+
+    async fn finally<'a>(
+        &self,
+        _: &HookContext<'a>,
+        _: &EvaluationDetails<Value>,
+        _: Option<&'a HookHints>,
+    ) {
+    }
+}
+
+/// Creates a client of its own provider validating values with a hook.
+async fn client(domain: &str, action: ViolationAction, errors: &Errors) -> Client {
+    let mut api = OpenFeature::singleton_mut().await;
+    api.set_named_provider(domain, flags()).await;
+    api.create_named_client(domain)
+        .with_hook(errors.clone())
+        .with_hook(JsonSchemaHook::new(schemas(), action))
+}
+
+#[tokio::test]
+async fn providers_fail_evaluations_of_invalid_values() {
+    let provider = JsonSchemaProvider::new(flags(), schemas(), ViolationAction::Fail);
+    let context = EvaluationContext::default();
+
+    let error = provider
+        .resolve_struct_value("banner", &context)
+        .await
+        .unwrap_err();
+    assert_eq!(error.code, EvaluationErrorCode::TypeMismatch);
+    assert!(error
+        .message
+        .unwrap()
+        .starts_with("Value of banner violates its schema"));
+    assert_eq!(
+        provider
+            .resolve_string_value("theme", &context)
+            .await
+            .unwrap()
+            .value,
+        "dark"
+    );
+}
+
+#[tokio::test]
+async fn providers_serve_invalid_values_when_logging() {
+    let provider = JsonSchemaProvider::new(flags(), schemas(), ViolationAction::Log);
+
+    let details = provider
+        .resolve_struct_value("banner", &EvaluationContext::default())
+        .await
+        .unwrap();
+    assert_eq!(
+        details.value.fields.get("color").and_then(Value::as_i64),
+        Some(3)
+    );
+}
+
+#[tokio::test]
+async fn hooks_fail_the_after_stage_of_invalid_values() {
+    let errors = Errors::default();
+    let client = client("fail", ViolationAction::Fail, &errors).await;
+
+    client.get_string_value("theme", None, None).await.unwrap();
+    assert!(errors.0.lock().unwrap().is_empty());
+
+    // The client still returns the resolved value, after running the error hooks.
+    client
+        .get_struct_value::<StructValue>("banner", None, None)
+        .await
+        .unwrap();
+    let errors = errors.0.lock().unwrap();
+    assert_eq!(errors.len(), 1);
+    assert_eq!(errors[0].code, EvaluationErrorCode::TypeMismatch);
+}
+
+#[tokio::test]
+async fn hooks_only_log_violations_when_logging() {
+    let errors = Errors::default();
+    let client = client("log", ViolationAction::Log, &errors).await;
+
+    client
+        .get_struct_value::<StructValue>("banner", None, None)
+        .await
+        .unwrap();
+    assert!(errors.0.lock().unwrap().is_empty());
+}
+
+#[test]
+fn reports_the_hooks_of_the_wrapped_provider() {
+    let provider = JsonSchemaProvider::new(
+        InMemoryProvider::new().with_hook(LoggingHook::default()),
+        JsonSchemas::new(),
+        ViolationAction::Fail,
+    );
+    assert_eq!(provider.hooks().len(), 1);
+}
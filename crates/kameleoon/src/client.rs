@@ -0,0 +1,88 @@
+use std::sync::{Mutex, PoisonError};
+
+use openfeature_contrib_common::HttpError;
+use reqwest::header::{HeaderValue, ETAG, IF_NONE_MATCH};
+use reqwest::{Client, StatusCode};
+use tracing::debug;
+
+use crate::configuration::Configuration;
+use crate::{KameleoonError, KameleoonOptions};
+
+/// Thin HTTP client for the Kameleoon SDK configuration and Data API.
+pub(crate) struct KameleoonClient {
+    http: Client,
+    configuration_url: String,
+    data_url: String,
+    site_code: String,
+    environment: Option<String>,
+    etag: Mutex<Option<HeaderValue>>,
+}
+
+impl KameleoonClient {
+    pub(crate) fn new(options: &KameleoonOptions) -> Result<Self, KameleoonError> {
+        let http = Client::builder().timeout(options.request_timeout).build()?;
+
+        Ok(Self {
+            http,
+            configuration_url: options.configuration_url.trim_end_matches('/').to_string(),
+            data_url: options.data_url.trim_end_matches('/').to_string(),
+            site_code: options.site_code.clone(),
+            environment: options.environment.clone(),
+            etag: Mutex::new(None),
+        })
+    }
+
+    /// Fetches the SDK configuration, returning `None` when the server reports it as unchanged.
+    pub(crate) async fn fetch_configuration(
+        &self,
+    ) -> Result<Option<Configuration>, KameleoonError> {
+        let url = format!("{}/v3/{}", self.configuration_url, self.site_code);
+        let mut request = self.http.get(&url);
+        if let Some(environment) = &self.environment {
+            request = request.query(&[("environment", environment)]);
+        }
+        if let Some(etag) = self
+            .etag
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clone()
+        {
+            request = request.header(IF_NONE_MATCH, etag);
+        }
+
+        let response = request.send().await?;
+        match response.status() {
+            StatusCode::NOT_MODIFIED => {
+                debug!("Kameleoon configuration not modified");
+                Ok(None)
+            }
+            status if status.is_success() => {
+                let etag = response.headers().get(ETAG).cloned();
+                let configuration = response.json::<Configuration>().await?;
+                *self.etag.lock().unwrap_or_else(PoisonError::into_inner) = etag;
+                Ok(Some(configuration))
+            }
+            _ => Err(HttpError::status_of(&response).into()),
+        }
+    }
+
+    /// Fetches the remote data stored under `key`, returning `None` when it does not exist.
+    pub(crate) async fn fetch_remote_data(
+        &self,
+        key: &str,
+    ) -> Result<Option<serde_json::Value>, KameleoonError> {
+        let url = format!("{}/data", self.data_url);
+        let response = self
+            .http
+            .get(&url)
+            .query(&[("siteCode", self.site_code.as_str()), ("key", key)])
+            .send()
+            .await?;
+
+        match response.status() {
+            StatusCode::NOT_FOUND => Ok(None),
+            status if status.is_success() => Ok(Some(response.json().await?)),
+            _ => Err(HttpError::status_of(&response).into()),
+        }
+    }
+}
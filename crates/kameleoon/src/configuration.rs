@@ -0,0 +1,573 @@
+//! The feature flag section of the Kameleoon server SDK configuration and its local evaluation.
+//!
+//! Bucketing follows the official SDKs: the visitor code, the container id (rule or experiment)
+//! and the optional respool time are concatenated, hashed with 32-bit MurmurHash3 and scaled to
+//! `[0, 1]`, so visitors receive the same variation as with any other Kameleoon SDK.
+
+use std::collections::HashMap;
+use std::io::Cursor;
+
+use open_feature::EvaluationReason;
+use regex::Regex;
+use serde::Deserialize;
+use tracing::debug;
+
+/// Variation key Kameleoon uses for a feature that is turned off.
+pub(crate) const OFF_VARIATION: &str = "off";
+
+const CUSTOM_DATUM: &str = "CUSTOM_DATUM";
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct Configuration {
+    #[serde(default)]
+    pub(crate) feature_flags: Vec<FeatureFlag>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct FeatureFlag {
+    pub(crate) feature_key: String,
+    #[serde(default)]
+    variations: Vec<Variation>,
+    default_variation_key: String,
+    #[serde(default = "enabled")]
+    environment_enabled: bool,
+    #[serde(default)]
+    rules: Vec<Rule>,
+    #[serde(default)]
+    bucketing_custom_data_index: Option<usize>,
+}
+
+fn enabled() -> bool {
+    true
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct Variation {
+    pub(crate) key: String,
+    #[serde(default)]
+    pub(crate) variables: Vec<Variable>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct Variable {
+    pub(crate) key: String,
+    #[serde(default)]
+    pub(crate) value: serde_json::Value,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Rule {
+    id: i64,
+    #[serde(rename = "type", default)]
+    kind: RuleType,
+    #[serde(default)]
+    exposition: f64,
+    #[serde(default)]
+    experiment_id: Option<i64>,
+    #[serde(default)]
+    respool_time: Option<serde_json::Value>,
+    #[serde(default)]
+    variation_by_exposition: Vec<VariationByExposition>,
+    #[serde(default)]
+    segment: Option<Segment>,
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+enum RuleType {
+    #[default]
+    Experimentation,
+    TargetedDelivery,
+    #[serde(other)]
+    Unknown,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct VariationByExposition {
+    #[serde(default)]
+    variation_key: Option<String>,
+    #[serde(default)]
+    exposition: f64,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Segment {
+    #[serde(default)]
+    conditions_data: Option<ConditionsData>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ConditionsData {
+    #[serde(default)]
+    first_level_or_operators: Vec<bool>,
+    #[serde(default)]
+    first_level: Vec<ConditionGroup>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ConditionGroup {
+    #[serde(default)]
+    or_operators: Vec<bool>,
+    #[serde(default)]
+    conditions: Vec<Condition>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Condition {
+    targeting_type: String,
+    #[serde(default = "enabled")]
+    is_include: bool,
+    #[serde(default)]
+    custom_data_index: Option<serde_json::Value>,
+    #[serde(default)]
+    value_match_type: Option<String>,
+    #[serde(default)]
+    value: Option<serde_json::Value>,
+}
+
+/// The visitor an evaluation is made for.
+#[derive(Debug)]
+pub(crate) struct Visitor<'a> {
+    pub(crate) code: &'a str,
+    /// Custom data values, keyed by custom data index.
+    pub(crate) custom_data: HashMap<usize, String>,
+}
+
+/// Outcome of evaluating a [`FeatureFlag`].
+#[derive(Debug)]
+pub(crate) struct Evaluation<'a> {
+    pub(crate) variation_key: &'a str,
+    pub(crate) variation: Option<&'a Variation>,
+    pub(crate) rule_id: Option<i64>,
+    pub(crate) reason: EvaluationReason,
+}
+
+impl FeatureFlag {
+    pub(crate) fn evaluate(&self, visitor: &Visitor<'_>) -> Evaluation<'_> {
+        let (variation_key, rule_id, reason) = if self.environment_enabled {
+            self.evaluate_rules(visitor).map_or(
+                (
+                    self.default_variation_key.as_str(),
+                    None,
+                    EvaluationReason::Default,
+                ),
+                |(key, rule, reason)| (key, Some(rule), reason),
+            )
+        } else {
+            (
+                self.default_variation_key.as_str(),
+                None,
+                EvaluationReason::Disabled,
+            )
+        };
+
+        Evaluation {
+            variation_key,
+            variation: self
+                .variations
+                .iter()
+                .find(|variation| variation.key == variation_key),
+            rule_id,
+            reason,
+        }
+    }
+
+    fn evaluate_rules(&self, visitor: &Visitor<'_>) -> Option<(&str, i64, EvaluationReason)> {
+        let code = self
+            .bucketing_custom_data_index
+            .and_then(|index| visitor.custom_data.get(&index))
+            .map_or(visitor.code, String::as_str);
+
+        for rule in &self.rules {
+            if !rule
+                .segment
+                .as_ref()
+                .and_then(|segment| segment.conditions_data.as_ref())
+                .map_or(true, |conditions| conditions.matches(visitor))
+            {
+                continue;
+            }
+
+            let respool_time = rule.respool_time.as_ref().map(respool_suffix);
+            let respool_time = respool_time.as_deref().unwrap_or_default();
+            if hash(code, rule.id, respool_time) <= rule.exposition {
+                match rule.kind {
+                    RuleType::TargetedDelivery => {
+                        if let Some(key) = rule
+                            .variation_by_exposition
+                            .first()
+                            .and_then(|variation| variation.variation_key.as_deref())
+                        {
+                            return Some((key, rule.id, EvaluationReason::TargetingMatch));
+                        }
+                    }
+                    RuleType::Experimentation => {
+                        let container = rule.experiment_id.unwrap_or(rule.id);
+                        let value = hash(code, container, respool_time);
+                        let mut total = 0.0;
+                        for variation in &rule.variation_by_exposition {
+                            total += variation.exposition;
+                            if value <= total {
+                                if let Some(key) = variation.variation_key.as_deref() {
+                                    return Some((key, rule.id, EvaluationReason::Split));
+                                }
+                                break;
+                            }
+                        }
+                    }
+                    RuleType::Unknown => debug!(
+                        "Skipping rule {} of unknown type on feature {}",
+                        rule.id, self.feature_key
+                    ),
+                }
+            } else if rule.kind == RuleType::TargetedDelivery {
+                // A visitor outside the exposition of a delivery rule gets the default variation.
+                break;
+            }
+        }
+
+        None
+    }
+}
+
+impl ConditionsData {
+    fn matches(&self, visitor: &Visitor<'_>) -> bool {
+        combine(
+            self.first_level.iter().map(|group| group.matches(visitor)),
+            &self.first_level_or_operators,
+        )
+    }
+}
+
+impl ConditionGroup {
+    fn matches(&self, visitor: &Visitor<'_>) -> bool {
+        combine(
+            self.conditions
+                .iter()
+                .map(|condition| condition.matches(visitor)),
+            &self.or_operators,
+        )
+    }
+}
+
+impl Condition {
+    fn matches(&self, visitor: &Visitor<'_>) -> bool {
+        let matched = match self.targeting_type.as_str() {
+            CUSTOM_DATUM => self.matches_custom_datum(visitor),
+            other => {
+                debug!("Unsupported Kameleoon targeting condition {other}");
+                return false;
+            }
+        };
+        matched == self.is_include
+    }
+
+    fn matches_custom_datum(&self, visitor: &Visitor<'_>) -> bool {
+        let index = match &self.custom_data_index {
+            Some(serde_json::Value::Number(index)) => index.as_u64(),
+            Some(serde_json::Value::String(index)) => index.parse().ok(),
+            _ => None,
+        };
+        let value = index
+            .and_then(|index| usize::try_from(index).ok())
+            .and_then(|index| visitor.custom_data.get(&index));
+        let expected = match &self.value {
+            Some(serde_json::Value::String(value)) => value.clone(),
+            Some(value) => value.to_string(),
+            None => String::new(),
+        };
+
+        let Some(value) = value else {
+            return self.value_match_type.as_deref() == Some("UNDEFINED");
+        };
+        match self.value_match_type.as_deref() {
+            Some("EXACT") => *value == expected,
+            Some("CONTAINS") => value.contains(&expected),
+            Some("REGULAR_EXPRESSION") => {
+                Regex::new(&expected).is_ok_and(|regex| regex.is_match(value))
+            }
+            Some("LOWER") => compare(value, &expected).is_some_and(|(v, e)| v < e),
+            Some("EQUAL") => compare(value, &expected).is_some_and(|(v, e)| v == e),
+            Some("GREATER") => compare(value, &expected).is_some_and(|(v, e)| v > e),
+            Some("TRUE") => value == "true",
+            Some("FALSE") => value == "false",
+            Some("AMONG_VALUES") => serde_json::from_str::<Vec<serde_json::Value>>(&expected)
+                .is_ok_and(|values| {
+                    values.iter().any(|candidate| match candidate {
+                        serde_json::Value::String(candidate) => candidate == value,
+                        candidate => serde_json::from_str::<serde_json::Value>(value)
+                            .is_ok_and(|value| value == *candidate),
+                    })
+                }),
+            _ => false,
+        }
+    }
+}
+
+/// Combines condition results, `AND` binding tighter than `OR`.
+fn combine(results: impl Iterator<Item = bool>, or_operators: &[bool]) -> bool {
+    let mut any = false;
+    let mut all = true;
+    let mut empty = true;
+    for (i, result) in results.enumerate() {
+        empty = false;
+        if i > 0 && or_operators.get(i - 1).copied().unwrap_or(false) {
+            any |= all;
+            all = true;
+        }
+        all &= result;
+    }
+    empty || any || all
+}
+
+fn compare(value: &str, expected: &str) -> Option<(f64, f64)> {
+    Some((value.parse().ok()?, expected.parse().ok()?))
+}
+
+fn respool_suffix(value: &serde_json::Value) -> String {
+    match value {
+        serde_json::Value::String(value) => value.clone(),
+        value => value.to_string(),
+    }
+}
+
+/// Maps a visitor onto `[0, 1]` for the given container (rule or experiment).
+fn hash(code: &str, container_id: i64, suffix: &str) -> f64 {
+    let input = format!("{code}{container_id}{suffix}");
+    let hash = murmur3::murmur3_32(&mut Cursor::new(input.as_bytes()), 0)
+        .expect("reading from memory cannot fail");
+    f64::from(hash) / 2f64.powi(32)
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn flag(rules: serde_json::Value) -> FeatureFlag {
+        serde_json::from_value(json!({
+            "featureKey": "new-checkout",
+            "defaultVariationKey": "off",
+            "variations": [
+                {"key": "on", "variables": [{"key": "title", "value": "New"}]},
+                {"key": "off"}
+            ],
+            "rules": rules
+        }))
+        .expect("invalid feature flag")
+    }
+
+    fn visitor<'a>(code: &'a str, custom_data: &[(usize, &str)]) -> Visitor<'a> {
+        Visitor {
+            code,
+            custom_data: custom_data
+                .iter()
+                .map(|(index, value)| (*index, value.to_string()))
+                .collect(),
+        }
+    }
+
+    fn variation(flag: &FeatureFlag, visitor: &Visitor<'_>) -> (String, EvaluationReason) {
+        let evaluation = flag.evaluate(visitor);
+        (evaluation.variation_key.to_string(), evaluation.reason)
+    }
+
+    fn delivery(id: i64, exposition: f64) -> serde_json::Value {
+        json!({
+            "id": id,
+            "type": "TARGETED_DELIVERY",
+            "exposition": exposition,
+            "variationByExposition": [{"variationKey": "on", "exposition": 1.0}]
+        })
+    }
+
+    fn experiment(id: i64) -> serde_json::Value {
+        json!({
+            "id": id,
+            "type": "EXPERIMENTATION",
+            "exposition": 1.0,
+            "variationByExposition": [
+                {"variationKey": "on", "exposition": 0.5},
+                {"variationKey": "off", "exposition": 0.5}
+            ]
+        })
+    }
+
+    fn datum(match_type: &str, value: serde_json::Value) -> serde_json::Value {
+        json!({
+            "targetingType": "CUSTOM_DATUM",
+            "customDataIndex": "0",
+            "valueMatchType": match_type,
+            "value": value
+        })
+    }
+
+    fn matches(condition: serde_json::Value, value: Option<&str>) -> bool {
+        let condition: Condition = serde_json::from_value(condition).unwrap();
+        let custom_data = value
+            .map(|value| (0, value))
+            .into_iter()
+            .collect::<Vec<_>>();
+        condition.matches(&visitor("visitor-a", &custom_data))
+    }
+
+    #[test]
+    fn hashes_match_the_kameleoon_sdks() {
+        assert!((hash("visitor-a", 100, "") - 0.867_849_518_544_971_9).abs() < 1e-12);
+        assert!((hash("visitor-d", 100, "") - 0.343_294_340_651_482_34).abs() < 1e-12);
+        assert!((hash("visitor-a", 200, "") - 0.191_156_537_737_697_36).abs() < 1e-12);
+        assert!((hash("visitor-a", 100, "v1") - 0.031_489_485_176_280_14).abs() < 1e-12);
+    }
+
+    #[test]
+    fn delivery_rules_expose_visitors_within_their_exposition() {
+        // visitor-d hashes to 0.34 and visitor-a to 0.87 for rule 100.
+        let flag = flag(json!([delivery(100, 0.5), delivery(300, 1.0)]));
+
+        let evaluation = flag.evaluate(&visitor("visitor-d", &[]));
+        assert_eq!(evaluation.variation_key, "on");
+        assert_eq!(evaluation.rule_id, Some(100));
+        assert_eq!(evaluation.reason, EvaluationReason::TargetingMatch);
+        assert_eq!(evaluation.variation.unwrap().variables[0].key, "title");
+        // Visitors outside the exposition of a delivery rule skip the remaining rules.
+        assert_eq!(
+            variation(&flag, &visitor("visitor-a", &[])),
+            ("off".to_string(), EvaluationReason::Default)
+        );
+    }
+
+    #[test]
+    fn experiments_split_visitors_by_variation_exposition() {
+        // For rule 200, visitor-a hashes to 0.19 and visitor-b to 0.98.
+        let flag = flag(json!([experiment(200)]));
+
+        assert_eq!(
+            variation(&flag, &visitor("visitor-a", &[])),
+            ("on".to_string(), EvaluationReason::Split)
+        );
+        assert_eq!(
+            variation(&flag, &visitor("visitor-b", &[])),
+            ("off".to_string(), EvaluationReason::Split)
+        );
+    }
+
+    #[test]
+    fn experiments_bucket_by_experiment_id() {
+        // For experiment 100, visitor-a hashes to 0.87.
+        let mut rule = experiment(200);
+        rule["experimentId"] = json!(100);
+        let flag = flag(json!([rule]));
+
+        assert_eq!(variation(&flag, &visitor("visitor-a", &[])).0, "off");
+    }
+
+    #[test]
+    fn respool_times_rebucket_visitors() {
+        // With the respool time, visitor-a hashes to 0.03 for rule 100.
+        let mut rule = delivery(100, 0.5);
+        rule["respoolTime"] = json!("v1");
+        let flag = flag(json!([rule]));
+
+        assert_eq!(variation(&flag, &visitor("visitor-a", &[])).0, "on");
+    }
+
+    #[test]
+    fn bucketing_custom_data_replaces_the_visitor_code() {
+        // account-1 hashes to 0.76 for rule 100, visitor-d to 0.34.
+        let mut flag = flag(json!([delivery(100, 0.5)]));
+        flag.bucketing_custom_data_index = Some(1);
+
+        assert_eq!(
+            variation(&flag, &visitor("visitor-d", &[(1, "account-1")])).0,
+            "off"
+        );
+        assert_eq!(variation(&flag, &visitor("visitor-d", &[])).0, "on");
+    }
+
+    #[test]
+    fn disabled_environments_serve_the_default_variation() {
+        let mut flag = flag(json!([delivery(100, 1.0)]));
+        flag.environment_enabled = false;
+
+        assert_eq!(
+            variation(&flag, &visitor("visitor-d", &[])),
+            ("off".to_string(), EvaluationReason::Disabled)
+        );
+    }
+
+    #[test]
+    fn segments_restrict_rules_to_matching_visitors() {
+        let mut rule = delivery(100, 1.0);
+        rule["segment"] = json!({"conditionsData": {
+            "firstLevel": [{"conditions": [datum("EXACT", json!("premium"))]}]
+        }});
+        let flag = flag(json!([rule]));
+
+        assert_eq!(
+            variation(&flag, &visitor("visitor-a", &[(0, "premium")])).0,
+            "on"
+        );
+        assert_eq!(
+            variation(&flag, &visitor("visitor-a", &[(0, "free")])).0,
+            "off"
+        );
+        assert_eq!(variation(&flag, &visitor("visitor-a", &[])).0, "off");
+    }
+
+    #[test]
+    fn custom_datum_conditions_compare_like_kameleoon() {
+        assert!(matches(datum("CONTAINS", json!("rem")), Some("premium")));
+        assert!(matches(
+            datum("REGULAR_EXPRESSION", json!("^pre")),
+            Some("premium")
+        ));
+        assert!(!matches(
+            datum("REGULAR_EXPRESSION", json!("(")),
+            Some("premium")
+        ));
+        assert!(matches(datum("LOWER", json!(10)), Some("9.5")));
+        assert!(matches(datum("EQUAL", json!("10")), Some("10.0")));
+        assert!(!matches(datum("GREATER", json!(10)), Some("ten")));
+        assert!(matches(datum("TRUE", json!(null)), Some("true")));
+        assert!(matches(
+            datum("AMONG_VALUES", json!("[\"gold\", 5]")),
+            Some("5")
+        ));
+        assert!(!matches(
+            datum("AMONG_VALUES", json!("[\"gold\"]")),
+            Some("silver")
+        ));
+        assert!(matches(datum("UNDEFINED", json!(null)), None));
+        assert!(!matches(datum("EXACT", json!("premium")), None));
+
+        let mut excluded = datum("EXACT", json!("premium"));
+        excluded["isInclude"] = json!(false);
+        assert!(matches(excluded, Some("free")));
+        assert!(!matches(
+            json!({"targetingType": "BROWSER", "isInclude": false}),
+            None
+        ));
+    }
+
+    #[test]
+    fn and_binds_tighter_than_or() {
+        // true OR false AND false
+        assert!(combine([true, false, false].into_iter(), &[true, false]));
+        // false AND true OR false
+        assert!(!combine([false, true, false].into_iter(), &[false, true]));
+        // false OR true AND true
+        assert!(combine([false, true, true].into_iter(), &[true, false]));
+        assert!(combine(std::iter::empty(), &[]));
+    }
+}
@@ -0,0 +1,65 @@
+use std::collections::HashMap;
+
+use open_feature::EvaluationContext;
+use openfeature_contrib_common::{field_to_string, DateTimeFormat};
+
+/// Maximum length of a Kameleoon visitor code.
+const MAX_VISITOR_CODE_LENGTH: usize = 255;
+
+/// Derives the Kameleoon visitor code from the targeting key, truncated to the 255 characters
+/// Kameleoon accepts.
+pub(crate) fn visitor_code(context: &EvaluationContext) -> Option<&str> {
+    let key = context.targeting_key.as_deref()?;
+    Some(match key.char_indices().nth(MAX_VISITOR_CODE_LENGTH) {
+        Some((end, _)) => &key[..end],
+        None => key,
+    })
+}
+
+/// Converts the custom fields listed in `indexes` into Kameleoon custom data, keyed by index.
+///
+/// Custom data values are compared as strings, so numbers and booleans are formatted and
+/// date-times are sent as RFC 3339. Struct fields holding a `StructValue` are sent as JSON,
+/// other struct fields are skipped.
+pub(crate) fn to_custom_data(
+    context: &EvaluationContext,
+    indexes: &HashMap<String, usize>,
+) -> HashMap<usize, String> {
+    context
+        .custom_fields
+        .iter()
+        .filter_map(|(key, value)| {
+            let index = *indexes.get(key)?;
+            let value = field_to_string(value, DateTimeFormat::Rfc3339)?;
+            Some((index, value))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn visitor_codes_are_truncated_to_255_characters() {
+        let long = "é".repeat(300);
+        let context = EvaluationContext::default().with_targeting_key(long.as_str());
+
+        assert_eq!(visitor_code(&context), Some("é".repeat(255).as_str()));
+        assert_eq!(visitor_code(&EvaluationContext::default()), None);
+    }
+
+    #[test]
+    fn mapped_fields_become_custom_data() {
+        let context = EvaluationContext::default()
+            .with_custom_field("plan", "premium")
+            .with_custom_field("seats", 5)
+            .with_custom_field("unmapped", "ignored");
+        let indexes = HashMap::from([("plan".to_string(), 0), ("seats".to_string(), 3)]);
+
+        assert_eq!(
+            to_custom_data(&context, &indexes),
+            HashMap::from([(0, "premium".to_string()), (3, "5".to_string())])
+        );
+    }
+}
@@ -0,0 +1,26 @@
+use openfeature_contrib_common::HttpError;
+use thiserror::Error;
+
+/// Errors raised by the [`KameleoonProvider`](crate::KameleoonProvider).
+#[derive(Debug, Error)]
+pub enum KameleoonError {
+    /// The HTTP request to Kameleoon failed.
+    #[error("request to Kameleoon failed: {0}")]
+    Http(#[from] HttpError),
+}
+
+impl From<reqwest::Error> for KameleoonError {
+    fn from(error: reqwest::Error) -> Self {
+        Self::Http(error.into())
+    }
+}
+
+impl KameleoonError {
+    /// Whether the request may succeed when sent again: connection failures, timeouts, rate
+    /// limiting and server errors.
+    pub fn is_retryable(&self) -> bool {
+        match self {
+            Self::Http(e) => e.is_retryable(),
+        }
+    }
+}
@@ -0,0 +1,381 @@
+//! [Kameleoon](https://www.kameleoon.com/) feature experimentation provider for OpenFeature.
+//!
+//! The provider downloads the server SDK configuration of a Kameleoon site and evaluates feature
+//! flags locally, with the same bucketing as the official Kameleoon SDKs: visitors see the same
+//! variation whether a flag is evaluated through OpenFeature or another Kameleoon SDK. The
+//! configuration is refreshed in the background. Targeting segments built from custom data
+//! conditions are supported; other condition types never match.
+//!
+//! # Context mapping
+//!
+//! * the targeting key is used as the visitor code, truncated to the 255 characters Kameleoon
+//!   accepts, and is required;
+//! * custom fields listed in [`KameleoonOptions::custom_data`] become Kameleoon custom data with
+//!   the configured index;
+//! * a `variableKey` custom field selects the feature variable to resolve.
+//!
+//! # Value mapping
+//!
+//! The variation key is reported in [`ResolutionDetails::variant`]. Boolean flags resolve to
+//! whether the feature is active, i.e. the variation is not `off`, unless a `variableKey` is
+//! given. String, integer, float and struct flags resolve to the variable named by `variableKey`,
+//! or to the first variable of the variation.
+//!
+//! # Example
+//!
+//! ```rust,no_run
+//! use open_feature::provider::FeatureProvider;
+//! use open_feature::EvaluationContext;
+//! use open_feature_kameleoon::{KameleoonOptions, KameleoonProvider};
+//!
+//! #[tokio::main]
+//! async fn main() {
+//!     let provider = KameleoonProvider::new(KameleoonOptions {
+//!         site_code: "a8st4f59bj".to_string(),
+//!         custom_data: [("plan".to_string(), 0)].into(),
+//!         ..Default::default()
+//!     })
+//!     .await
+//!     .expect("Failed to create Kameleoon provider");
+//!
+//!     let context = EvaluationContext::default()
+//!         .with_targeting_key("visitor-123")
+//!         .with_custom_field("plan", "premium")
+//!         .with_custom_field("variableKey", "title");
+//!     let details = provider.resolve_string_value("new-checkout", &context).await;
+//!     println!("{details:?}");
+//! }
+//! ```
+
+mod client;
+mod configuration;
+mod context;
+mod error;
+
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+
+use async_trait::async_trait;
+use open_feature::provider::{FeatureProvider, ProviderMetadata, ResolutionDetails};
+use open_feature::{
+    EvaluationContext, EvaluationContextFieldValue, EvaluationError, EvaluationErrorCode,
+    EvaluationResult, FlagMetadata, StructValue, Value,
+};
+use tokio::sync::RwLock;
+use tokio::task::JoinHandle;
+use tracing::{debug, error, warn};
+
+use crate::client::KameleoonClient;
+use crate::configuration::{
+    Configuration, Evaluation, FeatureFlag, Variable, Visitor, OFF_VARIATION,
+};
+use crate::context::{to_custom_data, visitor_code};
+pub use crate::error::KameleoonError;
+
+/// Custom field selecting the feature variable to resolve.
+pub const VARIABLE_KEY: &str = "variableKey";
+
+/// Configuration of the [`KameleoonProvider`].
+#[derive(Debug, Clone)]
+pub struct KameleoonOptions {
+    /// Kameleoon site code.
+    pub site_code: String,
+    /// Environment whose feature flag settings are used. `None` uses the site's default.
+    pub environment: Option<String>,
+    /// Base URL of the SDK configuration service.
+    pub configuration_url: String,
+    /// Base URL of the Kameleoon Data API, used for remote data.
+    pub data_url: String,
+    /// Interval between configuration refreshes.
+    pub refresh_interval: Duration,
+    /// Timeout of requests to Kameleoon.
+    pub request_timeout: Duration,
+    /// Maps custom fields of the evaluation context to Kameleoon custom data indexes.
+    pub custom_data: HashMap<String, usize>,
+}
+
+impl Default for KameleoonOptions {
+    fn default() -> Self {
+        Self {
+            site_code: String::new(),
+            environment: None,
+            configuration_url: "https://sdk-config.kameleoon.eu".to_string(),
+            data_url: "https://data.kameleoon.io".to_string(),
+            refresh_interval: Duration::from_secs(60 * 60),
+            request_timeout: Duration::from_secs(10),
+            custom_data: HashMap::new(),
+        }
+    }
+}
+
+type FeatureFlags = Arc<RwLock<HashMap<String, FeatureFlag>>>;
+
+/// OpenFeature provider evaluating Kameleoon feature flags locally.
+pub struct KameleoonProvider {
+    metadata: ProviderMetadata,
+    custom_data: HashMap<String, usize>,
+    client: Arc<KameleoonClient>,
+    flags: FeatureFlags,
+    refresh: JoinHandle<()>,
+}
+
+impl KameleoonProvider {
+    /// Creates the provider, fetching the configuration and starting the background refresh.
+    pub async fn new(options: KameleoonOptions) -> Result<Self, KameleoonError> {
+        let client = Arc::new(KameleoonClient::new(&options)?);
+        let flags = client
+            .fetch_configuration()
+            .await?
+            .map(index_flags)
+            .unwrap_or_default();
+        let flags = Arc::new(RwLock::new(flags));
+        let refresh = spawn_refresh(client.clone(), flags.clone(), options.refresh_interval);
+
+        Ok(Self {
+            metadata: ProviderMetadata::new("kameleoon"),
+            custom_data: options.custom_data,
+            client,
+            flags,
+            refresh,
+        })
+    }
+
+    /// Fetches the [remote data](https://developers.kameleoon.com/apis/data-api-rest/all-endpoints/get-data/)
+    /// stored under `key` from the Kameleoon Data API, returning `None` when it does not exist.
+    pub async fn get_remote_data(
+        &self,
+        key: &str,
+    ) -> Result<Option<serde_json::Value>, KameleoonError> {
+        self.client.fetch_remote_data(key).await
+    }
+
+    async fn resolve<T>(
+        &self,
+        flag_key: &str,
+        context: &EvaluationContext,
+        resolve: impl FnOnce(&Evaluation<'_>) -> EvaluationResult<T>,
+    ) -> EvaluationResult<ResolutionDetails<T>> {
+        let code = visitor_code(context).ok_or_else(|| {
+            EvaluationError::builder()
+                .code(EvaluationErrorCode::TargetingKeyMissing)
+                .message("Kameleoon requires a targeting key as visitor code")
+                .build()
+        })?;
+        let visitor = Visitor {
+            code,
+            custom_data: to_custom_data(context, &self.custom_data),
+        };
+
+        let flags = self.flags.read().await;
+        let flag = flags.get(flag_key).ok_or_else(|| {
+            EvaluationError::builder()
+                .code(EvaluationErrorCode::FlagNotFound)
+                .message(format!("Flag {flag_key} not found"))
+                .build()
+        })?;
+        let evaluation = flag.evaluate(&visitor);
+
+        let mut flag_metadata = FlagMetadata::default();
+        if let Some(rule_id) = evaluation.rule_id {
+            flag_metadata.add_value("ruleId", rule_id);
+        }
+
+        Ok(ResolutionDetails {
+            value: resolve(&evaluation)?,
+            variant: Some(evaluation.variation_key.to_string()),
+            reason: Some(evaluation.reason.clone()),
+            flag_metadata: Some(flag_metadata),
+        })
+    }
+}
+
+impl Drop for KameleoonProvider {
+    fn drop(&mut self) {
+        self.refresh.abort();
+    }
+}
+
+#[async_trait]
+impl FeatureProvider for KameleoonProvider {
+    fn metadata(&self) -> &ProviderMetadata {
+        &self.metadata
+    }
+
+    async fn resolve_bool_value(
+        &self,
+        flag_key: &str,
+        context: &EvaluationContext,
+    ) -> EvaluationResult<ResolutionDetails<bool>> {
+        if context.custom_fields.contains_key(VARIABLE_KEY) {
+            return self
+                .resolve(flag_key, context, |evaluation| {
+                    match &variable(flag_key, evaluation, context)?.value {
+                        serde_json::Value::Bool(value) => Ok(*value),
+                        serde_json::Value::String(value) => value.parse().map_err(|_| {
+                            type_mismatch(format!("Variable of flag {flag_key} is not a boolean"))
+                        }),
+                        _ => Err(type_mismatch(format!(
+                            "Variable of flag {flag_key} is not a boolean"
+                        ))),
+                    }
+                })
+                .await;
+        }
+
+        self.resolve(flag_key, context, |evaluation| {
+            Ok(evaluation.variation_key != OFF_VARIATION)
+        })
+        .await
+    }
+
+    async fn resolve_int_value(
+        &self,
+        flag_key: &str,
+        context: &EvaluationContext,
+    ) -> EvaluationResult<ResolutionDetails<i64>> {
+        self.resolve(flag_key, context, |evaluation| {
+            let variable = variable(flag_key, evaluation, context)?;
+            match &variable.value {
+                serde_json::Value::Number(value) => value.as_i64(),
+                serde_json::Value::String(value) => value.parse().ok(),
+                _ => None,
+            }
+            .ok_or_else(|| type_mismatch(format!("Variable of flag {flag_key} is not an integer")))
+        })
+        .await
+    }
+
+    async fn resolve_float_value(
+        &self,
+        flag_key: &str,
+        context: &EvaluationContext,
+    ) -> EvaluationResult<ResolutionDetails<f64>> {
+        self.resolve(flag_key, context, |evaluation| {
+            let variable = variable(flag_key, evaluation, context)?;
+            match &variable.value {
+                serde_json::Value::Number(value) => value.as_f64(),
+                serde_json::Value::String(value) => value.parse().ok(),
+                _ => None,
+            }
+            .ok_or_else(|| type_mismatch(format!("Variable of flag {flag_key} is not a number")))
+        })
+        .await
+    }
+
+    async fn resolve_string_value(
+        &self,
+        flag_key: &str,
+        context: &EvaluationContext,
+    ) -> EvaluationResult<ResolutionDetails<String>> {
+        self.resolve(flag_key, context, |evaluation| {
+            match &variable(flag_key, evaluation, context)?.value {
+                serde_json::Value::String(value) => Ok(value.clone()),
+                _ => Err(type_mismatch(format!(
+                    "Variable of flag {flag_key} is not a string"
+                ))),
+            }
+        })
+        .await
+    }
+
+    async fn resolve_struct_value(
+        &self,
+        flag_key: &str,
+        context: &EvaluationContext,
+    ) -> EvaluationResult<ResolutionDetails<StructValue>> {
+        self.resolve(flag_key, context, |evaluation| {
+            let json = match &variable(flag_key, evaluation, context)?.value {
+                // JSON variables are usually delivered as encoded strings.
+                serde_json::Value::String(value) => serde_json::from_str(value).map_err(|e| {
+                    EvaluationError::builder()
+                        .code(EvaluationErrorCode::ParseError)
+                        .message(format!(
+                            "Variable of flag {flag_key} is not valid JSON: {e}"
+                        ))
+                        .build()
+                })?,
+                value => value.clone(),
+            };
+            match Value::try_from(json)? {
+                Value::Struct(value) => Ok(value),
+                _ => Err(type_mismatch(format!(
+                    "Variable of flag {flag_key} is not a JSON object"
+                ))),
+            }
+        })
+        .await
+    }
+}
+
+/// Selects the variable named by the `variableKey` custom field, or the first variable.
+fn variable<'a>(
+    flag_key: &str,
+    evaluation: &Evaluation<'a>,
+    context: &EvaluationContext,
+) -> EvaluationResult<&'a Variable> {
+    let variables = evaluation
+        .variation
+        .map(|variation| variation.variables.as_slice())
+        .unwrap_or_default();
+    let variable = match context.custom_fields.get(VARIABLE_KEY) {
+        Some(EvaluationContextFieldValue::String(key)) => {
+            variables.iter().find(|variable| variable.key == *key)
+        }
+        Some(_) => {
+            return Err(EvaluationError::builder()
+                .code(EvaluationErrorCode::InvalidContext)
+                .message(format!("{VARIABLE_KEY} must be a string"))
+                .build())
+        }
+        None => variables.first(),
+    };
+
+    variable.ok_or_else(|| {
+        type_mismatch(format!(
+            "Variation {} of flag {flag_key} has no matching variable",
+            evaluation.variation_key
+        ))
+    })
+}
+
+fn index_flags(configuration: Configuration) -> HashMap<String, FeatureFlag> {
+    configuration
+        .feature_flags
+        .into_iter()
+        .map(|flag| (flag.feature_key.clone(), flag))
+        .collect()
+}
+
+fn spawn_refresh(
+    client: Arc<KameleoonClient>,
+    flags: FeatureFlags,
+    interval: Duration,
+) -> JoinHandle<()> {
+    tokio::spawn(async move {
+        loop {
+            tokio::time::sleep(interval).await;
+            match client.fetch_configuration().await {
+                Ok(Some(configuration)) => {
+                    debug!(
+                        "Refreshed {} Kameleoon feature flags",
+                        configuration.feature_flags.len()
+                    );
+                    *flags.write().await = index_flags(configuration);
+                }
+                Ok(None) => {}
+                Err(e) if e.is_retryable() => {
+                    warn!("Failed to refresh Kameleoon configuration: {e}")
+                }
+                Err(e) => error!("Failed to refresh Kameleoon configuration: {e}"),
+            }
+        }
+    })
+}
+
+fn type_mismatch(message: String) -> EvaluationError {
+    EvaluationError::builder()
+        .code(EvaluationErrorCode::TypeMismatch)
+        .message(message)
+        .build()
+}
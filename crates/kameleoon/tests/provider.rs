@@ -0,0 +1,248 @@
+use std::collections::HashMap;
+use std::time::Duration;
+
+use open_feature::provider::FeatureProvider;
+use open_feature::{EvaluationContext, EvaluationErrorCode, EvaluationReason, StructValue};
+use open_feature_kameleoon::{KameleoonError, KameleoonOptions, KameleoonProvider};
+use openfeature_contrib_common::HttpError;
+use serde_json::json;
+use wiremock::matchers::{header, method, path, query_param};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+fn configuration() -> serde_json::Value {
+    json!({"featureFlags": [{
+        "featureKey": "new-checkout",
+        "defaultVariationKey": "off",
+        "variations": [
+            {
+                "key": "on",
+                "variables": [
+                    {"key": "title", "value": "New checkout"},
+                    {"key": "max-items", "value": 25},
+                    {"key": "ratio", "value": "0.5"},
+                    {"key": "enabled", "value": true},
+                    {"key": "banner", "value": "{\"color\": \"blue\"}"}
+                ]
+            },
+            {"key": "off"}
+        ],
+        "rules": [{
+            "id": 100,
+            "type": "TARGETED_DELIVERY",
+            "exposition": 1.0,
+            "variationByExposition": [{"variationKey": "on", "exposition": 1.0}],
+            "segment": {"conditionsData": {"firstLevel": [{"conditions": [{
+                "targetingType": "CUSTOM_DATUM",
+                "customDataIndex": 0,
+                "valueMatchType": "EXACT",
+                "value": "premium"
+            }]}]}}
+        }]
+    }]})
+}
+
+async fn server() -> MockServer {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/v3/site"))
+        .and(query_param("environment", "production"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(configuration()))
+        .mount(&server)
+        .await;
+    server
+}
+
+fn options(server: &MockServer) -> KameleoonOptions {
+    KameleoonOptions {
+        site_code: "site".to_string(),
+        environment: Some("production".to_string()),
+        configuration_url: server.uri(),
+        data_url: server.uri(),
+        custom_data: HashMap::from([("plan".to_string(), 0)]),
+        ..Default::default()
+    }
+}
+
+fn premium(variable_key: Option<&str>) -> EvaluationContext {
+    let context = EvaluationContext::default()
+        .with_targeting_key("visitor-1")
+        .with_custom_field("plan", "premium");
+    match variable_key {
+        Some(key) => context.with_custom_field("variableKey", key),
+        None => context,
+    }
+}
+
+#[tokio::test]
+async fn feature_flags_are_evaluated_locally() {
+    let server = server().await;
+    let provider = KameleoonProvider::new(options(&server)).await.unwrap();
+
+    let details = provider
+        .resolve_bool_value("new-checkout", &premium(None))
+        .await
+        .unwrap();
+    assert!(details.value);
+    assert_eq!(details.variant.as_deref(), Some("on"));
+    assert_eq!(details.reason, Some(EvaluationReason::TargetingMatch));
+
+    let free = EvaluationContext::default()
+        .with_targeting_key("visitor-1")
+        .with_custom_field("plan", "free");
+    let details = provider
+        .resolve_bool_value("new-checkout", &free)
+        .await
+        .unwrap();
+    assert!(!details.value);
+    assert_eq!(details.reason, Some(EvaluationReason::Default));
+}
+
+#[tokio::test]
+async fn variables_resolve_typed_flags() {
+    let server = server().await;
+    let provider = KameleoonProvider::new(options(&server)).await.unwrap();
+
+    let details = provider
+        .resolve_string_value("new-checkout", &premium(None))
+        .await
+        .unwrap();
+    assert_eq!(details.value, "New checkout");
+    let details = provider
+        .resolve_int_value("new-checkout", &premium(Some("max-items")))
+        .await
+        .unwrap();
+    assert_eq!(details.value, 25);
+    let details = provider
+        .resolve_float_value("new-checkout", &premium(Some("ratio")))
+        .await
+        .unwrap();
+    assert_eq!(details.value, 0.5);
+    let details = provider
+        .resolve_bool_value("new-checkout", &premium(Some("enabled")))
+        .await
+        .unwrap();
+    assert!(details.value);
+    let details = provider
+        .resolve_struct_value("new-checkout", &premium(Some("banner")))
+        .await
+        .unwrap();
+    assert_eq!(
+        details.value,
+        StructValue::default().with_field("color", "blue")
+    );
+}
+
+#[tokio::test]
+async fn evaluation_errors_are_reported() {
+    let server = server().await;
+    let provider = KameleoonProvider::new(options(&server)).await.unwrap();
+
+    let error = provider
+        .resolve_bool_value("new-checkout", &EvaluationContext::default())
+        .await
+        .unwrap_err();
+    assert_eq!(error.code, EvaluationErrorCode::TargetingKeyMissing);
+    let error = provider
+        .resolve_bool_value("missing", &premium(None))
+        .await
+        .unwrap_err();
+    assert_eq!(error.code, EvaluationErrorCode::FlagNotFound);
+    let error = provider
+        .resolve_int_value("new-checkout", &premium(Some("title")))
+        .await
+        .unwrap_err();
+    assert_eq!(error.code, EvaluationErrorCode::TypeMismatch);
+    let error = provider
+        .resolve_string_value("new-checkout", &premium(Some("missing")))
+        .await
+        .unwrap_err();
+    assert_eq!(error.code, EvaluationErrorCode::TypeMismatch);
+    let context = premium(None).with_custom_field("variableKey", 5);
+    let error = provider
+        .resolve_string_value("new-checkout", &context)
+        .await
+        .unwrap_err();
+    assert_eq!(error.code, EvaluationErrorCode::InvalidContext);
+}
+
+#[tokio::test]
+async fn unchanged_configurations_are_not_downloaded_again() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/v3/site"))
+        .and(header("if-none-match", "\"v1\""))
+        .respond_with(ResponseTemplate::new(304))
+        .with_priority(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/v3/site"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .insert_header("etag", "\"v1\"")
+                .set_body_json(configuration()),
+        )
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let provider = KameleoonProvider::new(KameleoonOptions {
+        environment: None,
+        refresh_interval: Duration::from_millis(20),
+        ..options(&server)
+    })
+    .await
+    .unwrap();
+    tokio::time::timeout(Duration::from_secs(5), async {
+        while server.received_requests().await.unwrap().len() < 3 {
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+    })
+    .await
+    .expect("the configuration was not refreshed");
+
+    let details = provider
+        .resolve_bool_value("new-checkout", &premium(None))
+        .await
+        .unwrap();
+    assert!(details.value);
+}
+
+#[tokio::test]
+async fn remote_data_is_fetched_from_the_data_api() {
+    let server = server().await;
+    Mock::given(method("GET"))
+        .and(path("/data"))
+        .and(query_param("siteCode", "site"))
+        .and(query_param("key", "visitor-1"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({"tier": "gold"})))
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/data"))
+        .and(query_param("key", "missing"))
+        .respond_with(ResponseTemplate::new(404))
+        .mount(&server)
+        .await;
+    let provider = KameleoonProvider::new(options(&server)).await.unwrap();
+
+    assert_eq!(
+        provider.get_remote_data("visitor-1").await.unwrap(),
+        Some(json!({"tier": "gold"}))
+    );
+    assert_eq!(provider.get_remote_data("missing").await.unwrap(), None);
+}
+
+#[tokio::test]
+async fn failing_configuration_fetches_fail_creation() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .respond_with(ResponseTemplate::new(403))
+        .mount(&server)
+        .await;
+
+    let result = KameleoonProvider::new(options(&server)).await;
+    assert!(
+        matches!(result, Err(KameleoonError::Http(HttpError::Status { status, .. })) if status == 403)
+    );
+}
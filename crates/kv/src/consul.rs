@@ -0,0 +1,262 @@
+//! Consul KV backend, kept fresh with blocking queries.
+
+use std::collections::HashMap;
+use std::time::Duration;
+
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use openfeature_contrib_common::{Backoff, HttpError};
+use reqwest::{Client, StatusCode};
+use serde::Deserialize;
+use tokio::sync::broadcast;
+use tokio::task::JoinHandle;
+use tracing::warn;
+
+use crate::snapshot::{replace, Snapshot};
+use crate::{FlagsChanged, KvError};
+
+const INDEX_HEADER: &str = "X-Consul-Index";
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct Entry {
+    key: String,
+    value: Option<String>,
+}
+
+pub(crate) struct ConsulClient {
+    http: Client,
+    url: String,
+    prefix: String,
+    token: Option<String>,
+    datacenter: Option<String>,
+    wait: Duration,
+}
+
+impl ConsulClient {
+    pub(crate) fn new(
+        address: &str,
+        prefix: &str,
+        token: Option<String>,
+        datacenter: Option<String>,
+        wait: Duration,
+    ) -> Result<Self, KvError> {
+        Ok(Self {
+            // Blocking queries have their own timeout, see `list`.
+            http: Client::builder().build()?,
+            url: format!("{}/v1/kv/{prefix}", address.trim_end_matches('/')),
+            prefix: prefix.to_string(),
+            token,
+            datacenter,
+            wait,
+        })
+    }
+
+    /// Reads the flags under the prefix with their index. A non-zero `index` makes this a
+    /// blocking query, answered once the flags changed or the wait time elapsed.
+    pub(crate) async fn list(&self, index: u64) -> Result<(HashMap<String, String>, u64), KvError> {
+        let mut request = self
+            .http
+            .get(&self.url)
+            .query(&[("recurse", "true")])
+            // Consul adds up to wait/16 of jitter to blocking queries.
+            .timeout(self.wait + self.wait / 16 + Duration::from_secs(10));
+        if index > 0 {
+            request = request.query(&[
+                ("index", index.to_string()),
+                ("wait", format!("{}s", self.wait.as_secs())),
+            ]);
+        }
+        if let Some(datacenter) = &self.datacenter {
+            request = request.query(&[("dc", datacenter)]);
+        }
+        if let Some(token) = &self.token {
+            request = request.header("X-Consul-Token", token);
+        }
+        let response = request.send().await?;
+
+        let index = response
+            .headers()
+            .get(INDEX_HEADER)
+            .and_then(|index| index.to_str().ok()?.parse().ok())
+            .unwrap_or(0);
+        let entries: Vec<Entry> = match response.status() {
+            // No key under the prefix.
+            StatusCode::NOT_FOUND => Vec::new(),
+            status if status.is_success() => response.json().await?,
+            _ => return Err(HttpError::status_of(&response).into()),
+        };
+
+        let mut flags = HashMap::new();
+        for entry in entries {
+            // Folders have no value.
+            let (Some(flag_key), Some(value)) = (entry.key.strip_prefix(&self.prefix), entry.value)
+            else {
+                continue;
+            };
+            if flag_key.is_empty() || flag_key.ends_with('/') {
+                continue;
+            }
+            let value = STANDARD
+                .decode(value)
+                .ok()
+                .and_then(|value| String::from_utf8(value).ok())
+                .ok_or_else(|| {
+                    KvError::InvalidResponse(format!("value of {} is not UTF-8", entry.key))
+                })?;
+            flags.insert(flag_key.to_string(), value);
+        }
+        Ok((flags, index))
+    }
+}
+
+/// Keeps `snapshot` in sync with Consul, starting from the blocking query `index`.
+pub(crate) fn spawn_watch(
+    client: ConsulClient,
+    mut index: u64,
+    snapshot: Snapshot,
+    changes: broadcast::Sender<FlagsChanged>,
+    mut backoff: Backoff,
+) -> JoinHandle<()> {
+    tokio::spawn(async move {
+        loop {
+            match client.list(index).await {
+                Ok((flags, new_index)) => {
+                    // Consul requires resetting the index when it goes backwards.
+                    index = if new_index < index { 0 } else { new_index };
+                    replace(&snapshot, &changes, flags).await;
+                    backoff.reset();
+                }
+                Err(e) => {
+                    warn!("Failed to watch Consul keys: {e}");
+                    tokio::time::sleep(backoff.next_delay()).await;
+                }
+            }
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+    use wiremock::matchers::{header, method, path, query_param, query_param_is_missing};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    use super::*;
+
+    fn client(server: &MockServer, token: Option<&str>, datacenter: Option<&str>) -> ConsulClient {
+        ConsulClient::new(
+            &format!("{}/", server.uri()),
+            "flags/",
+            token.map(str::to_string),
+            datacenter.map(str::to_string),
+            Duration::from_secs(300),
+        )
+        .unwrap()
+    }
+
+    fn entry(key: &str, value: Option<&str>) -> serde_json::Value {
+        json!({"Key": key, "Value": value.map(|value| STANDARD.encode(value))})
+    }
+
+    #[tokio::test]
+    async fn lists_decoded_flags_without_folders() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/v1/kv/flags/"))
+            .and(query_param("recurse", "true"))
+            .and(query_param_is_missing("index"))
+            .and(header("X-Consul-Token", "secret"))
+            .and(query_param("dc", "eu-west"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .insert_header(INDEX_HEADER, "42")
+                    .set_body_json(json!([
+                        entry("flags/", None),
+                        entry("flags/enabled", Some("true")),
+                        entry("flags/team/", None),
+                        entry("flags/team/color", Some("\"blue\"")),
+                        entry("flags/unset", None),
+                    ])),
+            )
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let (flags, index) = client(&server, Some("secret"), Some("eu-west"))
+            .list(0)
+            .await
+            .unwrap();
+
+        assert_eq!(index, 42);
+        assert_eq!(
+            flags,
+            HashMap::from([
+                ("enabled".to_string(), "true".to_string()),
+                ("team/color".to_string(), "\"blue\"".to_string()),
+            ])
+        );
+    }
+
+    #[tokio::test]
+    async fn blocking_queries_pass_the_index_and_wait() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/v1/kv/flags/"))
+            .and(query_param("index", "42"))
+            .and(query_param("wait", "300s"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .insert_header(INDEX_HEADER, "43")
+                    .set_body_json(json!([entry("flags/enabled", Some("false"))])),
+            )
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let (flags, index) = client(&server, None, None).list(42).await.unwrap();
+
+        assert_eq!(index, 43);
+        assert_eq!(flags["enabled"], "false");
+    }
+
+    #[tokio::test]
+    async fn a_missing_prefix_has_no_flags() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .respond_with(ResponseTemplate::new(404).insert_header(INDEX_HEADER, "7"))
+            .mount(&server)
+            .await;
+
+        let (flags, index) = client(&server, None, None).list(0).await.unwrap();
+
+        assert!(flags.is_empty());
+        assert_eq!(index, 7);
+    }
+
+    #[tokio::test]
+    async fn rejects_failed_and_invalid_responses() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(query_param("index", "1"))
+            .respond_with(ResponseTemplate::new(403))
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(query_param_is_missing("index"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!([
+                {"Key": "flags/enabled", "Value": "not base64!"}
+            ])))
+            .mount(&server)
+            .await;
+        let client = client(&server, None, None);
+
+        let error = client.list(1).await.unwrap_err();
+        assert!(matches!(
+            error,
+            KvError::Http(HttpError::Status { status, .. }) if status == StatusCode::FORBIDDEN
+        ));
+        let error = client.list(0).await.unwrap_err();
+        assert!(matches!(error, KvError::InvalidResponse(_)));
+    }
+}
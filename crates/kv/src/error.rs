@@ -0,0 +1,20 @@
+use openfeature_contrib_common::HttpError;
+use thiserror::Error;
+
+/// Errors raised while reading flags from the key-value store.
+#[derive(Debug, Error)]
+pub enum KvError {
+    /// The request to the store failed.
+    #[error("request to the key-value store failed: {0}")]
+    Http(#[from] HttpError),
+
+    /// The store answered with a response that could not be decoded.
+    #[error("invalid response from the key-value store: {0}")]
+    InvalidResponse(String),
+}
+
+impl From<reqwest::Error> for KvError {
+    fn from(error: reqwest::Error) -> Self {
+        Self::Http(error.into())
+    }
+}
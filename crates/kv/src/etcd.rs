@@ -0,0 +1,449 @@
+//! etcd backend, using the JSON gateway of the v3 API and its watch stream.
+
+use std::collections::HashMap;
+use std::time::Duration;
+
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use openfeature_contrib_common::{check_status, Backoff};
+use reqwest::Client;
+use serde::{Deserialize, Deserializer};
+use tokio::sync::broadcast;
+use tokio::task::JoinHandle;
+use tracing::{debug, warn};
+
+use crate::snapshot::{replace, update, Snapshot};
+use crate::{FlagsChanged, KvError};
+
+#[derive(Debug, Deserialize)]
+struct RangeResponse {
+    header: Header,
+    #[serde(default)]
+    kvs: Vec<KeyValue>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Header {
+    #[serde(default, deserialize_with = "int64")]
+    revision: i64,
+}
+
+#[derive(Debug, Deserialize)]
+struct KeyValue {
+    key: String,
+    #[serde(default)]
+    value: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct WatchMessage {
+    result: Option<WatchResult>,
+    error: Option<serde_json::Value>,
+}
+
+#[derive(Debug, Deserialize)]
+struct WatchResult {
+    #[serde(default)]
+    events: Vec<WatchEvent>,
+    #[serde(default, deserialize_with = "int64")]
+    compact_revision: i64,
+    #[serde(default)]
+    canceled: bool,
+}
+
+#[derive(Debug, Deserialize)]
+struct WatchEvent {
+    /// `PUT` is the default of the enum and therefore omitted.
+    #[serde(rename = "type", default)]
+    kind: Option<String>,
+    kv: KeyValue,
+}
+
+pub(crate) struct EtcdClient {
+    http: Client,
+    endpoint: String,
+    prefix: String,
+    key: String,
+    range_end: String,
+}
+
+impl EtcdClient {
+    pub(crate) fn new(endpoint: &str, prefix: &str) -> Result<Self, KvError> {
+        // An empty prefix selects every key: `\0` to `\0`.
+        let (key, range_end) = match prefix_end(prefix.as_bytes()) {
+            Some(range_end) => (prefix.as_bytes().to_vec(), range_end),
+            None => (vec![0], vec![0]),
+        };
+        Ok(Self {
+            // Watches stay open indefinitely, so only connecting is subject to a timeout.
+            http: Client::builder()
+                .connect_timeout(Duration::from_secs(10))
+                .build()?,
+            endpoint: endpoint.trim_end_matches('/').to_string(),
+            prefix: prefix.to_string(),
+            key: STANDARD.encode(key),
+            range_end: STANDARD.encode(range_end),
+        })
+    }
+
+    /// Reads the flags under the prefix with the revision of the store.
+    pub(crate) async fn range(&self) -> Result<(HashMap<String, String>, i64), KvError> {
+        let url = format!("{}/v3/kv/range", self.endpoint);
+        let response = self
+            .http
+            .post(&url)
+            .timeout(Duration::from_secs(10))
+            .json(&serde_json::json!({ "key": self.key, "range_end": self.range_end }))
+            .send()
+            .await?;
+        let response = check_status(response)?;
+
+        let response: RangeResponse = response.json().await?;
+        let mut flags = HashMap::new();
+        for kv in response.kvs {
+            if let Some(flag_key) = self.flag_key(&kv.key)? {
+                flags.insert(flag_key, decode(&kv.value)?);
+            }
+        }
+        Ok((flags, response.header.revision))
+    }
+
+    /// Applies the changes after `revision` to `snapshot` until the watch ends.
+    async fn watch(
+        &self,
+        revision: i64,
+        snapshot: &Snapshot,
+        changes: &broadcast::Sender<FlagsChanged>,
+    ) -> Result<(), KvError> {
+        let url = format!("{}/v3/watch", self.endpoint);
+        let response = self
+            .http
+            .post(&url)
+            .json(&serde_json::json!({
+                "create_request": {
+                    "key": self.key,
+                    "range_end": self.range_end,
+                    "start_revision": (revision + 1).to_string(),
+                }
+            }))
+            .send()
+            .await?;
+        let mut response = check_status(response)?;
+
+        let mut buffer = Vec::new();
+        while let Some(chunk) = response.chunk().await? {
+            buffer.extend_from_slice(&chunk);
+            let mut messages = serde_json::Deserializer::from_slice(&buffer).into_iter();
+            let mut consumed = 0;
+            while let Some(message) = messages.next() {
+                let message: WatchMessage = match message {
+                    Ok(message) => message,
+                    Err(e) if e.is_eof() => break,
+                    Err(e) => return Err(KvError::InvalidResponse(e.to_string())),
+                };
+                consumed = messages.byte_offset();
+
+                if let Some(error) = message.error {
+                    return Err(KvError::InvalidResponse(error.to_string()));
+                }
+                let Some(result) = message.result else {
+                    continue;
+                };
+                if result.canceled || result.compact_revision > 0 {
+                    return Err(KvError::InvalidResponse(format!(
+                        "watch canceled, compacted at revision {}",
+                        result.compact_revision
+                    )));
+                }
+
+                let mut updates = Vec::with_capacity(result.events.len());
+                for event in result.events {
+                    let Some(flag_key) = self.flag_key(&event.kv.key)? else {
+                        continue;
+                    };
+                    let value = match event.kind.as_deref() {
+                        Some("DELETE") => None,
+                        _ => Some(decode(&event.kv.value)?),
+                    };
+                    updates.push((flag_key, value));
+                }
+                update(snapshot, changes, updates).await;
+            }
+            buffer.drain(..consumed);
+        }
+        Ok(())
+    }
+
+    fn flag_key(&self, key: &str) -> Result<Option<String>, KvError> {
+        let key = decode(key)?;
+        Ok(key
+            .strip_prefix(&self.prefix)
+            .filter(|flag_key| !flag_key.is_empty())
+            .map(str::to_string))
+    }
+}
+
+/// Keeps `snapshot` in sync with etcd, watching the changes after `revision`.
+///
+/// After the watch ends, the flags are read again before watching anew, so changes missed in
+/// between are not lost.
+pub(crate) fn spawn_watch(
+    client: EtcdClient,
+    revision: i64,
+    snapshot: Snapshot,
+    changes: broadcast::Sender<FlagsChanged>,
+    mut backoff: Backoff,
+) -> JoinHandle<()> {
+    tokio::spawn(async move {
+        let mut revision = Some(revision);
+        loop {
+            let start = match revision.take() {
+                Some(revision) => revision,
+                None => match client.range().await {
+                    Ok((flags, revision)) => {
+                        replace(&snapshot, &changes, flags).await;
+                        revision
+                    }
+                    Err(e) => {
+                        warn!("Failed to read etcd keys: {e}");
+                        tokio::time::sleep(backoff.next_delay()).await;
+                        continue;
+                    }
+                },
+            };
+
+            match client.watch(start, &snapshot, &changes).await {
+                Ok(()) => {
+                    debug!("etcd watch ended");
+                    backoff.reset();
+                }
+                Err(e) => warn!("Failed to watch etcd keys: {e}"),
+            }
+            tokio::time::sleep(backoff.next_delay()).await;
+        }
+    })
+}
+
+/// The smallest key greater than every key starting with `prefix`, if there is one.
+fn prefix_end(prefix: &[u8]) -> Option<Vec<u8>> {
+    let mut end = prefix.to_vec();
+    while let Some(last) = end.pop() {
+        if last < u8::MAX {
+            end.push(last + 1);
+            return Some(end);
+        }
+    }
+    None
+}
+
+fn decode(value: &str) -> Result<String, KvError> {
+    STANDARD
+        .decode(value)
+        .ok()
+        .and_then(|value| String::from_utf8(value).ok())
+        .ok_or_else(|| KvError::InvalidResponse(format!("{value} is not base64 encoded UTF-8")))
+}
+
+/// Deserializes an int64, which the JSON gateway encodes as a string.
+fn int64<'de, D: Deserializer<'de>>(deserializer: D) -> Result<i64, D::Error> {
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Int64 {
+        Number(i64),
+        String(String),
+    }
+
+    match Int64::deserialize(deserializer)? {
+        Int64::Number(value) => Ok(value),
+        Int64::String(value) => value.parse().map_err(serde::de::Error::custom),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+    use wiremock::matchers::{body_json, body_partial_json, method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    use super::*;
+
+    fn encode(value: &str) -> String {
+        STANDARD.encode(value)
+    }
+
+    fn put(key: &str, value: &str) -> serde_json::Value {
+        json!({"kv": {"key": encode(key), "value": encode(value)}})
+    }
+
+    fn delete(key: &str) -> serde_json::Value {
+        json!({"type": "DELETE", "kv": {"key": encode(key)}})
+    }
+
+    async fn watch(
+        body: String,
+    ) -> (
+        Result<(), KvError>,
+        HashMap<String, String>,
+        Vec<FlagsChanged>,
+    ) {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/v3/watch"))
+            .and(body_partial_json(json!({
+                "create_request": {
+                    "key": encode("flags/"),
+                    "range_end": encode("flags0"),
+                    "start_revision": "8"
+                }
+            })))
+            .respond_with(ResponseTemplate::new(200).set_body_string(body))
+            .mount(&server)
+            .await;
+
+        let client = EtcdClient::new(&server.uri(), "flags/").unwrap();
+        let snapshot = Snapshot::default();
+        snapshot
+            .write()
+            .await
+            .insert("stale".to_string(), "1".to_string());
+        let (sender, mut receiver) = broadcast::channel(16);
+        let result = client.watch(7, &snapshot, &sender).await;
+
+        let mut changes = Vec::new();
+        while let Ok(changed) = receiver.try_recv() {
+            changes.push(changed);
+        }
+        let flags = snapshot.read().await.clone();
+        (result, flags, changes)
+    }
+
+    #[test]
+    fn prefix_ends_increment_the_last_byte() {
+        assert_eq!(prefix_end(b"flags/"), Some(b"flags0".to_vec()));
+        assert_eq!(prefix_end(b"a\xff\xff"), Some(b"b".to_vec()));
+        assert_eq!(prefix_end(b"\xff"), None);
+        assert_eq!(prefix_end(b""), None);
+    }
+
+    #[test]
+    fn int64_accepts_strings_and_numbers() {
+        let header: Header = serde_json::from_value(json!({"revision": "12"})).unwrap();
+        assert_eq!(header.revision, 12);
+        let header: Header = serde_json::from_value(json!({"revision": 12})).unwrap();
+        assert_eq!(header.revision, 12);
+        let header: Header = serde_json::from_value(json!({})).unwrap();
+        assert_eq!(header.revision, 0);
+        assert!(serde_json::from_value::<Header>(json!({"revision": "twelve"})).is_err());
+    }
+
+    #[tokio::test]
+    async fn ranges_the_keys_under_the_prefix() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/v3/kv/range"))
+            .and(body_json(json!({
+                "key": encode("flags/"),
+                "range_end": encode("flags0")
+            })))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "header": {"revision": "7"},
+                "kvs": [
+                    {"key": encode("flags/"), "value": encode("ignored")},
+                    {"key": encode("flags/enabled"), "value": encode("true")},
+                    {"key": encode("flags/empty")}
+                ]
+            })))
+            .mount(&server)
+            .await;
+
+        let (flags, revision) = EtcdClient::new(&format!("{}/", server.uri()), "flags/")
+            .unwrap()
+            .range()
+            .await
+            .unwrap();
+
+        assert_eq!(revision, 7);
+        assert_eq!(
+            flags,
+            HashMap::from([
+                ("enabled".to_string(), "true".to_string()),
+                ("empty".to_string(), String::new()),
+            ])
+        );
+    }
+
+    #[tokio::test]
+    async fn an_empty_prefix_ranges_every_key() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/v3/kv/range"))
+            .and(body_json(json!({"key": "AA==", "range_end": "AA=="})))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({"header": {}})))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let (flags, revision) = EtcdClient::new(&server.uri(), "")
+            .unwrap()
+            .range()
+            .await
+            .unwrap();
+
+        assert!(flags.is_empty());
+        assert_eq!(revision, 0);
+    }
+
+    #[tokio::test]
+    async fn applies_watch_events() {
+        let body = [
+            json!({"result": {"header": {"revision": "7"}, "created": true}}),
+            json!({"result": {"events": [put("flags/enabled", "true"), put("other", "1")]}}),
+            json!({"result": {"events": [delete("flags/stale"), put("flags/limit", "5")]}}),
+        ]
+        .map(|message| message.to_string())
+        .join("\n");
+
+        let (result, flags, changes) = watch(body).await;
+
+        result.unwrap();
+        assert_eq!(
+            flags,
+            HashMap::from([
+                ("enabled".to_string(), "true".to_string()),
+                ("limit".to_string(), "5".to_string()),
+            ])
+        );
+        assert_eq!(
+            changes,
+            [
+                FlagsChanged {
+                    flag_keys: vec!["enabled".to_string()]
+                },
+                FlagsChanged {
+                    flag_keys: vec!["stale".to_string(), "limit".to_string()]
+                },
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn compacted_and_canceled_watches_fail() {
+        for message in [
+            json!({"result": {"compact_revision": "3"}}),
+            json!({"result": {"canceled": true}}),
+            json!({"error": {"grpc_code": 14, "message": "unavailable"}}),
+        ] {
+            let (result, flags, _) = watch(message.to_string()).await;
+
+            assert!(matches!(result, Err(KvError::InvalidResponse(_))));
+            assert_eq!(flags.len(), 1);
+        }
+    }
+
+    #[tokio::test]
+    async fn malformed_watch_messages_fail() {
+        let (result, _, _) = watch("{\"result\": }".to_string()).await;
+
+        assert!(matches!(result, Err(KvError::InvalidResponse(_))));
+    }
+}
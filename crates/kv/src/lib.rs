@@ -0,0 +1,314 @@
+//! Key-value store provider for OpenFeature, backed by [Consul](https://www.consul.io/) or
+//! [etcd](https://etcd.io/).
+//!
+//! The provider resolves flags from the keys under a prefix: the key `{prefix}new-checkout`
+//! holds the value of the flag `new-checkout`. A local snapshot of those keys is kept fresh with
+//! Consul blocking queries or an etcd watch, so evaluations never wait for the store, and every
+//! change is announced to the receivers of [`KvProvider::subscribe`].
+//!
+//! # Value mapping
+//!
+//! Every key holds a JSON value of the flag's type: `true`, `42`, `0.75`, `"blue"` or an object
+//! such as `{"color": "blue"}`. Strings must be quoted; keys that are not valid JSON fail to
+//! evaluate with `PARSE_ERROR`.
+//!
+//! # Example
+//!
+//! ```rust,no_run
+//! use open_feature::provider::FeatureProvider;
+//! use open_feature::EvaluationContext;
+//! use open_feature_kv::{Backend, KvOptions, KvProvider};
+//!
+//! #[tokio::main]
+//! async fn main() {
+//!     let provider = KvProvider::new(KvOptions {
+//!         backend: Backend::Etcd {
+//!             endpoint: "http://127.0.0.1:2379".to_string(),
+//!         },
+//!         prefix: "/config/flags/".to_string(),
+//!         ..Default::default()
+//!     })
+//!     .await
+//!     .expect("Failed to create key-value provider");
+//!
+//!     let mut changes = provider.subscribe();
+//!     tokio::spawn(async move {
+//!         while let Ok(changed) = changes.recv().await {
+//!             println!("Changed: {:?}", changed.flag_keys);
+//!         }
+//!     });
+//!
+//!     let details = provider
+//!         .resolve_bool_value("new-checkout", &EvaluationContext::default())
+//!         .await;
+//!     println!("{details:?}");
+//! }
+//! ```
+
+mod consul;
+mod error;
+mod etcd;
+mod snapshot;
+
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+
+use async_trait::async_trait;
+use open_feature::provider::{FeatureProvider, ProviderMetadata, ResolutionDetails};
+use open_feature::{
+    EvaluationContext, EvaluationError, EvaluationErrorCode, EvaluationReason, EvaluationResult,
+    StructValue, Value,
+};
+use tokio::sync::{broadcast, RwLock};
+use tokio::task::JoinHandle;
+
+pub use openfeature_contrib_common::BackoffPolicy;
+
+use crate::consul::ConsulClient;
+pub use crate::error::KvError;
+use crate::etcd::EtcdClient;
+use crate::snapshot::Snapshot;
+
+/// The key-value store holding the flags.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Backend {
+    /// Consul KV, read through the HTTP API.
+    Consul {
+        /// Base URL of the Consul agent.
+        address: String,
+        /// ACL token with read access to the prefix.
+        token: Option<String>,
+        /// Datacenter to read from, instead of the agent's.
+        datacenter: Option<String>,
+    },
+    /// etcd, read through the JSON gateway of the v3 API.
+    Etcd {
+        /// Base URL of an etcd member.
+        endpoint: String,
+    },
+}
+
+impl Default for Backend {
+    fn default() -> Self {
+        Self::Consul {
+            address: "http://127.0.0.1:8500".to_string(),
+            token: None,
+            datacenter: None,
+        }
+    }
+}
+
+/// Configuration of the [`KvProvider`].
+#[derive(Debug, Clone)]
+pub struct KvOptions {
+    /// The store holding the flags.
+    pub backend: Backend,
+    /// Prefix of the flag keys.
+    pub prefix: String,
+    /// How long a Consul blocking query waits for changes before it is renewed.
+    pub blocking_wait: Duration,
+    /// Delays before watching again while the store cannot be reached.
+    pub retry_backoff: BackoffPolicy,
+}
+
+impl Default for KvOptions {
+    fn default() -> Self {
+        Self {
+            backend: Backend::default(),
+            prefix: "openfeature/".to_string(),
+            blocking_wait: Duration::from_secs(300),
+            retry_backoff: BackoffPolicy::default(),
+        }
+    }
+}
+
+/// Announces flags that were added, changed or removed in the store.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FlagsChanged {
+    /// Keys of the changed flags.
+    pub flag_keys: Vec<String>,
+}
+
+/// OpenFeature provider resolving flags from Consul or etcd keys.
+pub struct KvProvider {
+    metadata: ProviderMetadata,
+    snapshot: Snapshot,
+    changes: broadcast::Sender<FlagsChanged>,
+    watch: JoinHandle<()>,
+}
+
+impl KvProvider {
+    /// Creates the provider, reading the flags and starting to watch them.
+    pub async fn new(options: KvOptions) -> Result<Self, KvError> {
+        let snapshot = Arc::new(RwLock::new(HashMap::new()));
+        let (changes, _) = broadcast::channel(16);
+
+        let (name, watch) = match &options.backend {
+            Backend::Consul {
+                address,
+                token,
+                datacenter,
+            } => {
+                let client = ConsulClient::new(
+                    address,
+                    &options.prefix,
+                    token.clone(),
+                    datacenter.clone(),
+                    options.blocking_wait,
+                )?;
+                let (flags, index) = client.list(0).await?;
+                *snapshot.write().await = flags;
+                let watch = consul::spawn_watch(
+                    client,
+                    index,
+                    snapshot.clone(),
+                    changes.clone(),
+                    options.retry_backoff.backoff(),
+                );
+                ("consul", watch)
+            }
+            Backend::Etcd { endpoint } => {
+                let client = EtcdClient::new(endpoint, &options.prefix)?;
+                let (flags, revision) = client.range().await?;
+                *snapshot.write().await = flags;
+                let watch = etcd::spawn_watch(
+                    client,
+                    revision,
+                    snapshot.clone(),
+                    changes.clone(),
+                    options.retry_backoff.backoff(),
+                );
+                ("etcd", watch)
+            }
+        };
+
+        Ok(Self {
+            metadata: ProviderMetadata::new(name),
+            snapshot,
+            changes,
+            watch,
+        })
+    }
+
+    /// Returns a receiver of the flag changes observed from now on.
+    pub fn subscribe(&self) -> broadcast::Receiver<FlagsChanged> {
+        self.changes.subscribe()
+    }
+
+    async fn resolve<T>(
+        &self,
+        flag_key: &str,
+        convert: impl FnOnce(serde_json::Value) -> EvaluationResult<T>,
+    ) -> EvaluationResult<ResolutionDetails<T>> {
+        let value = {
+            let snapshot = self.snapshot.read().await;
+            let raw = snapshot.get(flag_key).ok_or_else(|| {
+                EvaluationError::builder()
+                    .code(EvaluationErrorCode::FlagNotFound)
+                    .message(format!("Flag {flag_key} not found"))
+                    .build()
+            })?;
+            serde_json::from_str(raw).map_err(|e| {
+                EvaluationError::builder()
+                    .code(EvaluationErrorCode::ParseError)
+                    .message(format!("Value of {flag_key} is not valid JSON: {e}"))
+                    .build()
+            })?
+        };
+
+        Ok(ResolutionDetails {
+            value: convert(value)?,
+            variant: None,
+            reason: Some(EvaluationReason::Static),
+            flag_metadata: None,
+        })
+    }
+}
+
+impl Drop for KvProvider {
+    fn drop(&mut self) {
+        self.watch.abort();
+    }
+}
+
+#[async_trait]
+impl FeatureProvider for KvProvider {
+    fn metadata(&self) -> &ProviderMetadata {
+        &self.metadata
+    }
+
+    async fn resolve_bool_value(
+        &self,
+        flag_key: &str,
+        _context: &EvaluationContext,
+    ) -> EvaluationResult<ResolutionDetails<bool>> {
+        self.resolve(flag_key, |value| {
+            value
+                .as_bool()
+                .ok_or_else(|| type_mismatch(format!("Value of {flag_key} is not a boolean")))
+        })
+        .await
+    }
+
+    async fn resolve_int_value(
+        &self,
+        flag_key: &str,
+        _context: &EvaluationContext,
+    ) -> EvaluationResult<ResolutionDetails<i64>> {
+        self.resolve(flag_key, |value| {
+            value
+                .as_i64()
+                .ok_or_else(|| type_mismatch(format!("Value of {flag_key} is not an integer")))
+        })
+        .await
+    }
+
+    async fn resolve_float_value(
+        &self,
+        flag_key: &str,
+        _context: &EvaluationContext,
+    ) -> EvaluationResult<ResolutionDetails<f64>> {
+        self.resolve(flag_key, |value| {
+            value
+                .as_f64()
+                .ok_or_else(|| type_mismatch(format!("Value of {flag_key} is not a number")))
+        })
+        .await
+    }
+
+    async fn resolve_string_value(
+        &self,
+        flag_key: &str,
+        _context: &EvaluationContext,
+    ) -> EvaluationResult<ResolutionDetails<String>> {
+        self.resolve(flag_key, |value| match value {
+            serde_json::Value::String(value) => Ok(value),
+            _ => Err(type_mismatch(format!(
+                "Value of {flag_key} is not a string"
+            ))),
+        })
+        .await
+    }
+
+    async fn resolve_struct_value(
+        &self,
+        flag_key: &str,
+        _context: &EvaluationContext,
+    ) -> EvaluationResult<ResolutionDetails<StructValue>> {
+        self.resolve(flag_key, |value| match Value::try_from(value)? {
+            Value::Struct(value) => Ok(value),
+            _ => Err(type_mismatch(format!(
+                "Value of {flag_key} is not a JSON object"
+            ))),
+        })
+        .await
+    }
+}
+
+fn type_mismatch(message: String) -> EvaluationError {
+    EvaluationError::builder()
+        .code(EvaluationErrorCode::TypeMismatch)
+        .message(message)
+        .build()
+}
@@ -0,0 +1,146 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use tokio::sync::{broadcast, RwLock};
+use tracing::debug;
+
+use crate::FlagsChanged;
+
+/// Raw flag values, keyed by flag key.
+pub(crate) type Snapshot = Arc<RwLock<HashMap<String, String>>>;
+
+/// Replaces the snapshot with `flags`, announcing the flags that changed.
+pub(crate) async fn replace(
+    snapshot: &Snapshot,
+    changes: &broadcast::Sender<FlagsChanged>,
+    flags: HashMap<String, String>,
+) {
+    let mut snapshot = snapshot.write().await;
+    let mut flag_keys: Vec<String> = snapshot
+        .keys()
+        .filter(|key| !flags.contains_key(*key))
+        .cloned()
+        .collect();
+    flag_keys.extend(
+        flags
+            .iter()
+            .filter(|(key, value)| snapshot.get(*key) != Some(value))
+            .map(|(key, _)| key.clone()),
+    );
+    *snapshot = flags;
+    announce(changes, flag_keys);
+}
+
+/// Applies individual updates to the snapshot; `None` removes a flag.
+pub(crate) async fn update(
+    snapshot: &Snapshot,
+    changes: &broadcast::Sender<FlagsChanged>,
+    updates: Vec<(String, Option<String>)>,
+) {
+    let mut snapshot = snapshot.write().await;
+    let mut flag_keys = Vec::with_capacity(updates.len());
+    for (key, value) in updates {
+        let previous = match value {
+            Some(value) => snapshot.insert(key.clone(), value),
+            None => snapshot.remove(&key),
+        };
+        if previous.as_ref() != snapshot.get(&key) {
+            flag_keys.push(key);
+        }
+    }
+    announce(changes, flag_keys);
+}
+
+fn announce(changes: &broadcast::Sender<FlagsChanged>, flag_keys: Vec<String>) {
+    if flag_keys.is_empty() {
+        return;
+    }
+    debug!("Flags changed: {flag_keys:?}");
+    // Sending only fails when nobody is subscribed.
+    let _ = changes.send(FlagsChanged { flag_keys });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn flags(flags: &[(&str, &str)]) -> HashMap<String, String> {
+        flags
+            .iter()
+            .map(|(key, value)| (key.to_string(), value.to_string()))
+            .collect()
+    }
+
+    fn changed(changes: &mut broadcast::Receiver<FlagsChanged>) -> Vec<String> {
+        let mut flag_keys = changes.try_recv().expect("no change announced").flag_keys;
+        flag_keys.sort();
+        flag_keys
+    }
+
+    #[tokio::test]
+    async fn replacing_announces_added_changed_and_removed_flags() {
+        let snapshot = Snapshot::default();
+        let (sender, mut changes) = broadcast::channel(16);
+        replace(
+            &snapshot,
+            &sender,
+            flags(&[("a", "1"), ("b", "1"), ("c", "1")]),
+        )
+        .await;
+        assert_eq!(changed(&mut changes), ["a", "b", "c"]);
+
+        replace(
+            &snapshot,
+            &sender,
+            flags(&[("a", "1"), ("b", "2"), ("d", "1")]),
+        )
+        .await;
+
+        assert_eq!(changed(&mut changes), ["b", "c", "d"]);
+        assert_eq!(
+            *snapshot.read().await,
+            flags(&[("a", "1"), ("b", "2"), ("d", "1")])
+        );
+    }
+
+    #[tokio::test]
+    async fn unchanged_flags_are_not_announced() {
+        let snapshot = Snapshot::default();
+        let (sender, mut changes) = broadcast::channel(16);
+        replace(&snapshot, &sender, flags(&[("a", "1")])).await;
+        changes.try_recv().unwrap();
+
+        replace(&snapshot, &sender, flags(&[("a", "1")])).await;
+        update(
+            &snapshot,
+            &sender,
+            vec![("a".to_string(), Some("1".to_string()))],
+        )
+        .await;
+        update(&snapshot, &sender, vec![("b".to_string(), None)]).await;
+
+        assert!(changes.try_recv().is_err());
+    }
+
+    #[tokio::test]
+    async fn updates_insert_and_remove_flags() {
+        let snapshot = Snapshot::default();
+        let (sender, mut changes) = broadcast::channel(16);
+        replace(&snapshot, &sender, flags(&[("a", "1"), ("b", "1")])).await;
+        changes.try_recv().unwrap();
+
+        update(
+            &snapshot,
+            &sender,
+            vec![
+                ("a".to_string(), None),
+                ("b".to_string(), Some("2".to_string())),
+                ("c".to_string(), Some("1".to_string())),
+            ],
+        )
+        .await;
+
+        assert_eq!(changed(&mut changes), ["a", "b", "c"]);
+        assert_eq!(*snapshot.read().await, flags(&[("b", "2"), ("c", "1")]));
+    }
+}
@@ -0,0 +1,263 @@
+use std::time::Duration;
+
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use open_feature::provider::FeatureProvider;
+use open_feature::{EvaluationContext, EvaluationErrorCode};
+use open_feature_kv::{Backend, BackoffPolicy, KvError, KvOptions, KvProvider};
+use openfeature_contrib_common::HttpError;
+use serde_json::json;
+use wiremock::matchers::{method, path, query_param, query_param_is_missing};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+fn consul_entries(entries: &[(&str, &str)]) -> ResponseTemplate {
+    let entries = entries
+        .iter()
+        .map(|(key, value)| json!({"Key": format!("openfeature/{key}"), "Value": STANDARD.encode(value)}))
+        .collect::<Vec<_>>();
+    ResponseTemplate::new(200).set_body_json(entries)
+}
+
+fn options(backend: Backend) -> KvOptions {
+    KvOptions {
+        backend,
+        // Keep the watch from retrying during a test.
+        retry_backoff: BackoffPolicy {
+            initial: Duration::from_secs(60),
+            max: Duration::from_secs(60),
+            ..Default::default()
+        },
+        ..Default::default()
+    }
+}
+
+fn consul(server: &MockServer) -> Backend {
+    Backend::Consul {
+        address: server.uri(),
+        token: None,
+        datacenter: None,
+    }
+}
+
+async fn consul_server() -> MockServer {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/v1/kv/openfeature/"))
+        .and(query_param_is_missing("index"))
+        .respond_with(
+            consul_entries(&[
+                ("enabled", "true"),
+                ("limit", "25"),
+                ("ratio", "0.5"),
+                ("color", "\"blue\""),
+                ("theme", r#"{"color": "blue"}"#),
+                ("unquoted", "blue"),
+            ])
+            .insert_header("X-Consul-Index", "5"),
+        )
+        .mount(&server)
+        .await;
+    server
+}
+
+#[tokio::test]
+async fn resolves_json_values_of_every_type() {
+    let server = consul_server().await;
+    // The watch blocks until the test is over.
+    Mock::given(method("GET"))
+        .and(query_param("index", "5"))
+        .respond_with(ResponseTemplate::new(500).set_delay(Duration::from_secs(60)))
+        .mount(&server)
+        .await;
+    let provider = KvProvider::new(options(consul(&server))).await.unwrap();
+    let context = &EvaluationContext::default();
+
+    assert_eq!(provider.metadata().name, "consul");
+    assert!(
+        provider
+            .resolve_bool_value("enabled", context)
+            .await
+            .unwrap()
+            .value
+    );
+    assert_eq!(
+        provider
+            .resolve_int_value("limit", context)
+            .await
+            .unwrap()
+            .value,
+        25
+    );
+    assert_eq!(
+        provider
+            .resolve_float_value("ratio", context)
+            .await
+            .unwrap()
+            .value,
+        0.5
+    );
+    assert_eq!(
+        provider
+            .resolve_string_value("color", context)
+            .await
+            .unwrap()
+            .value,
+        "blue"
+    );
+    let theme = provider
+        .resolve_struct_value("theme", context)
+        .await
+        .unwrap()
+        .value;
+    assert_eq!(theme.fields["color"], "blue".into());
+}
+
+#[tokio::test]
+async fn reports_missing_mistyped_and_unparsable_flags() {
+    let server = consul_server().await;
+    Mock::given(method("GET"))
+        .and(query_param("index", "5"))
+        .respond_with(ResponseTemplate::new(500).set_delay(Duration::from_secs(60)))
+        .mount(&server)
+        .await;
+    let provider = KvProvider::new(options(consul(&server))).await.unwrap();
+    let context = &EvaluationContext::default();
+
+    let error = provider
+        .resolve_bool_value("missing", context)
+        .await
+        .unwrap_err();
+    assert_eq!(error.code, EvaluationErrorCode::FlagNotFound);
+    let error = provider
+        .resolve_string_value("unquoted", context)
+        .await
+        .unwrap_err();
+    assert_eq!(error.code, EvaluationErrorCode::ParseError);
+    for error in [
+        provider
+            .resolve_bool_value("limit", context)
+            .await
+            .unwrap_err(),
+        provider
+            .resolve_int_value("ratio", context)
+            .await
+            .unwrap_err(),
+        provider
+            .resolve_string_value("enabled", context)
+            .await
+            .unwrap_err(),
+        provider
+            .resolve_struct_value("color", context)
+            .await
+            .unwrap_err(),
+    ] {
+        assert_eq!(error.code, EvaluationErrorCode::TypeMismatch);
+    }
+}
+
+#[tokio::test]
+async fn announces_changes_from_blocking_queries() {
+    let server = consul_server().await;
+    Mock::given(method("GET"))
+        .and(query_param("index", "5"))
+        .respond_with(
+            consul_entries(&[("enabled", "false"), ("limit", "25")])
+                .insert_header("X-Consul-Index", "6")
+                .set_delay(Duration::from_millis(200)),
+        )
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(query_param("index", "6"))
+        .respond_with(ResponseTemplate::new(500).set_delay(Duration::from_secs(60)))
+        .mount(&server)
+        .await;
+    let provider = KvProvider::new(options(consul(&server))).await.unwrap();
+    let mut changes = provider.subscribe();
+
+    let mut changed = tokio::time::timeout(Duration::from_secs(5), changes.recv())
+        .await
+        .unwrap()
+        .unwrap();
+    changed.flag_keys.sort();
+
+    assert_eq!(
+        changed.flag_keys,
+        ["color", "enabled", "ratio", "theme", "unquoted"]
+    );
+    let details = provider
+        .resolve_bool_value("enabled", &EvaluationContext::default())
+        .await
+        .unwrap();
+    assert!(!details.value);
+}
+
+#[tokio::test]
+async fn fails_when_consul_rejects_the_token() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .respond_with(ResponseTemplate::new(403))
+        .mount(&server)
+        .await;
+
+    let result = KvProvider::new(options(consul(&server))).await;
+
+    assert!(matches!(
+        result,
+        Err(KvError::Http(HttpError::Status { .. }))
+    ));
+}
+
+#[tokio::test]
+async fn reads_and_watches_etcd() {
+    let server = MockServer::start().await;
+    let encode = |value: &str| STANDARD.encode(value);
+    Mock::given(method("POST"))
+        .and(path("/v3/kv/range"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "header": {"revision": "7"},
+            "kvs": [{"key": encode("openfeature/enabled"), "value": encode("true")}]
+        })))
+        .mount(&server)
+        .await;
+    let events = json!({"result": {"events": [
+        {"kv": {"key": encode("openfeature/enabled"), "value": encode("false")}}
+    ]}});
+    Mock::given(method("POST"))
+        .and(path("/v3/watch"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .set_body_string(events.to_string())
+                .set_delay(Duration::from_millis(200)),
+        )
+        .mount(&server)
+        .await;
+    let provider = KvProvider::new(options(Backend::Etcd {
+        endpoint: server.uri(),
+    }))
+    .await
+    .unwrap();
+    let mut changes = provider.subscribe();
+    let context = &EvaluationContext::default();
+
+    assert_eq!(provider.metadata().name, "etcd");
+    assert!(
+        provider
+            .resolve_bool_value("enabled", context)
+            .await
+            .unwrap()
+            .value
+    );
+    let changed = tokio::time::timeout(Duration::from_secs(5), changes.recv())
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(changed.flag_keys, ["enabled"]);
+    assert!(
+        !provider
+            .resolve_bool_value("enabled", context)
+            .await
+            .unwrap()
+            .value
+    );
+}
@@ -0,0 +1,186 @@
+//! Structured logging hook for OpenFeature.
+//!
+//! [`LoggingHook`] implements the logging hook of the OpenFeature specification with `tracing`:
+//! it logs the `before` and `after` stages of every evaluation at a configurable level, and the
+//! `error` stage at the error level. Events have the `openfeature` target and stable field names:
+//!
+//! | Field                | Stages | Content                           |
+//! |----------------------|--------|-----------------------------------|
+//! | `stage`              | all    | `before`, `after` or `error`      |
+//! | `domain`             | all    | Name of the client                |
+//! | `provider_name`      | all    | Name of the provider              |
+//! | `flag_key`           | all    | Key of the evaluated flag         |
+//! | `default_value`      | all    | Default value of the evaluation   |
+//! | `evaluation_context` | all    | Evaluation context, when included |
+//! | `variant`            | after  | Variant of the evaluation         |
+//! | `reason`             | after  | Reason of the evaluation          |
+//! | `value`              | after  | Value of the evaluation           |
+//! | `error_code`         | error  | Error code, e.g. `FLAG_NOT_FOUND` |
+//! | `error_message`      | error  | Error message                     |
+//!
+//! The evaluation context may hold personal data, so it is excluded unless
+//! [`LoggingHookOptions::include_evaluation_context`] is set.
+//!
+//! # Example
+//!
+//! ```rust
+//! use open_feature::OpenFeature;
+//! use open_feature_in_memory::InMemoryProvider;
+//! use open_feature_logging_hook::{LoggingHook, LoggingHookOptions};
+//! use tracing::Level;
+//!
+//! #[tokio::main]
+//! async fn main() {
+//!     let provider = InMemoryProvider::new();
+//!     provider.set_flag("new-checkout", true, None, None);
+//!
+//!     let mut api = OpenFeature::singleton_mut().await;
+//!     api.set_provider(provider).await;
+//!     let client = api.create_client().with_hook(LoggingHook::new(LoggingHookOptions {
+//!         level: Level::INFO,
+//!         ..Default::default()
+//!     }));
+//!
+//!     assert!(client.get_bool_value("new-checkout", None, None).await.unwrap());
+//! }
+//! ```
+
+use async_trait::async_trait;
+use open_feature::{
+    EvaluationContext, EvaluationDetails, EvaluationError, Hook, HookContext, HookHints, Value,
+};
+use openfeature_contrib_common::error_code_name;
+use tracing::field::{debug, DebugValue};
+use tracing::Level;
+
+/// Logs an event with a level known at runtime.
+macro_rules! log {
+    ($level:expr, $($fields:tt)+) => {
+        match $level {
+            Level::ERROR => tracing::error!(target: "openfeature", $($fields)+),
+            Level::WARN => tracing::warn!(target: "openfeature", $($fields)+),
+            Level::INFO => tracing::info!(target: "openfeature", $($fields)+),
+            Level::DEBUG => tracing::debug!(target: "openfeature", $($fields)+),
+            Level::TRACE => tracing::trace!(target: "openfeature", $($fields)+),
+        }
+    };
+}
+
+/// Configuration of the [`LoggingHook`].
+#[derive(Debug, Clone)]
+pub struct LoggingHookOptions {
+    /// Level of the `before` and `after` stages.
+    pub level: Level,
+    /// Level of the `error` stage.
+    pub error_level: Level,
+    /// Whether events hold the evaluation context.
+    pub include_evaluation_context: bool,
+}
+
+impl Default for LoggingHookOptions {
+    fn default() -> Self {
+        Self {
+            level: Level::DEBUG,
+            error_level: Level::ERROR,
+            include_evaluation_context: false,
+        }
+    }
+}
+
+/// OpenFeature hook logging the evaluation lifecycle with `tracing`.
+#[derive(Debug, Clone, Default)]
+pub struct LoggingHook {
+    options: LoggingHookOptions,
+}
+
+impl LoggingHook {
+    /// Creates the hook.
+    pub fn new(options: LoggingHookOptions) -> Self {
+        Self { options }
+    }
+
+    fn evaluation_context<'a>(
+        &self,
+        context: &HookContext<'a>,
+    ) -> Option<DebugValue<&'a EvaluationContext>> {
+        self.options
+            .include_evaluation_context
+            .then(|| debug(context.evaluation_context))
+    }
+}
+
+#[async_trait]
+impl Hook for LoggingHook {
+    async fn before<'a>(
+        &self,
+        context: &HookContext<'a>,
+        _: Option<&'a HookHints>,
+    ) -> Result<Option<EvaluationContext>, EvaluationError> {
+        log!(
+            self.options.level,
+            stage = "before",
+            domain = context.client_metadata.name,
+            provider_name = context.provider_metadata.name,
+            flag_key = context.flag_key,
+            default_value = context.default_value.as_ref().map(debug),
+            evaluation_context = self.evaluation_context(context),
+            "Before stage of {}",
+            context.flag_key,
+        );
+
+        Ok(None)
+    }
+
+    async fn after<'a>(
+        &self,
+        context: &HookContext<'a>,
+        details: &EvaluationDetails<Value>,
+        _: Option<&'a HookHints>,
+    ) -> Result<(), EvaluationError> {
+        log!(
+            self.options.level,
+            stage = "after",
+            domain = context.client_metadata.name,
+            provider_name = context.provider_metadata.name,
+            flag_key = context.flag_key,
+            default_value = context.default_value.as_ref().map(debug),
+            evaluation_context = self.evaluation_context(context),
+            variant = details.variant,
+            reason = details.reason.as_ref().map(tracing::field::display),
+            value = ?details.value,
+            "After stage of {}",
+            context.flag_key,
+        );
+
+        Ok(())
+    }
+
+    async fn error<'a>(
+        &self,
+        context: &HookContext<'a>,
+        error: &EvaluationError,
+        _: Option<&'a HookHints>,
+    ) {
+        log!(
+            self.options.error_level,
+            stage = "error",
+            domain = context.client_metadata.name,
+            provider_name = context.provider_metadata.name,
+            flag_key = context.flag_key,
+            default_value = context.default_value.as_ref().map(debug),
+            evaluation_context = self.evaluation_context(context),
+            error_code = error_code_name(&error.code),
+            error_message = error.message,
+            "Error stage of {}",
+            context.flag_key,
+        );
+    }
+
+    async fn finally<'a>(
+        &self,
+        _: &HookContext<'a>,
+        _: &EvaluationDetails<Value>,
+        _: Option<&'a HookHints>,
+    ) {
+    }
+}
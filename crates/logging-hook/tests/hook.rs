@@ -0,0 +1,153 @@
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::{Arc, Mutex};
+
+use open_feature::{Client, EvaluationContext, OpenFeature};
+use open_feature_in_memory::InMemoryProvider;
+use open_feature_logging_hook::{LoggingHook, LoggingHookOptions};
+use tracing::field::{Field, Visit};
+use tracing::{Event, Level, Subscriber};
+use tracing_subscriber::layer::{Context, SubscriberExt};
+use tracing_subscriber::{Layer, Registry};
+
+/// Event logged by the hook, with its fields formatted.
+#[derive(Debug)]
+struct Logged {
+    level: Level,
+    target: String,
+    fields: HashMap<String, String>,
+}
+
+impl Logged {
+    fn field(&self, name: &str) -> Option<&str> {
+        self.fields.get(name).map(String::as_str)
+    }
+}
+
+impl Visit for Logged {
+    fn record_str(&mut self, field: &Field, value: &str) {
+        self.fields
+            .insert(field.name().to_string(), value.to_string());
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+        self.fields
+            .insert(field.name().to_string(), format!("{value:?}"));
+    }
+}
+
+/// Layer collecting the events logged while it is the default subscriber.
+#[derive(Clone, Default)]
+struct Capture(Arc<Mutex<Vec<Logged>>>);
+
+impl<S: Subscriber> Layer<S> for Capture {
+    fn on_event(&self, event: &Event<'_>, _: Context<'_, S>) {
+        let mut logged = Logged {
+            level: *event.metadata().level(),
+            target: event.metadata().target().to_string(),
+            fields: HashMap::new(),
+        };
+        event.record(&mut logged);
+        self.0.lock().unwrap().push(logged);
+    }
+}
+
+/// Creates a client of its own provider, so tests do not share flags or hooks.
+async fn client(domain: &str, options: LoggingHookOptions) -> Client {
+    let provider = InMemoryProvider::new();
+    provider.set_flag("new-checkout", true, Some("on"), None);
+    let mut api = OpenFeature::singleton_mut().await;
+    api.set_named_provider(domain, provider).await;
+    api.create_named_client(domain)
+        .with_hook(LoggingHook::new(options))
+}
+
+/// Events the hook logs while evaluating `flag_key` with `context`.
+async fn logged(client: &Client, flag_key: &str, context: &EvaluationContext) -> Vec<Logged> {
+    let capture = Capture::default();
+    let _default = tracing::subscriber::set_default(Registry::default().with(capture.clone()));
+    let _ = client.get_bool_value(flag_key, Some(context), None).await;
+    let events = std::mem::take(&mut *capture.0.lock().unwrap());
+    events
+        .into_iter()
+        .filter(|event| event.target == "openfeature")
+        .collect()
+}
+
+#[tokio::test]
+async fn logs_the_before_and_after_stages_at_the_level() {
+    let client = client(
+        "stages",
+        LoggingHookOptions {
+            level: Level::INFO,
+            ..Default::default()
+        },
+    )
+    .await;
+
+    let events = logged(&client, "new-checkout", &EvaluationContext::default()).await;
+    assert_eq!(events.len(), 2);
+    let (before, after) = (&events[0], &events[1]);
+    assert_eq!(before.level, Level::INFO);
+    assert_eq!(before.field("stage"), Some("before"));
+    assert_eq!(before.field("domain"), Some("stages"));
+    assert_eq!(before.field("provider_name"), Some("in-memory"));
+    assert_eq!(before.field("flag_key"), Some("new-checkout"));
+    assert_eq!(before.field("default_value"), Some("Bool(false)"));
+    assert_eq!(before.field("variant"), None);
+
+    assert_eq!(after.level, Level::INFO);
+    assert_eq!(after.field("stage"), Some("after"));
+    assert_eq!(after.field("variant"), Some("on"));
+    assert_eq!(after.field("reason"), Some("STATIC"));
+    assert_eq!(after.field("value"), Some("Bool(true)"));
+}
+
+#[tokio::test]
+async fn logs_the_error_stage_at_the_error_level() {
+    let client = client(
+        "errors",
+        LoggingHookOptions {
+            level: Level::TRACE,
+            error_level: Level::WARN,
+            ..Default::default()
+        },
+    )
+    .await;
+
+    let events = logged(&client, "missing", &EvaluationContext::default()).await;
+    assert_eq!(events.len(), 2);
+    let (before, error) = (&events[0], &events[1]);
+    assert_eq!(before.level, Level::TRACE);
+    assert_eq!(error.level, Level::WARN);
+    assert_eq!(error.field("stage"), Some("error"));
+    assert_eq!(error.field("flag_key"), Some("missing"));
+    assert_eq!(error.field("error_code"), Some("FLAG_NOT_FOUND"));
+    assert!(error.field("error_message").is_some());
+    assert_eq!(error.field("value"), None);
+}
+
+#[tokio::test]
+async fn excludes_the_evaluation_context_unless_included() {
+    let context = EvaluationContext::default().with_targeting_key("user-1");
+
+    let excluded = client("excluded", LoggingHookOptions::default()).await;
+    let events = logged(&excluded, "new-checkout", &context).await;
+    assert_eq!(events.len(), 2);
+    assert!(events
+        .iter()
+        .all(|event| event.field("evaluation_context").is_none()));
+
+    let included = client(
+        "included",
+        LoggingHookOptions {
+            include_evaluation_context: true,
+            ..Default::default()
+        },
+    )
+    .await;
+    let events = logged(&included, "new-checkout", &context).await;
+    assert!(events.iter().all(|event| event
+        .field("evaluation_context")
+        .is_some_and(|context| context.contains("user-1"))));
+}
@@ -0,0 +1,240 @@
+//! Mock [OFREP](https://github.com/open-feature/protocol) server for integration tests.
+//!
+//! [`MockOfrepServer`] serves the OpenFeature Remote Evaluation Protocol on a local port, so
+//! providers speaking OFREP and the applications using them can be tested without a flag
+//! backend. Tests program the flags served, and can slow the server down, simulate rate limiting
+//! and inspect the requests it received.
+//!
+//! Both evaluation endpoints are served:
+//!
+//! * `POST /ofrep/v1/evaluate/flags/{key}` evaluates a single flag;
+//! * `POST /ofrep/v1/evaluate/flags` evaluates every flag, answering `304 Not Modified` when the
+//!   `If-None-Match` header carries the current `ETag`.
+//!
+//! Request bodies must hold a `context` object. Flags are not targeted: every context gets the
+//! programmed answer.
+//!
+//! # Example
+//!
+//! ```rust
+//! use std::time::Duration;
+//!
+//! use open_feature_ofrep_mock::{Flag, MockOfrepServer};
+//! use serde_json::json;
+//!
+//! #[tokio::main]
+//! async fn main() {
+//!     let server = MockOfrepServer::start().await.expect("Failed to start server");
+//!     server.set_flag("new-checkout", Flag::new(true).with_variant("on"));
+//!     server.set_latency(Duration::from_millis(10));
+//!
+//!     let response = reqwest::Client::new()
+//!         .post(format!("{}/ofrep/v1/evaluate/flags/new-checkout", server.url()))
+//!         .json(&json!({ "context": { "targetingKey": "user-123" } }))
+//!         .send()
+//!         .await
+//!         .unwrap();
+//!     let body: serde_json::Value = response.json().await.unwrap();
+//!     assert_eq!(body["value"], true);
+//!     assert_eq!(body["variant"], "on");
+//!
+//!     let requests = server.requests();
+//!     assert_eq!(requests[0].path, "/ofrep/v1/evaluate/flags/new-checkout");
+//!     assert_eq!(requests[0].body.as_ref().unwrap()["context"]["targetingKey"], "user-123");
+//! }
+//! ```
+
+mod server;
+
+use std::collections::HashMap;
+use std::net::{Ipv4Addr, SocketAddr};
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
+use std::time::Duration;
+
+use serde_json::{Map, Value};
+use tokio::net::TcpListener;
+use tokio::task::JoinHandle;
+
+/// The answer to evaluations of a flag.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Flag {
+    /// Value of the flag.
+    pub value: Value,
+    /// Variant of the value.
+    pub variant: Option<String>,
+    /// Reason of the evaluation, e.g. `STATIC` or `TARGETING_MATCH`.
+    pub reason: String,
+    /// Flag metadata.
+    pub metadata: Map<String, Value>,
+}
+
+impl Flag {
+    /// Creates a flag serving `value` with the `STATIC` reason.
+    pub fn new(value: impl Into<Value>) -> Self {
+        Self {
+            value: value.into(),
+            variant: None,
+            reason: "STATIC".to_string(),
+            metadata: Map::new(),
+        }
+    }
+
+    /// Sets the variant of the value.
+    pub fn with_variant(mut self, variant: impl Into<String>) -> Self {
+        self.variant = Some(variant.into());
+        self
+    }
+
+    /// Sets the reason of the evaluation.
+    pub fn with_reason(mut self, reason: impl Into<String>) -> Self {
+        self.reason = reason.into();
+        self
+    }
+
+    /// Adds a flag metadata entry.
+    pub fn with_metadata(mut self, key: impl Into<String>, value: impl Into<Value>) -> Self {
+        self.metadata.insert(key.into(), value.into());
+        self
+    }
+}
+
+/// A request received by the server.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CapturedRequest {
+    /// HTTP method, e.g. `POST`.
+    pub method: String,
+    /// Path of the request URI.
+    pub path: String,
+    /// Headers of the request; names are lowercase.
+    pub headers: HashMap<String, String>,
+    /// Body of the request, or `None` when it is empty or not JSON.
+    pub body: Option<Value>,
+}
+
+#[derive(Debug, Clone)]
+enum Fixture {
+    Flag(Flag),
+    Error { code: String, details: String },
+}
+
+#[derive(Debug, Default)]
+struct State {
+    flags: HashMap<String, Fixture>,
+    /// Incremented by every flag change; the `ETag` of bulk evaluations.
+    revision: u64,
+    latency: Duration,
+    rate_limited_requests: usize,
+    retry_after: Duration,
+    requests: Vec<CapturedRequest>,
+}
+
+type SharedState = Arc<Mutex<State>>;
+
+fn lock(state: &SharedState) -> MutexGuard<'_, State> {
+    state.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+/// OFREP server running on a local port until dropped.
+#[derive(Debug)]
+pub struct MockOfrepServer {
+    address: SocketAddr,
+    state: SharedState,
+    server: JoinHandle<()>,
+}
+
+impl MockOfrepServer {
+    /// Starts a server without flags on a free port of `127.0.0.1`.
+    pub async fn start() -> std::io::Result<Self> {
+        let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, 0)).await?;
+        let address = listener.local_addr()?;
+        let state = SharedState::default();
+        let server = server::spawn(listener, state.clone());
+
+        Ok(Self {
+            address,
+            state,
+            server,
+        })
+    }
+
+    /// Address the server listens on.
+    pub fn address(&self) -> SocketAddr {
+        self.address
+    }
+
+    /// Base URL of the server, e.g. `http://127.0.0.1:41234`, to configure providers with.
+    pub fn url(&self) -> String {
+        format!("http://{}", self.address)
+    }
+
+    /// Serves `flag` for `flag_key`, replacing any previous answer.
+    pub fn set_flag(&self, flag_key: impl Into<String>, flag: Flag) {
+        self.set_fixture(flag_key.into(), Fixture::Flag(flag));
+    }
+
+    /// Fails evaluations of `flag_key` with an OFREP error code, e.g. `PARSE_ERROR` or
+    /// `TARGETING_KEY_MISSING`. `FLAG_NOT_FOUND` is answered with `404 Not Found`, every other
+    /// code with `400 Bad Request`.
+    pub fn set_error(
+        &self,
+        flag_key: impl Into<String>,
+        error_code: impl Into<String>,
+        details: impl Into<String>,
+    ) {
+        let fixture = Fixture::Error {
+            code: error_code.into(),
+            details: details.into(),
+        };
+        self.set_fixture(flag_key.into(), fixture);
+    }
+
+    /// Removes a flag, so evaluating it fails with `FLAG_NOT_FOUND`.
+    pub fn remove_flag(&self, flag_key: &str) {
+        let mut state = lock(&self.state);
+        if state.flags.remove(flag_key).is_some() {
+            state.revision += 1;
+        }
+    }
+
+    /// Removes every flag.
+    pub fn clear_flags(&self) {
+        let mut state = lock(&self.state);
+        state.flags.clear();
+        state.revision += 1;
+    }
+
+    /// Delays every response by `latency`.
+    pub fn set_latency(&self, latency: Duration) {
+        lock(&self.state).latency = latency;
+    }
+
+    /// Answers the next `requests` requests with `429 Too Many Requests` and a `Retry-After`
+    /// header of `retry_after`, rounded up to whole seconds.
+    pub fn rate_limit(&self, requests: usize, retry_after: Duration) {
+        let mut state = lock(&self.state);
+        state.rate_limited_requests = requests;
+        state.retry_after = retry_after;
+    }
+
+    /// Returns the requests received so far, oldest first.
+    pub fn requests(&self) -> Vec<CapturedRequest> {
+        lock(&self.state).requests.clone()
+    }
+
+    /// Forgets the requests received so far.
+    pub fn clear_requests(&self) {
+        lock(&self.state).requests.clear();
+    }
+
+    fn set_fixture(&self, flag_key: String, fixture: Fixture) {
+        let mut state = lock(&self.state);
+        state.flags.insert(flag_key, fixture);
+        state.revision += 1;
+    }
+}
+
+impl Drop for MockOfrepServer {
+    fn drop(&mut self) {
+        self.server.abort();
+    }
+}
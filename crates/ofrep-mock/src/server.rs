@@ -0,0 +1,179 @@
+use axum::body::Bytes;
+use axum::extract::{Path, State};
+use axum::http::header::{ETAG, IF_NONE_MATCH, RETRY_AFTER};
+use axum::http::{HeaderMap, Method, StatusCode, Uri};
+use axum::response::{IntoResponse, Response};
+use axum::routing::post;
+use axum::{Json, Router};
+use serde_json::{json, Value};
+use tokio::net::TcpListener;
+use tokio::task::JoinHandle;
+use tracing::warn;
+
+use crate::{lock, CapturedRequest, Fixture, Flag, SharedState};
+
+pub(crate) fn spawn(listener: TcpListener, state: SharedState) -> JoinHandle<()> {
+    let router = Router::new()
+        .route("/ofrep/v1/evaluate/flags", post(evaluate_all))
+        .route("/ofrep/v1/evaluate/flags/{key}", post(evaluate))
+        .with_state(state);
+
+    tokio::spawn(async move {
+        if let Err(e) = axum::serve(listener, router).await {
+            warn!("Mock OFREP server failed: {e}");
+        }
+    })
+}
+
+async fn evaluate(
+    State(state): State<SharedState>,
+    Path(flag_key): Path<String>,
+    method: Method,
+    uri: Uri,
+    headers: HeaderMap,
+    body: Bytes,
+) -> Response {
+    if let Err(response) = accept(&state, &method, &uri, &headers, &body).await {
+        return response;
+    }
+
+    let fixture = lock(&state).flags.get(&flag_key).cloned();
+    match fixture {
+        Some(Fixture::Flag(flag)) => Json(evaluation(&flag_key, &flag)).into_response(),
+        Some(Fixture::Error { code, details }) => {
+            let status = if code == "FLAG_NOT_FOUND" {
+                StatusCode::NOT_FOUND
+            } else {
+                StatusCode::BAD_REQUEST
+            };
+            error(status, &flag_key, &code, &details)
+        }
+        None => error(
+            StatusCode::NOT_FOUND,
+            &flag_key,
+            "FLAG_NOT_FOUND",
+            &format!("Flag {flag_key} not found"),
+        ),
+    }
+}
+
+async fn evaluate_all(
+    State(state): State<SharedState>,
+    method: Method,
+    uri: Uri,
+    headers: HeaderMap,
+    body: Bytes,
+) -> Response {
+    if let Err(response) = accept(&state, &method, &uri, &headers, &body).await {
+        return response;
+    }
+
+    let state = lock(&state);
+    let etag = format!("\"{}\"", state.revision);
+    if headers
+        .get(IF_NONE_MATCH)
+        .is_some_and(|value| value.as_bytes() == etag.as_bytes())
+    {
+        return (StatusCode::NOT_MODIFIED, [(ETAG, etag)]).into_response();
+    }
+
+    let mut flag_keys: Vec<&String> = state.flags.keys().collect();
+    flag_keys.sort();
+    let flags: Vec<Value> = flag_keys
+        .into_iter()
+        .map(|flag_key| match &state.flags[flag_key] {
+            Fixture::Flag(flag) => evaluation(flag_key, flag),
+            Fixture::Error { code, details } => json!({
+                "key": flag_key,
+                "errorCode": code,
+                "errorDetails": details,
+            }),
+        })
+        .collect();
+
+    ([(ETAG, etag)], Json(json!({ "flags": flags }))).into_response()
+}
+
+/// Captures the request, applies the latency and rate limiting, and validates the context.
+async fn accept(
+    state: &SharedState,
+    method: &Method,
+    uri: &Uri,
+    headers: &HeaderMap,
+    body: &[u8],
+) -> Result<(), Response> {
+    let body = serde_json::from_slice::<Value>(body).ok();
+    let latency = {
+        let mut state = lock(state);
+        state.requests.push(CapturedRequest {
+            method: method.to_string(),
+            path: uri.path().to_string(),
+            headers: headers
+                .iter()
+                .filter_map(|(name, value)| {
+                    Some((name.to_string(), value.to_str().ok()?.to_string()))
+                })
+                .collect(),
+            body: body.clone(),
+        });
+        state.latency
+    };
+    if !latency.is_zero() {
+        tokio::time::sleep(latency).await;
+    }
+
+    {
+        let mut state = lock(state);
+        if state.rate_limited_requests > 0 {
+            state.rate_limited_requests -= 1;
+            let retry_after =
+                state.retry_after.as_secs() + u64::from(state.retry_after.subsec_nanos() > 0);
+            return Err((
+                StatusCode::TOO_MANY_REQUESTS,
+                [(RETRY_AFTER, retry_after.to_string())],
+            )
+                .into_response());
+        }
+    }
+
+    if !body
+        .as_ref()
+        .is_some_and(|body| body["context"].is_object())
+    {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            Json(json!({
+                "errorCode": "INVALID_CONTEXT",
+                "errorDetails": "Request body must hold a context object",
+            })),
+        )
+            .into_response());
+    }
+    Ok(())
+}
+
+fn evaluation(flag_key: &str, flag: &Flag) -> Value {
+    let mut evaluation = json!({
+        "key": flag_key,
+        "value": flag.value,
+        "reason": flag.reason,
+        "metadata": flag.metadata,
+    });
+    // The variant is optional, not nullable.
+    if let Some(variant) = &flag.variant {
+        evaluation["variant"] = variant.as_str().into();
+    }
+    evaluation
+}
+
+fn error(status: StatusCode, flag_key: &str, code: &str, details: &str) -> Response {
+    (
+        status,
+        Json(json!({
+            "key": flag_key,
+            "errorCode": code,
+            "errorDetails": details,
+        })),
+    )
+        .into_response()
+}
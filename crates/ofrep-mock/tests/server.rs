@@ -0,0 +1,185 @@
+use std::time::Duration;
+
+use open_feature_ofrep_mock::{Flag, MockOfrepServer};
+use reqwest::header::{ETAG, IF_NONE_MATCH, RETRY_AFTER};
+use reqwest::{Response, StatusCode};
+use serde_json::{json, Value};
+
+async fn post(server: &MockOfrepServer, path: &str, body: Value) -> Response {
+    reqwest::Client::new()
+        .post(format!("{}/ofrep/v1/evaluate/flags{path}", server.url()))
+        .json(&body)
+        .send()
+        .await
+        .unwrap()
+}
+
+fn context() -> Value {
+    json!({ "context": { "targetingKey": "user-1" } })
+}
+
+#[tokio::test]
+async fn evaluates_flags() {
+    let server = MockOfrepServer::start().await.unwrap();
+    server.set_flag(
+        "new-checkout",
+        Flag::new(true)
+            .with_variant("on")
+            .with_reason("TARGETING_MATCH")
+            .with_metadata("version", 3),
+    );
+
+    let response = post(&server, "/new-checkout", context()).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(
+        response.json::<Value>().await.unwrap(),
+        json!({
+            "key": "new-checkout",
+            "value": true,
+            "variant": "on",
+            "reason": "TARGETING_MATCH",
+            "metadata": {"version": 3},
+        })
+    );
+}
+
+#[tokio::test]
+async fn omits_missing_variants() {
+    let server = MockOfrepServer::start().await.unwrap();
+    server.set_flag("limit", Flag::new(25));
+
+    let body: Value = post(&server, "/limit", context())
+        .await
+        .json()
+        .await
+        .unwrap();
+    assert!(body.get("variant").is_none());
+    let body: Value = post(&server, "", context()).await.json().await.unwrap();
+    assert!(body["flags"][0].get("variant").is_none());
+}
+
+#[tokio::test]
+async fn answers_error_fixtures() {
+    let server = MockOfrepServer::start().await.unwrap();
+    server.set_error("broken", "PARSE_ERROR", "Invalid flag configuration");
+    server.set_error("gone", "FLAG_NOT_FOUND", "Flag gone was archived");
+    server.set_flag("removed", Flag::new(true));
+    server.remove_flag("removed");
+
+    let response = post(&server, "/broken", context()).await;
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    assert_eq!(
+        response.json::<Value>().await.unwrap(),
+        json!({
+            "key": "broken",
+            "errorCode": "PARSE_ERROR",
+            "errorDetails": "Invalid flag configuration",
+        })
+    );
+    let response = post(&server, "/gone", context()).await;
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    let response = post(&server, "/removed", context()).await;
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    assert_eq!(
+        response.json::<Value>().await.unwrap()["errorCode"],
+        "FLAG_NOT_FOUND"
+    );
+
+    let body: Value = post(&server, "", context()).await.json().await.unwrap();
+    assert_eq!(
+        body["flags"],
+        json!([
+            {"key": "broken", "errorCode": "PARSE_ERROR", "errorDetails": "Invalid flag configuration"},
+            {"key": "gone", "errorCode": "FLAG_NOT_FOUND", "errorDetails": "Flag gone was archived"},
+        ])
+    );
+}
+
+#[tokio::test]
+async fn rejects_requests_without_a_context() {
+    let server = MockOfrepServer::start().await.unwrap();
+    server.set_flag("new-checkout", Flag::new(true));
+
+    for body in [json!({}), json!({ "context": "user-1" })] {
+        let response = post(&server, "/new-checkout", body).await;
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+        assert_eq!(
+            response.json::<Value>().await.unwrap()["errorCode"],
+            "INVALID_CONTEXT"
+        );
+    }
+    let response = post(&server, "", json!(null)).await;
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn answers_not_modified_until_the_flags_change() {
+    let server = MockOfrepServer::start().await.unwrap();
+    server.set_flag("new-checkout", Flag::new(true));
+
+    let response = post(&server, "", context()).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let etag = response.headers()[ETAG].clone();
+
+    let revalidate = || {
+        reqwest::Client::new()
+            .post(format!("{}/ofrep/v1/evaluate/flags", server.url()))
+            .header(IF_NONE_MATCH, etag.clone())
+            .json(&context())
+            .send()
+    };
+    let response = revalidate().await.unwrap();
+    assert_eq!(response.status(), StatusCode::NOT_MODIFIED);
+    assert_eq!(response.headers()[ETAG], etag);
+
+    server.set_flag("new-checkout", Flag::new(false));
+    let response = revalidate().await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_ne!(response.headers()[ETAG], etag);
+    let body: Value = response.json().await.unwrap();
+    assert_eq!(body["flags"][0]["value"], false);
+}
+
+#[tokio::test]
+async fn rate_limits_the_next_requests() {
+    let server = MockOfrepServer::start().await.unwrap();
+    server.set_flag("new-checkout", Flag::new(true));
+    server.rate_limit(2, Duration::from_millis(1_500));
+
+    for _ in 0..2 {
+        let response = post(&server, "/new-checkout", context()).await;
+        assert_eq!(response.status(), StatusCode::TOO_MANY_REQUESTS);
+        assert_eq!(response.headers()[RETRY_AFTER], "2");
+    }
+    let response = post(&server, "/new-checkout", context()).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    // Rate limited requests are captured too.
+    assert_eq!(server.requests().len(), 3);
+}
+
+#[tokio::test]
+async fn captures_requests() {
+    let server = MockOfrepServer::start().await.unwrap();
+    server.set_flag("new-checkout", Flag::new(true));
+
+    reqwest::Client::new()
+        .post(format!(
+            "{}/ofrep/v1/evaluate/flags/new-checkout",
+            server.url()
+        ))
+        .header("Authorization", "Bearer secret")
+        .json(&context())
+        .send()
+        .await
+        .unwrap();
+
+    let requests = server.requests();
+    assert_eq!(requests.len(), 1);
+    assert_eq!(requests[0].method, "POST");
+    assert_eq!(requests[0].path, "/ofrep/v1/evaluate/flags/new-checkout");
+    assert_eq!(requests[0].headers["authorization"], "Bearer secret");
+    assert_eq!(requests[0].body, Some(context()));
+
+    server.clear_requests();
+    assert!(server.requests().is_empty());
+}
@@ -0,0 +1,283 @@
+//! OpenTelemetry metrics hook for OpenFeature.
+//!
+//! [`MetricsHook`] records flag evaluations as OpenTelemetry metrics, whatever the provider, so
+//! dashboards stay the same across heterogeneous providers:
+//!
+//! | Metric                                   | Instrument    | Attributes                          |
+//! |------------------------------------------|---------------|-------------------------------------|
+//! | `feature_flag.evaluation_requests_total` | Counter       | key, provider name                  |
+//! | `feature_flag.evaluation_success_total`  | Counter       | key, provider name, variant, reason |
+//! | `feature_flag.evaluation_error_total`    | Counter       | key, provider name, `error.type`    |
+//! | `feature_flag.evaluation_active_count`   | UpDownCounter | key                                 |
+//! | `feature_flag.evaluation_duration`       | Histogram (s) | key, provider name, reason          |
+//!
+//! Attributes follow the feature flag semantic conventions: `feature_flag.key`,
+//! `feature_flag.provider.name`, `feature_flag.result.variant` and `feature_flag.result.reason`.
+//! Reasons and error types are lower-cased, e.g. `targeting_match` and `flag_not_found`.
+//!
+//! Evaluations cancelled between the `before` and `finally` stages stop counting as active after
+//! [`ABANDONED_AFTER`], and their duration is not recorded.
+//!
+//! # Example
+//!
+//! ```rust
+//! use open_feature::OpenFeature;
+//! use open_feature_in_memory::InMemoryProvider;
+//! use open_feature_otel_metrics_hook::MetricsHook;
+//!
+//! #[tokio::main]
+//! async fn main() {
+//!     let provider = InMemoryProvider::new();
+//!     provider.set_flag("new-checkout", true, None, None);
+//!
+//!     let mut api = OpenFeature::singleton_mut().await;
+//!     api.set_provider(provider).await;
+//!     let client = api.create_client().with_hook(MetricsHook::new());
+//!
+//!     assert!(client.get_bool_value("new-checkout", None, None).await.unwrap());
+//! }
+//! ```
+
+use std::sync::{Mutex, MutexGuard, PoisonError};
+use std::thread::{self, ThreadId};
+use std::time::Duration;
+
+use async_trait::async_trait;
+use open_feature::{
+    EvaluationContext, EvaluationDetails, EvaluationError, EvaluationErrorCode, EvaluationReason,
+    Hook, HookContext, HookHints, Value,
+};
+use openfeature_contrib_common::{error_code_name, BoundedMap};
+use opentelemetry::metrics::{Counter, Histogram, Meter, UpDownCounter};
+use opentelemetry::{global, KeyValue};
+use tokio::task;
+use tokio::time::Instant;
+
+/// Name of the meter of [`MetricsHook::new`].
+pub const METER_NAME: &str = "openfeature";
+
+/// Time after which an evaluation that has not reached the `finally` stage is assumed cancelled:
+/// it no longer counts as active and its duration is not recorded.
+pub const ABANDONED_AFTER: Duration = Duration::from_secs(60);
+
+/// Maximum number of evaluations tracked at once; the oldest ones are abandoned beyond.
+const MAX_TRACKED: usize = 100_000;
+
+/// Buckets of the duration histogram, from in-process evaluations to remote calls.
+const DURATION_BOUNDARIES: [f64; 13] = [
+    0.0001, 0.0005, 0.001, 0.0025, 0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5,
+];
+
+const KEY: &str = "feature_flag.key";
+const PROVIDER_NAME: &str = "feature_flag.provider.name";
+const VARIANT: &str = "feature_flag.result.variant";
+const REASON: &str = "feature_flag.result.reason";
+const ERROR_TYPE: &str = "error.type";
+
+/// Task, or thread outside of a Tokio task, running an evaluation.
+///
+/// Hooks are called sequentially from the evaluating task, so the start of an evaluation recorded
+/// in `before` is found again in `finally`. Cancelled evaluations never reach `finally`, so
+/// starts are also dropped after [`ABANDONED_AFTER`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+enum Evaluator {
+    Task(task::Id),
+    Thread(ThreadId),
+}
+
+impl Evaluator {
+    fn current() -> Self {
+        match task::try_id() {
+            Some(id) => Self::Task(id),
+            None => Self::Thread(thread::current().id()),
+        }
+    }
+}
+
+/// OpenFeature hook recording evaluation metrics with OpenTelemetry.
+pub struct MetricsHook {
+    requests: Counter<u64>,
+    successes: Counter<u64>,
+    errors: Counter<u64>,
+    active: UpDownCounter<i64>,
+    duration: Histogram<f64>,
+    started: Mutex<BoundedMap<(Evaluator, String), Vec<Instant>>>,
+}
+
+impl MetricsHook {
+    /// Creates the hook, recording with the `openfeature` meter of the global meter provider.
+    pub fn new() -> Self {
+        Self::with_meter(&global::meter(METER_NAME))
+    }
+
+    /// Creates the hook, recording with `meter`.
+    pub fn with_meter(meter: &Meter) -> Self {
+        Self {
+            requests: meter
+                .u64_counter("feature_flag.evaluation_requests_total")
+                .with_description("Number of flag evaluation requests")
+                .build(),
+            successes: meter
+                .u64_counter("feature_flag.evaluation_success_total")
+                .with_description("Number of successful flag evaluations")
+                .build(),
+            errors: meter
+                .u64_counter("feature_flag.evaluation_error_total")
+                .with_description("Number of failed flag evaluations")
+                .build(),
+            active: meter
+                .i64_up_down_counter("feature_flag.evaluation_active_count")
+                .with_description("Number of flag evaluations in progress")
+                .build(),
+            duration: meter
+                .f64_histogram("feature_flag.evaluation_duration")
+                .with_description("Duration of flag evaluations")
+                .with_unit("s")
+                .with_boundaries(DURATION_BOUNDARIES.to_vec())
+                .build(),
+            started: Mutex::new(BoundedMap::new(MAX_TRACKED)),
+        }
+    }
+
+    fn started(&self) -> MutexGuard<'_, BoundedMap<(Evaluator, String), Vec<Instant>>> {
+        self.started.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Stops counting evaluations as active.
+    fn abandon(&self, flag_key: String, starts: Vec<Instant>) {
+        self.active
+            .add(-(starts.len() as i64), &[KeyValue::new(KEY, flag_key)]);
+    }
+
+    /// Records the start of an evaluation, abandoning the evaluations started too long ago.
+    fn start(&self, flag_key: &str) {
+        let mut started = self.started();
+        let now = Instant::now();
+        // Evaluations are ordered by their latest start, so the first ones are the oldest.
+        while started.oldest().is_some_and(|(_, starts)| {
+            starts
+                .first()
+                .is_some_and(|start| now.duration_since(*start) >= ABANDONED_AFTER)
+        }) {
+            if let Some(((_, flag_key), starts)) = started.pop_oldest() {
+                self.abandon(flag_key, starts);
+            }
+        }
+
+        let evaluation = (Evaluator::current(), flag_key.to_string());
+        let mut starts = started.remove(&evaluation).unwrap_or_default();
+        starts.push(now);
+        if let Some(((_, flag_key), starts)) = started.insert(evaluation, starts) {
+            self.abandon(flag_key, starts);
+        }
+    }
+
+    /// Start of the evaluation reaching the `finally` stage, unless it was abandoned.
+    fn finish(&self, flag_key: &str) -> Option<Instant> {
+        let mut started = self.started();
+        let evaluation = (Evaluator::current(), flag_key.to_string());
+        let mut starts = started.remove(&evaluation)?;
+        let start = starts.pop();
+        if !starts.is_empty() {
+            started.insert(evaluation, starts);
+        }
+        start
+    }
+}
+
+impl Default for MetricsHook {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn reason_name(reason: &EvaluationReason) -> String {
+    reason.to_string().to_lowercase()
+}
+
+fn error_type(code: &EvaluationErrorCode) -> String {
+    error_code_name(code).to_lowercase()
+}
+
+#[async_trait]
+impl Hook for MetricsHook {
+    async fn before<'a>(
+        &self,
+        context: &HookContext<'a>,
+        _: Option<&'a HookHints>,
+    ) -> Result<Option<EvaluationContext>, EvaluationError> {
+        let key = KeyValue::new(KEY, context.flag_key.to_string());
+        self.requests.add(
+            1,
+            &[
+                key.clone(),
+                KeyValue::new(PROVIDER_NAME, context.provider_metadata.name.clone()),
+            ],
+        );
+        self.active.add(1, &[key]);
+        self.start(context.flag_key);
+
+        Ok(None)
+    }
+
+    async fn after<'a>(
+        &self,
+        context: &HookContext<'a>,
+        details: &EvaluationDetails<Value>,
+        _: Option<&'a HookHints>,
+    ) -> Result<(), EvaluationError> {
+        let mut attributes = vec![
+            KeyValue::new(KEY, context.flag_key.to_string()),
+            KeyValue::new(PROVIDER_NAME, context.provider_metadata.name.clone()),
+        ];
+        if let Some(variant) = &details.variant {
+            attributes.push(KeyValue::new(VARIANT, variant.clone()));
+        }
+        if let Some(reason) = &details.reason {
+            attributes.push(KeyValue::new(REASON, reason_name(reason)));
+        }
+        self.successes.add(1, &attributes);
+
+        Ok(())
+    }
+
+    async fn error<'a>(
+        &self,
+        context: &HookContext<'a>,
+        error: &EvaluationError,
+        _: Option<&'a HookHints>,
+    ) {
+        self.errors.add(
+            1,
+            &[
+                KeyValue::new(KEY, context.flag_key.to_string()),
+                KeyValue::new(PROVIDER_NAME, context.provider_metadata.name.clone()),
+                KeyValue::new(ERROR_TYPE, error_type(&error.code)),
+            ],
+        );
+    }
+
+    async fn finally<'a>(
+        &self,
+        context: &HookContext<'a>,
+        details: &EvaluationDetails<Value>,
+        _: Option<&'a HookHints>,
+    ) {
+        // `before` is skipped when an earlier hook fails.
+        let Some(started) = self.finish(context.flag_key) else {
+            return;
+        };
+
+        let key = KeyValue::new(KEY, context.flag_key.to_string());
+        self.active.add(-1, std::slice::from_ref(&key));
+        let mut attributes = vec![
+            key,
+            KeyValue::new(PROVIDER_NAME, context.provider_metadata.name.clone()),
+        ];
+        if let Some(reason) = &details.reason {
+            attributes.push(KeyValue::new(REASON, reason_name(reason)));
+        }
+        self.duration
+            .record(started.elapsed().as_secs_f64(), &attributes);
+    }
+}
@@ -0,0 +1,218 @@
+use std::time::Duration;
+
+use async_trait::async_trait;
+use open_feature::provider::{FeatureProvider, ProviderMetadata, ResolutionDetails};
+use open_feature::{Client, EvaluationContext, EvaluationResult, OpenFeature};
+use open_feature_in_memory::InMemoryProvider;
+use open_feature_otel_metrics_hook::{MetricsHook, ABANDONED_AFTER};
+use openfeature_contrib_common::{impl_resolve_methods, FlagValue};
+use opentelemetry::metrics::MeterProvider;
+use opentelemetry::KeyValue;
+use opentelemetry_sdk::metrics::data::{AggregatedMetrics, MetricData, ResourceMetrics};
+use opentelemetry_sdk::metrics::{InMemoryMetricExporter, PeriodicReader, SdkMeterProvider};
+
+/// Provider answering a flag after 10 seconds.
+struct Slow(InMemoryProvider);
+
+impl Slow {
+    async fn resolve<T: FlagValue>(
+        &self,
+        flag_key: &str,
+        context: &EvaluationContext,
+    ) -> EvaluationResult<ResolutionDetails<T>> {
+        tokio::time::sleep(Duration::from_secs(10)).await;
+        T::resolve(&self.0, flag_key, context).await
+    }
+}
+
+#[async_trait]
+impl FeatureProvider for Slow {
+    fn metadata(&self) -> &ProviderMetadata {
+        self.0.metadata()
+    }
+
+    impl_resolve_methods!();
+}
+
+/// Metrics recorded by a hook, exported to memory.
+struct Metrics {
+    provider: SdkMeterProvider,
+    exporter: InMemoryMetricExporter,
+}
+
+impl Metrics {
+    fn new() -> (Self, MetricsHook) {
+        let exporter = InMemoryMetricExporter::default();
+        let provider = SdkMeterProvider::builder()
+            .with_reader(PeriodicReader::builder(exporter.clone()).build())
+            .build();
+        let hook = MetricsHook::with_meter(&provider.meter("test"));
+        (Self { provider, exporter }, hook)
+    }
+
+    fn collect(&self) -> ResourceMetrics {
+        self.provider.force_flush().unwrap();
+        self.exporter.get_finished_metrics().unwrap().pop().unwrap()
+    }
+
+    /// Sum of the data points of a counter with all of `attributes`.
+    fn sum(&self, name: &str, attributes: &[KeyValue]) -> i64 {
+        let matches = |point_attributes: Vec<&KeyValue>| {
+            attributes
+                .iter()
+                .all(|attribute| point_attributes.contains(&attribute))
+        };
+        let metrics = self.collect();
+        let Some(metric) = metrics
+            .scope_metrics()
+            .flat_map(|scope| scope.metrics())
+            .find(|metric| metric.name() == name)
+        else {
+            return 0;
+        };
+        match metric.data() {
+            AggregatedMetrics::U64(MetricData::Sum(sum)) => sum
+                .data_points()
+                .filter(|point| matches(point.attributes().collect()))
+                .map(|point| point.value() as i64)
+                .sum(),
+            AggregatedMetrics::I64(MetricData::Sum(sum)) => sum
+                .data_points()
+                .filter(|point| matches(point.attributes().collect()))
+                .map(|point| point.value())
+                .sum(),
+            AggregatedMetrics::F64(MetricData::Histogram(histogram)) => histogram
+                .data_points()
+                .filter(|point| matches(point.attributes().collect()))
+                .map(|point| point.count() as i64)
+                .sum(),
+            data => panic!("unexpected data of {name}: {data:?}"),
+        }
+    }
+}
+
+fn key(flag_key: &str) -> KeyValue {
+    KeyValue::new("feature_flag.key", flag_key.to_string())
+}
+
+async fn client(domain: &str, provider: impl FeatureProvider, hook: MetricsHook) -> Client {
+    let mut api = OpenFeature::singleton_mut().await;
+    api.set_named_provider(domain, provider).await;
+    api.create_named_client(domain).with_hook(hook)
+}
+
+fn flags() -> InMemoryProvider {
+    let flags = InMemoryProvider::new();
+    flags.set_flag("enabled", true, Some("on"), None);
+    flags
+}
+
+#[tokio::test]
+async fn records_successful_evaluations() {
+    let (metrics, hook) = Metrics::new();
+    let client = client("successes", flags(), hook).await;
+    for _ in 0..2 {
+        assert!(client.get_bool_value("enabled", None, None).await.unwrap());
+    }
+
+    let provider = KeyValue::new("feature_flag.provider.name", "in-memory");
+    assert_eq!(
+        metrics.sum(
+            "feature_flag.evaluation_requests_total",
+            &[key("enabled"), provider.clone()]
+        ),
+        2
+    );
+    assert_eq!(
+        metrics.sum(
+            "feature_flag.evaluation_success_total",
+            &[
+                key("enabled"),
+                provider.clone(),
+                KeyValue::new("feature_flag.result.variant", "on"),
+                KeyValue::new("feature_flag.result.reason", "static"),
+            ]
+        ),
+        2
+    );
+    assert_eq!(
+        metrics.sum(
+            "feature_flag.evaluation_duration",
+            &[
+                key("enabled"),
+                provider,
+                KeyValue::new("feature_flag.result.reason", "static")
+            ]
+        ),
+        2
+    );
+    assert_eq!(
+        metrics.sum("feature_flag.evaluation_active_count", &[key("enabled")]),
+        0
+    );
+    assert_eq!(metrics.sum("feature_flag.evaluation_error_total", &[]), 0);
+}
+
+#[tokio::test]
+async fn records_failed_evaluations_by_error_type() {
+    let (metrics, hook) = Metrics::new();
+    let client = client("errors", flags(), hook).await;
+    assert!(client.get_bool_value("missing", None, None).await.is_err());
+    assert!(client.get_int_value("enabled", None, None).await.is_err());
+
+    let error_type = |name: &str| KeyValue::new("error.type", name.to_string());
+    assert_eq!(
+        metrics.sum(
+            "feature_flag.evaluation_error_total",
+            &[key("missing"), error_type("flag_not_found")]
+        ),
+        1
+    );
+    assert_eq!(
+        metrics.sum(
+            "feature_flag.evaluation_error_total",
+            &[key("enabled"), error_type("type_mismatch")]
+        ),
+        1
+    );
+    assert_eq!(metrics.sum("feature_flag.evaluation_active_count", &[]), 0);
+    assert_eq!(
+        metrics.sum("feature_flag.evaluation_duration", &[key("missing")]),
+        1
+    );
+}
+
+#[tokio::test(start_paused = true)]
+async fn abandons_cancelled_evaluations() {
+    let (metrics, hook) = Metrics::new();
+    let slow = flags();
+    slow.set_flag("slow", true, None, None);
+    let client = client("cancelled", Slow(slow), hook).await;
+
+    let cancelled = tokio::time::timeout(
+        Duration::from_secs(1),
+        client.get_bool_value("slow", None, None),
+    )
+    .await;
+    assert!(cancelled.is_err());
+    assert_eq!(
+        metrics.sum("feature_flag.evaluation_active_count", &[key("slow")]),
+        1
+    );
+
+    // Evaluations started later abandon the cancelled one.
+    tokio::time::advance(ABANDONED_AFTER).await;
+    assert!(client.get_bool_value("enabled", None, None).await.unwrap());
+    assert_eq!(
+        metrics.sum("feature_flag.evaluation_active_count", &[key("slow")]),
+        0
+    );
+    assert_eq!(
+        metrics.sum("feature_flag.evaluation_duration", &[key("slow")]),
+        0
+    );
+    assert_eq!(
+        metrics.sum("feature_flag.evaluation_duration", &[key("enabled")]),
+        1
+    );
+}
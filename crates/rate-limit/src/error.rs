@@ -0,0 +1,9 @@
+use thiserror::Error;
+
+/// Errors raised while creating a [`RateLimitedProvider`](crate::RateLimitedProvider).
+#[derive(Debug, Error)]
+pub enum RateLimitError {
+    /// The token bucket holds no token, so every evaluation would be shed.
+    #[error("rate limit burst must not be zero")]
+    ZeroBurst,
+}
@@ -0,0 +1,242 @@
+//! Rate-limiting provider decorator for OpenFeature.
+//!
+//! [`RateLimitedProvider`] caps the evaluations passed to the wrapped provider with a token
+//! bucket, protecting remote flag backends during traffic surges. Evaluations above the limit
+//! are shed: they are answered with the last value the wrapped provider resolved for the same
+//! flag, type and context, or else with the default configured for the flag, and the
+//! `RATE_LIMITED` reason. Shed evaluations without either fail with a `GENERAL` error.
+//!
+//! # Example
+//!
+//! ```rust
+//! use std::collections::HashMap;
+//!
+//! use open_feature::provider::FeatureProvider;
+//! use open_feature::{EvaluationContext, EvaluationReason};
+//! use open_feature_in_memory::InMemoryProvider;
+//! use open_feature_rate_limit::{RateLimitOptions, RateLimitedProvider};
+//!
+//! #[tokio::main]
+//! async fn main() {
+//!     let flags = InMemoryProvider::new();
+//!     flags.set_flag("new-checkout", true, None, None);
+//!
+//!     let provider = RateLimitedProvider::new(
+//!         flags,
+//!         RateLimitOptions {
+//!             rate: 1.0,
+//!             burst: 1,
+//!             defaults: HashMap::from([("max-items".to_string(), 10.into())]),
+//!             ..Default::default()
+//!         },
+//!     )
+//!     .unwrap();
+//!
+//!     let context = EvaluationContext::default();
+//!     let first = provider.resolve_bool_value("new-checkout", &context).await.unwrap();
+//!     let shed = provider.resolve_bool_value("new-checkout", &context).await.unwrap();
+//!     assert_eq!(first.value, shed.value);
+//!     assert_eq!(shed.reason, Some(EvaluationReason::Other("RATE_LIMITED".to_string())));
+//!
+//!     let default = provider.resolve_int_value("max-items", &context).await.unwrap();
+//!     assert_eq!(default.value, 10);
+//!     assert_eq!(provider.stats().shed, 2);
+//! }
+//! ```
+
+mod error;
+
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Mutex, MutexGuard, PoisonError};
+
+use async_trait::async_trait;
+use open_feature::provider::{
+    FeatureProvider, ProviderMetadata, ProviderStatus, ResolutionDetails,
+};
+use open_feature::{
+    EvaluationContext, EvaluationError, EvaluationReason, EvaluationResult, HookWrapper, Value,
+};
+use openfeature_contrib_common::{
+    canonical_context, impl_resolve_methods, rate_limited_error_code, BoundedMap, FlagValue,
+    ResolutionKey, StoredResolution,
+};
+use tokio::time::Instant;
+use tracing::{info, warn};
+
+pub use crate::error::RateLimitError;
+
+/// Reason of shed evaluations.
+pub const RATE_LIMITED: &str = "RATE_LIMITED";
+
+/// Configuration of the [`RateLimitedProvider`].
+#[derive(Debug, Clone)]
+pub struct RateLimitOptions {
+    /// Evaluations per second passed to the wrapped provider.
+    pub rate: f64,
+    /// Evaluations passed at once after a quiet period, the capacity of the token bucket. Must
+    /// not be zero.
+    pub burst: u32,
+    /// Values of shed evaluations of flags without a last known value.
+    pub defaults: HashMap<String, Value>,
+    /// Maximum number of last known values kept. Once reached, the value remembered first is
+    /// dropped for a new one. 0 answers shed evaluations with the defaults only.
+    pub max_last_known: usize,
+}
+
+impl Default for RateLimitOptions {
+    fn default() -> Self {
+        Self {
+            rate: 100.0,
+            burst: 100,
+            defaults: HashMap::new(),
+            max_last_known: 10_000,
+        }
+    }
+}
+
+/// Counts of the evaluations made so far.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct RateLimitStats {
+    /// Evaluations passed to the wrapped provider.
+    pub passed: u64,
+    /// Evaluations shed because the limit was exceeded.
+    pub shed: u64,
+}
+
+#[derive(Debug)]
+struct Bucket {
+    tokens: f64,
+    refilled: Instant,
+}
+
+/// OpenFeature provider capping the evaluations passed to a wrapped provider.
+///
+/// The provider reports the metadata, status and hooks of the wrapped provider.
+pub struct RateLimitedProvider<P> {
+    provider: P,
+    options: RateLimitOptions,
+    bucket: Mutex<Bucket>,
+    last_known: Mutex<BoundedMap<ResolutionKey, StoredResolution>>,
+    shedding: AtomicBool,
+    passed: AtomicU64,
+    shed: AtomicU64,
+}
+
+fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    mutex.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+impl<P: FeatureProvider> RateLimitedProvider<P> {
+    /// Wraps `provider`, starting with a full token bucket.
+    ///
+    /// Fails with [`RateLimitError::ZeroBurst`] when [`RateLimitOptions::burst`] is zero.
+    pub fn new(provider: P, options: RateLimitOptions) -> Result<Self, RateLimitError> {
+        if options.burst == 0 {
+            return Err(RateLimitError::ZeroBurst);
+        }
+        Ok(Self {
+            provider,
+            bucket: Mutex::new(Bucket {
+                tokens: f64::from(options.burst),
+                refilled: Instant::now(),
+            }),
+            last_known: Mutex::new(BoundedMap::new(options.max_last_known)),
+            options,
+            shedding: AtomicBool::new(false),
+            passed: AtomicU64::new(0),
+            shed: AtomicU64::new(0),
+        })
+    }
+
+    /// Counts of the evaluations made so far.
+    pub fn stats(&self) -> RateLimitStats {
+        RateLimitStats {
+            passed: self.passed.load(Ordering::Relaxed),
+            shed: self.shed.load(Ordering::Relaxed),
+        }
+    }
+
+    /// Takes a token from the bucket, returning `false` when it is empty.
+    fn acquire(&self) -> bool {
+        let mut bucket = lock(&self.bucket);
+        let now = Instant::now();
+        let elapsed = now.duration_since(bucket.refilled).as_secs_f64();
+        bucket.tokens = (bucket.tokens + elapsed * self.options.rate.max(0.0))
+            .min(f64::from(self.options.burst));
+        bucket.refilled = now;
+
+        let acquired = bucket.tokens >= 1.0;
+        if acquired {
+            bucket.tokens -= 1.0;
+        }
+        acquired
+    }
+
+    async fn resolve<T: FlagValue>(
+        &self,
+        flag_key: &str,
+        context: &EvaluationContext,
+    ) -> EvaluationResult<ResolutionDetails<T>> {
+        let evaluate = T::resolve(&self.provider, flag_key, context);
+        let key = (self.options.max_last_known > 0)
+            .then(|| canonical_context(context))
+            .flatten()
+            .map(|context| ResolutionKey::new::<T>(flag_key, context));
+
+        if self.acquire() {
+            self.passed.fetch_add(1, Ordering::Relaxed);
+            if self.shedding.swap(false, Ordering::Relaxed) {
+                info!("Evaluations are below the rate limit again");
+            }
+            let result = evaluate.await;
+            if let (Some(key), Ok(details)) = (key, &result) {
+                self.remember(key, details);
+            }
+            return result;
+        }
+
+        self.shed.fetch_add(1, Ordering::Relaxed);
+        if !self.shedding.swap(true, Ordering::Relaxed) {
+            warn!("Evaluations exceed the rate limit, shedding load");
+        }
+
+        let reason = EvaluationReason::Other(RATE_LIMITED.to_string());
+        key.and_then(|key| lock(&self.last_known).get(&key)?.details(reason.clone()))
+            .or_else(|| {
+                let default = self.options.defaults.get(flag_key)?;
+                StoredResolution::from_value(default.clone()).details(reason)
+            })
+            .ok_or_else(|| {
+                EvaluationError::builder()
+                    .code(rate_limited_error_code())
+                    .message(format!("Evaluation of {flag_key} exceeded the rate limit"))
+                    .build()
+            })
+    }
+
+    fn remember<T: FlagValue>(&self, key: ResolutionKey, details: &ResolutionDetails<T>) {
+        lock(&self.last_known).insert(key, StoredResolution::new(details));
+    }
+}
+
+#[async_trait]
+impl<P: FeatureProvider> FeatureProvider for RateLimitedProvider<P> {
+    async fn initialize(&mut self, context: &EvaluationContext) {
+        self.provider.initialize(context).await;
+    }
+
+    fn status(&self) -> ProviderStatus {
+        self.provider.status()
+    }
+
+    fn metadata(&self) -> &ProviderMetadata {
+        self.provider.metadata()
+    }
+
+    fn hooks(&self) -> &[HookWrapper] {
+        self.provider.hooks()
+    }
+
+    impl_resolve_methods!();
+}
@@ -0,0 +1,200 @@
+use std::collections::HashMap;
+use std::time::Duration;
+
+use open_feature::provider::FeatureProvider;
+use open_feature::{EvaluationContext, EvaluationErrorCode, EvaluationReason, LoggingHook};
+use open_feature_in_memory::InMemoryProvider;
+use open_feature_rate_limit::{
+    RateLimitError, RateLimitOptions, RateLimitStats, RateLimitedProvider, RATE_LIMITED,
+};
+
+fn flags() -> InMemoryProvider {
+    let flags = InMemoryProvider::new();
+    flags.set_flag("enabled", true, Some("on"), None);
+    flags.set_flag("limit", 25, None, None);
+    flags
+}
+
+fn options(rate: f64, burst: u32) -> RateLimitOptions {
+    RateLimitOptions {
+        rate,
+        burst,
+        ..Default::default()
+    }
+}
+
+fn rate_limited() -> Option<EvaluationReason> {
+    Some(EvaluationReason::Other(RATE_LIMITED.to_string()))
+}
+
+/// Whether an evaluation of `enabled` reached the wrapped provider.
+async fn passes<P: FeatureProvider>(provider: &RateLimitedProvider<P>) -> bool {
+    let context = EvaluationContext::default();
+    let details = provider
+        .resolve_bool_value("enabled", &context)
+        .await
+        .unwrap();
+    details.reason != rate_limited()
+}
+
+#[tokio::test(start_paused = true)]
+async fn sheds_evaluations_beyond_the_burst() {
+    let provider = RateLimitedProvider::new(flags(), options(1.0, 3)).unwrap();
+
+    for _ in 0..3 {
+        assert!(passes(&provider).await);
+    }
+    assert!(!passes(&provider).await);
+    assert_eq!(provider.stats(), RateLimitStats { passed: 3, shed: 1 });
+}
+
+#[tokio::test(start_paused = true)]
+async fn refills_the_bucket_at_the_rate() {
+    let provider = RateLimitedProvider::new(flags(), options(1.0, 1)).unwrap();
+    assert!(passes(&provider).await);
+
+    tokio::time::advance(Duration::from_millis(500)).await;
+    assert!(!passes(&provider).await);
+    tokio::time::advance(Duration::from_millis(500)).await;
+    assert!(passes(&provider).await);
+    assert!(!passes(&provider).await);
+
+    tokio::time::advance(Duration::from_secs(2)).await;
+    assert!(passes(&provider).await);
+    assert_eq!(provider.stats(), RateLimitStats { passed: 3, shed: 2 });
+}
+
+#[tokio::test(start_paused = true)]
+async fn refills_up_to_the_burst() {
+    let provider = RateLimitedProvider::new(flags(), options(10.0, 2)).unwrap();
+    assert!(passes(&provider).await);
+    assert!(passes(&provider).await);
+
+    tokio::time::advance(Duration::from_secs(60)).await;
+    assert!(passes(&provider).await);
+    assert!(passes(&provider).await);
+    assert!(!passes(&provider).await);
+}
+
+#[tokio::test(start_paused = true)]
+async fn a_zero_rate_never_refills() {
+    let provider = RateLimitedProvider::new(flags(), options(0.0, 1)).unwrap();
+    assert!(passes(&provider).await);
+
+    tokio::time::advance(Duration::from_secs(3_600)).await;
+    assert!(!passes(&provider).await);
+}
+
+#[tokio::test(start_paused = true)]
+async fn answers_shed_evaluations_with_the_last_known_value() {
+    let flags = flags();
+    let provider = RateLimitedProvider::new(flags.clone(), options(1.0, 1)).unwrap();
+    let context = EvaluationContext::default().with_targeting_key("user-1");
+    provider
+        .resolve_bool_value("enabled", &context)
+        .await
+        .unwrap();
+
+    flags.set_flag("enabled", false, Some("off"), None);
+    let details = provider
+        .resolve_bool_value("enabled", &context)
+        .await
+        .unwrap();
+    assert!(details.value);
+    assert_eq!(details.variant.as_deref(), Some("on"));
+    assert_eq!(details.reason, rate_limited());
+
+    // Last known values are kept per context and type.
+    let other = EvaluationContext::default().with_targeting_key("user-2");
+    let error = provider
+        .resolve_bool_value("enabled", &other)
+        .await
+        .unwrap_err();
+    assert_eq!(
+        error.code,
+        EvaluationErrorCode::General("Rate limited".to_string())
+    );
+    assert!(provider
+        .resolve_string_value("enabled", &context)
+        .await
+        .is_err());
+}
+
+#[tokio::test(start_paused = true)]
+async fn answers_shed_evaluations_with_the_defaults() {
+    let provider = RateLimitedProvider::new(
+        flags(),
+        RateLimitOptions {
+            rate: 1.0,
+            burst: 1,
+            defaults: HashMap::from([("limit".to_string(), 10.into())]),
+            max_last_known: 0,
+        },
+    )
+    .unwrap();
+    let context = EvaluationContext::default();
+    assert_eq!(
+        provider
+            .resolve_int_value("limit", &context)
+            .await
+            .unwrap()
+            .value,
+        25
+    );
+
+    let details = provider.resolve_int_value("limit", &context).await.unwrap();
+    assert_eq!(details.value, 10);
+    assert_eq!(details.variant, None);
+    assert_eq!(details.reason, rate_limited());
+    // Defaults of another type are not served.
+    assert!(provider
+        .resolve_bool_value("limit", &context)
+        .await
+        .is_err());
+}
+
+#[test]
+fn reports_the_hooks_of_the_wrapped_provider() {
+    let flags = flags().with_hook(LoggingHook::default());
+    let provider = RateLimitedProvider::new(flags, options(1.0, 1)).unwrap();
+    assert_eq!(provider.hooks().len(), 1);
+}
+
+#[test]
+fn rejects_a_zero_burst() {
+    assert!(matches!(
+        RateLimitedProvider::new(flags(), options(1.0, 0)),
+        Err(RateLimitError::ZeroBurst)
+    ));
+}
+
+#[tokio::test(start_paused = true)]
+async fn drops_the_oldest_last_known_value_once_full() {
+    let provider = RateLimitedProvider::new(
+        flags(),
+        RateLimitOptions {
+            rate: 0.0,
+            burst: 2,
+            max_last_known: 1,
+            ..Default::default()
+        },
+    )
+    .unwrap();
+    let user = |targeting_key: &str| EvaluationContext::default().with_targeting_key(targeting_key);
+    for targeting_key in ["user-1", "user-2"] {
+        provider
+            .resolve_bool_value("enabled", &user(targeting_key))
+            .await
+            .unwrap();
+    }
+
+    assert!(provider
+        .resolve_bool_value("enabled", &user("user-1"))
+        .await
+        .is_err());
+    let details = provider
+        .resolve_bool_value("enabled", &user("user-2"))
+        .await
+        .unwrap();
+    assert_eq!(details.reason, rate_limited());
+}
@@ -0,0 +1,27 @@
+use std::path::PathBuf;
+
+use thiserror::Error;
+
+/// Errors raised while opening a recording.
+#[derive(Debug, Error)]
+pub enum RecorderError {
+    /// The recording could not be opened or read.
+    #[error("failed to open {path}: {source}")]
+    Io {
+        /// Path of the recording.
+        path: PathBuf,
+        /// Cause of the failure.
+        source: std::io::Error,
+    },
+
+    /// A line of the recording is not a valid record.
+    #[error("invalid record on line {line} of {path}: {source}")]
+    Parse {
+        /// Path of the recording.
+        path: PathBuf,
+        /// Line number, starting at 1.
+        line: usize,
+        /// Cause of the failure.
+        source: serde_json::Error,
+    },
+}
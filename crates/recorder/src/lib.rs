@@ -0,0 +1,54 @@
+//! Evaluation recorder and replay provider for OpenFeature.
+//!
+//! [`RecordingProvider`] wraps a provider and appends every evaluation to a JSONL recording:
+//! the flag key, the requested type, a hash of the evaluation context and the result.
+//! [`ReplayProvider`] serves a recording back deterministically, so production flag behaviour
+//! can be reproduced offline in tests and incident analysis.
+//!
+//! ```json
+//! {"flagKey":"new-checkout","type":"boolean","contextHash":"5e1c…","value":true,"variant":"on","reason":"TARGETING_MATCH"}
+//! {"flagKey":"max-items","type":"integer","contextHash":"5e1c…","errorCode":"FLAG_NOT_FOUND","errorMessage":"Flag max-items not found"}
+//! ```
+//!
+//! Contexts are hashed with SHA-256 over their targeting key and custom fields, so recordings
+//! hold no user data. A replayed evaluation matches when the flag key, the type and the hash of
+//! the context are equal. Evaluations whose context holds a field without a JSON representation,
+//! such as a non-finite float, are not recorded.
+//!
+//! # Example
+//!
+//! ```rust
+//! use open_feature::provider::FeatureProvider;
+//! use open_feature::EvaluationContext;
+//! use open_feature_in_memory::InMemoryProvider;
+//! use open_feature_recorder::{RecordingProvider, ReplayProvider};
+//!
+//! #[tokio::main]
+//! async fn main() {
+//!     let path = std::env::temp_dir().join("open-feature-recorder-example.jsonl");
+//!     let _ = std::fs::remove_file(&path);
+//!
+//!     let flags = InMemoryProvider::new();
+//!     flags.set_flag("new-checkout", true, Some("on"), None);
+//!     let recorder = RecordingProvider::new(flags, &path).expect("Failed to open recording");
+//!
+//!     let context = EvaluationContext::default().with_targeting_key("user-123");
+//!     let recorded = recorder.resolve_bool_value("new-checkout", &context).await.unwrap();
+//!     recorder.flush().await;
+//!
+//!     let replay = ReplayProvider::new(&path).await.expect("Failed to read recording");
+//!     let replayed = replay.resolve_bool_value("new-checkout", &context).await.unwrap();
+//!     assert_eq!(recorded.value, replayed.value);
+//!     assert_eq!(recorded.variant, replayed.variant);
+//!     assert_eq!(recorded.reason, replayed.reason);
+//! }
+//! ```
+
+mod error;
+mod record;
+mod recording;
+mod replay;
+
+pub use crate::error::RecorderError;
+pub use crate::recording::RecordingProvider;
+pub use crate::replay::ReplayProvider;
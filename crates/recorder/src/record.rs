@@ -0,0 +1,200 @@
+use open_feature::provider::ResolutionDetails;
+use open_feature::{
+    EvaluationContext, EvaluationError, EvaluationErrorCode, EvaluationReason, EvaluationResult,
+    FlagMetadata, FlagMetadataValue, Value,
+};
+use openfeature_contrib_common::{canonical_context, value_to_json, FlagType, FlagValue};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Number};
+use sha2::{Digest, Sha256};
+
+/// One line of a recording.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct Record {
+    pub(crate) flag_key: String,
+    #[serde(rename = "type", with = "flag_type")]
+    pub(crate) flag_type: FlagType,
+    pub(crate) context_hash: String,
+    #[serde(flatten)]
+    pub(crate) outcome: Outcome,
+}
+
+/// Result of a recorded evaluation. Failures come first, so records with an error code are not
+/// mistaken for resolutions.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(untagged)]
+pub(crate) enum Outcome {
+    #[serde(rename_all = "camelCase")]
+    Failed {
+        error_code: String,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        error_message: Option<String>,
+    },
+    #[serde(rename_all = "camelCase")]
+    Resolved {
+        value: serde_json::Value,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        variant: Option<String>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        reason: Option<String>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        flag_metadata: Option<Map<String, serde_json::Value>>,
+    },
+}
+
+impl Outcome {
+    pub(crate) fn new<T: Clone + Into<Value>>(
+        result: &EvaluationResult<ResolutionDetails<T>>,
+    ) -> Self {
+        match result {
+            Ok(details) => Self::Resolved {
+                value: value_to_json(&details.value.clone().into()),
+                variant: details.variant.clone(),
+                reason: details.reason.as_ref().map(ToString::to_string),
+                flag_metadata: details.flag_metadata.as_ref().map(metadata_to_json),
+            },
+            Err(e) => Self::Failed {
+                error_code: e.code.to_string(),
+                error_message: e.message.clone(),
+            },
+        }
+    }
+
+    /// Turns the outcome back into a result.
+    pub(crate) fn replay<T: FlagValue>(
+        &self,
+        flag_key: &str,
+    ) -> EvaluationResult<ResolutionDetails<T>> {
+        match self {
+            Self::Failed {
+                error_code,
+                error_message,
+            } => Err(EvaluationError {
+                code: parse_error_code(error_code),
+                message: error_message.clone(),
+            }),
+            Self::Resolved {
+                value,
+                variant,
+                reason,
+                flag_metadata,
+            } => Ok(ResolutionDetails {
+                value: Value::try_from(value)
+                    .ok()
+                    .and_then(T::from_value)
+                    .ok_or_else(|| {
+                        EvaluationError::builder()
+                            .code(EvaluationErrorCode::ParseError)
+                            .message(format!("Recorded value of {flag_key} has another type"))
+                            .build()
+                    })?,
+                variant: variant.clone(),
+                reason: reason.as_deref().map(parse_reason),
+                flag_metadata: flag_metadata.as_ref().map(json_to_metadata),
+            }),
+        }
+    }
+}
+
+/// Stable hash of the targeting key and custom fields of a context, or `None` when a field has no
+/// JSON representation.
+pub(crate) fn context_hash(context: &EvaluationContext) -> Option<String> {
+    canonical_context(context).map(|context| format!("{:x}", Sha256::digest(context)))
+}
+
+/// Serializes flag types by their name, e.g. `boolean`.
+mod flag_type {
+    use openfeature_contrib_common::FlagType;
+    use serde::de::Error;
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    const TYPES: [FlagType; 5] = [
+        FlagType::Boolean,
+        FlagType::Integer,
+        FlagType::Float,
+        FlagType::String,
+        FlagType::Object,
+    ];
+    const NAMES: &[&str] = &["boolean", "integer", "float", "string", "object"];
+
+    pub(super) fn serialize<S: Serializer>(
+        flag_type: &FlagType,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(flag_type.as_str())
+    }
+
+    pub(super) fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<FlagType, D::Error> {
+        let name = String::deserialize(deserializer)?;
+        TYPES
+            .into_iter()
+            .find(|flag_type| flag_type.as_str() == name)
+            .ok_or_else(|| D::Error::unknown_variant(&name, NAMES))
+    }
+}
+
+fn metadata_to_json(metadata: &FlagMetadata) -> Map<String, serde_json::Value> {
+    metadata
+        .values
+        .iter()
+        .map(|(key, value)| {
+            let value = match value {
+                FlagMetadataValue::Bool(value) => serde_json::Value::Bool(*value),
+                FlagMetadataValue::Int(value) => serde_json::Value::Number((*value).into()),
+                FlagMetadataValue::Float(value) => Number::from_f64(*value)
+                    .map_or(serde_json::Value::Null, serde_json::Value::Number),
+                FlagMetadataValue::String(value) => serde_json::Value::String(value.clone()),
+            };
+            (key.clone(), value)
+        })
+        .collect()
+}
+
+fn json_to_metadata(metadata: &Map<String, serde_json::Value>) -> FlagMetadata {
+    let values = metadata
+        .iter()
+        .filter_map(|(key, value)| {
+            let value = match value {
+                serde_json::Value::Bool(value) => FlagMetadataValue::Bool(*value),
+                serde_json::Value::Number(value) => match value.as_i64() {
+                    Some(value) => FlagMetadataValue::Int(value),
+                    None => FlagMetadataValue::Float(value.as_f64()?),
+                },
+                serde_json::Value::String(value) => FlagMetadataValue::String(value.clone()),
+                _ => return None,
+            };
+            Some((key.clone(), value))
+        })
+        .collect();
+    FlagMetadata { values }
+}
+
+fn parse_reason(reason: &str) -> EvaluationReason {
+    match reason {
+        "STATIC" => EvaluationReason::Static,
+        "DEFAULT" => EvaluationReason::Default,
+        "TARGETING_MATCH" => EvaluationReason::TargetingMatch,
+        "SPLIT" => EvaluationReason::Split,
+        "CACHED" => EvaluationReason::Cached,
+        "DISABLED" => EvaluationReason::Disabled,
+        "UNKNOWN" => EvaluationReason::Unknown,
+        "ERROR" => EvaluationReason::Error,
+        other => EvaluationReason::Other(other.to_string()),
+    }
+}
+
+/// Parses the display form of an error code; general errors display their own code.
+fn parse_error_code(code: &str) -> EvaluationErrorCode {
+    match code {
+        "PROVIDER_NOT_READY" => EvaluationErrorCode::ProviderNotReady,
+        "FLAG_NOT_FOUND" => EvaluationErrorCode::FlagNotFound,
+        "PARSE_ERROR" => EvaluationErrorCode::ParseError,
+        "TYPE_MISMATCH" => EvaluationErrorCode::TypeMismatch,
+        "TARGETING_KEY_MISSING" => EvaluationErrorCode::TargetingKeyMissing,
+        "INVALID_CONTEXT" => EvaluationErrorCode::InvalidContext,
+        other => EvaluationErrorCode::General(other.to_string()),
+    }
+}
@@ -0,0 +1,167 @@
+use std::fs::{File, OpenOptions};
+use std::io::Write;
+use std::path::PathBuf;
+
+use async_trait::async_trait;
+use open_feature::provider::{
+    FeatureProvider, ProviderMetadata, ProviderStatus, ResolutionDetails,
+};
+use open_feature::{EvaluationContext, EvaluationResult, HookWrapper};
+use openfeature_contrib_common::{impl_resolve_methods, FlagValue};
+use tokio::sync::{mpsc, oneshot};
+use tracing::warn;
+
+use crate::error::RecorderError;
+use crate::record::{context_hash, Outcome, Record};
+
+/// Records waiting to be written; records beyond are dropped.
+const PENDING_RECORDS: usize = 1_024;
+
+enum Command {
+    Record(String),
+    Flush(oneshot::Sender<()>),
+}
+
+/// Provider decorator appending every evaluation of the wrapped provider to a JSONL recording.
+///
+/// The decorator is transparent: it reports the metadata, status and hooks of the wrapped provider
+/// and returns its results unchanged. Evaluations do not wait for their record to be written: a
+/// background task writes the records on Tokio's blocking thread pool. Records that cannot be
+/// written, or that find too many records waiting, are logged and dropped.
+pub struct RecordingProvider<P> {
+    provider: P,
+    commands: mpsc::Sender<Command>,
+}
+
+impl<P: FeatureProvider> RecordingProvider<P> {
+    /// Wraps `provider`, appending its evaluations to the recording at `path`. The file is
+    /// created if it does not exist.
+    ///
+    /// # Panics
+    ///
+    /// When called outside a Tokio runtime, which runs the writer of the records.
+    pub fn new(provider: P, path: impl Into<PathBuf>) -> Result<Self, RecorderError> {
+        let path = path.into();
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&path)
+            .map_err(|source| RecorderError::Io {
+                path: path.clone(),
+                source,
+            })?;
+
+        let (commands, receiver) = mpsc::channel(PENDING_RECORDS);
+        tokio::spawn(write_records(path, file, receiver));
+        Ok(Self { provider, commands })
+    }
+
+    /// The wrapped provider.
+    pub fn inner(&self) -> &P {
+        &self.provider
+    }
+
+    /// Waits until the records of the evaluations made so far are written.
+    pub async fn flush(&self) {
+        let (done, written) = oneshot::channel();
+        if self.commands.send(Command::Flush(done)).await.is_ok() {
+            let _ = written.await;
+        }
+    }
+
+    async fn resolve<T: FlagValue>(
+        &self,
+        flag_key: &str,
+        context: &EvaluationContext,
+    ) -> EvaluationResult<ResolutionDetails<T>> {
+        let result = T::resolve(&self.provider, flag_key, context).await;
+        self.record(flag_key, context, &result);
+        result
+    }
+
+    fn record<T: FlagValue>(
+        &self,
+        flag_key: &str,
+        context: &EvaluationContext,
+        result: &EvaluationResult<ResolutionDetails<T>>,
+    ) {
+        let Some(context_hash) = context_hash(context) else {
+            warn!("Not recording the evaluation of {flag_key}: its context cannot be hashed");
+            return;
+        };
+        let record = Record {
+            flag_key: flag_key.to_string(),
+            flag_type: T::FLAG_TYPE,
+            context_hash,
+            outcome: Outcome::new(result),
+        };
+        let line = match serde_json::to_string(&record) {
+            Ok(line) => line,
+            Err(e) => {
+                warn!("Failed to serialize the evaluation of {flag_key}: {e}");
+                return;
+            }
+        };
+
+        if self.commands.try_send(Command::Record(line)).is_err() {
+            warn!("Not recording the evaluation of {flag_key}: too many records are waiting");
+        }
+    }
+}
+
+/// Writes the records received in batches, each on the blocking thread pool.
+async fn write_records(mut path: PathBuf, mut file: File, mut commands: mpsc::Receiver<Command>) {
+    let mut batch = Vec::new();
+    while commands.recv_many(&mut batch, PENDING_RECORDS).await > 0 {
+        let pending = std::mem::take(&mut batch);
+        let written = tokio::task::spawn_blocking(move || {
+            let mut lines = String::new();
+            let mut flushed = Vec::new();
+            for command in pending {
+                match command {
+                    Command::Record(line) => {
+                        lines.push_str(&line);
+                        lines.push('\n');
+                    }
+                    Command::Flush(done) => flushed.push(done),
+                }
+            }
+            if let Err(e) = file.write_all(lines.as_bytes()) {
+                warn!("Failed to record to {}: {e}", path.display());
+            }
+            for done in flushed {
+                let _ = done.send(());
+            }
+            (path, file)
+        })
+        .await;
+        match written {
+            Ok(writer) => (path, file) = writer,
+            Err(e) => {
+                warn!("Stopped recording: {e}");
+                return;
+            }
+        }
+    }
+}
+
+#[async_trait]
+impl<P: FeatureProvider> FeatureProvider for RecordingProvider<P> {
+    async fn initialize(&mut self, context: &EvaluationContext) {
+        self.provider.initialize(context).await;
+    }
+
+    fn status(&self) -> ProviderStatus {
+        self.provider.status()
+    }
+
+    fn metadata(&self) -> &ProviderMetadata {
+        self.provider.metadata()
+    }
+
+    fn hooks(&self) -> &[HookWrapper] {
+        self.provider.hooks()
+    }
+
+    impl_resolve_methods!();
+}
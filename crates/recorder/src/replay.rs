@@ -0,0 +1,86 @@
+use std::collections::HashMap;
+use std::path::Path;
+
+use async_trait::async_trait;
+use open_feature::provider::{FeatureProvider, ProviderMetadata, ResolutionDetails};
+use open_feature::{EvaluationContext, EvaluationError, EvaluationErrorCode, EvaluationResult};
+use openfeature_contrib_common::{impl_resolve_methods, FlagValue, ResolutionKey};
+
+use crate::error::RecorderError;
+use crate::record::{context_hash, Outcome, Record};
+
+/// OpenFeature provider serving the evaluations of a recording.
+///
+/// Evaluations are matched by flag key, type and context; when a recording holds several
+/// evaluations of the same flag for the same context, the last one is served. Evaluations that
+/// were not recorded fail with `FLAG_NOT_FOUND`.
+#[derive(Debug)]
+pub struct ReplayProvider {
+    metadata: ProviderMetadata,
+    /// Outcomes keyed by the hash of their context.
+    outcomes: HashMap<ResolutionKey, Outcome>,
+}
+impl ReplayProvider {
+    /// Creates the provider, reading the recording at `path`.
+    pub async fn new(path: impl AsRef<Path>) -> Result<Self, RecorderError> {
+        let path = path.as_ref();
+        let content =
+            tokio::fs::read_to_string(path)
+                .await
+                .map_err(|source| RecorderError::Io {
+                    path: path.to_path_buf(),
+                    source,
+                })?;
+
+        let mut outcomes = HashMap::new();
+        for (index, line) in content.lines().enumerate() {
+            if line.trim().is_empty() {
+                continue;
+            }
+            let record: Record =
+                serde_json::from_str(line).map_err(|source| RecorderError::Parse {
+                    path: path.to_path_buf(),
+                    line: index + 1,
+                    source,
+                })?;
+            let key = ResolutionKey {
+                flag_key: record.flag_key,
+                flag_type: record.flag_type,
+                context: record.context_hash,
+            };
+            outcomes.insert(key, record.outcome);
+        }
+
+        Ok(Self {
+            metadata: ProviderMetadata::new("replay"),
+            outcomes,
+        })
+    }
+
+    async fn resolve<T: FlagValue>(
+        &self,
+        flag_key: &str,
+        context: &EvaluationContext,
+    ) -> EvaluationResult<ResolutionDetails<T>> {
+        let outcome = context_hash(context)
+            .and_then(|hash| self.outcomes.get(&ResolutionKey::new::<T>(flag_key, hash)));
+        let outcome = outcome.ok_or_else(|| {
+            EvaluationError::builder()
+                .code(EvaluationErrorCode::FlagNotFound)
+                .message(format!(
+                    "No recorded evaluation of {flag_key} for this context"
+                ))
+                .build()
+        })?;
+        outcome.replay(flag_key)
+    }
+}
+
+#[async_trait]
+impl FeatureProvider for ReplayProvider {
+    fn metadata(&self) -> &ProviderMetadata {
+        &self.metadata
+    }
+
+    impl_resolve_methods!();
+}
@@ -0,0 +1,190 @@
+use std::path::{Path, PathBuf};
+
+use open_feature::provider::FeatureProvider;
+use open_feature::{
+    EvaluationContext, EvaluationErrorCode, EvaluationReason, FlagMetadataValue, LoggingHook,
+    StructValue,
+};
+use open_feature_in_memory::InMemoryProvider;
+use open_feature_recorder::{RecorderError, RecordingProvider, ReplayProvider};
+
+fn recording(name: &str) -> PathBuf {
+    let path = std::env::temp_dir().join(format!("{}-{name}.jsonl", std::process::id()));
+    let _ = std::fs::remove_file(&path);
+    path
+}
+
+fn user(targeting_key: &str) -> EvaluationContext {
+    EvaluationContext::default()
+        .with_targeting_key(targeting_key)
+        .with_custom_field("plan", "premium")
+}
+
+async fn replay(path: &Path) -> ReplayProvider {
+    ReplayProvider::new(path).await.unwrap()
+}
+
+#[tokio::test]
+async fn replays_struct_values_and_errors() {
+    let path = recording("struct-and-errors");
+    let flags = InMemoryProvider::new();
+    let layout = StructValue::default()
+        .with_field("columns", 2)
+        .with_field("theme", "dark");
+    flags.set_flag("layout", layout.clone(), Some("grid"), None);
+    flags.set_flag("limit", "n/a", None, None);
+    let recorder = RecordingProvider::new(flags, &path).unwrap();
+    let context = user("user-1");
+
+    recorder
+        .resolve_struct_value("layout", &context)
+        .await
+        .unwrap();
+    let recorded = recorder
+        .resolve_int_value("limit", &context)
+        .await
+        .unwrap_err();
+    recorder
+        .resolve_bool_value("missing", &context)
+        .await
+        .unwrap_err();
+    recorder.flush().await;
+
+    let replay = replay(&path).await;
+    let details = replay
+        .resolve_struct_value("layout", &context)
+        .await
+        .unwrap();
+    assert_eq!(details.value, layout);
+    assert_eq!(details.variant.as_deref(), Some("grid"));
+    assert_eq!(details.reason, Some(EvaluationReason::Static));
+
+    let error = replay
+        .resolve_int_value("limit", &context)
+        .await
+        .unwrap_err();
+    assert_eq!(error.code, EvaluationErrorCode::TypeMismatch);
+    assert_eq!(error.message, recorded.message);
+    let error = replay
+        .resolve_bool_value("missing", &context)
+        .await
+        .unwrap_err();
+    assert_eq!(error.code, EvaluationErrorCode::FlagNotFound);
+
+    // Evaluations are matched by type and context.
+    assert!(replay.resolve_bool_value("layout", &context).await.is_err());
+    assert!(replay
+        .resolve_struct_value("layout", &user("user-2"))
+        .await
+        .is_err());
+}
+
+#[tokio::test]
+async fn replays_the_last_record_of_an_evaluation() {
+    let path = recording("last-record");
+    let flags = InMemoryProvider::new();
+    let recorder = RecordingProvider::new(flags.clone(), &path).unwrap();
+    let context = user("user-1");
+
+    flags.set_flag("limit", 25, None, None);
+    recorder.resolve_int_value("limit", &context).await.unwrap();
+    flags.set_flag("limit", 30, Some("raised"), None);
+    recorder.resolve_int_value("limit", &context).await.unwrap();
+    recorder.flush().await;
+
+    let details = replay(&path)
+        .await
+        .resolve_int_value("limit", &context)
+        .await
+        .unwrap();
+    assert_eq!(details.value, 30);
+    assert_eq!(details.variant.as_deref(), Some("raised"));
+}
+
+#[tokio::test]
+async fn replays_flag_metadata_and_general_errors() {
+    let path = recording("metadata");
+    let context = EvaluationContext::default();
+    let hash = {
+        let recorder = RecordingProvider::new(InMemoryProvider::new(), &path).unwrap();
+        recorder
+            .resolve_bool_value("missing", &context)
+            .await
+            .unwrap_err();
+        recorder.flush().await;
+        let line = std::fs::read_to_string(&path).unwrap();
+        let record: serde_json::Value = serde_json::from_str(line.trim()).unwrap();
+        record["contextHash"].as_str().unwrap().to_string()
+    };
+    std::fs::write(
+        &path,
+        format!(
+            concat!(
+                r#"{{"flagKey":"new-checkout","type":"boolean","contextHash":"{hash}","value":true,"#,
+                r#""reason":"SPLIT","flagMetadata":{{"team":"checkout","version":3,"ratio":0.5,"beta":true}}}}"#,
+                "\n",
+                r#"{{"flagKey":"max-items","type":"integer","contextHash":"{hash}","errorCode":"TIMEOUT"}}"#,
+                "\n",
+            ),
+            hash = hash
+        ),
+    )
+    .unwrap();
+
+    let replay = replay(&path).await;
+    let details = replay
+        .resolve_bool_value("new-checkout", &context)
+        .await
+        .unwrap();
+    assert_eq!(details.reason, Some(EvaluationReason::Split));
+    let metadata = details.flag_metadata.unwrap().values;
+    assert_eq!(
+        metadata["team"],
+        FlagMetadataValue::String("checkout".to_string())
+    );
+    assert_eq!(metadata["version"], FlagMetadataValue::Int(3));
+    assert_eq!(metadata["ratio"], FlagMetadataValue::Float(0.5));
+    assert_eq!(metadata["beta"], FlagMetadataValue::Bool(true));
+
+    let error = replay
+        .resolve_int_value("max-items", &context)
+        .await
+        .unwrap_err();
+    assert_eq!(
+        error.code,
+        EvaluationErrorCode::General("TIMEOUT".to_string())
+    );
+}
+
+#[tokio::test]
+async fn reports_the_line_of_invalid_records() {
+    let path = recording("invalid");
+    std::fs::write(
+        &path,
+        concat!(
+            r#"{"flagKey":"a","type":"boolean","contextHash":"x","value":true}"#,
+            "\n\n",
+            r#"{"flagKey":"b","type":"bool","contextHash":"x","value":true}"#,
+            "\n",
+        ),
+    )
+    .unwrap();
+
+    match ReplayProvider::new(&path).await.unwrap_err() {
+        RecorderError::Parse { line, source, .. } => {
+            assert_eq!(line, 3);
+            assert!(
+                source.to_string().contains("unknown variant `bool`"),
+                "{source}"
+            );
+        }
+        e => panic!("unexpected error {e}"),
+    }
+}
+
+#[tokio::test]
+async fn reports_the_hooks_of_the_wrapped_provider() {
+    let flags = InMemoryProvider::new().with_hook(LoggingHook::default());
+    let provider = RecordingProvider::new(flags, recording("hooks")).unwrap();
+    assert_eq!(provider.hooks().len(), 1);
+}
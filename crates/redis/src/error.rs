@@ -0,0 +1,9 @@
+use thiserror::Error;
+
+/// Errors raised while creating a [`RedisProvider`](crate::RedisProvider).
+#[derive(Debug, Error)]
+pub enum RedisProviderError {
+    /// The connection URL is invalid or Redis is unreachable.
+    #[error("failed to connect to Redis: {0}")]
+    Redis(#[from] redis::RedisError),
+}
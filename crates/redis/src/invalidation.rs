@@ -0,0 +1,109 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use futures_util::StreamExt;
+use openfeature_contrib_common::Backoff;
+use tokio::sync::RwLock;
+use tokio::task::JoinHandle;
+use tracing::{debug, warn};
+
+/// Raw flag values cached while the invalidation channel is subscribed.
+#[derive(Debug, Default)]
+pub(crate) struct Cache {
+    /// Whether invalidations are being received; the cache is bypassed otherwise.
+    pub(crate) subscribed: bool,
+    /// Incremented by every invalidation, so reads racing with one are not cached.
+    pub(crate) generation: u64,
+    pub(crate) flags: HashMap<String, Option<String>>,
+}
+
+impl Cache {
+    /// Drops `flag_key`, or every flag when `None`.
+    fn invalidate(&mut self, flag_key: Option<&str>) {
+        self.generation += 1;
+        match flag_key {
+            Some(flag_key) => {
+                self.flags.remove(flag_key);
+            }
+            None => self.flags.clear(),
+        }
+    }
+}
+
+/// Subscribes to `channel` and invalidates cached flags for every message.
+///
+/// A message carries the key of the changed flag; an empty message or `*` invalidates all flags.
+/// While the subscription is down, the cache is cleared and bypassed.
+pub(crate) fn spawn_subscriber(
+    client: redis::Client,
+    channel: String,
+    cache: Arc<RwLock<Cache>>,
+    mut backoff: Backoff,
+) -> JoinHandle<()> {
+    tokio::spawn(async move {
+        loop {
+            match subscribe(&client, &channel).await {
+                Ok(pubsub) => {
+                    {
+                        let mut cache = cache.write().await;
+                        cache.subscribed = true;
+                        cache.invalidate(None);
+                    }
+                    backoff.reset();
+                    debug!("Subscribed to Redis channel {channel}");
+
+                    let mut messages = pubsub.into_on_message();
+                    while let Some(message) = messages.next().await {
+                        let payload: String = message.get_payload().unwrap_or_default();
+                        let flag_key =
+                            Some(payload.as_str()).filter(|key| !matches!(*key, "" | "*"));
+                        cache.write().await.invalidate(flag_key);
+                    }
+                    warn!("Redis subscription to {channel} ended");
+                }
+                Err(e) => warn!("Failed to subscribe to Redis channel {channel}: {e}"),
+            }
+
+            {
+                let mut cache = cache.write().await;
+                cache.subscribed = false;
+                cache.invalidate(None);
+            }
+            tokio::time::sleep(backoff.next_delay()).await;
+        }
+    })
+}
+
+async fn subscribe(
+    client: &redis::Client,
+    channel: &str,
+) -> redis::RedisResult<redis::aio::PubSub> {
+    let mut pubsub = client.get_async_pubsub().await?;
+    pubsub.subscribe(channel).await?;
+    Ok(pubsub)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn invalidation_drops_flags_and_bumps_the_generation() {
+        let mut cache = Cache::default();
+        cache
+            .flags
+            .insert("a".to_string(), Some("true".to_string()));
+        cache.flags.insert("b".to_string(), None);
+
+        cache.invalidate(Some("a"));
+        assert_eq!(cache.generation, 1);
+        assert_eq!(cache.flags.keys().collect::<Vec<_>>(), ["b"]);
+
+        cache.invalidate(Some("unknown"));
+        assert_eq!(cache.generation, 2);
+
+        cache.invalidate(None);
+        assert_eq!(cache.generation, 3);
+        assert!(cache.flags.is_empty());
+    }
+}
@@ -0,0 +1,58 @@
+use std::collections::HashMap;
+
+use redis::aio::ConnectionManager;
+use redis::AsyncCommands;
+use thiserror::Error;
+
+use crate::KeyLayout;
+
+/// Why flags could not be read from Redis.
+#[derive(Debug, Error)]
+pub(crate) enum LoadError {
+    #[error("{0}")]
+    Redis(#[from] redis::RedisError),
+    #[error("flag document {0} is not a JSON object")]
+    Document(String),
+}
+
+/// Reads the raw value of `flag_key` according to `layout`.
+///
+/// The result always holds an entry for `flag_key`, `None` if the flag does not exist. A flag
+/// document yields every flag it contains so they can be cached together; its string members
+/// are returned unquoted and other members as JSON.
+pub(crate) async fn load(
+    mut connection: ConnectionManager,
+    layout: &KeyLayout,
+    flag_key: &str,
+) -> Result<HashMap<String, Option<String>>, LoadError> {
+    let mut flags = HashMap::new();
+    match layout {
+        KeyLayout::Hash { key } => {
+            let value: Option<String> = connection.hget(key, flag_key).await?;
+            flags.insert(flag_key.to_string(), value);
+        }
+        KeyLayout::Prefix { prefix } => {
+            let value: Option<String> = connection.get(format!("{prefix}{flag_key}")).await?;
+            flags.insert(flag_key.to_string(), value);
+        }
+        KeyLayout::Document { key } => {
+            let document: Option<String> = connection.get(key).await?;
+            if let Some(document) = document {
+                let serde_json::Value::Object(members) = serde_json::from_str(&document)
+                    .map_err(|_| LoadError::Document(key.clone()))?
+                else {
+                    return Err(LoadError::Document(key.clone()));
+                };
+                flags.extend(members.into_iter().map(|(name, value)| {
+                    let value = match value {
+                        serde_json::Value::String(value) => value,
+                        value => value.to_string(),
+                    };
+                    (name, Some(value))
+                }));
+            }
+            flags.entry(flag_key.to_string()).or_insert(None);
+        }
+    }
+    Ok(flags)
+}
@@ -0,0 +1,466 @@
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::{Arc, Mutex, PoisonError};
+use std::time::Duration;
+
+use open_feature::provider::FeatureProvider;
+use open_feature::{EvaluationContext, EvaluationErrorCode};
+use open_feature_redis::{KeyLayout, RedisOptions, RedisProvider};
+use tokio::io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::mpsc;
+
+/// Minimal RESP2 server answering the commands the provider sends.
+#[derive(Clone, Default)]
+struct FakeRedis {
+    state: Arc<Mutex<State>>,
+}
+
+#[derive(Default)]
+struct State {
+    strings: HashMap<String, String>,
+    hashes: HashMap<String, HashMap<String, String>>,
+    reads: usize,
+    subscribers: Vec<mpsc::UnboundedSender<Vec<u8>>>,
+}
+
+impl FakeRedis {
+    async fn start(&self) -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("redis://{}", listener.local_addr().unwrap());
+        let redis = self.clone();
+        tokio::spawn(async move {
+            while let Ok((socket, _)) = listener.accept().await {
+                tokio::spawn(redis.clone().serve(socket));
+            }
+        });
+        url
+    }
+
+    fn state(&self) -> std::sync::MutexGuard<'_, State> {
+        self.state.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    fn set(&self, key: &str, value: &str) {
+        self.state()
+            .strings
+            .insert(key.to_string(), value.to_string());
+    }
+
+    fn hset(&self, key: &str, field: &str, value: &str) {
+        self.state()
+            .hashes
+            .entry(key.to_string())
+            .or_default()
+            .insert(field.to_string(), value.to_string());
+    }
+
+    fn reads(&self) -> usize {
+        self.state().reads
+    }
+
+    fn publish(&self, channel: &str, payload: &str) {
+        let message = array(&["message", channel, payload]);
+        for subscriber in &self.state().subscribers {
+            let _ = subscriber.send(message.clone());
+        }
+    }
+
+    async fn wait_for_subscriber(&self) {
+        tokio::time::timeout(Duration::from_secs(5), async {
+            while self.state().subscribers.is_empty() {
+                tokio::time::sleep(Duration::from_millis(10)).await;
+            }
+        })
+        .await
+        .expect("provider did not subscribe");
+    }
+
+    async fn serve(self, socket: TcpStream) {
+        let (reader, mut writer) = socket.into_split();
+        let (sender, mut replies) = mpsc::unbounded_channel::<Vec<u8>>();
+        tokio::spawn(async move {
+            while let Some(reply) = replies.recv().await {
+                if writer.write_all(&reply).await.is_err() {
+                    break;
+                }
+            }
+        });
+
+        let mut reader = BufReader::new(reader);
+        while let Some(command) = read_command(&mut reader).await {
+            let args = command.iter().map(String::as_str).collect::<Vec<_>>();
+            let reply = {
+                let mut state = self.state();
+                match args.as_slice() {
+                    [get, key] if get.eq_ignore_ascii_case("GET") => {
+                        state.reads += 1;
+                        bulk(state.strings.get(*key))
+                    }
+                    [hget, key, field] if hget.eq_ignore_ascii_case("HGET") => {
+                        state.reads += 1;
+                        bulk(state.hashes.get(*key).and_then(|hash| hash.get(*field)))
+                    }
+                    [subscribe, channel] if subscribe.eq_ignore_ascii_case("SUBSCRIBE") => {
+                        state.subscribers.push(sender.clone());
+                        format!("*3\r\n{}{}:1\r\n", bulk_str("subscribe"), bulk_str(channel))
+                            .into_bytes()
+                    }
+                    _ => b"+OK\r\n".to_vec(),
+                }
+            };
+            if sender.send(reply).is_err() {
+                break;
+            }
+        }
+    }
+}
+
+async fn read_command(
+    reader: &mut BufReader<tokio::net::tcp::OwnedReadHalf>,
+) -> Option<Vec<String>> {
+    let mut line = String::new();
+    reader.read_line(&mut line).await.ok()?;
+    let count: usize = line.trim_end().strip_prefix('*')?.parse().ok()?;
+    let mut args = Vec::with_capacity(count);
+    for _ in 0..count {
+        line.clear();
+        reader.read_line(&mut line).await.ok()?;
+        let len: usize = line.trim_end().strip_prefix('$')?.parse().ok()?;
+        let mut arg = vec![0; len + 2];
+        reader.read_exact(&mut arg).await.ok()?;
+        arg.truncate(len);
+        args.push(String::from_utf8(arg).ok()?);
+    }
+    Some(args)
+}
+
+fn bulk_str(value: &str) -> String {
+    format!("${}\r\n{value}\r\n", value.len())
+}
+
+fn bulk(value: Option<&String>) -> Vec<u8> {
+    match value {
+        Some(value) => bulk_str(value).into_bytes(),
+        None => b"$-1\r\n".to_vec(),
+    }
+}
+
+fn array(values: &[&str]) -> Vec<u8> {
+    let mut array = format!("*{}\r\n", values.len()).into_bytes();
+    for value in values {
+        array.extend(bulk_str(value).into_bytes());
+    }
+    array
+}
+
+fn options(url: String, layout: KeyLayout, invalidation_channel: Option<&str>) -> RedisOptions {
+    RedisOptions {
+        url,
+        layout,
+        invalidation_channel: invalidation_channel.map(str::to_string),
+        ..Default::default()
+    }
+}
+
+fn hash() -> KeyLayout {
+    KeyLayout::Hash {
+        key: "flags".to_string(),
+    }
+}
+
+async fn eventually<F: Future<Output = bool>>(mut condition: impl FnMut() -> F) {
+    tokio::time::timeout(Duration::from_secs(5), async {
+        while !condition().await {
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+    })
+    .await
+    .expect("condition not met");
+}
+
+#[tokio::test]
+async fn parses_values_of_every_type() {
+    let redis = FakeRedis::default();
+    redis.hset("flags", "enabled", "true");
+    redis.hset("flags", "limit", " 25 ");
+    redis.hset("flags", "ratio", "0.5");
+    redis.hset("flags", "plain", "hello");
+    redis.hset("flags", "quoted", "\"hello\"");
+    redis.hset("flags", "theme", r#"{"color": "blue"}"#);
+    let provider = RedisProvider::new(options(redis.start().await, hash(), None))
+        .await
+        .unwrap();
+    let context = &EvaluationContext::default();
+
+    assert!(
+        provider
+            .resolve_bool_value("enabled", context)
+            .await
+            .unwrap()
+            .value
+    );
+    assert_eq!(
+        provider
+            .resolve_int_value("limit", context)
+            .await
+            .unwrap()
+            .value,
+        25
+    );
+    assert_eq!(
+        provider
+            .resolve_float_value("ratio", context)
+            .await
+            .unwrap()
+            .value,
+        0.5
+    );
+    for key in ["plain", "quoted"] {
+        let value = provider
+            .resolve_string_value(key, context)
+            .await
+            .unwrap()
+            .value;
+        assert_eq!(value, "hello");
+    }
+    let theme = provider
+        .resolve_struct_value("theme", context)
+        .await
+        .unwrap()
+        .value;
+    assert_eq!(theme.fields["color"], "blue".into());
+}
+
+#[tokio::test]
+async fn reports_missing_and_mistyped_flags() {
+    let redis = FakeRedis::default();
+    redis.hset("flags", "plain", "hello");
+    redis.hset("flags", "list", "[1, 2]");
+    let provider = RedisProvider::new(options(redis.start().await, hash(), None))
+        .await
+        .unwrap();
+    let context = &EvaluationContext::default();
+
+    let error = provider
+        .resolve_bool_value("missing", context)
+        .await
+        .unwrap_err();
+    assert_eq!(error.code, EvaluationErrorCode::FlagNotFound);
+    for error in [
+        provider
+            .resolve_bool_value("plain", context)
+            .await
+            .unwrap_err(),
+        provider
+            .resolve_int_value("plain", context)
+            .await
+            .unwrap_err(),
+        provider
+            .resolve_float_value("plain", context)
+            .await
+            .unwrap_err(),
+        provider
+            .resolve_struct_value("list", context)
+            .await
+            .unwrap_err(),
+    ] {
+        assert_eq!(error.code, EvaluationErrorCode::TypeMismatch);
+    }
+    let error = provider
+        .resolve_struct_value("plain", context)
+        .await
+        .unwrap_err();
+    assert_eq!(error.code, EvaluationErrorCode::ParseError);
+}
+
+#[tokio::test]
+async fn reads_flags_stored_under_a_prefix() {
+    let redis = FakeRedis::default();
+    redis.set("flag:enabled", "true");
+    let layout = KeyLayout::Prefix {
+        prefix: "flag:".to_string(),
+    };
+    let provider = RedisProvider::new(options(redis.start().await, layout, None))
+        .await
+        .unwrap();
+    let context = &EvaluationContext::default();
+
+    assert!(
+        provider
+            .resolve_bool_value("enabled", context)
+            .await
+            .unwrap()
+            .value
+    );
+    let error = provider
+        .resolve_bool_value("disabled", context)
+        .await
+        .unwrap_err();
+    assert_eq!(error.code, EvaluationErrorCode::FlagNotFound);
+}
+
+#[tokio::test]
+async fn reads_flags_from_a_json_document() {
+    let redis = FakeRedis::default();
+    redis.set(
+        "flags",
+        r#"{"enabled": true, "limit": 25, "banner": "hello", "theme": {"color": "blue"}}"#,
+    );
+    let layout = KeyLayout::Document {
+        key: "flags".to_string(),
+    };
+    let provider = RedisProvider::new(options(redis.start().await, layout, None))
+        .await
+        .unwrap();
+    let context = &EvaluationContext::default();
+
+    assert!(
+        provider
+            .resolve_bool_value("enabled", context)
+            .await
+            .unwrap()
+            .value
+    );
+    assert_eq!(
+        provider
+            .resolve_int_value("limit", context)
+            .await
+            .unwrap()
+            .value,
+        25
+    );
+    assert_eq!(
+        provider
+            .resolve_string_value("banner", context)
+            .await
+            .unwrap()
+            .value,
+        "hello"
+    );
+    let theme = provider
+        .resolve_struct_value("theme", context)
+        .await
+        .unwrap()
+        .value;
+    assert_eq!(theme.fields["color"], "blue".into());
+    let error = provider
+        .resolve_bool_value("missing", context)
+        .await
+        .unwrap_err();
+    assert_eq!(error.code, EvaluationErrorCode::FlagNotFound);
+}
+
+#[tokio::test]
+async fn rejects_documents_that_are_not_objects() {
+    let redis = FakeRedis::default();
+    redis.set("flags", "[true]");
+    let layout = KeyLayout::Document {
+        key: "flags".to_string(),
+    };
+    let provider = RedisProvider::new(options(redis.start().await, layout, None))
+        .await
+        .unwrap();
+
+    let error = provider
+        .resolve_bool_value("enabled", &EvaluationContext::default())
+        .await
+        .unwrap_err();
+
+    assert!(matches!(error.code, EvaluationErrorCode::General(_)));
+}
+
+#[tokio::test]
+async fn reads_redis_on_every_evaluation_without_a_channel() {
+    let redis = FakeRedis::default();
+    redis.hset("flags", "enabled", "true");
+    let provider = RedisProvider::new(options(redis.start().await, hash(), None))
+        .await
+        .unwrap();
+    let context = &EvaluationContext::default();
+
+    provider
+        .resolve_bool_value("enabled", context)
+        .await
+        .unwrap();
+    redis.hset("flags", "enabled", "false");
+    let details = provider
+        .resolve_bool_value("enabled", context)
+        .await
+        .unwrap();
+
+    assert!(!details.value);
+    assert_eq!(redis.reads(), 2);
+}
+
+#[tokio::test]
+async fn caches_flags_until_invalidated() {
+    let redis = FakeRedis::default();
+    redis.hset("flags", "enabled", "true");
+    redis.hset("flags", "limit", "1");
+    let provider = RedisProvider::new(options(
+        redis.start().await,
+        hash(),
+        Some("openfeature:invalidate"),
+    ))
+    .await
+    .unwrap();
+    let (provider, context, redis) = (&provider, &EvaluationContext::default(), &redis);
+    redis.wait_for_subscriber().await;
+
+    // The cache is used once the subscription has been confirmed.
+    eventually(|| async move {
+        provider
+            .resolve_bool_value("enabled", context)
+            .await
+            .unwrap();
+        let reads = redis.reads();
+        provider
+            .resolve_bool_value("enabled", context)
+            .await
+            .unwrap();
+        redis.reads() == reads
+    })
+    .await;
+    provider.resolve_int_value("limit", context).await.unwrap();
+
+    redis.hset("flags", "enabled", "false");
+    redis.hset("flags", "limit", "2");
+    assert!(
+        provider
+            .resolve_bool_value("enabled", context)
+            .await
+            .unwrap()
+            .value
+    );
+
+    redis.publish("openfeature:invalidate", "enabled");
+    eventually(|| async move {
+        !provider
+            .resolve_bool_value("enabled", context)
+            .await
+            .unwrap()
+            .value
+    })
+    .await;
+    let limit = provider.resolve_int_value("limit", context).await.unwrap();
+    assert_eq!(limit.value, 1);
+
+    redis.publish("openfeature:invalidate", "*");
+    eventually(|| async move {
+        provider
+            .resolve_int_value("limit", context)
+            .await
+            .unwrap()
+            .value
+            == 2
+    })
+    .await;
+}
+
+#[tokio::test]
+async fn rejects_invalid_urls() {
+    let result = RedisProvider::new(options("http://localhost".to_string(), hash(), None)).await;
+
+    assert!(result.is_err());
+}
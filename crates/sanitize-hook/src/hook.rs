@@ -0,0 +1,60 @@
+use async_trait::async_trait;
+use open_feature::{
+    EvaluationContext, EvaluationDetails, EvaluationError, Hook, HookContext, HookHints, Value,
+};
+
+use crate::sanitizer::SanitizeOptions;
+
+/// OpenFeature hook sanitizing the evaluation context in its `before` stage.
+///
+/// Providers and the hooks that run after this one see the sanitized context. Register it as a
+/// global hook, so it runs before client and invocation hooks.
+///
+/// The `open-feature` 0.3 client restores the fields a `before` hook removes, so fields to
+/// remove are redacted instead. Wrap the provider in a
+/// [`SanitizingProvider`](crate::SanitizingProvider) to remove them.
+#[derive(Debug, Clone)]
+pub struct SanitizeHook {
+    options: SanitizeOptions,
+}
+
+impl SanitizeHook {
+    /// Creates the hook.
+    pub fn new(options: SanitizeOptions) -> Self {
+        Self { options }
+    }
+}
+
+#[async_trait]
+impl Hook for SanitizeHook {
+    async fn before<'a>(
+        &self,
+        context: &HookContext<'a>,
+        _: Option<&'a HookHints>,
+    ) -> Result<Option<EvaluationContext>, EvaluationError> {
+        let context = context.evaluation_context;
+        Ok(self
+            .options
+            .applies_to(context)
+            .then(|| self.options.apply(context, false)))
+    }
+
+    async fn after<'a>(
+        &self,
+        _: &HookContext<'a>,
+        _: &EvaluationDetails<Value>,
+        _: Option<&'a HookHints>,
+    ) -> Result<(), EvaluationError> {
+        Ok(())
+    }
+
+    async fn error<'a>(&self, _: &HookContext<'a>, _: &EvaluationError, _: Option<&'a HookHints>) {}
+
+    async fn finally<'a>(
+        &self,
+        _: &HookContext<'a>,
+        _: &EvaluationDetails<Value>,
+        _: Option<&'a HookHints>,
+    ) {
+    }
+}
@@ -0,0 +1,57 @@
+//! Context sanitization hook for OpenFeature.
+//!
+//! Evaluation contexts often carry personal data, such as emails or IP addresses, that flag
+//! backends do not need. [`SanitizeOptions`] describes a privacy policy once, for all flag
+//! evaluations: sensitive fields are removed, redacted or replaced by a salted hash, which keeps
+//! equality targeting and percentage rollouts working without revealing the value.
+//!
+//! - [`SanitizeHook`] applies the policy in the `before` stage, so providers and the hooks that
+//!   run after it, e.g. logging hooks, never see the sensitive values.
+//! - [`SanitizingProvider`] wraps a provider and applies the policy to the contexts it receives.
+//!   Since the `open-feature` 0.3 client restores the fields a `before` hook removes, the hook
+//!   redacts fields to remove, while the provider does remove them.
+//!
+//! # Example
+//!
+//! ```rust
+//! use std::collections::HashMap;
+//!
+//! use open_feature::{EvaluationContext, OpenFeature};
+//! use open_feature_in_memory::InMemoryProvider;
+//! use open_feature_sanitize_hook::{FieldAction, SanitizeHook, SanitizeOptions};
+//!
+//! #[tokio::main]
+//! async fn main() {
+//!     let provider = InMemoryProvider::new();
+//!     provider.set_flag("new-checkout", false, None, None);
+//!     provider.set_targeting("new-checkout", |context| {
+//!         let email = context.custom_fields.get("email")?.as_str()?;
+//!         Some((email == "REDACTED").into())
+//!     });
+//!
+//!     let options = SanitizeOptions {
+//!         fields: HashMap::from([
+//!             ("email".to_string(), FieldAction::Redact),
+//!             ("ip".to_string(), FieldAction::Hash),
+//!         ]),
+//!         salt: "per-deployment-secret".to_string(),
+//!         ..Default::default()
+//!     };
+//!
+//!     let mut api = OpenFeature::singleton_mut().await;
+//!     api.set_provider(provider).await;
+//!     api.add_hook(SanitizeHook::new(options)).await;
+//!     let client = api.create_client();
+//!
+//!     let context = EvaluationContext::default().with_custom_field("email", "jane@example.com");
+//!     assert!(client.get_bool_value("new-checkout", Some(&context), None).await.unwrap());
+//! }
+//! ```
+
+mod hook;
+mod provider;
+mod sanitizer;
+
+pub use crate::hook::SanitizeHook;
+pub use crate::provider::SanitizingProvider;
+pub use crate::sanitizer::{FieldAction, SanitizeOptions, REDACTED};
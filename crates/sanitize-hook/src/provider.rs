@@ -0,0 +1,55 @@
+use async_trait::async_trait;
+use open_feature::provider::{
+    FeatureProvider, ProviderMetadata, ProviderStatus, ResolutionDetails,
+};
+use open_feature::{EvaluationContext, EvaluationResult, HookWrapper};
+use openfeature_contrib_common::{impl_resolve_methods, FlagValue};
+
+use crate::sanitizer::SanitizeOptions;
+
+/// OpenFeature provider sanitizing evaluation contexts before passing them to a wrapped
+/// provider.
+///
+/// The provider reports the metadata, status and hooks of the wrapped provider.
+pub struct SanitizingProvider<P> {
+    provider: P,
+    options: SanitizeOptions,
+}
+
+impl<P: FeatureProvider> SanitizingProvider<P> {
+    /// Wraps `provider`.
+    pub fn new(provider: P, options: SanitizeOptions) -> Self {
+        Self { provider, options }
+    }
+
+    async fn resolve<T: FlagValue>(
+        &self,
+        flag_key: &str,
+        context: &EvaluationContext,
+    ) -> EvaluationResult<ResolutionDetails<T>> {
+        let context = self.options.sanitize(context);
+        T::resolve(&self.provider, flag_key, &context).await
+    }
+}
+
+#[async_trait]
+impl<P: FeatureProvider> FeatureProvider for SanitizingProvider<P> {
+    async fn initialize(&mut self, context: &EvaluationContext) {
+        let context = self.options.sanitize(context);
+        self.provider.initialize(&context).await;
+    }
+
+    fn status(&self) -> ProviderStatus {
+        self.provider.status()
+    }
+
+    fn metadata(&self) -> &ProviderMetadata {
+        self.provider.metadata()
+    }
+
+    fn hooks(&self) -> &[HookWrapper] {
+        self.provider.hooks()
+    }
+
+    impl_resolve_methods!();
+}
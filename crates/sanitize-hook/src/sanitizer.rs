@@ -0,0 +1,111 @@
+use std::collections::HashMap;
+
+use open_feature::{EvaluationContext, EvaluationContextFieldValue};
+use openfeature_contrib_common::{field_to_string, DateTimeFormat};
+use sha2::{Digest, Sha256};
+
+/// Value of redacted fields.
+pub const REDACTED: &str = "REDACTED";
+
+/// What happens to a sensitive context field.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FieldAction {
+    /// The field is removed.
+    Remove,
+    /// The field is replaced by [`REDACTED`].
+    Redact,
+    /// The field is replaced by the hex-encoded SHA-256 hash of the salt and its value, so
+    /// targeting on equality and percentage rollouts keep working. `StructValue` fields are hashed
+    /// as JSON; other struct fields, which have no value to hash, are removed.
+    Hash,
+}
+
+/// Privacy policy applied to evaluation contexts.
+#[derive(Debug, Clone, Default)]
+pub struct SanitizeOptions {
+    /// Actions on custom fields, by field name. Other fields are kept.
+    pub fields: HashMap<String, FieldAction>,
+    /// Action on the targeting key, kept when `None`.
+    pub targeting_key: Option<FieldAction>,
+    /// Salt of hashed values, preventing dictionary attacks on them.
+    pub salt: String,
+}
+
+impl SanitizeOptions {
+    /// Applies the policy to `context`.
+    ///
+    /// ```rust
+    /// use std::collections::HashMap;
+    ///
+    /// use open_feature::EvaluationContext;
+    /// use open_feature_sanitize_hook::{FieldAction, SanitizeOptions};
+    ///
+    /// let options = SanitizeOptions {
+    ///     fields: HashMap::from([("email".to_string(), FieldAction::Remove)]),
+    ///     ..Default::default()
+    /// };
+    /// let context = EvaluationContext::default()
+    ///     .with_custom_field("email", "jane@example.com")
+    ///     .with_custom_field("plan", "premium");
+    ///
+    /// let sanitized = options.sanitize(&context);
+    /// assert!(!sanitized.custom_fields.contains_key("email"));
+    /// assert!(sanitized.custom_fields.contains_key("plan"));
+    /// ```
+    pub fn sanitize(&self, context: &EvaluationContext) -> EvaluationContext {
+        self.apply(context, true)
+    }
+
+    /// Applies the policy to `context`, redacting instead of removing when `remove` is false.
+    pub(crate) fn apply(&self, context: &EvaluationContext, remove: bool) -> EvaluationContext {
+        let mut sanitized = context.clone();
+
+        if let (Some(action), Some(targeting_key)) = (self.targeting_key, &context.targeting_key) {
+            sanitized.targeting_key = match action {
+                FieldAction::Remove if remove => None,
+                FieldAction::Remove | FieldAction::Redact => Some(REDACTED.to_string()),
+                FieldAction::Hash => Some(self.hash(targeting_key)),
+            };
+        }
+
+        for (name, action) in &self.fields {
+            let Some(value) = context.custom_fields.get(name) else {
+                continue;
+            };
+            let hashed = match action {
+                FieldAction::Hash => field_to_string(value, DateTimeFormat::Rfc3339)
+                    .map(|value| EvaluationContextFieldValue::String(self.hash(&value))),
+                FieldAction::Remove | FieldAction::Redact => None,
+            };
+            match hashed {
+                Some(hashed) => {
+                    sanitized.custom_fields.insert(name.clone(), hashed);
+                }
+                None if remove && *action != FieldAction::Redact => {
+                    sanitized.custom_fields.remove(name);
+                }
+                None => {
+                    sanitized.custom_fields.insert(
+                        name.clone(),
+                        EvaluationContextFieldValue::String(REDACTED.to_string()),
+                    );
+                }
+            }
+        }
+
+        sanitized
+    }
+
+    /// Whether the policy changes `context`.
+    pub(crate) fn applies_to(&self, context: &EvaluationContext) -> bool {
+        (self.targeting_key.is_some() && context.targeting_key.is_some())
+            || self
+                .fields
+                .keys()
+                .any(|name| context.custom_fields.contains_key(name))
+    }
+
+    fn hash(&self, value: &str) -> String {
+        format!("{:x}", Sha256::digest(format!("{}{value}", self.salt)))
+    }
+}
@@ -0,0 +1,165 @@
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+use open_feature::provider::FeatureProvider;
+use open_feature::{
+    EvaluationContext, EvaluationContextFieldValue, LoggingHook, OpenFeature, StructValue,
+};
+use open_feature_in_memory::InMemoryProvider;
+use open_feature_sanitize_hook::{
+    FieldAction, SanitizeHook, SanitizeOptions, SanitizingProvider, REDACTED,
+};
+
+/// SHA-256 hashes of values salted with `pepper`.
+const IP_HASH: &str = "e5c27bfc245a82046bafc77e48ae21bb976d4fb91dab7af43d00bc8701239624";
+const ADDRESS_HASH: &str = "7a6c47dc93d44a5b9e8bfd82bbf391df9ea332ff4c213fa1a7fcdbd7b27a8772";
+const USER_HASH: &str = "c911913e9d293d83fcea4b661f8ff2bc40c38f7b4169630dbcecfee80210402b";
+
+/// Value the application keeps in its context without a flag-friendly representation.
+struct Session;
+
+/// Provider of `new-checkout`, remembering the last context it evaluated.
+fn capturing() -> (InMemoryProvider, Arc<Mutex<Option<EvaluationContext>>>) {
+    let provider = InMemoryProvider::new();
+    provider.set_flag("new-checkout", true, None, None);
+    let seen = Arc::new(Mutex::new(None));
+    let captured = seen.clone();
+    provider.set_targeting("new-checkout", move |context| {
+        *captured.lock().unwrap() = Some(context.clone());
+        None
+    });
+    (provider, seen)
+}
+
+fn options(targeting_key: Option<FieldAction>) -> SanitizeOptions {
+    SanitizeOptions {
+        fields: HashMap::from([
+            ("email".to_string(), FieldAction::Remove),
+            ("name".to_string(), FieldAction::Redact),
+            ("ip".to_string(), FieldAction::Hash),
+            ("address".to_string(), FieldAction::Hash),
+            ("session".to_string(), FieldAction::Hash),
+        ]),
+        targeting_key,
+        salt: "pepper".to_string(),
+    }
+}
+
+fn context() -> EvaluationContext {
+    EvaluationContext::default()
+        .with_targeting_key("user-1")
+        .with_custom_field("email", "jane@example.com")
+        .with_custom_field("name", "Jane")
+        .with_custom_field("ip", "10.0.0.1")
+        .with_custom_field(
+            "address",
+            EvaluationContextFieldValue::new_struct(
+                StructValue::default().with_field("city", "Amsterdam"),
+            ),
+        )
+        .with_custom_field("session", EvaluationContextFieldValue::new_struct(Session))
+        .with_custom_field("plan", "premium")
+}
+
+fn field<'a>(context: &'a EvaluationContext, name: &str) -> Option<&'a str> {
+    context.custom_fields.get(name)?.as_str()
+}
+
+async fn evaluated_by_provider(options: SanitizeOptions) -> EvaluationContext {
+    let (flags, seen) = capturing();
+    let provider = SanitizingProvider::new(flags, options);
+    provider
+        .resolve_bool_value("new-checkout", &context())
+        .await
+        .unwrap();
+    let seen = seen.lock().unwrap().take();
+    seen.unwrap()
+}
+
+async fn evaluated_through_hook(domain: &str, options: SanitizeOptions) -> EvaluationContext {
+    let (flags, seen) = capturing();
+    let mut api = OpenFeature::singleton_mut().await;
+    api.set_named_provider(domain, flags).await;
+    let client = api
+        .create_named_client(domain)
+        .with_hook(SanitizeHook::new(options));
+    drop(api);
+
+    assert!(client
+        .get_bool_value("new-checkout", Some(&context()), None)
+        .await
+        .unwrap());
+    let seen = seen.lock().unwrap().take();
+    seen.unwrap()
+}
+
+#[tokio::test]
+async fn providers_remove_redact_and_hash_fields() {
+    let seen = evaluated_by_provider(options(None)).await;
+
+    assert!(!seen.custom_fields.contains_key("email"));
+    assert_eq!(field(&seen, "name"), Some(REDACTED));
+    assert_eq!(field(&seen, "ip"), Some(IP_HASH));
+    assert_eq!(field(&seen, "plan"), Some("premium"));
+    assert_eq!(seen.targeting_key.as_deref(), Some("user-1"));
+}
+
+#[tokio::test]
+async fn struct_values_are_hashed_as_json_and_opaque_structs_removed() {
+    let seen = evaluated_by_provider(options(None)).await;
+    assert_eq!(field(&seen, "address"), Some(ADDRESS_HASH));
+    assert!(!seen.custom_fields.contains_key("session"));
+
+    // Without removing, opaque structs are redacted.
+    let seen = evaluated_through_hook("opaque", options(None)).await;
+    assert_eq!(field(&seen, "address"), Some(ADDRESS_HASH));
+    assert_eq!(field(&seen, "session"), Some(REDACTED));
+}
+
+#[tokio::test]
+async fn providers_apply_the_targeting_key_action() {
+    let removed = evaluated_by_provider(options(Some(FieldAction::Remove))).await;
+    assert_eq!(removed.targeting_key, None);
+    let redacted = evaluated_by_provider(options(Some(FieldAction::Redact))).await;
+    assert_eq!(redacted.targeting_key.as_deref(), Some(REDACTED));
+    let hashed = evaluated_by_provider(options(Some(FieldAction::Hash))).await;
+    assert_eq!(hashed.targeting_key.as_deref(), Some(USER_HASH));
+}
+
+#[tokio::test]
+async fn hooks_apply_the_targeting_key_action() {
+    let removed = evaluated_through_hook("key-removed", options(Some(FieldAction::Remove))).await;
+    assert_eq!(removed.targeting_key.as_deref(), Some(REDACTED));
+    let redacted = evaluated_through_hook("key-redacted", options(Some(FieldAction::Redact))).await;
+    assert_eq!(redacted.targeting_key.as_deref(), Some(REDACTED));
+    let hashed = evaluated_through_hook("key-hashed", options(Some(FieldAction::Hash))).await;
+    assert_eq!(hashed.targeting_key.as_deref(), Some(USER_HASH));
+}
+
+/// The client merges the context returned by `before` hooks with the original one, restoring
+/// the fields they remove.
+#[tokio::test]
+async fn hooks_do_not_let_the_client_restore_removed_fields() {
+    let seen = evaluated_through_hook("merged", options(None)).await;
+
+    assert_eq!(field(&seen, "email"), Some(REDACTED));
+    assert_eq!(field(&seen, "name"), Some(REDACTED));
+    assert_eq!(field(&seen, "ip"), Some(IP_HASH));
+    assert_eq!(field(&seen, "plan"), Some("premium"));
+    assert_eq!(seen.targeting_key.as_deref(), Some("user-1"));
+}
+
+#[test]
+fn sanitizes_contexts_directly() {
+    let sanitized = options(Some(FieldAction::Remove)).sanitize(&context());
+    assert_eq!(sanitized.targeting_key, None);
+    assert!(!sanitized.custom_fields.contains_key("email"));
+    assert_eq!(field(&sanitized, "ip"), Some(IP_HASH));
+}
+
+#[test]
+fn reports_the_hooks_of_the_wrapped_provider() {
+    let flags = InMemoryProvider::new().with_hook(LoggingHook::default());
+    let provider = SanitizingProvider::new(flags, SanitizeOptions::default());
+    assert_eq!(provider.hooks().len(), 1);
+}
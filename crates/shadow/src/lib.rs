@@ -0,0 +1,251 @@
+//! Shadow-comparison provider decorator for OpenFeature.
+//!
+//! [`ShadowProvider`] evaluates flags with a primary provider and returns its results, while
+//! evaluating the same flags with a shadow provider in the background. Results that differ are
+//! logged, counted in [`ShadowProvider::stats`] and announced to the receivers of
+//! [`ShadowProvider::subscribe`]. This de-risks migrations between flag backends: the new backend
+//! runs as the shadow until it agrees with the old one.
+//!
+//! Values are compared, not variants or reasons, since backends name them differently. Two
+//! failed evaluations agree when their error codes are equal. At most
+//! [`ShadowOptions::max_concurrent`] shadow evaluations run at once; comparisons beyond are
+//! dropped rather than queued, so a slow shadow cannot pile up tasks.
+//!
+//! # Example
+//!
+//! ```rust
+//! use open_feature::provider::FeatureProvider;
+//! use open_feature::EvaluationContext;
+//! use open_feature_in_memory::InMemoryProvider;
+//! use open_feature_shadow::{ShadowOptions, ShadowProvider};
+//!
+//! #[tokio::main]
+//! async fn main() {
+//!     let current = InMemoryProvider::new();
+//!     current.set_flag("new-checkout", true, None, None);
+//!     let candidate = InMemoryProvider::new();
+//!     candidate.set_flag("new-checkout", false, None, None);
+//!
+//!     let provider = ShadowProvider::new(current, candidate, ShadowOptions::default());
+//!     let mut mismatches = provider.subscribe();
+//!
+//!     let details = provider
+//!         .resolve_bool_value("new-checkout", &EvaluationContext::default())
+//!         .await
+//!         .unwrap();
+//!     assert!(details.value);
+//!
+//!     let mismatch = mismatches.recv().await.unwrap();
+//!     assert_eq!(mismatch.flag_key, "new-checkout");
+//!     assert_eq!(provider.stats().mismatches, 1);
+//! }
+//! ```
+
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use open_feature::provider::{
+    FeatureProvider, ProviderMetadata, ProviderStatus, ResolutionDetails,
+};
+use open_feature::{EvaluationContext, EvaluationErrorCode, EvaluationResult, HookWrapper, Value};
+use openfeature_contrib_common::{impl_resolve_methods, FlagValue};
+use rand::Rng;
+use tokio::sync::{broadcast, Semaphore};
+use tracing::warn;
+
+/// Configuration of the [`ShadowProvider`].
+#[derive(Debug, Clone)]
+pub struct ShadowOptions {
+    /// Fraction of evaluations also sent to the shadow provider, between 0 and 1.
+    pub sample_rate: f64,
+    /// Maximum number of shadow evaluations running at once. Sampled evaluations beyond are not
+    /// compared.
+    pub max_concurrent: u32,
+}
+
+impl Default for ShadowOptions {
+    fn default() -> Self {
+        Self {
+            sample_rate: 1.0,
+            max_concurrent: 64,
+        }
+    }
+}
+
+/// Result of an evaluation, as compared between the providers.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ShadowOutcome {
+    /// The flag resolved to a value.
+    Value(Value),
+    /// The evaluation failed.
+    Error(EvaluationErrorCode),
+}
+
+impl<T: Clone + Into<Value>> From<&EvaluationResult<ResolutionDetails<T>>> for ShadowOutcome {
+    fn from(result: &EvaluationResult<ResolutionDetails<T>>) -> Self {
+        match result {
+            Ok(details) => Self::Value(details.value.clone().into()),
+            Err(e) => Self::Error(e.code.clone()),
+        }
+    }
+}
+
+/// An evaluation for which the providers disagreed.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Mismatch {
+    /// Key of the evaluated flag.
+    pub flag_key: String,
+    /// Result of the primary provider, returned to the application.
+    pub primary: ShadowOutcome,
+    /// Result of the shadow provider.
+    pub shadow: ShadowOutcome,
+}
+
+/// Counts of the comparisons made so far.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ShadowStats {
+    /// Evaluations compared with the shadow provider.
+    pub comparisons: u64,
+    /// Comparisons for which the providers disagreed.
+    pub mismatches: u64,
+    /// Sampled evaluations not compared because [`ShadowOptions::max_concurrent`] shadow
+    /// evaluations were running.
+    pub dropped: u64,
+}
+
+#[derive(Debug)]
+struct Shared {
+    comparisons: AtomicU64,
+    mismatches: AtomicU64,
+    dropped: AtomicU64,
+    events: broadcast::Sender<Mismatch>,
+}
+
+impl Shared {
+    fn compare(&self, flag_key: String, primary: ShadowOutcome, shadow: ShadowOutcome) {
+        self.comparisons.fetch_add(1, Ordering::Relaxed);
+        if primary == shadow {
+            return;
+        }
+
+        self.mismatches.fetch_add(1, Ordering::Relaxed);
+        warn!("Shadow provider disagrees on {flag_key}: primary {primary:?}, shadow {shadow:?}");
+        let _ = self.events.send(Mismatch {
+            flag_key,
+            primary,
+            shadow,
+        });
+    }
+}
+
+/// OpenFeature provider returning the results of a primary provider while comparing them with a
+/// shadow provider.
+///
+/// The provider reports the metadata, status and hooks of the primary.
+pub struct ShadowProvider<P, S> {
+    primary: P,
+    shadow: Arc<S>,
+    options: ShadowOptions,
+    /// Permits of the running shadow evaluations, which hold the only other references to the
+    /// shadow provider.
+    running: Arc<Semaphore>,
+    shared: Arc<Shared>,
+}
+
+impl<P: FeatureProvider, S: FeatureProvider + 'static> ShadowProvider<P, S> {
+    /// Creates the provider. Shadow evaluations are spawned on the current Tokio runtime.
+    pub fn new(primary: P, shadow: S, options: ShadowOptions) -> Self {
+        Self {
+            primary,
+            shadow: Arc::new(shadow),
+            running: Arc::new(Semaphore::new(options.max_concurrent as usize)),
+            options,
+            shared: Arc::new(Shared {
+                comparisons: AtomicU64::new(0),
+                mismatches: AtomicU64::new(0),
+                dropped: AtomicU64::new(0),
+                events: broadcast::channel(64).0,
+            }),
+        }
+    }
+
+    /// Returns a receiver of the mismatches found from now on.
+    pub fn subscribe(&self) -> broadcast::Receiver<Mismatch> {
+        self.shared.events.subscribe()
+    }
+
+    /// Counts of the comparisons made so far.
+    pub fn stats(&self) -> ShadowStats {
+        ShadowStats {
+            comparisons: self.shared.comparisons.load(Ordering::Relaxed),
+            mismatches: self.shared.mismatches.load(Ordering::Relaxed),
+            dropped: self.shared.dropped.load(Ordering::Relaxed),
+        }
+    }
+
+    async fn resolve<T: FlagValue + Send + 'static>(
+        &self,
+        flag_key: &str,
+        context: &EvaluationContext,
+    ) -> EvaluationResult<ResolutionDetails<T>> {
+        let result = T::resolve(&self.primary, flag_key, context).await;
+        if !self.sampled() {
+            return result;
+        }
+
+        let Ok(permit) = self.running.clone().try_acquire_owned() else {
+            self.shared.dropped.fetch_add(1, Ordering::Relaxed);
+            return result;
+        };
+        let expected = ShadowOutcome::from(&result);
+        let shadow = self.shadow.clone();
+        let shared = self.shared.clone();
+        let flag_key = flag_key.to_string();
+        let context = context.clone();
+        tokio::spawn(async move {
+            let actual = ShadowOutcome::from(&T::resolve(&*shadow, &flag_key, &context).await);
+            // Released after the shadow provider, see `initialize`.
+            drop(shadow);
+            shared.compare(flag_key, expected, actual);
+            drop(permit);
+        });
+        result
+    }
+
+    fn sampled(&self) -> bool {
+        let rate = self.options.sample_rate;
+        rate >= 1.0 || (rate > 0.0 && rand::thread_rng().gen_bool(rate))
+    }
+}
+
+#[async_trait]
+impl<P: FeatureProvider, S: FeatureProvider + 'static> FeatureProvider for ShadowProvider<P, S> {
+    async fn initialize(&mut self, context: &EvaluationContext) {
+        self.primary.initialize(context).await;
+        // Once every permit is taken, no shadow evaluation holds the shadow provider anymore.
+        let _permits = self
+            .running
+            .acquire_many(self.options.max_concurrent)
+            .await
+            .expect("the semaphore is never closed");
+        Arc::get_mut(&mut self.shadow)
+            .expect("only shadow evaluations share the shadow provider")
+            .initialize(context)
+            .await;
+    }
+
+    fn status(&self) -> ProviderStatus {
+        self.primary.status()
+    }
+
+    fn metadata(&self) -> &ProviderMetadata {
+        self.primary.metadata()
+    }
+
+    fn hooks(&self) -> &[HookWrapper] {
+        self.primary.hooks()
+    }
+
+    impl_resolve_methods!();
+}
@@ -0,0 +1,192 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use async_trait::async_trait;
+use open_feature::provider::{FeatureProvider, ProviderMetadata, ResolutionDetails};
+use open_feature::{EvaluationContext, EvaluationErrorCode, EvaluationResult, LoggingHook};
+use open_feature_in_memory::InMemoryProvider;
+use open_feature_shadow::{Mismatch, ShadowOptions, ShadowOutcome, ShadowProvider, ShadowStats};
+use openfeature_contrib_common::{impl_resolve_methods, FlagValue};
+
+/// Provider answering a second after reading its flags, recording its initialization.
+struct Slow {
+    flags: InMemoryProvider,
+    initialized: Arc<AtomicBool>,
+}
+
+impl Slow {
+    async fn resolve<T: FlagValue>(
+        &self,
+        flag_key: &str,
+        context: &EvaluationContext,
+    ) -> EvaluationResult<ResolutionDetails<T>> {
+        let details = T::resolve(&self.flags, flag_key, context).await;
+        tokio::time::sleep(Duration::from_secs(1)).await;
+        details
+    }
+}
+
+#[async_trait]
+impl FeatureProvider for Slow {
+    async fn initialize(&mut self, _context: &EvaluationContext) {
+        self.initialized.store(true, Ordering::Relaxed);
+    }
+
+    fn metadata(&self) -> &ProviderMetadata {
+        self.flags.metadata()
+    }
+
+    impl_resolve_methods!();
+}
+
+fn flags(enabled: bool) -> InMemoryProvider {
+    let flags = InMemoryProvider::new();
+    flags.set_flag("enabled", enabled, None, None);
+    flags.set_flag("limit", 25, None, None);
+    flags
+}
+
+fn slow(flags: InMemoryProvider) -> (Slow, Arc<AtomicBool>) {
+    let initialized = Arc::new(AtomicBool::new(false));
+    let provider = Slow {
+        flags,
+        initialized: initialized.clone(),
+    };
+    (provider, initialized)
+}
+
+fn options(sample_rate: f64, max_concurrent: u32) -> ShadowOptions {
+    ShadowOptions {
+        sample_rate,
+        max_concurrent,
+    }
+}
+
+async fn evaluate<P: FeatureProvider>(provider: &P, times: usize) {
+    for _ in 0..times {
+        provider
+            .resolve_bool_value("enabled", &EvaluationContext::default())
+            .await
+            .unwrap();
+    }
+}
+
+/// Lets the spawned shadow evaluations finish.
+async fn settle() {
+    tokio::time::sleep(Duration::from_secs(5)).await;
+}
+
+#[tokio::test(start_paused = true)]
+async fn reports_mismatches_and_returns_the_primary_results() {
+    let provider = ShadowProvider::new(flags(true), flags(false), options(1.0, 64));
+    let mut mismatches = provider.subscribe();
+    let context = EvaluationContext::default();
+
+    let details = provider
+        .resolve_bool_value("enabled", &context)
+        .await
+        .unwrap();
+    assert!(details.value);
+    provider.resolve_int_value("limit", &context).await.unwrap();
+    settle().await;
+
+    assert_eq!(
+        mismatches.try_recv().unwrap(),
+        Mismatch {
+            flag_key: "enabled".to_string(),
+            primary: ShadowOutcome::Value(true.into()),
+            shadow: ShadowOutcome::Value(false.into()),
+        }
+    );
+    assert!(mismatches.try_recv().is_err());
+    assert_eq!(
+        provider.stats(),
+        ShadowStats {
+            comparisons: 2,
+            mismatches: 1,
+            dropped: 0
+        }
+    );
+}
+
+#[tokio::test(start_paused = true)]
+async fn compares_errors_by_code() {
+    let shadow = flags(true);
+    shadow.set_flag("limit", "n/a", None, None);
+    let provider = ShadowProvider::new(flags(true), shadow, options(1.0, 64));
+    let mut mismatches = provider.subscribe();
+    let context = EvaluationContext::default();
+
+    assert!(provider
+        .resolve_int_value("missing", &context)
+        .await
+        .is_err());
+    provider.resolve_int_value("limit", &context).await.unwrap();
+    settle().await;
+
+    let mismatch = mismatches.try_recv().unwrap();
+    assert_eq!(mismatch.flag_key, "limit");
+    assert_eq!(
+        mismatch.shadow,
+        ShadowOutcome::Error(EvaluationErrorCode::TypeMismatch)
+    );
+    assert_eq!(provider.stats().comparisons, 2);
+    assert_eq!(provider.stats().mismatches, 1);
+}
+
+#[tokio::test(start_paused = true)]
+async fn samples_evaluations_at_the_rate() {
+    let never = ShadowProvider::new(flags(true), flags(false), options(0.0, 64));
+    evaluate(&never, 100).await;
+    settle().await;
+    assert_eq!(never.stats(), ShadowStats::default());
+
+    let half = ShadowProvider::new(flags(true), flags(false), options(0.5, 1_000));
+    evaluate(&half, 1_000).await;
+    settle().await;
+    let stats = half.stats();
+    assert!((400..600).contains(&stats.comparisons), "{stats:?}");
+    assert_eq!(stats.mismatches, stats.comparisons);
+}
+
+#[tokio::test(start_paused = true)]
+async fn drops_comparisons_beyond_the_concurrency_limit() {
+    let (shadow, _) = slow(flags(true));
+    let provider = ShadowProvider::new(flags(true), shadow, options(1.0, 2));
+
+    evaluate(&provider, 5).await;
+    assert_eq!(provider.stats().dropped, 3);
+    settle().await;
+    evaluate(&provider, 1).await;
+    settle().await;
+    assert_eq!(
+        provider.stats(),
+        ShadowStats {
+            comparisons: 3,
+            mismatches: 0,
+            dropped: 3
+        }
+    );
+}
+
+#[tokio::test(start_paused = true)]
+async fn initializes_the_shadow_after_its_running_evaluations() {
+    let (shadow, initialized) = slow(flags(true));
+    let mut provider = ShadowProvider::new(flags(true), shadow, options(1.0, 4));
+    evaluate(&provider, 2).await;
+
+    provider.initialize(&EvaluationContext::default()).await;
+    assert!(initialized.load(Ordering::Relaxed));
+    assert_eq!(provider.stats().comparisons, 2);
+}
+
+#[test]
+fn reports_the_hooks_of_the_primary() {
+    let provider = ShadowProvider::new(
+        InMemoryProvider::new().with_hook(LoggingHook::default()),
+        InMemoryProvider::new().with_hook(LoggingHook::default()),
+        ShadowOptions::default(),
+    );
+    assert_eq!(provider.hooks().len(), 1);
+}
@@ -0,0 +1,79 @@
+use open_feature::{EvaluationContext, EvaluationContextFieldValue};
+use openfeature_contrib_common::{field_to_json, DateTimeFormat};
+use serde_json::{Map, Value};
+
+/// Custom field used as the Split bucketing key.
+pub(crate) const BUCKETING_KEY: &str = "bucketingKey";
+
+/// Converts the custom fields of an [`EvaluationContext`] into Split attributes.
+///
+/// Split compares `DATETIME` attributes as milliseconds since the Unix epoch, so date-times are
+/// converted accordingly. Struct fields have no Split attribute type and are skipped, as is the
+/// reserved bucketing key field.
+pub(crate) fn to_attributes(context: &EvaluationContext) -> Map<String, Value> {
+    context
+        .custom_fields
+        .iter()
+        .filter(|(key, value)| key.as_str() != BUCKETING_KEY && !value.is_struct())
+        .filter_map(|(key, value)| {
+            Some((
+                key.clone(),
+                field_to_json(value, DateTimeFormat::UnixMillis)?,
+            ))
+        })
+        .collect()
+}
+
+/// Reads the optional bucketing key from the evaluation context.
+pub(crate) fn bucketing_key(context: &EvaluationContext) -> Option<&str> {
+    context
+        .custom_fields
+        .get(BUCKETING_KEY)
+        .and_then(EvaluationContextFieldValue::as_str)
+}
+
+#[cfg(test)]
+mod tests {
+    use open_feature::StructValue;
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn custom_fields_become_attributes() {
+        let context = EvaluationContext::default()
+            .with_targeting_key("user-1")
+            .with_custom_field(BUCKETING_KEY, "account-1")
+            .with_custom_field("plan", "premium")
+            .with_custom_field("seats", 5)
+            .with_custom_field("beta", true)
+            .with_custom_field(
+                "signup",
+                time::OffsetDateTime::from_unix_timestamp(1_700_000_000).unwrap(),
+            )
+            .with_custom_field(
+                "address",
+                EvaluationContextFieldValue::new_struct(
+                    StructValue::default().with_field("city", "Paris"),
+                ),
+            );
+
+        assert_eq!(
+            Value::Object(to_attributes(&context)),
+            json!({
+                "plan": "premium",
+                "seats": 5,
+                "beta": true,
+                "signup": 1_700_000_000_000_i64
+            })
+        );
+        assert_eq!(bucketing_key(&context), Some("account-1"));
+    }
+
+    #[test]
+    fn non_string_bucketing_keys_are_ignored() {
+        let context = EvaluationContext::default().with_custom_field(BUCKETING_KEY, 5);
+
+        assert_eq!(bucketing_key(&context), None);
+    }
+}
@@ -0,0 +1,13 @@
+use thiserror::Error;
+
+/// Errors raised while creating a [`SplitProvider`](crate::SplitProvider).
+#[derive(Debug, Error)]
+pub enum SplitError {
+    /// The configured auth token cannot be used as a header value.
+    #[error("invalid Split Evaluator auth token")]
+    InvalidAuthToken,
+
+    /// The HTTP client could not be built.
+    #[error("failed to build HTTP client: {0}")]
+    Http(#[from] reqwest::Error),
+}
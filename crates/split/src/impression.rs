@@ -0,0 +1,40 @@
+use std::time::SystemTime;
+
+use serde_json::{Map, Value};
+
+/// A single treatment assignment, handed to the configured [`ImpressionListener`].
+#[derive(Debug, Clone)]
+pub struct Impression {
+    /// The matching key (the OpenFeature targeting key).
+    pub key: String,
+    /// The bucketing key, if one was provided.
+    pub bucketing_key: Option<String>,
+    /// The name of the evaluated feature flag.
+    pub feature: String,
+    /// The treatment returned by Split.
+    pub treatment: String,
+    /// The raw treatment configuration, if any.
+    pub config: Option<String>,
+    /// The attributes sent along with the evaluation.
+    pub attributes: Map<String, Value>,
+    /// When the evaluation happened.
+    pub time: SystemTime,
+}
+
+/// Receives an [`Impression`] for every successful evaluation.
+///
+/// Listeners are invoked inline on the evaluation path, so implementations should hand the
+/// impression off (e.g. to a channel) rather than perform blocking I/O.
+pub trait ImpressionListener: Send + Sync {
+    /// Called after Split returned a treatment.
+    fn log_impression(&self, impression: &Impression);
+}
+
+impl<F> ImpressionListener for F
+where
+    F: Fn(&Impression) + Send + Sync,
+{
+    fn log_impression(&self, impression: &Impression) {
+        self(impression)
+    }
+}
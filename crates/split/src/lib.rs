@@ -0,0 +1,337 @@
+//! [Split](https://www.split.io/) (Harness Feature Management & Experimentation) provider for
+//! OpenFeature.
+//!
+//! Split does not ship a Rust SDK, so the provider evaluates flags through the
+//! [Split Evaluator](https://help.split.io/hc/en-us/articles/360020037072-Split-Evaluator), the
+//! microservice Split provides for languages without a native SDK. The evaluator keeps the
+//! Split definitions in sync and reports impressions to Split; this provider maps OpenFeature
+//! evaluations onto its `get-treatment-with-config` endpoint.
+//!
+//! # Value mapping
+//!
+//! * the targeting key is the Split matching key and is required;
+//! * a `bucketingKey` custom field is sent as the bucketing key;
+//! * all other custom fields are sent as attributes (date-times as epoch milliseconds);
+//! * the treatment is reported as the variant. Boolean flags accept `on`/`off` and
+//!   `true`/`false` treatments, numeric flags parse the treatment, and struct flags resolve to
+//!   the treatment's JSON configuration.
+//!
+//! # Example
+//!
+//! ```rust,no_run
+//! use open_feature::provider::FeatureProvider;
+//! use open_feature::EvaluationContext;
+//! use open_feature_split::{SplitOptions, SplitProvider};
+//!
+//! #[tokio::main]
+//! async fn main() {
+//!     let provider = SplitProvider::new(SplitOptions {
+//!         evaluator_url: "http://split-evaluator:7548".to_string(),
+//!         auth_token: Some("secret".to_string()),
+//!         ..Default::default()
+//!     })
+//!     .expect("Failed to create Split provider");
+//!
+//!     let context = EvaluationContext::default().with_targeting_key("user-123");
+//!     let details = provider.resolve_string_value("checkout", &context).await;
+//!     println!("{details:?}");
+//! }
+//! ```
+
+mod context;
+mod error;
+mod impression;
+
+use std::sync::Arc;
+use std::time::{Duration, SystemTime};
+
+use async_trait::async_trait;
+use open_feature::provider::{FeatureProvider, ProviderMetadata, ResolutionDetails};
+use open_feature::{
+    EvaluationContext, EvaluationError, EvaluationErrorCode, EvaluationReason, EvaluationResult,
+    FlagMetadata, StructValue, Value,
+};
+use openfeature_contrib_common::http_error_code;
+use reqwest::header::{HeaderMap, HeaderValue, AUTHORIZATION};
+use reqwest::Client;
+use serde::Deserialize;
+use tracing::debug;
+
+use crate::context::{bucketing_key, to_attributes};
+pub use crate::error::SplitError;
+pub use crate::impression::{Impression, ImpressionListener};
+
+/// Treatment Split returns when a flag does not exist or cannot be evaluated.
+const CONTROL_TREATMENT: &str = "control";
+
+/// Configuration of the [`SplitProvider`].
+#[derive(Clone)]
+pub struct SplitOptions {
+    /// Base URL of the Split Evaluator.
+    pub evaluator_url: String,
+    /// Value of the evaluator's `SPLIT_EVALUATOR_AUTH_TOKEN`, sent in the `Authorization` header.
+    pub auth_token: Option<String>,
+    /// Timeout applied to every evaluation request.
+    pub request_timeout: Duration,
+    /// Listener notified of every treatment assignment.
+    pub impression_listener: Option<Arc<dyn ImpressionListener>>,
+}
+
+impl Default for SplitOptions {
+    fn default() -> Self {
+        Self {
+            evaluator_url: "http://localhost:7548".to_string(),
+            auth_token: None,
+            request_timeout: Duration::from_secs(5),
+            impression_listener: None,
+        }
+    }
+}
+
+/// A treatment returned by the Split Evaluator.
+#[derive(Debug, Deserialize)]
+struct Treatment {
+    treatment: String,
+    #[serde(default)]
+    config: Option<String>,
+}
+
+/// OpenFeature provider backed by the Split Evaluator.
+pub struct SplitProvider {
+    metadata: ProviderMetadata,
+    client: Client,
+    url: String,
+    impression_listener: Option<Arc<dyn ImpressionListener>>,
+}
+
+impl SplitProvider {
+    /// Creates the provider. No request is made until the first evaluation.
+    pub fn new(options: SplitOptions) -> Result<Self, SplitError> {
+        let mut headers = HeaderMap::new();
+        if let Some(token) = &options.auth_token {
+            let value = HeaderValue::from_str(token).map_err(|_| SplitError::InvalidAuthToken)?;
+            headers.insert(AUTHORIZATION, value);
+        }
+
+        let client = Client::builder()
+            .default_headers(headers)
+            .timeout(options.request_timeout)
+            .build()?;
+
+        Ok(Self {
+            metadata: ProviderMetadata::new("split"),
+            client,
+            url: options.evaluator_url.trim_end_matches('/').to_string(),
+            impression_listener: options.impression_listener,
+        })
+    }
+
+    async fn get_treatment(
+        &self,
+        flag_key: &str,
+        context: &EvaluationContext,
+    ) -> EvaluationResult<Treatment> {
+        let key = context.targeting_key.as_deref().ok_or_else(|| {
+            EvaluationError::builder()
+                .code(EvaluationErrorCode::TargetingKeyMissing)
+                .message("Split requires a targeting key")
+                .build()
+        })?;
+        let bucketing_key = bucketing_key(context);
+        let attributes = to_attributes(context);
+
+        let mut query = vec![
+            ("key", key.to_string()),
+            ("split-name", flag_key.to_string()),
+        ];
+        if let Some(bucketing_key) = bucketing_key {
+            query.push(("bucketing-key", bucketing_key.to_string()));
+        }
+        if !attributes.is_empty() {
+            query.push((
+                "attributes",
+                serde_json::Value::Object(attributes.clone()).to_string(),
+            ));
+        }
+
+        let response = self
+            .client
+            .get(format!("{}/client/get-treatment-with-config", self.url))
+            .query(&query)
+            .send()
+            .await
+            .map_err(|e| general_error(format!("Split Evaluator request failed: {e}")))?;
+        let status = response.status();
+        if !status.is_success() {
+            return Err(EvaluationError::builder()
+                .code(http_error_code(status.as_u16()))
+                .message(format!(
+                    "Split Evaluator request for {flag_key} failed with status {status}"
+                ))
+                .build());
+        }
+        let response = response.json::<Treatment>().await.map_err(|e| {
+            EvaluationError::builder()
+                .code(EvaluationErrorCode::ParseError)
+                .message(format!("Invalid Split Evaluator response: {e}"))
+                .build()
+        })?;
+        debug!("Split treatment for {flag_key}: {}", response.treatment);
+
+        if response.treatment == CONTROL_TREATMENT {
+            return Err(EvaluationError::builder()
+                .code(EvaluationErrorCode::FlagNotFound)
+                .message(format!(
+                    "Split returned the control treatment for {flag_key}"
+                ))
+                .build());
+        }
+
+        if let Some(listener) = &self.impression_listener {
+            listener.log_impression(&Impression {
+                key: key.to_string(),
+                bucketing_key: bucketing_key.map(str::to_string),
+                feature: flag_key.to_string(),
+                treatment: response.treatment.clone(),
+                config: response.config.clone(),
+                attributes,
+                time: SystemTime::now(),
+            });
+        }
+
+        Ok(response)
+    }
+
+    async fn resolve<T>(
+        &self,
+        flag_key: &str,
+        context: &EvaluationContext,
+        convert: impl FnOnce(&Treatment) -> EvaluationResult<T>,
+    ) -> EvaluationResult<ResolutionDetails<T>> {
+        let treatment = self.get_treatment(flag_key, context).await?;
+        let value = convert(&treatment)?;
+
+        let mut flag_metadata = FlagMetadata::default();
+        if let Some(config) = treatment.config {
+            flag_metadata.add_value("config", config);
+        }
+
+        Ok(ResolutionDetails {
+            value,
+            variant: Some(treatment.treatment),
+            reason: Some(EvaluationReason::TargetingMatch),
+            flag_metadata: Some(flag_metadata),
+        })
+    }
+}
+
+#[async_trait]
+impl FeatureProvider for SplitProvider {
+    fn metadata(&self) -> &ProviderMetadata {
+        &self.metadata
+    }
+
+    async fn resolve_bool_value(
+        &self,
+        flag_key: &str,
+        context: &EvaluationContext,
+    ) -> EvaluationResult<ResolutionDetails<bool>> {
+        self.resolve(flag_key, context, |treatment| {
+            match treatment.treatment.to_ascii_lowercase().as_str() {
+                "on" | "true" => Ok(true),
+                "off" | "false" => Ok(false),
+                other => Err(type_mismatch(format!(
+                    "Treatment {other} of {flag_key} is not a boolean"
+                ))),
+            }
+        })
+        .await
+    }
+
+    async fn resolve_int_value(
+        &self,
+        flag_key: &str,
+        context: &EvaluationContext,
+    ) -> EvaluationResult<ResolutionDetails<i64>> {
+        self.resolve(flag_key, context, |treatment| {
+            treatment.treatment.parse().map_err(|_| {
+                type_mismatch(format!(
+                    "Treatment {} of {flag_key} is not an integer",
+                    treatment.treatment
+                ))
+            })
+        })
+        .await
+    }
+
+    async fn resolve_float_value(
+        &self,
+        flag_key: &str,
+        context: &EvaluationContext,
+    ) -> EvaluationResult<ResolutionDetails<f64>> {
+        self.resolve(flag_key, context, |treatment| {
+            treatment.treatment.parse().map_err(|_| {
+                type_mismatch(format!(
+                    "Treatment {} of {flag_key} is not a float",
+                    treatment.treatment
+                ))
+            })
+        })
+        .await
+    }
+
+    async fn resolve_string_value(
+        &self,
+        flag_key: &str,
+        context: &EvaluationContext,
+    ) -> EvaluationResult<ResolutionDetails<String>> {
+        self.resolve(flag_key, context, |treatment| {
+            Ok(treatment.treatment.clone())
+        })
+        .await
+    }
+
+    async fn resolve_struct_value(
+        &self,
+        flag_key: &str,
+        context: &EvaluationContext,
+    ) -> EvaluationResult<ResolutionDetails<StructValue>> {
+        self.resolve(flag_key, context, |treatment| {
+            let config = treatment.config.as_deref().ok_or_else(|| {
+                type_mismatch(format!(
+                    "Treatment {} of {flag_key} has no configuration",
+                    treatment.treatment
+                ))
+            })?;
+            let json: serde_json::Value = serde_json::from_str(config).map_err(|e| {
+                EvaluationError::builder()
+                    .code(EvaluationErrorCode::ParseError)
+                    .message(format!(
+                        "Configuration of {flag_key} is not valid JSON: {e}"
+                    ))
+                    .build()
+            })?;
+            match Value::try_from(json)? {
+                Value::Struct(value) => Ok(value),
+                _ => Err(type_mismatch(format!(
+                    "Configuration of {flag_key} is not a JSON object"
+                ))),
+            }
+        })
+        .await
+    }
+}
+
+fn general_error(message: String) -> EvaluationError {
+    EvaluationError::builder()
+        .code(EvaluationErrorCode::General(message.clone()))
+        .message(message)
+        .build()
+}
+
+fn type_mismatch(message: String) -> EvaluationError {
+    EvaluationError::builder()
+        .code(EvaluationErrorCode::TypeMismatch)
+        .message(message)
+        .build()
+}
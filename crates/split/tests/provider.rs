@@ -0,0 +1,223 @@
+use std::sync::{Arc, Mutex};
+
+use open_feature::provider::FeatureProvider;
+use open_feature::{EvaluationContext, EvaluationErrorCode, EvaluationReason, StructValue};
+use open_feature_split::{Impression, SplitOptions, SplitProvider};
+use serde_json::json;
+use wiremock::matchers::{header, method, path, query_param};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+const TREATMENT_PATH: &str = "/client/get-treatment-with-config";
+
+async fn evaluator(split_name: &str, response: ResponseTemplate) -> MockServer {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path(TREATMENT_PATH))
+        .and(query_param("key", "user-1"))
+        .and(query_param("split-name", split_name))
+        .and(header("authorization", "secret"))
+        .respond_with(response)
+        .mount(&server)
+        .await;
+    server
+}
+
+fn treatment(treatment: &str, config: Option<&str>) -> ResponseTemplate {
+    ResponseTemplate::new(200).set_body_json(json!({
+        "splitName": "checkout",
+        "treatment": treatment,
+        "config": config
+    }))
+}
+
+fn provider(server: &MockServer) -> SplitProvider {
+    SplitProvider::new(SplitOptions {
+        evaluator_url: server.uri(),
+        auth_token: Some("secret".to_string()),
+        ..Default::default()
+    })
+    .unwrap()
+}
+
+fn user() -> EvaluationContext {
+    EvaluationContext::default().with_targeting_key("user-1")
+}
+
+#[tokio::test]
+async fn treatments_resolve_typed_flags() {
+    let server = MockServer::start().await;
+    for (split_name, value, config) in [
+        ("on", "on", Some("{\"color\": \"blue\"}")),
+        ("false", "false", None),
+        ("int", "25", None),
+        ("float", "0.5", None),
+    ] {
+        Mock::given(method("GET"))
+            .and(path(TREATMENT_PATH))
+            .and(query_param("split-name", split_name))
+            .respond_with(treatment(value, config))
+            .mount(&server)
+            .await;
+    }
+    let provider = SplitProvider::new(SplitOptions {
+        evaluator_url: server.uri(),
+        ..Default::default()
+    })
+    .unwrap();
+
+    let details = provider.resolve_bool_value("on", &user()).await.unwrap();
+    assert!(details.value);
+    assert_eq!(details.variant.as_deref(), Some("on"));
+    assert_eq!(details.reason, Some(EvaluationReason::TargetingMatch));
+    assert!(
+        !provider
+            .resolve_bool_value("false", &user())
+            .await
+            .unwrap()
+            .value
+    );
+    assert_eq!(
+        provider
+            .resolve_int_value("int", &user())
+            .await
+            .unwrap()
+            .value,
+        25
+    );
+    assert_eq!(
+        provider
+            .resolve_float_value("float", &user())
+            .await
+            .unwrap()
+            .value,
+        0.5
+    );
+    let details = provider.resolve_struct_value("on", &user()).await.unwrap();
+    assert_eq!(
+        details.value,
+        StructValue::default().with_field("color", "blue")
+    );
+    assert_eq!(details.variant.as_deref(), Some("on"));
+}
+
+#[tokio::test]
+async fn context_fields_are_sent_as_key_and_attributes() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path(TREATMENT_PATH))
+        .and(query_param("key", "user-1"))
+        .and(query_param("bucketing-key", "account-1"))
+        .and(query_param("attributes", "{\"plan\":\"premium\"}"))
+        .respond_with(treatment("on", None))
+        .expect(1)
+        .mount(&server)
+        .await;
+    let provider = SplitProvider::new(SplitOptions {
+        evaluator_url: server.uri(),
+        ..Default::default()
+    })
+    .unwrap();
+    let context = user()
+        .with_custom_field("bucketingKey", "account-1")
+        .with_custom_field("plan", "premium");
+
+    assert!(
+        provider
+            .resolve_bool_value("checkout", &context)
+            .await
+            .unwrap()
+            .value
+    );
+}
+
+#[tokio::test]
+async fn impressions_are_reported_to_the_listener() {
+    let server = evaluator("checkout", treatment("on", Some("{}"))).await;
+    let impressions = Arc::new(Mutex::new(Vec::<Impression>::new()));
+    let listener = impressions.clone();
+    let provider = SplitProvider::new(SplitOptions {
+        evaluator_url: server.uri(),
+        auth_token: Some("secret".to_string()),
+        impression_listener: Some(Arc::new(move |impression: &Impression| {
+            listener.lock().unwrap().push(impression.clone())
+        })),
+        ..Default::default()
+    })
+    .unwrap();
+
+    provider
+        .resolve_bool_value("checkout", &user())
+        .await
+        .unwrap();
+
+    let impressions = impressions.lock().unwrap();
+    assert_eq!(impressions.len(), 1);
+    assert_eq!(impressions[0].key, "user-1");
+    assert_eq!(impressions[0].feature, "checkout");
+    assert_eq!(impressions[0].treatment, "on");
+    assert_eq!(impressions[0].config.as_deref(), Some("{}"));
+}
+
+#[tokio::test]
+async fn control_treatments_are_not_found() {
+    let server = evaluator("checkout", treatment("control", None)).await;
+
+    let error = provider(&server)
+        .resolve_string_value("checkout", &user())
+        .await
+        .unwrap_err();
+    assert_eq!(error.code, EvaluationErrorCode::FlagNotFound);
+}
+
+#[tokio::test]
+async fn mismatched_treatments_fail() {
+    let server = evaluator("checkout", treatment("v2", Some("[1]"))).await;
+    let provider = provider(&server);
+
+    let error = provider
+        .resolve_bool_value("checkout", &user())
+        .await
+        .unwrap_err();
+    assert_eq!(error.code, EvaluationErrorCode::TypeMismatch);
+    let error = provider
+        .resolve_int_value("checkout", &user())
+        .await
+        .unwrap_err();
+    assert_eq!(error.code, EvaluationErrorCode::TypeMismatch);
+    let error = provider
+        .resolve_struct_value("checkout", &user())
+        .await
+        .unwrap_err();
+    assert_eq!(error.code, EvaluationErrorCode::TypeMismatch);
+}
+
+#[tokio::test]
+async fn a_targeting_key_is_required() {
+    let server = evaluator("checkout", treatment("on", None)).await;
+
+    let error = provider(&server)
+        .resolve_bool_value("checkout", &EvaluationContext::default())
+        .await
+        .unwrap_err();
+    assert_eq!(error.code, EvaluationErrorCode::TargetingKeyMissing);
+}
+
+#[tokio::test]
+async fn evaluator_errors_map_to_error_codes() {
+    let server = evaluator("checkout", ResponseTemplate::new(401)).await;
+    let error = provider(&server)
+        .resolve_bool_value("checkout", &user())
+        .await
+        .unwrap_err();
+    assert_eq!(
+        error.code,
+        EvaluationErrorCode::General("Unauthorized".to_string())
+    );
+
+    let server = evaluator("checkout", ResponseTemplate::new(200).set_body_string("{")).await;
+    let error = provider(&server)
+        .resolve_bool_value("checkout", &user())
+        .await
+        .unwrap_err();
+    assert_eq!(error.code, EvaluationErrorCode::ParseError);
+}
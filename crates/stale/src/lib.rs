@@ -0,0 +1,238 @@
+//! Stale-value fallback provider decorator for OpenFeature.
+//!
+//! [`StaleProvider`] remembers the last value the wrapped provider resolved for every flag and
+//! context key. When the wrapped provider later fails, e.g. because its backend is down, the
+//! evaluation is answered with that value and the `STALE` reason instead of the application's
+//! default. Any provider gains crash resilience without backend-specific work.
+//!
+//! Hooks of the `open-feature` 0.3 client cannot replace the result of a failed evaluation,
+//! which is why the fallback is a decorator rather than a hook.
+//!
+//! # Example
+//!
+//! ```rust
+//! use open_feature::provider::FeatureProvider;
+//! use open_feature::{EvaluationContext, EvaluationReason};
+//! use open_feature_in_memory::InMemoryProvider;
+//! use open_feature_stale::{StaleOptions, StaleProvider};
+//!
+//! #[tokio::main]
+//! async fn main() {
+//!     let flags = InMemoryProvider::new();
+//!     flags.set_flag("max-items", 25, None, None);
+//!
+//!     let provider = StaleProvider::new(flags.clone(), StaleOptions::default());
+//!     let context = EvaluationContext::default().with_targeting_key("user-1");
+//!     provider.resolve_int_value("max-items", &context).await.unwrap();
+//!
+//!     // The backend starts serving a malformed value.
+//!     flags.set_flag("max-items", "n/a", None, None);
+//!     let details = provider.resolve_int_value("max-items", &context).await.unwrap();
+//!     assert_eq!(details.value, 25);
+//!     assert_eq!(details.reason, Some(EvaluationReason::Other("STALE".to_string())));
+//!     assert_eq!(provider.stats().stale, 1);
+//! }
+//! ```
+
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Mutex, MutexGuard, PoisonError};
+use std::time::Duration;
+
+use async_trait::async_trait;
+use open_feature::provider::{
+    FeatureProvider, ProviderMetadata, ProviderStatus, ResolutionDetails,
+};
+use open_feature::{
+    EvaluationContext, EvaluationErrorCode, EvaluationReason, EvaluationResult, HookWrapper,
+};
+use openfeature_contrib_common::{
+    canonical_context, impl_resolve_methods, BoundedMap, FlagValue, ResolutionKey, StoredResolution,
+};
+use tokio::time::Instant;
+use tracing::warn;
+
+/// Reason of evaluations answered with a remembered value.
+pub const STALE: &str = "STALE";
+
+/// Part of the evaluation context that values are remembered for.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ContextKey {
+    /// The targeting key, so a failed evaluation gets the value last resolved for the same
+    /// subject, whatever its other fields.
+    #[default]
+    TargetingKey,
+    /// The targeting key and all custom fields.
+    Context,
+}
+
+/// Configuration of the [`StaleProvider`].
+#[derive(Debug, Clone)]
+pub struct StaleOptions {
+    /// Part of the evaluation context that values are remembered for.
+    pub context_key: ContextKey,
+    /// Age beyond which remembered values are no longer served, unlimited when `None`.
+    pub max_age: Option<Duration>,
+    /// Maximum number of values remembered. Once reached, the value remembered first is dropped
+    /// for a new one.
+    pub max_entries: usize,
+}
+
+impl Default for StaleOptions {
+    fn default() -> Self {
+        Self {
+            context_key: ContextKey::TargetingKey,
+            max_age: None,
+            max_entries: 10_000,
+        }
+    }
+}
+
+/// Counts of the evaluations made so far.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct StaleStats {
+    /// Evaluations resolved by the wrapped provider.
+    pub fresh: u64,
+    /// Failed evaluations answered with a remembered value.
+    pub stale: u64,
+    /// Failed evaluations without a remembered value to answer with.
+    pub failed: u64,
+}
+
+/// Remembered resolutions are keyed by the targeting key or the canonical context, depending on
+/// the [`ContextKey`].
+type Key = ResolutionKey<Option<String>>;
+
+#[derive(Debug)]
+struct Remembered {
+    resolution: StoredResolution,
+    resolved: Instant,
+}
+
+/// OpenFeature provider answering failed evaluations of a wrapped provider with the last value
+/// it resolved.
+///
+/// The provider reports the metadata, status and hooks of the wrapped provider.
+pub struct StaleProvider<P> {
+    provider: P,
+    options: StaleOptions,
+    remembered: Mutex<BoundedMap<Key, Remembered>>,
+    fresh: AtomicU64,
+    stale: AtomicU64,
+    failed: AtomicU64,
+}
+
+impl<P: FeatureProvider> StaleProvider<P> {
+    /// Wraps `provider`.
+    pub fn new(provider: P, options: StaleOptions) -> Self {
+        Self {
+            provider,
+            remembered: Mutex::new(BoundedMap::new(options.max_entries)),
+            options,
+            fresh: AtomicU64::new(0),
+            stale: AtomicU64::new(0),
+            failed: AtomicU64::new(0),
+        }
+    }
+
+    /// Counts of the evaluations made so far.
+    pub fn stats(&self) -> StaleStats {
+        StaleStats {
+            fresh: self.fresh.load(Ordering::Relaxed),
+            stale: self.stale.load(Ordering::Relaxed),
+            failed: self.failed.load(Ordering::Relaxed),
+        }
+    }
+
+    fn remembered(&self) -> MutexGuard<'_, BoundedMap<Key, Remembered>> {
+        self.remembered
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+    }
+
+    async fn resolve<T: FlagValue>(
+        &self,
+        flag_key: &str,
+        context: &EvaluationContext,
+    ) -> EvaluationResult<ResolutionDetails<T>> {
+        let evaluate = T::resolve(&self.provider, flag_key, context);
+        // Contexts without a canonical form have no remembered values.
+        let context = match self.options.context_key {
+            ContextKey::TargetingKey => Some(context.targeting_key.clone()),
+            ContextKey::Context => canonical_context(context).map(Some),
+        };
+        let key = context.map(|context| Key::new::<T>(flag_key, context));
+
+        let error = match evaluate.await {
+            Ok(details) => {
+                self.fresh.fetch_add(1, Ordering::Relaxed);
+                if let Some(key) = key {
+                    self.remember(key, &details);
+                }
+                return Ok(details);
+            }
+            Err(error) if error.code == EvaluationErrorCode::FlagNotFound => return Err(error),
+            Err(error) => error,
+        };
+
+        let stale = key.and_then(|key| {
+            let remembered = self.remembered();
+            remembered
+                .get(&key)
+                .filter(|entry| {
+                    self.options
+                        .max_age
+                        .map_or(true, |max_age| entry.resolved.elapsed() <= max_age)
+                })
+                .and_then(|entry| {
+                    entry
+                        .resolution
+                        .details(EvaluationReason::Other(STALE.to_string()))
+                })
+        });
+        match stale {
+            Some(details) => {
+                self.stale.fetch_add(1, Ordering::Relaxed);
+                warn!(
+                    "Evaluation of {flag_key} failed, serving its last known value: {}",
+                    error.message.as_deref().unwrap_or(&error.code.to_string())
+                );
+                Ok(details)
+            }
+            None => {
+                self.failed.fetch_add(1, Ordering::Relaxed);
+                Err(error)
+            }
+        }
+    }
+
+    fn remember<T: FlagValue>(&self, key: Key, details: &ResolutionDetails<T>) {
+        self.remembered().insert(
+            key,
+            Remembered {
+                resolution: StoredResolution::new(details),
+                resolved: Instant::now(),
+            },
+        );
+    }
+}
+
+#[async_trait]
+impl<P: FeatureProvider> FeatureProvider for StaleProvider<P> {
+    async fn initialize(&mut self, context: &EvaluationContext) {
+        self.provider.initialize(context).await;
+    }
+
+    fn status(&self) -> ProviderStatus {
+        self.provider.status()
+    }
+
+    fn metadata(&self) -> &ProviderMetadata {
+        self.provider.metadata()
+    }
+
+    fn hooks(&self) -> &[HookWrapper] {
+        self.provider.hooks()
+    }
+
+    impl_resolve_methods!();
+}
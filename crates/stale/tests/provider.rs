@@ -0,0 +1,227 @@
+use std::time::Duration;
+
+use open_feature::provider::FeatureProvider;
+use open_feature::{EvaluationContext, EvaluationErrorCode, EvaluationReason, LoggingHook};
+use open_feature_in_memory::InMemoryProvider;
+use open_feature_stale::{ContextKey, StaleOptions, StaleProvider, StaleStats, STALE};
+
+fn flags() -> InMemoryProvider {
+    let flags = InMemoryProvider::new();
+    flags.set_flag("limit", 25, Some("default"), None);
+    flags
+}
+
+/// Makes evaluations of `limit` fail, as a malformed backend value would.
+fn break_limit(flags: &InMemoryProvider) {
+    flags.set_flag("limit", "n/a", None, None);
+}
+
+fn user(targeting_key: &str, plan: &str) -> EvaluationContext {
+    EvaluationContext::default()
+        .with_targeting_key(targeting_key)
+        .with_custom_field("plan", plan)
+}
+
+fn stale() -> Option<EvaluationReason> {
+    Some(EvaluationReason::Other(STALE.to_string()))
+}
+
+#[tokio::test]
+async fn answers_failed_evaluations_with_the_last_value() {
+    let flags = flags();
+    let provider = StaleProvider::new(flags.clone(), StaleOptions::default());
+    let context = user("user-1", "free");
+    provider.resolve_int_value("limit", &context).await.unwrap();
+
+    break_limit(&flags);
+    let details = provider.resolve_int_value("limit", &context).await.unwrap();
+    assert_eq!(details.value, 25);
+    assert_eq!(details.variant.as_deref(), Some("default"));
+    assert_eq!(details.reason, stale());
+
+    // Remembered values are kept per type.
+    let error = provider
+        .resolve_bool_value("limit", &context)
+        .await
+        .unwrap_err();
+    assert_eq!(error.code, EvaluationErrorCode::TypeMismatch);
+    assert_eq!(
+        provider.stats(),
+        StaleStats {
+            fresh: 1,
+            stale: 1,
+            failed: 1
+        }
+    );
+}
+
+#[tokio::test]
+async fn missing_flags_are_not_answered() {
+    let flags = flags();
+    let provider = StaleProvider::new(flags.clone(), StaleOptions::default());
+    let context = user("user-1", "free");
+    provider.resolve_int_value("limit", &context).await.unwrap();
+
+    flags.remove_flag("limit");
+    let error = provider
+        .resolve_int_value("limit", &context)
+        .await
+        .unwrap_err();
+    assert_eq!(error.code, EvaluationErrorCode::FlagNotFound);
+}
+
+#[tokio::test]
+async fn targeting_keys_share_values_across_other_fields() {
+    let flags = flags();
+    let provider = StaleProvider::new(flags.clone(), StaleOptions::default());
+    provider
+        .resolve_int_value("limit", &user("user-1", "free"))
+        .await
+        .unwrap();
+
+    break_limit(&flags);
+    let details = provider
+        .resolve_int_value("limit", &user("user-1", "premium"))
+        .await
+        .unwrap();
+    assert_eq!(details.reason, stale());
+    assert!(provider
+        .resolve_int_value("limit", &user("user-2", "free"))
+        .await
+        .is_err());
+}
+
+#[tokio::test]
+async fn contexts_keep_values_per_field() {
+    let flags = flags();
+    let provider = StaleProvider::new(
+        flags.clone(),
+        StaleOptions {
+            context_key: ContextKey::Context,
+            ..Default::default()
+        },
+    );
+    provider
+        .resolve_int_value("limit", &user("user-1", "free"))
+        .await
+        .unwrap();
+
+    break_limit(&flags);
+    let details = provider
+        .resolve_int_value("limit", &user("user-1", "free"))
+        .await
+        .unwrap();
+    assert_eq!(details.reason, stale());
+    assert!(provider
+        .resolve_int_value("limit", &user("user-1", "premium"))
+        .await
+        .is_err());
+}
+
+#[tokio::test]
+async fn contexts_without_a_canonical_form_are_not_remembered() {
+    let flags = flags();
+    let provider = StaleProvider::new(
+        flags.clone(),
+        StaleOptions {
+            context_key: ContextKey::Context,
+            ..Default::default()
+        },
+    );
+    let context = EvaluationContext::default().with_custom_field("ratio", f64::NAN);
+    provider.resolve_int_value("limit", &context).await.unwrap();
+
+    break_limit(&flags);
+    assert!(provider.resolve_int_value("limit", &context).await.is_err());
+}
+
+#[tokio::test(start_paused = true)]
+async fn values_older_than_the_max_age_are_not_served() {
+    let flags = flags();
+    let provider = StaleProvider::new(
+        flags.clone(),
+        StaleOptions {
+            max_age: Some(Duration::from_secs(60)),
+            ..Default::default()
+        },
+    );
+    let context = user("user-1", "free");
+    provider.resolve_int_value("limit", &context).await.unwrap();
+    break_limit(&flags);
+
+    tokio::time::advance(Duration::from_secs(60)).await;
+    let details = provider.resolve_int_value("limit", &context).await.unwrap();
+    assert_eq!(details.reason, stale());
+
+    tokio::time::advance(Duration::from_secs(1)).await;
+    let error = provider
+        .resolve_int_value("limit", &context)
+        .await
+        .unwrap_err();
+    assert_eq!(error.code, EvaluationErrorCode::TypeMismatch);
+    assert_eq!(provider.stats().failed, 1);
+}
+
+#[tokio::test(start_paused = true)]
+async fn fresh_resolutions_reset_the_age() {
+    let flags = flags();
+    let provider = StaleProvider::new(
+        flags.clone(),
+        StaleOptions {
+            max_age: Some(Duration::from_secs(60)),
+            ..Default::default()
+        },
+    );
+    let context = user("user-1", "free");
+    provider.resolve_int_value("limit", &context).await.unwrap();
+
+    tokio::time::advance(Duration::from_secs(50)).await;
+    flags.set_flag("limit", 30, None, None);
+    provider.resolve_int_value("limit", &context).await.unwrap();
+    break_limit(&flags);
+
+    tokio::time::advance(Duration::from_secs(50)).await;
+    let details = provider.resolve_int_value("limit", &context).await.unwrap();
+    assert_eq!(details.value, 30);
+    assert_eq!(details.reason, stale());
+}
+
+#[tokio::test]
+async fn drops_the_value_remembered_first_once_full() {
+    let flags = flags();
+    let provider = StaleProvider::new(
+        flags.clone(),
+        StaleOptions {
+            max_entries: 1,
+            ..Default::default()
+        },
+    );
+    provider
+        .resolve_int_value("limit", &user("user-1", "free"))
+        .await
+        .unwrap();
+    flags.set_flag("limit", 30, None, None);
+    provider
+        .resolve_int_value("limit", &user("user-2", "free"))
+        .await
+        .unwrap();
+
+    break_limit(&flags);
+    assert!(provider
+        .resolve_int_value("limit", &user("user-1", "free"))
+        .await
+        .is_err());
+    let details = provider
+        .resolve_int_value("limit", &user("user-2", "free"))
+        .await
+        .unwrap();
+    assert_eq!(details.value, 30);
+    assert_eq!(details.reason, stale());
+}
+
+#[test]
+fn reports_the_hooks_of_the_wrapped_provider() {
+    let flags = flags().with_hook(LoggingHook::default());
+    let provider = StaleProvider::new(flags, StaleOptions::default());
+    assert_eq!(provider.hooks().len(), 1);
+}
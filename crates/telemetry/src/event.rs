@@ -0,0 +1,88 @@
+use std::time::Duration;
+
+use open_feature::provider::ResolutionDetails;
+use open_feature::{EvaluationErrorCode, EvaluationReason, EvaluationResult};
+use openfeature_contrib_common::{error_code_name, FlagType};
+use serde_json::json;
+
+/// A flag evaluation, as emitted to a [`TelemetrySink`](crate::TelemetrySink).
+#[derive(Debug, Clone, PartialEq)]
+pub struct EvaluationEvent {
+    /// Key of the evaluated flag.
+    pub flag_key: String,
+    /// Requested type of the flag.
+    pub flag_type: FlagType,
+    /// Name of the wrapped provider.
+    pub provider_name: String,
+    /// Variant of a successful evaluation.
+    pub variant: Option<String>,
+    /// Reason of the evaluation, `ERROR` when it failed.
+    pub reason: Option<EvaluationReason>,
+    /// Code of a failed evaluation.
+    pub error_code: Option<EvaluationErrorCode>,
+    /// Message of a failed evaluation.
+    pub error_message: Option<String>,
+    /// Time the wrapped provider took to evaluate the flag.
+    pub duration: Duration,
+}
+
+impl EvaluationEvent {
+    pub(crate) fn new<T>(
+        flag_key: &str,
+        flag_type: FlagType,
+        provider_name: &str,
+        result: &EvaluationResult<ResolutionDetails<T>>,
+        duration: Duration,
+    ) -> Self {
+        let (variant, reason, error_code, error_message) = match result {
+            Ok(details) => (details.variant.clone(), details.reason.clone(), None, None),
+            Err(e) => (
+                None,
+                Some(EvaluationReason::Error),
+                Some(e.code.clone()),
+                e.message.clone(),
+            ),
+        };
+        Self {
+            flag_key: flag_key.to_string(),
+            flag_type,
+            provider_name: provider_name.to_string(),
+            variant,
+            reason,
+            error_code,
+            error_message,
+            duration,
+        }
+    }
+
+    /// Name of the error code, see [`error_code_name`].
+    pub fn error_type(&self) -> Option<&'static str> {
+        self.error_code.as_ref().map(error_code_name)
+    }
+
+    /// The event as a JSON object, with camel-cased keys and the duration in milliseconds.
+    ///
+    /// ```json
+    /// {"durationMs":0.42,"flagKey":"new-checkout","provider":"flagd","reason":"TARGETING_MATCH","type":"boolean","variant":"on"}
+    /// ```
+    pub fn to_json(&self) -> serde_json::Value {
+        let mut event = json!({
+            "flagKey": self.flag_key,
+            "type": self.flag_type.as_str(),
+            "provider": self.provider_name,
+            "durationMs": self.duration.as_secs_f64() * 1000.0,
+        });
+        let fields = [
+            ("variant", self.variant.clone()),
+            ("reason", self.reason.as_ref().map(ToString::to_string)),
+            ("errorCode", self.error_type().map(str::to_string)),
+            ("errorMessage", self.error_message.clone()),
+        ];
+        for (name, value) in fields {
+            if let Some(value) = value {
+                event[name] = value.into();
+            }
+        }
+        event
+    }
+}
@@ -0,0 +1,118 @@
+//! Telemetry provider decorator for OpenFeature.
+//!
+//! [`TelemetryProvider`] wraps any provider and emits an [`EvaluationEvent`] for every
+//! evaluation: the flag key and type, the provider name, the variant, the reason, the error code
+//! and the duration. Events go to a pluggable [`TelemetrySink`], so teams get the same flag
+//! analytics whatever the backend:
+//!
+//! - [`JsonSink`] writes JSON lines, e.g. to the standard output.
+//! - [`ChannelSink`] sends events to a Tokio channel.
+//! - `OpenTelemetrySink` records events on the active span, with the `opentelemetry` feature.
+//! - Closures taking an `&EvaluationEvent` are sinks too.
+//!
+//! # Example
+//!
+//! ```rust
+//! use open_feature::provider::FeatureProvider;
+//! use open_feature::{EvaluationContext, EvaluationReason};
+//! use open_feature_in_memory::InMemoryProvider;
+//! use open_feature_telemetry::{ChannelSink, TelemetryProvider};
+//!
+//! #[tokio::main]
+//! async fn main() {
+//!     let flags = InMemoryProvider::new();
+//!     flags.set_flag("new-checkout", true, Some("on"), None);
+//!
+//!     let (sink, mut events) = ChannelSink::channel(1024);
+//!     let provider = TelemetryProvider::new(flags, sink);
+//!
+//!     provider
+//!         .resolve_bool_value("new-checkout", &EvaluationContext::default())
+//!         .await
+//!         .unwrap();
+//!
+//!     let event = events.recv().await.unwrap();
+//!     assert_eq!(event.flag_key, "new-checkout");
+//!     assert_eq!(event.variant.as_deref(), Some("on"));
+//!     assert_eq!(event.reason, Some(EvaluationReason::Static));
+//! }
+//! ```
+
+mod event;
+#[cfg(feature = "opentelemetry")]
+mod otel;
+mod sink;
+
+use std::time::Instant;
+
+use async_trait::async_trait;
+use open_feature::provider::{
+    FeatureProvider, ProviderMetadata, ProviderStatus, ResolutionDetails,
+};
+use open_feature::{EvaluationContext, EvaluationResult, HookWrapper};
+use openfeature_contrib_common::{impl_resolve_methods, FlagValue};
+
+pub use crate::event::EvaluationEvent;
+#[cfg(feature = "opentelemetry")]
+pub use crate::otel::OpenTelemetrySink;
+pub use crate::sink::{ChannelSink, JsonSink, TelemetrySink};
+pub use openfeature_contrib_common::FlagType;
+
+/// OpenFeature provider emitting an event for every evaluation of a wrapped provider.
+///
+/// The provider reports the metadata, status and hooks of the wrapped provider.
+pub struct TelemetryProvider<P, S> {
+    provider: P,
+    sink: S,
+}
+
+impl<P: FeatureProvider, S: TelemetrySink> TelemetryProvider<P, S> {
+    /// Wraps `provider`, emitting events to `sink`.
+    pub fn new(provider: P, sink: S) -> Self {
+        Self { provider, sink }
+    }
+
+    /// Returns the sink.
+    pub fn sink(&self) -> &S {
+        &self.sink
+    }
+
+    async fn resolve<T: FlagValue>(
+        &self,
+        flag_key: &str,
+        context: &EvaluationContext,
+    ) -> EvaluationResult<ResolutionDetails<T>> {
+        let started = Instant::now();
+        let result = T::resolve(&self.provider, flag_key, context).await;
+        let event = EvaluationEvent::new(
+            flag_key,
+            T::FLAG_TYPE,
+            &self.provider.metadata().name,
+            &result,
+            started.elapsed(),
+        );
+        self.sink.emit(&event);
+        result
+    }
+}
+
+#[async_trait]
+impl<P: FeatureProvider, S: TelemetrySink + 'static> FeatureProvider for TelemetryProvider<P, S> {
+    async fn initialize(&mut self, context: &EvaluationContext) {
+        self.provider.initialize(context).await;
+    }
+
+    fn status(&self) -> ProviderStatus {
+        self.provider.status()
+    }
+
+    fn metadata(&self) -> &ProviderMetadata {
+        self.provider.metadata()
+    }
+
+    fn hooks(&self) -> &[HookWrapper] {
+        self.provider.hooks()
+    }
+
+    impl_resolve_methods!();
+}
@@ -0,0 +1,45 @@
+use opentelemetry::trace::get_active_span;
+use opentelemetry::KeyValue;
+
+use crate::event::EvaluationEvent;
+use crate::sink::TelemetrySink;
+
+/// Sink recording events as `feature_flag.evaluation` events of the active OpenTelemetry span,
+/// with the attributes of the feature flag semantic conventions.
+///
+/// Evaluations outside a span are not recorded.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct OpenTelemetrySink;
+
+impl TelemetrySink for OpenTelemetrySink {
+    fn emit(&self, event: &EvaluationEvent) {
+        let mut attributes = vec![
+            KeyValue::new("feature_flag.key", event.flag_key.clone()),
+            KeyValue::new("feature_flag.provider.name", event.provider_name.clone()),
+            KeyValue::new(
+                "feature_flag.evaluation.duration",
+                event.duration.as_secs_f64(),
+            ),
+        ];
+        if let Some(variant) = &event.variant {
+            attributes.push(KeyValue::new(
+                "feature_flag.result.variant",
+                variant.clone(),
+            ));
+        }
+        if let Some(reason) = &event.reason {
+            attributes.push(KeyValue::new(
+                "feature_flag.result.reason",
+                reason.to_string().to_lowercase(),
+            ));
+        }
+        if let Some(error_type) = event.error_type() {
+            attributes.push(KeyValue::new("error.type", error_type.to_lowercase()));
+        }
+        if let Some(message) = &event.error_message {
+            attributes.push(KeyValue::new("error.message", message.clone()));
+        }
+
+        get_active_span(|span| span.add_event("feature_flag.evaluation", attributes));
+    }
+}
@@ -0,0 +1,93 @@
+use std::io::{self, Stdout, Write};
+use std::sync::{Mutex, PoisonError};
+
+use tokio::sync::mpsc;
+use tracing::{debug, warn};
+
+use crate::event::EvaluationEvent;
+
+/// Destination of evaluation events.
+///
+/// `emit` is called on the evaluating task once per evaluation, so it should not block. Closures
+/// taking an [`EvaluationEvent`] are sinks.
+pub trait TelemetrySink: Send + Sync {
+    /// Emits the event of an evaluation.
+    fn emit(&self, event: &EvaluationEvent);
+}
+
+impl<F: Fn(&EvaluationEvent) + Send + Sync> TelemetrySink for F {
+    fn emit(&self, event: &EvaluationEvent) {
+        self(event)
+    }
+}
+
+/// Sink writing events as JSON lines, see [`EvaluationEvent::to_json`].
+#[derive(Debug)]
+pub struct JsonSink<W> {
+    writer: Mutex<W>,
+}
+
+impl JsonSink<Stdout> {
+    /// Writes events to the standard output.
+    pub fn stdout() -> Self {
+        Self::new(io::stdout())
+    }
+}
+
+impl<W: Write + Send> JsonSink<W> {
+    /// Writes events to `writer`.
+    pub fn new(writer: W) -> Self {
+        Self {
+            writer: Mutex::new(writer),
+        }
+    }
+
+    /// Returns the writer.
+    pub fn into_inner(self) -> W {
+        self.writer
+            .into_inner()
+            .unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+impl<W: Write + Send> TelemetrySink for JsonSink<W> {
+    fn emit(&self, event: &EvaluationEvent) {
+        let mut writer = self.writer.lock().unwrap_or_else(PoisonError::into_inner);
+        if let Err(e) = writeln!(writer, "{}", event.to_json()) {
+            warn!(
+                "Failed to write evaluation event of {}: {e}",
+                event.flag_key
+            );
+        }
+    }
+}
+
+/// Sink sending events to a Tokio channel, e.g. for a task shipping them to an analytics backend.
+///
+/// Events are dropped while the channel is full or closed, so a slow consumer never delays
+/// evaluations.
+#[derive(Debug, Clone)]
+pub struct ChannelSink {
+    sender: mpsc::Sender<EvaluationEvent>,
+}
+
+impl ChannelSink {
+    /// Sends events to `sender`.
+    pub fn new(sender: mpsc::Sender<EvaluationEvent>) -> Self {
+        Self { sender }
+    }
+
+    /// Creates a channel holding up to `capacity` events, returning the sink and the receiver.
+    pub fn channel(capacity: usize) -> (Self, mpsc::Receiver<EvaluationEvent>) {
+        let (sender, receiver) = mpsc::channel(capacity);
+        (Self::new(sender), receiver)
+    }
+}
+
+impl TelemetrySink for ChannelSink {
+    fn emit(&self, event: &EvaluationEvent) {
+        if let Err(e) = self.sender.try_send(event.clone()) {
+            debug!("Dropped evaluation event of {}: {e}", event.flag_key);
+        }
+    }
+}
@@ -0,0 +1,189 @@
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use open_feature::provider::FeatureProvider;
+use open_feature::{
+    EvaluationContext, EvaluationErrorCode, EvaluationReason, LoggingHook, StructValue,
+};
+use open_feature_in_memory::InMemoryProvider;
+use open_feature_telemetry::{
+    ChannelSink, EvaluationEvent, FlagType, JsonSink, TelemetryProvider, TelemetrySink,
+};
+use serde_json::json;
+
+fn flags() -> InMemoryProvider {
+    let flags = InMemoryProvider::new();
+    flags.set_flag("enabled", true, Some("on"), None);
+    flags.set_flag("limit", 25, None, None);
+    flags.set_flag("ratio", 0.5, None, None);
+    flags.set_flag("theme", "dark", None, None);
+    flags.set_flag(
+        "layout",
+        StructValue::default().with_field("columns", 2),
+        None,
+        None,
+    );
+    flags
+}
+
+/// Provider collecting its events in a vector.
+fn collecting() -> (
+    TelemetryProvider<InMemoryProvider, impl TelemetrySink>,
+    Arc<Mutex<Vec<EvaluationEvent>>>,
+) {
+    let events = Arc::new(Mutex::new(Vec::new()));
+    let sink = {
+        let events = events.clone();
+        move |event: &EvaluationEvent| events.lock().unwrap().push(event.clone())
+    };
+    (TelemetryProvider::new(flags(), sink), events)
+}
+
+#[tokio::test]
+async fn emits_an_event_per_evaluation_with_its_flag_type() {
+    let (provider, events) = collecting();
+    let context = EvaluationContext::default();
+
+    provider
+        .resolve_bool_value("enabled", &context)
+        .await
+        .unwrap();
+    provider.resolve_int_value("limit", &context).await.unwrap();
+    provider
+        .resolve_float_value("ratio", &context)
+        .await
+        .unwrap();
+    provider
+        .resolve_string_value("theme", &context)
+        .await
+        .unwrap();
+    provider
+        .resolve_struct_value("layout", &context)
+        .await
+        .unwrap();
+
+    let events = events.lock().unwrap();
+    let emitted: Vec<_> = events
+        .iter()
+        .map(|event| (event.flag_key.as_str(), event.flag_type))
+        .collect();
+    assert_eq!(
+        emitted,
+        [
+            ("enabled", FlagType::Boolean),
+            ("limit", FlagType::Integer),
+            ("ratio", FlagType::Float),
+            ("theme", FlagType::String),
+            ("layout", FlagType::Object),
+        ]
+    );
+    let event = &events[0];
+    assert_eq!(event.provider_name, "in-memory");
+    assert_eq!(event.variant.as_deref(), Some("on"));
+    assert_eq!(event.reason, Some(EvaluationReason::Static));
+    assert_eq!(event.error_code, None);
+    assert_eq!(event.error_type(), None);
+}
+
+#[tokio::test]
+async fn failed_evaluations_emit_the_error() {
+    let (provider, events) = collecting();
+    let context = EvaluationContext::default();
+
+    assert!(provider
+        .resolve_bool_value("missing", &context)
+        .await
+        .is_err());
+    assert!(provider
+        .resolve_bool_value("limit", &context)
+        .await
+        .is_err());
+
+    let events = events.lock().unwrap();
+    assert_eq!(events[0].reason, Some(EvaluationReason::Error));
+    assert_eq!(events[0].variant, None);
+    assert_eq!(
+        events[0].error_code,
+        Some(EvaluationErrorCode::FlagNotFound)
+    );
+    assert_eq!(events[0].error_type(), Some("FLAG_NOT_FOUND"));
+    assert_eq!(events[1].error_type(), Some("TYPE_MISMATCH"));
+    assert!(events[1].error_message.is_some());
+}
+
+fn event() -> EvaluationEvent {
+    EvaluationEvent {
+        flag_key: "new-checkout".to_string(),
+        flag_type: FlagType::Boolean,
+        provider_name: "flagd".to_string(),
+        variant: Some("on".to_string()),
+        reason: Some(EvaluationReason::TargetingMatch),
+        error_code: None,
+        error_message: None,
+        duration: Duration::from_millis(2),
+    }
+}
+
+#[test]
+fn events_serialize_to_camel_cased_json() {
+    assert_eq!(
+        event().to_json(),
+        json!({
+            "flagKey": "new-checkout",
+            "type": "boolean",
+            "provider": "flagd",
+            "durationMs": 2.0,
+            "variant": "on",
+            "reason": "TARGETING_MATCH",
+        })
+    );
+
+    let failed = EvaluationEvent {
+        variant: None,
+        reason: Some(EvaluationReason::Error),
+        error_code: Some(EvaluationErrorCode::General("Timeout".to_string())),
+        error_message: Some("evaluation timed out".to_string()),
+        ..event()
+    };
+    assert_eq!(failed.error_type(), Some("GENERAL"));
+    let json = failed.to_json();
+    assert_eq!(json["errorCode"], "GENERAL");
+    assert_eq!(json["errorMessage"], "evaluation timed out");
+    assert!(json.get("variant").is_none());
+}
+
+#[test]
+fn json_sinks_write_a_line_per_event() {
+    let sink = JsonSink::new(Vec::new());
+    sink.emit(&event());
+    sink.emit(&event());
+
+    let output = String::from_utf8(sink.into_inner()).unwrap();
+    let lines: Vec<_> = output.lines().collect();
+    assert_eq!(lines.len(), 2);
+    let line: serde_json::Value = serde_json::from_str(lines[0]).unwrap();
+    assert_eq!(line, event().to_json());
+}
+
+#[tokio::test]
+async fn channel_sinks_drop_events_while_the_channel_is_full() {
+    let (sink, mut events) = ChannelSink::channel(1);
+    let provider = TelemetryProvider::new(flags(), sink);
+    let context = EvaluationContext::default();
+
+    provider
+        .resolve_bool_value("enabled", &context)
+        .await
+        .unwrap();
+    provider.resolve_int_value("limit", &context).await.unwrap();
+
+    assert_eq!(events.recv().await.unwrap().flag_key, "enabled");
+    assert!(events.try_recv().is_err());
+}
+
+#[test]
+fn reports_the_hooks_of_the_wrapped_provider() {
+    let flags = flags().with_hook(LoggingHook::default());
+    let provider = TelemetryProvider::new(flags, |_: &EvaluationEvent| {});
+    assert_eq!(provider.hooks().len(), 1);
+}
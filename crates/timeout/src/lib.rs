@@ -0,0 +1,144 @@
+//! Evaluation timeout provider decorator for OpenFeature.
+//!
+//! [`TimeoutProvider`] bounds the latency of any provider: evaluations still running when their
+//! deadline passes are cancelled and fail with a `GENERAL` error, so the application gets its
+//! default value with the `ERROR` reason instead of waiting on a slow flag backend.
+//!
+//! Hooks cannot cancel the resolution they surround, which is why the deadline is enforced by a
+//! decorator rather than a hook.
+//!
+//! # Example
+//!
+//! ```rust
+//! use std::time::Duration;
+//!
+//! use open_feature::provider::FeatureProvider;
+//! use open_feature::EvaluationContext;
+//! use open_feature_in_memory::InMemoryProvider;
+//! use open_feature_timeout::{TimeoutOptions, TimeoutProvider};
+//!
+//! #[tokio::main]
+//! async fn main() {
+//!     let flags = InMemoryProvider::new();
+//!     flags.set_flag("new-checkout", true, None, None);
+//!
+//!     let provider = TimeoutProvider::new(
+//!         flags,
+//!         TimeoutOptions {
+//!             timeout: Duration::from_millis(50),
+//!             ..Default::default()
+//!         },
+//!     );
+//!
+//!     let details = provider
+//!         .resolve_bool_value("new-checkout", &EvaluationContext::default())
+//!         .await
+//!         .unwrap();
+//!     assert!(details.value);
+//!     assert_eq!(provider.timeouts(), 0);
+//! }
+//! ```
+
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
+
+use async_trait::async_trait;
+use open_feature::provider::{
+    FeatureProvider, ProviderMetadata, ProviderStatus, ResolutionDetails,
+};
+use open_feature::{EvaluationContext, EvaluationError, EvaluationResult, HookWrapper};
+use openfeature_contrib_common::{impl_resolve_methods, timeout_error_code, FlagValue};
+use tracing::warn;
+
+/// Configuration of the [`TimeoutProvider`].
+#[derive(Debug, Clone)]
+pub struct TimeoutOptions {
+    /// Deadline of evaluations.
+    pub timeout: Duration,
+    /// Deadlines of specific flags, overriding [`TimeoutOptions::timeout`].
+    pub flag_timeouts: HashMap<String, Duration>,
+}
+
+impl Default for TimeoutOptions {
+    fn default() -> Self {
+        Self {
+            timeout: Duration::from_millis(500),
+            flag_timeouts: HashMap::new(),
+        }
+    }
+}
+
+/// OpenFeature provider bounding the evaluation latency of a wrapped provider.
+///
+/// Evaluations must run within a Tokio runtime with the time driver enabled. The provider reports
+/// the metadata, status and hooks of the wrapped provider.
+pub struct TimeoutProvider<P> {
+    provider: P,
+    options: TimeoutOptions,
+    timeouts: AtomicU64,
+}
+
+impl<P: FeatureProvider> TimeoutProvider<P> {
+    /// Wraps `provider`.
+    pub fn new(provider: P, options: TimeoutOptions) -> Self {
+        Self {
+            provider,
+            options,
+            timeouts: AtomicU64::new(0),
+        }
+    }
+
+    /// Number of evaluations that timed out so far.
+    pub fn timeouts(&self) -> u64 {
+        self.timeouts.load(Ordering::Relaxed)
+    }
+
+    async fn resolve<T: FlagValue>(
+        &self,
+        flag_key: &str,
+        context: &EvaluationContext,
+    ) -> EvaluationResult<ResolutionDetails<T>> {
+        let evaluate = T::resolve(&self.provider, flag_key, context);
+        let timeout = self
+            .options
+            .flag_timeouts
+            .get(flag_key)
+            .copied()
+            .unwrap_or(self.options.timeout);
+        match tokio::time::timeout(timeout, evaluate).await {
+            Ok(result) => result,
+            Err(_) => {
+                self.timeouts.fetch_add(1, Ordering::Relaxed);
+                warn!("Evaluation of {flag_key} timed out after {timeout:?}");
+                Err(EvaluationError::builder()
+                    .code(timeout_error_code())
+                    .message(format!(
+                        "Evaluation of {flag_key} timed out after {timeout:?}"
+                    ))
+                    .build())
+            }
+        }
+    }
+}
+
+#[async_trait]
+impl<P: FeatureProvider> FeatureProvider for TimeoutProvider<P> {
+    async fn initialize(&mut self, context: &EvaluationContext) {
+        self.provider.initialize(context).await;
+    }
+
+    fn status(&self) -> ProviderStatus {
+        self.provider.status()
+    }
+
+    fn metadata(&self) -> &ProviderMetadata {
+        self.provider.metadata()
+    }
+
+    fn hooks(&self) -> &[HookWrapper] {
+        self.provider.hooks()
+    }
+
+    impl_resolve_methods!();
+}
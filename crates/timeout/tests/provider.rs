@@ -0,0 +1,115 @@
+use std::collections::HashMap;
+use std::time::Duration;
+
+use async_trait::async_trait;
+use open_feature::provider::{FeatureProvider, ProviderMetadata, ResolutionDetails};
+use open_feature::{EvaluationContext, EvaluationResult, LoggingHook};
+use open_feature_in_memory::InMemoryProvider;
+use open_feature_timeout::{TimeoutOptions, TimeoutProvider};
+use openfeature_contrib_common::{impl_resolve_methods, timeout_error_code, FlagValue};
+use tokio::time::Instant;
+
+/// Provider answering a second after being asked.
+struct Slow(InMemoryProvider);
+
+impl Slow {
+    async fn resolve<T: FlagValue>(
+        &self,
+        flag_key: &str,
+        context: &EvaluationContext,
+    ) -> EvaluationResult<ResolutionDetails<T>> {
+        tokio::time::sleep(Duration::from_secs(1)).await;
+        T::resolve(&self.0, flag_key, context).await
+    }
+}
+
+#[async_trait]
+impl FeatureProvider for Slow {
+    fn metadata(&self) -> &ProviderMetadata {
+        self.0.metadata()
+    }
+
+    impl_resolve_methods!();
+}
+
+fn slow() -> Slow {
+    let flags = InMemoryProvider::new();
+    flags.set_flag("enabled", true, Some("on"), None);
+    flags.set_flag("limit", 25, None, None);
+    Slow(flags)
+}
+
+#[tokio::test(start_paused = true)]
+async fn fails_evaluations_running_past_the_timeout() {
+    let provider = TimeoutProvider::new(slow(), TimeoutOptions::default());
+    let started = Instant::now();
+
+    let error = provider
+        .resolve_bool_value("enabled", &EvaluationContext::default())
+        .await
+        .unwrap_err();
+    assert_eq!(started.elapsed(), Duration::from_millis(500));
+    assert_eq!(error.code, timeout_error_code());
+    assert_eq!(
+        error.message.as_deref(),
+        Some("Evaluation of enabled timed out after 500ms")
+    );
+    assert_eq!(provider.timeouts(), 1);
+}
+
+#[tokio::test(start_paused = true)]
+async fn passes_evaluations_finishing_within_the_timeout() {
+    let provider = TimeoutProvider::new(
+        slow(),
+        TimeoutOptions {
+            timeout: Duration::from_secs(1),
+            ..Default::default()
+        },
+    );
+
+    let details = provider
+        .resolve_bool_value("enabled", &EvaluationContext::default())
+        .await
+        .unwrap();
+    assert!(details.value);
+    assert_eq!(details.variant.as_deref(), Some("on"));
+    assert_eq!(provider.timeouts(), 0);
+}
+
+#[tokio::test(start_paused = true)]
+async fn flag_timeouts_override_the_timeout() {
+    let provider = TimeoutProvider::new(
+        slow(),
+        TimeoutOptions {
+            timeout: Duration::from_millis(100),
+            flag_timeouts: HashMap::from([("enabled".to_string(), Duration::from_secs(2))]),
+        },
+    );
+    let context = EvaluationContext::default();
+
+    let started = Instant::now();
+    assert!(
+        provider
+            .resolve_bool_value("enabled", &context)
+            .await
+            .unwrap()
+            .value
+    );
+    assert_eq!(started.elapsed(), Duration::from_secs(1));
+
+    let started = Instant::now();
+    let error = provider
+        .resolve_int_value("limit", &context)
+        .await
+        .unwrap_err();
+    assert_eq!(started.elapsed(), Duration::from_millis(100));
+    assert_eq!(error.code, timeout_error_code());
+    assert_eq!(provider.timeouts(), 1);
+}
+
+#[test]
+fn reports_the_hooks_of_the_wrapped_provider() {
+    let flags = InMemoryProvider::new().with_hook(LoggingHook::default());
+    let provider = TimeoutProvider::new(flags, TimeoutOptions::default());
+    assert_eq!(provider.hooks().len(), 1);
+}
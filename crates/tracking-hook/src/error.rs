@@ -0,0 +1,30 @@
+use openfeature_contrib_common::HttpError;
+use thiserror::Error;
+
+/// Errors raised while creating the hook or forwarding exposure events.
+#[derive(Debug, Error)]
+pub enum TrackingError {
+    /// A configured header has an invalid name or value.
+    #[error("invalid header {0}")]
+    InvalidHeader(String),
+
+    /// The request to the analytics endpoint failed.
+    #[error("request to the analytics endpoint failed: {0}")]
+    Http(#[from] HttpError),
+}
+
+impl From<reqwest::Error> for TrackingError {
+    fn from(error: reqwest::Error) -> Self {
+        Self::Http(error.into())
+    }
+}
+
+impl TrackingError {
+    /// Whether posting the batch again may succeed.
+    pub(crate) fn is_retryable(&self) -> bool {
+        match self {
+            Self::InvalidHeader(_) => false,
+            Self::Http(e) => e.is_retryable(),
+        }
+    }
+}
@@ -0,0 +1,38 @@
+use serde::Serialize;
+use serde_json::{Map, Value};
+use time::OffsetDateTime;
+
+/// Exposure of a subject to a flag variant, as forwarded to the analytics endpoint.
+///
+/// Events serialize to JSON objects with camelCase keys, the timestamp in RFC 3339:
+///
+/// ```json
+/// {"timestamp":"2024-05-01T12:00:00Z","flagKey":"checkout-button","variant":"green",
+///  "value":"green","reason":"TARGETING_MATCH","targetingKey":"user-1",
+///  "providerName":"in-memory","domain":""}
+/// ```
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExposureEvent {
+    /// End of the evaluation.
+    #[serde(with = "time::serde::rfc3339")]
+    pub timestamp: OffsetDateTime,
+    /// Key of the evaluated flag.
+    pub flag_key: String,
+    /// Variant the subject was exposed to.
+    pub variant: Option<String>,
+    /// Value the subject was exposed to.
+    pub value: Value,
+    /// Reason of the evaluation.
+    pub reason: Option<String>,
+    /// Targeting key of the subject.
+    pub targeting_key: Option<String>,
+    /// Custom fields of the evaluation context, when
+    /// [`TrackingOptions::include_context`](crate::TrackingOptions::include_context) is set.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub context: Option<Map<String, Value>>,
+    /// Name of the provider.
+    pub provider_name: String,
+    /// Name of the client, empty for the default client.
+    pub domain: String,
+}
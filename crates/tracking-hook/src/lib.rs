@@ -0,0 +1,354 @@
+//! Exposure tracking hook for OpenFeature.
+//!
+//! [`TrackingHook`] turns every successful flag evaluation into an [`ExposureEvent`], telling
+//! which subject was exposed to which variant, and forwards the events in batches to an
+//! analytics endpoint. Experiments can then be analyzed even with providers that lack native
+//! exposure logging.
+//!
+//! Batches are posted as JSON objects holding the events in an `events` array:
+//!
+//! ```json
+//! {"events":[{"timestamp":"2024-05-01T12:00:00Z","flagKey":"checkout-button",
+//!   "variant":"green","value":"green","reason":"TARGETING_MATCH","targetingKey":"user-1",
+//!   "providerName":"in-memory","domain":""}]}
+//! ```
+//!
+//! Forwarding never slows evaluations down: events are dropped while
+//! [`TrackingOptions::capacity`] events are pending, and batches the endpoint rejects for good,
+//! or still rejects after [`TrackingOptions::max_retries`] retries, are dropped.
+//!
+//! # Example
+//!
+//! ```rust,no_run
+//! use std::collections::HashMap;
+//!
+//! use open_feature::{EvaluationContext, OpenFeature};
+//! use open_feature_in_memory::InMemoryProvider;
+//! use open_feature_tracking_hook::{TrackingHook, TrackingOptions};
+//!
+//! #[tokio::main]
+//! async fn main() {
+//!     let provider = InMemoryProvider::new();
+//!     provider.set_flag("checkout-button", "green", Some("green"), None);
+//!
+//!     let hook = TrackingHook::new(TrackingOptions {
+//!         url: "https://analytics.example.com/v1/exposures".to_string(),
+//!         headers: HashMap::from([("Authorization".to_string(), "Bearer token".to_string())]),
+//!         ..Default::default()
+//!     })
+//!     .unwrap();
+//!
+//!     let mut api = OpenFeature::singleton_mut().await;
+//!     api.set_provider(provider).await;
+//!     api.add_hook(hook.clone()).await;
+//!     let client = api.create_client();
+//!
+//!     let context = EvaluationContext::default().with_targeting_key("user-1");
+//!     let color = client.get_string_value("checkout-button", Some(&context), None).await;
+//!     assert_eq!(color.unwrap(), "green");
+//!
+//!     hook.flush().await;
+//! }
+//! ```
+
+mod error;
+mod event;
+
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use async_trait::async_trait;
+use open_feature::{
+    EvaluationContext, EvaluationDetails, EvaluationError, Hook, HookContext, HookHints, Value,
+};
+use openfeature_contrib_common::{check_status, fields_to_json, value_to_json, DateTimeFormat};
+use reqwest::header::{HeaderMap, HeaderName, HeaderValue};
+use reqwest::Client;
+use serde::Serialize;
+use time::OffsetDateTime;
+use tokio::sync::mpsc::error::TrySendError;
+use tokio::sync::{mpsc, oneshot};
+use tokio::time::MissedTickBehavior;
+use tracing::{debug, warn};
+
+pub use openfeature_contrib_common::BackoffPolicy;
+
+pub use crate::error::TrackingError;
+pub use crate::event::ExposureEvent;
+
+/// Lower bound of the flush interval, so that zero does not make the forwarder spin.
+const MIN_FLUSH_INTERVAL: Duration = Duration::from_millis(10);
+
+/// Configuration of the [`TrackingHook`].
+#[derive(Debug, Clone)]
+pub struct TrackingOptions {
+    /// URL the batches of events are posted to.
+    pub url: String,
+    /// Headers sent with every request, e.g. `Authorization`.
+    pub headers: HashMap<String, String>,
+    /// Timeout of requests.
+    pub request_timeout: Duration,
+    /// Number of events posted together.
+    pub batch_size: usize,
+    /// Longest time an event waits for its batch to fill up. Clamped to at least 10ms.
+    pub flush_interval: Duration,
+    /// Number of pending events beyond which new events are dropped.
+    pub capacity: usize,
+    /// Number of times a batch rejected with a retryable error (timeouts, statuses 408, 429, 500
+    /// and 502 to 504) is posted again before it is dropped. Other rejected batches are dropped
+    /// right away.
+    pub max_retries: u32,
+    /// Delays between attempts to post a rejected batch.
+    pub retry_backoff: BackoffPolicy,
+    /// Whether events include the custom fields of the evaluation context, which may hold
+    /// personal data.
+    pub include_context: bool,
+}
+
+impl Default for TrackingOptions {
+    fn default() -> Self {
+        Self {
+            url: String::new(),
+            headers: HashMap::new(),
+            request_timeout: Duration::from_secs(10),
+            batch_size: 100,
+            flush_interval: Duration::from_secs(10),
+            capacity: 10_000,
+            max_retries: 3,
+            retry_backoff: BackoffPolicy::default(),
+            include_context: false,
+        }
+    }
+}
+
+enum Command {
+    Event(Box<ExposureEvent>),
+    Flush(oneshot::Sender<()>),
+}
+
+/// OpenFeature hook forwarding an [`ExposureEvent`] per successful evaluation to an analytics
+/// endpoint.
+///
+/// Clones of the hook share their queue. The background task forwarding the events stops once
+/// all clones are dropped and the pending events are posted; call [`TrackingHook::flush`] before
+/// the application exits, since hooks registered with the API are never dropped.
+#[derive(Clone)]
+pub struct TrackingHook {
+    inner: Arc<Inner>,
+}
+
+struct Inner {
+    sender: mpsc::Sender<Command>,
+    include_context: bool,
+    dropped: Arc<AtomicU64>,
+}
+
+impl TrackingHook {
+    /// Creates the hook, starting the task forwarding events.
+    ///
+    /// Must be called within a Tokio runtime with the time driver enabled.
+    pub fn new(options: TrackingOptions) -> Result<Self, TrackingError> {
+        let forwarder = Forwarder::new(&options)?;
+        let (sender, receiver) = mpsc::channel(options.capacity.max(1));
+        let dropped = Arc::new(AtomicU64::new(0));
+        tokio::spawn(forward_events(
+            forwarder,
+            receiver,
+            options.clone(),
+            dropped.clone(),
+        ));
+
+        Ok(Self {
+            inner: Arc::new(Inner {
+                sender,
+                include_context: options.include_context,
+                dropped,
+            }),
+        })
+    }
+
+    /// Waits until the events of the evaluations completed so far are posted or dropped.
+    pub async fn flush(&self) {
+        let (done, forwarded) = oneshot::channel();
+        if self.inner.sender.send(Command::Flush(done)).await.is_ok() {
+            let _ = forwarded.await;
+        }
+    }
+
+    /// Number of events dropped so far, because the queue was full or the endpoint kept
+    /// rejecting their batch.
+    pub fn dropped(&self) -> u64 {
+        self.inner.dropped.load(Ordering::Relaxed)
+    }
+}
+
+#[async_trait]
+impl Hook for TrackingHook {
+    async fn before<'a>(
+        &self,
+        _: &HookContext<'a>,
+        _: Option<&'a HookHints>,
+    ) -> Result<Option<EvaluationContext>, EvaluationError> {
+        Ok(None)
+    }
+
+    async fn after<'a>(
+        &self,
+        context: &HookContext<'a>,
+        details: &EvaluationDetails<Value>,
+        _: Option<&'a HookHints>,
+    ) -> Result<(), EvaluationError> {
+        let event = ExposureEvent {
+            timestamp: OffsetDateTime::now_utc(),
+            flag_key: context.flag_key.to_string(),
+            variant: details.variant.clone(),
+            value: value_to_json(&details.value),
+            reason: details.reason.as_ref().map(ToString::to_string),
+            targeting_key: context.evaluation_context.targeting_key.clone(),
+            context: self
+                .inner
+                .include_context
+                .then(|| fields_to_json(context.evaluation_context, DateTimeFormat::Rfc3339)),
+            provider_name: context.provider_metadata.name.clone(),
+            domain: context.client_metadata.name.clone(),
+        };
+
+        match self.inner.sender.try_send(Command::Event(Box::new(event))) {
+            Ok(()) => {}
+            Err(TrySendError::Full(_)) => {
+                self.inner.dropped.fetch_add(1, Ordering::Relaxed);
+                debug!(
+                    "Exposure queue is full, dropped the event of {}",
+                    context.flag_key
+                );
+            }
+            Err(TrySendError::Closed(_)) => {
+                self.inner.dropped.fetch_add(1, Ordering::Relaxed);
+            }
+        }
+        Ok(())
+    }
+
+    async fn error<'a>(&self, _: &HookContext<'a>, _: &EvaluationError, _: Option<&'a HookHints>) {}
+
+    async fn finally<'a>(
+        &self,
+        _: &HookContext<'a>,
+        _: &EvaluationDetails<Value>,
+        _: Option<&'a HookHints>,
+    ) {
+    }
+}
+
+#[derive(Serialize)]
+struct Batch<'a> {
+    events: &'a [ExposureEvent],
+}
+
+struct Forwarder {
+    http: Client,
+    url: String,
+}
+
+impl Forwarder {
+    fn new(options: &TrackingOptions) -> Result<Self, TrackingError> {
+        let mut headers = HeaderMap::new();
+        for (name, value) in &options.headers {
+            let invalid = || TrackingError::InvalidHeader(name.clone());
+            headers.insert(
+                HeaderName::try_from(name).map_err(|_| invalid())?,
+                HeaderValue::from_str(value).map_err(|_| invalid())?,
+            );
+        }
+        let http = Client::builder()
+            .default_headers(headers)
+            .timeout(options.request_timeout)
+            .build()?;
+
+        Ok(Self {
+            http,
+            url: options.url.clone(),
+        })
+    }
+
+    async fn post(&self, events: &[ExposureEvent]) -> Result<(), TrackingError> {
+        let response = self
+            .http
+            .post(&self.url)
+            .json(&Batch { events })
+            .send()
+            .await?;
+        check_status(response)?;
+        Ok(())
+    }
+}
+
+async fn forward_events(
+    forwarder: Forwarder,
+    mut receiver: mpsc::Receiver<Command>,
+    options: TrackingOptions,
+    dropped: Arc<AtomicU64>,
+) {
+    let batch_size = options.batch_size.max(1);
+    let mut batch = Vec::with_capacity(batch_size);
+    let mut interval = tokio::time::interval(options.flush_interval.max(MIN_FLUSH_INTERVAL));
+    interval.set_missed_tick_behavior(MissedTickBehavior::Delay);
+    let forward = |batch: Vec<ExposureEvent>| forward_batch(&forwarder, batch, &options, &dropped);
+
+    loop {
+        tokio::select! {
+            command = receiver.recv() => match command {
+                Some(Command::Event(event)) => {
+                    batch.push(*event);
+                    if batch.len() >= batch_size {
+                        forward(std::mem::take(&mut batch)).await;
+                    }
+                }
+                Some(Command::Flush(done)) => {
+                    forward(std::mem::take(&mut batch)).await;
+                    let _ = done.send(());
+                }
+                None => {
+                    forward(batch).await;
+                    return;
+                }
+            },
+            _ = interval.tick() => forward(std::mem::take(&mut batch)).await,
+        }
+    }
+}
+
+/// Posts `batch`, retrying retryable failures up to [`TrackingOptions::max_retries`] times.
+async fn forward_batch(
+    forwarder: &Forwarder,
+    batch: Vec<ExposureEvent>,
+    options: &TrackingOptions,
+    dropped: &AtomicU64,
+) {
+    if batch.is_empty() {
+        return;
+    }
+    let mut backoff = options.retry_backoff.backoff();
+    let mut retries = 0;
+    loop {
+        match forwarder.post(&batch).await {
+            Ok(()) => return,
+            Err(e) if e.is_retryable() && retries < options.max_retries => {
+                retries += 1;
+                let delay = backoff.next_delay();
+                debug!(
+                    "Failed to forward {} exposure events, retrying in {delay:?}: {e}",
+                    batch.len()
+                );
+                tokio::time::sleep(delay).await;
+            }
+            Err(e) => {
+                dropped.fetch_add(batch.len() as u64, Ordering::Relaxed);
+                warn!("Dropped {} exposure events: {e}", batch.len());
+                return;
+            }
+        }
+    }
+}
@@ -0,0 +1,192 @@
+use std::time::Duration;
+
+use open_feature::{Client, EvaluationContext, OpenFeature};
+use open_feature_in_memory::InMemoryProvider;
+use open_feature_tracking_hook::{BackoffPolicy, TrackingHook, TrackingOptions};
+use serde_json::json;
+use wiremock::matchers::{body_partial_json, header, method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+fn options(server: &MockServer) -> TrackingOptions {
+    TrackingOptions {
+        url: format!("{}/v1/exposures", server.uri()),
+        retry_backoff: BackoffPolicy {
+            initial: Duration::from_millis(1),
+            max: Duration::from_millis(1),
+            ..Default::default()
+        },
+        ..Default::default()
+    }
+}
+
+/// Creates a client of its own provider, so tests do not share flags or hooks.
+async fn client(domain: &str, hook: &TrackingHook) -> Client {
+    let provider = InMemoryProvider::new();
+    provider.set_flag("checkout-button", "green", Some("green"), None);
+    let mut api = OpenFeature::singleton_mut().await;
+    api.set_named_provider(domain, provider).await;
+    api.create_named_client(domain).with_hook(hook.clone())
+}
+
+async fn wait_for_requests(server: &MockServer, count: usize) {
+    tokio::time::timeout(Duration::from_secs(5), async {
+        while server.received_requests().await.unwrap().len() < count {
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+    })
+    .await
+    .expect("the events were not posted");
+}
+
+async fn evaluate(client: &Client) {
+    let context = EvaluationContext::default().with_targeting_key("user-1");
+    client
+        .get_string_value("checkout-button", Some(&context), None)
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn posts_exposures_of_successful_evaluations() {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/v1/exposures"))
+        .and(header("Authorization", "Bearer token"))
+        .and(body_partial_json(json!({"events": [{
+            "flagKey": "checkout-button",
+            "variant": "green",
+            "value": "green",
+            "targetingKey": "user-1",
+            "domain": "exposures"
+        }]})))
+        .respond_with(ResponseTemplate::new(202))
+        .expect(1)
+        .mount(&server)
+        .await;
+    let hook = TrackingHook::new(TrackingOptions {
+        headers: [("Authorization".to_string(), "Bearer token".to_string())].into(),
+        ..options(&server)
+    })
+    .unwrap();
+    let client = client("exposures", &hook).await;
+
+    evaluate(&client).await;
+    client
+        .get_bool_value("missing", None, None)
+        .await
+        .unwrap_err();
+    hook.flush().await;
+
+    assert_eq!(hook.dropped(), 0);
+}
+
+#[tokio::test]
+async fn retries_transient_failures() {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .respond_with(ResponseTemplate::new(503))
+        .up_to_n_times(2)
+        .mount(&server)
+        .await;
+    Mock::given(method("POST"))
+        .respond_with(ResponseTemplate::new(202))
+        .expect(1)
+        .mount(&server)
+        .await;
+    let hook = TrackingHook::new(options(&server)).unwrap();
+    let client = client("transient", &hook).await;
+
+    evaluate(&client).await;
+    hook.flush().await;
+
+    assert_eq!(hook.dropped(), 0);
+    assert_eq!(server.received_requests().await.unwrap().len(), 3);
+}
+
+#[tokio::test]
+async fn drops_batches_after_max_retries() {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .respond_with(ResponseTemplate::new(503))
+        .expect(3)
+        .mount(&server)
+        .await;
+    let hook = TrackingHook::new(TrackingOptions {
+        max_retries: 2,
+        ..options(&server)
+    })
+    .unwrap();
+    let client = client("max-retries", &hook).await;
+
+    evaluate(&client).await;
+    hook.flush().await;
+
+    assert_eq!(hook.dropped(), 1);
+}
+
+#[tokio::test]
+async fn does_not_retry_client_errors() {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .respond_with(ResponseTemplate::new(400))
+        .expect(1)
+        .mount(&server)
+        .await;
+    let hook = TrackingHook::new(options(&server)).unwrap();
+    let client = client("client-errors", &hook).await;
+
+    evaluate(&client).await;
+    evaluate(&client).await;
+    hook.flush().await;
+
+    assert_eq!(hook.dropped(), 2);
+}
+
+#[tokio::test]
+async fn drops_events_while_the_queue_is_full() {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .respond_with(ResponseTemplate::new(202).set_delay(Duration::from_secs(60)))
+        .mount(&server)
+        .await;
+    let hook = TrackingHook::new(TrackingOptions {
+        batch_size: 1,
+        capacity: 1,
+        ..options(&server)
+    })
+    .unwrap();
+    let client = client("full-queue", &hook).await;
+
+    evaluate(&client).await;
+    wait_for_requests(&server, 1).await;
+    for _ in 0..3 {
+        evaluate(&client).await;
+    }
+
+    // The forwarder posts the first event and the queue holds the second.
+    assert_eq!(hook.dropped(), 2);
+}
+
+#[tokio::test]
+async fn a_zero_flush_interval_keeps_forwarding() {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .respond_with(ResponseTemplate::new(202))
+        .expect(2)
+        .mount(&server)
+        .await;
+    let hook = TrackingHook::new(TrackingOptions {
+        flush_interval: Duration::ZERO,
+        ..options(&server)
+    })
+    .unwrap();
+    let client = client("zero-interval", &hook).await;
+
+    // Without flushing, only the interval forwards the events.
+    for posted in 1..=2 {
+        evaluate(&client).await;
+        wait_for_requests(&server, posted).await;
+    }
+
+    assert_eq!(hook.dropped(), 0);
+}
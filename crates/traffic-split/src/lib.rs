@@ -0,0 +1,219 @@
+//! Traffic-split provider decorator for OpenFeature.
+//!
+//! [`TrafficSplitProvider`] routes a percentage of targeting keys to a new provider and the rest
+//! to an old one, so a migration between flag backends can roll out gradually. Routing hashes the
+//! targeting key: a key always goes to the same provider, and raising the percentage only moves
+//! keys from the old provider to the new one. Lowering it to 0 with
+//! [`TrafficSplitProvider::set_percentage`] rolls the migration back.
+//!
+//! Evaluations without a targeting key go to the old provider, unless the percentage is 100.
+//!
+//! # Example
+//!
+//! ```rust
+//! use open_feature::provider::FeatureProvider;
+//! use open_feature::EvaluationContext;
+//! use open_feature_in_memory::InMemoryProvider;
+//! use open_feature_traffic_split::{TrafficSplitOptions, TrafficSplitProvider};
+//!
+//! #[tokio::main]
+//! async fn main() {
+//!     let old = InMemoryProvider::new();
+//!     old.set_flag("new-checkout", false, None, None);
+//!     let new = InMemoryProvider::new();
+//!     new.set_flag("new-checkout", true, None, None);
+//!
+//!     let provider = TrafficSplitProvider::new(
+//!         old,
+//!         new,
+//!         TrafficSplitOptions {
+//!             percentage: 25.0,
+//!             ..Default::default()
+//!         },
+//!     );
+//!
+//!     let context = EvaluationContext::default().with_targeting_key("user-123");
+//!     let details = provider
+//!         .resolve_bool_value("new-checkout", &context)
+//!         .await
+//!         .unwrap();
+//!     assert_eq!(details.value, provider.routes_to_new(&context));
+//!
+//!     // Roll back.
+//!     provider.set_percentage(0.0);
+//!     assert!(!provider.routes_to_new(&context));
+//! }
+//! ```
+
+use std::io::Cursor;
+use std::sync::atomic::{AtomicU32, Ordering};
+
+use async_trait::async_trait;
+use open_feature::provider::{
+    FeatureProvider, ProviderMetadata, ProviderStatus, ResolutionDetails,
+};
+use open_feature::{EvaluationContext, EvaluationResult, HookWrapper};
+use openfeature_contrib_common::{impl_resolve_methods, FlagValue};
+
+/// Basis points of traffic, the resolution of the split.
+const ALL_TRAFFIC: u32 = 10_000;
+
+/// Configuration of the [`TrafficSplitProvider`].
+#[derive(Debug, Clone, Default)]
+pub struct TrafficSplitOptions {
+    /// Percentage of targeting keys routed to the new provider, between 0 and 100.
+    pub percentage: f64,
+    /// Salt of the targeting key hash. Changing it reshuffles which keys are routed to the new
+    /// provider.
+    pub salt: String,
+}
+
+/// OpenFeature provider routing a percentage of targeting keys to a new provider.
+///
+/// The provider runs the hooks of both providers.
+pub struct TrafficSplitProvider<O, N> {
+    metadata: ProviderMetadata,
+    old: O,
+    new: N,
+    hooks: Vec<HookWrapper>,
+    salt: String,
+    basis_points: AtomicU32,
+}
+
+impl<O: FeatureProvider, N: FeatureProvider> TrafficSplitProvider<O, N> {
+    /// Creates the provider.
+    pub fn new(old: O, new: N, options: TrafficSplitOptions) -> Self {
+        let hooks = [old.hooks(), new.hooks()].concat();
+        Self {
+            metadata: ProviderMetadata::new("traffic-split"),
+            hooks,
+            old,
+            new,
+            salt: options.salt,
+            basis_points: AtomicU32::new(basis_points(options.percentage)),
+        }
+    }
+
+    /// Changes the percentage of targeting keys routed to the new provider, e.g. to roll out
+    /// further or to roll back.
+    pub fn set_percentage(&self, percentage: f64) {
+        self.basis_points
+            .store(basis_points(percentage), Ordering::Relaxed);
+    }
+
+    /// The percentage of targeting keys routed to the new provider.
+    pub fn percentage(&self) -> f64 {
+        f64::from(self.basis_points.load(Ordering::Relaxed)) / 100.0
+    }
+
+    /// Whether evaluations for `context` go to the new provider.
+    pub fn routes_to_new(&self, context: &EvaluationContext) -> bool {
+        let basis_points = self.basis_points.load(Ordering::Relaxed);
+        if basis_points >= ALL_TRAFFIC {
+            return true;
+        }
+        match &context.targeting_key {
+            Some(targeting_key) => bucket(&self.salt, targeting_key) < basis_points,
+            None => false,
+        }
+    }
+
+    async fn resolve<T: FlagValue>(
+        &self,
+        flag_key: &str,
+        context: &EvaluationContext,
+    ) -> EvaluationResult<ResolutionDetails<T>> {
+        if self.routes_to_new(context) {
+            T::resolve(&self.new, flag_key, context).await
+        } else {
+            T::resolve(&self.old, flag_key, context).await
+        }
+    }
+}
+
+/// Converts a percentage into basis points, clamped to `[0, 10000]`.
+fn basis_points(percentage: f64) -> u32 {
+    if percentage.is_nan() {
+        return 0;
+    }
+    (percentage.clamp(0.0, 100.0) * 100.0).round() as u32
+}
+
+/// Maps a targeting key onto `[0, 10000)`.
+fn bucket(salt: &str, targeting_key: &str) -> u32 {
+    // Separated, so the salt `a` and key `bc` do not hash like the salt `ab` and key `c`.
+    let input = format!("{salt}:{targeting_key}");
+    let hash = murmur3::murmur3_32(&mut Cursor::new(input.as_bytes()), 0)
+        .expect("reading from memory cannot fail");
+    hash % ALL_TRAFFIC
+}
+
+#[async_trait]
+impl<O: FeatureProvider, N: FeatureProvider> FeatureProvider for TrafficSplitProvider<O, N> {
+    async fn initialize(&mut self, context: &EvaluationContext) {
+        self.old.initialize(context).await;
+        self.new.initialize(context).await;
+    }
+
+    /// The status of the old provider while it receives traffic, unless it is ready and the new
+    /// one is not.
+    fn status(&self) -> ProviderStatus {
+        match self.basis_points.load(Ordering::Relaxed) {
+            0 => self.old.status(),
+            ALL_TRAFFIC.. => self.new.status(),
+            _ => match self.old.status() {
+                ProviderStatus::Ready => self.new.status(),
+                status => status,
+            },
+        }
+    }
+
+    fn metadata(&self) -> &ProviderMetadata {
+        &self.metadata
+    }
+
+    fn hooks(&self) -> &[HookWrapper] {
+        &self.hooks
+    }
+
+    impl_resolve_methods!();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn percentages_convert_to_clamped_basis_points() {
+        assert_eq!(basis_points(0.0), 0);
+        assert_eq!(basis_points(25.5), 2_550);
+        assert_eq!(basis_points(0.004), 0);
+        assert_eq!(basis_points(0.005), 1);
+        assert_eq!(basis_points(100.0), ALL_TRAFFIC);
+        assert_eq!(basis_points(-5.0), 0);
+        assert_eq!(basis_points(150.0), ALL_TRAFFIC);
+        assert_eq!(basis_points(f64::INFINITY), ALL_TRAFFIC);
+        assert_eq!(basis_points(f64::NEG_INFINITY), 0);
+        assert_eq!(basis_points(f64::NAN), 0);
+    }
+
+    #[test]
+    fn buckets_are_murmur3_hashes_of_the_salted_key() {
+        assert_eq!(bucket("", "hello"), 0x1f47_f18f % ALL_TRAFFIC);
+        assert_eq!(bucket("hel", "lo"), 2_262);
+        assert_eq!(bucket("he", "llo"), 7_716);
+    }
+
+    #[test]
+    fn buckets_spread_keys_evenly() {
+        let mut deciles = [0; 10];
+        for i in 0..10_000 {
+            let bucket = bucket("salt", &format!("user-{i}"));
+            assert!(bucket < ALL_TRAFFIC);
+            deciles[bucket as usize / 1_000] += 1;
+        }
+        for count in deciles {
+            assert!((800..1_200).contains(&count), "{deciles:?}");
+        }
+    }
+}
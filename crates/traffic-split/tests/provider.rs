@@ -0,0 +1,131 @@
+use open_feature::provider::FeatureProvider;
+use open_feature::{EvaluationContext, LoggingHook};
+use open_feature_in_memory::InMemoryProvider;
+use open_feature_traffic_split::{TrafficSplitOptions, TrafficSplitProvider};
+
+fn provider(percentage: f64) -> TrafficSplitProvider<InMemoryProvider, InMemoryProvider> {
+    let old = InMemoryProvider::new();
+    old.set_flag("enabled", false, Some("old"), None);
+    let new = InMemoryProvider::new();
+    new.set_flag("enabled", true, Some("new"), None);
+    TrafficSplitProvider::new(
+        old,
+        new,
+        TrafficSplitOptions {
+            percentage,
+            salt: "migration".to_string(),
+        },
+    )
+}
+
+fn users() -> impl Iterator<Item = EvaluationContext> {
+    (0..1_000).map(|i| EvaluationContext::default().with_targeting_key(format!("user-{i}")))
+}
+
+fn routed_to_new<O: FeatureProvider, N: FeatureProvider>(
+    provider: &TrafficSplitProvider<O, N>,
+) -> Vec<bool> {
+    users().map(|user| provider.routes_to_new(&user)).collect()
+}
+
+#[tokio::test]
+async fn resolves_with_the_provider_the_key_is_routed_to() {
+    let provider = provider(50.0);
+
+    for user in users().take(100) {
+        let details = provider.resolve_bool_value("enabled", &user).await.unwrap();
+        assert_eq!(details.value, provider.routes_to_new(&user));
+        let expected = if details.value { "new" } else { "old" };
+        assert_eq!(details.variant.as_deref(), Some(expected));
+    }
+}
+
+#[test]
+fn routes_a_key_the_same_way_every_time() {
+    let routes = routed_to_new(&provider(30.0));
+
+    assert_eq!(routed_to_new(&provider(30.0)), routes);
+    let to_new = routes.iter().filter(|routed| **routed).count();
+    assert!((250..350).contains(&to_new), "{to_new}");
+}
+
+#[test]
+fn raising_the_percentage_only_moves_keys_to_the_new_provider() {
+    let provider = provider(10.0);
+    let mut previous = routed_to_new(&provider);
+
+    for percentage in [25.0, 50.0, 75.0, 99.0] {
+        provider.set_percentage(percentage);
+        let routes = routed_to_new(&provider);
+        for (before, after) in previous.iter().zip(&routes) {
+            assert!(!before || *after, "a key moved back at {percentage}%");
+        }
+        assert!(routes.iter().filter(|routed| **routed).count() > 0);
+        previous = routes;
+    }
+}
+
+#[test]
+fn zero_and_one_hundred_percent_are_exact() {
+    let provider = provider(0.0);
+    assert_eq!(provider.percentage(), 0.0);
+    assert!(routed_to_new(&provider).iter().all(|routed| !routed));
+
+    provider.set_percentage(100.0);
+    assert_eq!(provider.percentage(), 100.0);
+    assert!(routed_to_new(&provider).iter().all(|routed| *routed));
+    assert!(provider.routes_to_new(&EvaluationContext::default()));
+}
+
+#[test]
+fn out_of_range_and_nan_percentages_are_clamped() {
+    let provider = provider(f64::NAN);
+    assert_eq!(provider.percentage(), 0.0);
+    assert!(routed_to_new(&provider).iter().all(|routed| !routed));
+
+    provider.set_percentage(250.0);
+    assert_eq!(provider.percentage(), 100.0);
+    provider.set_percentage(f64::NAN);
+    assert_eq!(provider.percentage(), 0.0);
+    provider.set_percentage(-1.0);
+    assert_eq!(provider.percentage(), 0.0);
+}
+
+#[tokio::test]
+async fn evaluations_without_a_targeting_key_go_to_the_old_provider() {
+    let provider = provider(99.99);
+    let context = EvaluationContext::default();
+
+    assert!(!provider.routes_to_new(&context));
+    let details = provider
+        .resolve_bool_value("enabled", &context)
+        .await
+        .unwrap();
+    assert!(!details.value);
+}
+
+#[test]
+fn the_salt_reshuffles_the_routes() {
+    let salted = TrafficSplitProvider::new(
+        InMemoryProvider::new(),
+        InMemoryProvider::new(),
+        TrafficSplitOptions {
+            percentage: 30.0,
+            salt: "another migration".to_string(),
+        },
+    );
+
+    assert_ne!(routed_to_new(&salted), routed_to_new(&provider(30.0)));
+}
+
+#[test]
+fn reports_the_hooks_of_both_providers() {
+    let provider = TrafficSplitProvider::new(
+        InMemoryProvider::new().with_hook(LoggingHook::default()),
+        InMemoryProvider::new()
+            .with_hook(LoggingHook::default())
+            .with_hook(LoggingHook::default()),
+        TrafficSplitOptions::default(),
+    );
+    assert_eq!(provider.hooks().len(), 3);
+}
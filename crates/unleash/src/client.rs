@@ -0,0 +1,155 @@
+use std::sync::{Mutex, PoisonError};
+
+use chrono::{DateTime, Utc};
+use openfeature_contrib_common::{check_status, HttpError};
+use reqwest::header::{HeaderMap, HeaderName, HeaderValue, ETAG, IF_NONE_MATCH};
+use reqwest::{Client, StatusCode};
+use tracing::debug;
+use unleash_types::client_features::ClientFeatures;
+use unleash_types::client_metrics::{
+    ClientApplication, ClientMetrics, MetricBucket, MetricsMetadata, SdkType,
+};
+
+use crate::{UnleashError, UnleashOptions};
+
+const SDK_NAME: &str = concat!("open-feature-unleash:", env!("CARGO_PKG_VERSION"));
+
+/// Strategies evaluated by the embedded Yggdrasil engine, reported on client registration.
+const BUILT_IN_STRATEGIES: &[&str] = &[
+    "default",
+    "userWithId",
+    "gradualRolloutUserId",
+    "gradualRolloutSessionId",
+    "gradualRolloutRandom",
+    "flexibleRollout",
+    "remoteAddress",
+    "applicationHostname",
+];
+
+/// Thin HTTP client for the Unleash client API (`/client/features`, `/client/register` and
+/// `/client/metrics`).
+pub(crate) struct UnleashClient {
+    http: Client,
+    url: String,
+    app_name: String,
+    instance_id: String,
+    environment: Option<String>,
+    etag: Mutex<Option<HeaderValue>>,
+}
+
+impl UnleashClient {
+    pub(crate) fn new(url: &str, options: &UnleashOptions) -> Result<Self, UnleashError> {
+        let mut headers = HeaderMap::new();
+        insert_header(&mut headers, "unleash-appname", &options.app_name);
+        insert_header(&mut headers, "unleash-instanceid", &options.instance_id);
+        insert_header(&mut headers, "unleash-sdk", SDK_NAME);
+        if let Some(token) = &options.api_token {
+            insert_header(&mut headers, "authorization", token);
+        }
+        for (name, value) in &options.custom_headers {
+            insert_header(&mut headers, name, value);
+        }
+
+        let http = Client::builder()
+            .default_headers(headers)
+            .timeout(options.request_timeout)
+            .build()?;
+
+        Ok(Self {
+            http,
+            url: url.trim_end_matches('/').to_string(),
+            app_name: options.app_name.clone(),
+            instance_id: options.instance_id.clone(),
+            environment: options.environment.clone(),
+            etag: Mutex::new(None),
+        })
+    }
+
+    /// Fetches the feature set, returning `None` when the server reports it as unchanged.
+    pub(crate) async fn fetch_features(&self) -> Result<Option<ClientFeatures>, UnleashError> {
+        let url = format!("{}/client/features", self.url);
+        let mut request = self.http.get(&url);
+        if let Some(etag) = self
+            .etag
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clone()
+        {
+            request = request.header(IF_NONE_MATCH, etag);
+        }
+
+        let response = request.send().await?;
+        match response.status() {
+            StatusCode::NOT_MODIFIED => {
+                debug!("Unleash features not modified");
+                Ok(None)
+            }
+            status if status.is_success() => {
+                let etag = response.headers().get(ETAG).cloned();
+                let features = response.json::<ClientFeatures>().await?;
+                *self.etag.lock().unwrap_or_else(PoisonError::into_inner) = etag;
+                Ok(Some(features))
+            }
+            _ => Err(HttpError::status_of(&response).into()),
+        }
+    }
+
+    pub(crate) async fn register(
+        &self,
+        started: DateTime<Utc>,
+        interval_millis: u32,
+    ) -> Result<(), UnleashError> {
+        let registration = ClientApplication {
+            app_name: self.app_name.clone(),
+            environment: self.environment.clone(),
+            instance_id: Some(self.instance_id.clone()),
+            interval: interval_millis,
+            started,
+            strategies: BUILT_IN_STRATEGIES.iter().map(|s| s.to_string()).collect(),
+            metadata: metadata(),
+            ..Default::default()
+        };
+        self.post("client/register", &registration).await
+    }
+
+    pub(crate) async fn send_metrics(&self, bucket: MetricBucket) -> Result<(), UnleashError> {
+        let metrics = ClientMetrics {
+            app_name: self.app_name.clone(),
+            bucket: Some(bucket),
+            environment: self.environment.clone(),
+            instance_id: Some(self.instance_id.clone()),
+            connection_id: None,
+            impact_metrics: None,
+            metadata: metadata(),
+        };
+        self.post("client/metrics", &metrics).await
+    }
+
+    async fn post<T: serde::Serialize>(&self, path: &str, body: &T) -> Result<(), UnleashError> {
+        let url = format!("{}/{path}", self.url);
+        let response = self.http.post(&url).json(body).send().await?;
+        check_status(response)?;
+        Ok(())
+    }
+}
+
+fn metadata() -> MetricsMetadata {
+    MetricsMetadata {
+        sdk_version: Some(SDK_NAME.to_string()),
+        sdk_type: Some(SdkType::Backend),
+        platform_name: Some("rust".to_string()),
+        ..Default::default()
+    }
+}
+
+fn insert_header(headers: &mut HeaderMap, name: &str, value: &str) {
+    match (
+        HeaderName::from_bytes(name.as_bytes()),
+        HeaderValue::from_str(value),
+    ) {
+        (Ok(name), Ok(value)) => {
+            headers.insert(name, value);
+        }
+        _ => tracing::warn!("Ignoring invalid Unleash header {name}"),
+    }
+}
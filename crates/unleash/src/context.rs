@@ -0,0 +1,109 @@
+use std::collections::HashMap;
+
+use open_feature::EvaluationContext;
+use openfeature_contrib_common::{field_to_string, DateTimeFormat};
+use unleash_types::client_features::Context;
+
+const USER_ID: &str = "userId";
+const SESSION_ID: &str = "sessionId";
+const REMOTE_ADDRESS: &str = "remoteAddress";
+const ENVIRONMENT: &str = "environment";
+const APP_NAME: &str = "appName";
+const CURRENT_TIME: &str = "currentTime";
+
+/// Maps an OpenFeature [`EvaluationContext`] onto an Unleash [`Context`].
+///
+/// The targeting key becomes the Unleash `userId` (falling back to a `userId` custom field).
+/// The standard Unleash fields (`sessionId`, `remoteAddress`, `environment`, `appName` and
+/// `currentTime`) are read from custom fields of the same name, and every other field is passed
+/// as a string property, `StructValue` fields as JSON. Other struct fields have no string
+/// representation and are skipped.
+pub(crate) fn to_unleash_context(
+    context: &EvaluationContext,
+    app_name: &str,
+    environment: Option<&str>,
+) -> Context {
+    let mut properties = HashMap::new();
+    let mut unleash_context = Context {
+        user_id: context.targeting_key.clone(),
+        app_name: Some(app_name.to_string()),
+        environment: environment.map(str::to_string),
+        ..Default::default()
+    };
+
+    for (key, value) in &context.custom_fields {
+        let Some(value) = field_to_string(value, DateTimeFormat::Rfc3339) else {
+            continue;
+        };
+
+        match key.as_str() {
+            USER_ID => {
+                if unleash_context.user_id.is_none() {
+                    unleash_context.user_id = Some(value);
+                }
+            }
+            SESSION_ID => unleash_context.session_id = Some(value),
+            REMOTE_ADDRESS => unleash_context.remote_address = Some(value),
+            ENVIRONMENT => unleash_context.environment = Some(value),
+            APP_NAME => unleash_context.app_name = Some(value),
+            CURRENT_TIME => unleash_context.current_time = Some(value),
+            _ => {
+                properties.insert(key.clone(), value);
+            }
+        }
+    }
+
+    if !properties.is_empty() {
+        unleash_context.properties = Some(properties);
+    }
+
+    unleash_context
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn standard_fields_populate_the_unleash_context() {
+        let context = EvaluationContext::default()
+            .with_targeting_key("user-1")
+            .with_custom_field(USER_ID, "ignored")
+            .with_custom_field(SESSION_ID, "session-1")
+            .with_custom_field(REMOTE_ADDRESS, "10.0.0.1")
+            .with_custom_field(ENVIRONMENT, "staging")
+            .with_custom_field(CURRENT_TIME, "2024-06-01T12:00:00Z")
+            .with_custom_field("plan", "premium")
+            .with_custom_field("seats", 5);
+
+        let context = to_unleash_context(&context, "my-app", Some("production"));
+
+        assert_eq!(context.user_id.as_deref(), Some("user-1"));
+        assert_eq!(context.session_id.as_deref(), Some("session-1"));
+        assert_eq!(context.remote_address.as_deref(), Some("10.0.0.1"));
+        assert_eq!(context.environment.as_deref(), Some("staging"));
+        assert_eq!(context.app_name.as_deref(), Some("my-app"));
+        assert_eq!(
+            context.current_time.as_deref(),
+            Some("2024-06-01T12:00:00Z")
+        );
+        assert_eq!(
+            context.properties,
+            Some(HashMap::from([
+                ("plan".to_string(), "premium".to_string()),
+                ("seats".to_string(), "5".to_string()),
+            ]))
+        );
+    }
+
+    #[test]
+    fn user_id_field_stands_in_for_the_targeting_key() {
+        let context = EvaluationContext::default().with_custom_field(USER_ID, "user-2");
+
+        let context = to_unleash_context(&context, "my-app", None);
+
+        assert_eq!(context.user_id.as_deref(), Some("user-2"));
+        assert_eq!(context.environment, None);
+        assert!(context.properties.unwrap_or_default().is_empty());
+    }
+}
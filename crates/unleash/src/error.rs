@@ -0,0 +1,40 @@
+use openfeature_contrib_common::HttpError;
+use thiserror::Error;
+
+/// Errors raised while creating or refreshing an [`UnleashProvider`](crate::UnleashProvider).
+#[derive(Debug, Error)]
+pub enum UnleashError {
+    /// Neither an Unleash API URL nor a bootstrap source was configured.
+    #[error("either an Unleash API url or a bootstrap source must be configured")]
+    MissingSource,
+
+    /// The HTTP request to the Unleash API failed.
+    #[error("request to Unleash failed: {0}")]
+    Http(#[from] HttpError),
+
+    /// A feature payload could not be parsed.
+    #[error("invalid feature payload: {0}")]
+    Json(#[from] serde_json::Error),
+
+    /// The bootstrap file could not be read.
+    #[error("failed to read bootstrap file: {0}")]
+    Io(#[from] std::io::Error),
+}
+
+impl From<reqwest::Error> for UnleashError {
+    fn from(error: reqwest::Error) -> Self {
+        Self::Http(error.into())
+    }
+}
+
+impl UnleashError {
+    /// Whether the request may succeed when sent again, e.g. after a timeout or while the
+    /// Unleash API is unavailable.
+    pub fn is_retryable(&self) -> bool {
+        match self {
+            Self::MissingSource | Self::Json(_) => false,
+            Self::Io(_) => true,
+            Self::Http(e) => e.is_retryable(),
+        }
+    }
+}
@@ -0,0 +1,464 @@
+//! [Unleash](https://www.getunleash.io/) provider for OpenFeature.
+//!
+//! The provider evaluates feature toggles locally with Unleash's
+//! [Yggdrasil](https://github.com/Unleash/yggdrasil) engine, the same engine used by the
+//! official server-side SDKs. Toggle definitions are fetched from the Unleash client API (or an
+//! Unleash Edge / proxy exposing it) and refreshed in the background, and usage metrics are
+//! reported back so toggles show up as "seen" in the Unleash dashboard.
+//!
+//! # Context mapping
+//!
+//! * the targeting key becomes the Unleash `userId`;
+//! * custom fields named `sessionId`, `remoteAddress`, `environment`, `appName` and
+//!   `currentTime` populate the corresponding Unleash context fields;
+//! * every other custom field is sent as a string property (date-times as RFC 3339).
+//!
+//! # Value mapping
+//!
+//! Boolean flags resolve to the toggle's enabled state. String, integer, float and struct flags
+//! resolve to the payload of the selected variant, and the variant name is reported in
+//! [`ResolutionDetails::variant`]. A variant without a payload resolves string flags to the
+//! variant name.
+//!
+//! # Example
+//!
+//! ```rust,no_run
+//! use open_feature::provider::FeatureProvider;
+//! use open_feature::EvaluationContext;
+//! use open_feature_unleash::{UnleashOptions, UnleashProvider};
+//!
+//! #[tokio::main]
+//! async fn main() {
+//!     let provider = UnleashProvider::new(UnleashOptions {
+//!         url: Some("https://unleash.example.com/api".to_string()),
+//!         api_token: Some("default:development.secret".to_string()),
+//!         app_name: "my-app".to_string(),
+//!         ..Default::default()
+//!     })
+//!     .await
+//!     .expect("Failed to create Unleash provider");
+//!
+//!     let context = EvaluationContext::default().with_targeting_key("user-123");
+//!     let enabled = provider
+//!         .resolve_bool_value("new-checkout", &context)
+//!         .await
+//!         .map(|details| details.value)
+//!         .unwrap_or(false);
+//!     println!("new-checkout enabled: {enabled}");
+//! }
+//! ```
+//!
+//! # Offline mode
+//!
+//! Without a `url`, the provider serves toggles exclusively from [`UnleashOptions::bootstrap`],
+//! a `/api/client/features` response exported from Unleash. When both are configured the
+//! bootstrap is served until the first successful fetch.
+
+mod client;
+mod context;
+mod error;
+
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
+
+use async_trait::async_trait;
+use chrono::Utc;
+use open_feature::provider::{FeatureProvider, ProviderMetadata, ResolutionDetails};
+use open_feature::{
+    EvaluationContext, EvaluationError, EvaluationErrorCode, EvaluationReason, EvaluationResult,
+    FlagMetadata, StructValue, Value,
+};
+use tokio::sync::RwLock;
+use tokio::task::JoinHandle;
+use tracing::{debug, error, warn};
+use unleash_types::client_features::ClientFeatures;
+use unleash_yggdrasil::{EngineState, ExtendedVariantDef, ResolvedToggle, UpdateMessage};
+
+use crate::client::UnleashClient;
+use crate::context::to_unleash_context;
+pub use crate::error::UnleashError;
+
+/// Source of toggle definitions used before (or instead of) the Unleash API.
+#[derive(Debug, Clone)]
+pub enum Bootstrap {
+    /// A file containing a `/api/client/features` response.
+    File(PathBuf),
+    /// A `/api/client/features` response as raw JSON.
+    Json(String),
+    /// An already parsed feature set.
+    Features(ClientFeatures),
+}
+
+/// Configuration of the [`UnleashProvider`].
+#[derive(Debug, Clone)]
+pub struct UnleashOptions {
+    /// Base URL of the Unleash API, e.g. `https://unleash.example.com/api`.
+    /// `None` runs the provider offline from [`UnleashOptions::bootstrap`].
+    pub url: Option<String>,
+    /// Client API token sent in the `Authorization` header.
+    pub api_token: Option<String>,
+    /// Application name reported to Unleash and used as the `appName` context field.
+    pub app_name: String,
+    /// Instance identifier reported to Unleash. Defaults to a random UUID.
+    pub instance_id: String,
+    /// Default `environment` context field.
+    pub environment: Option<String>,
+    /// Interval between toggle refreshes.
+    pub refresh_interval: Duration,
+    /// Interval between metrics reports.
+    pub metrics_interval: Duration,
+    /// Disables client registration and metrics reporting.
+    pub disable_metrics: bool,
+    /// Additional headers sent with every request to Unleash.
+    pub custom_headers: HashMap<String, String>,
+    /// Timeout applied to every request to Unleash.
+    pub request_timeout: Duration,
+    /// Toggle definitions served until the first successful fetch, or permanently offline.
+    pub bootstrap: Option<Bootstrap>,
+}
+
+impl Default for UnleashOptions {
+    fn default() -> Self {
+        Self {
+            url: None,
+            api_token: None,
+            app_name: "open-feature-unleash".to_string(),
+            instance_id: uuid::Uuid::new_v4().to_string(),
+            environment: None,
+            refresh_interval: Duration::from_secs(15),
+            metrics_interval: Duration::from_secs(60),
+            disable_metrics: false,
+            custom_headers: HashMap::new(),
+            request_timeout: Duration::from_secs(10),
+            bootstrap: None,
+        }
+    }
+}
+
+/// Evaluation state shared with the background refresh task.
+#[derive(Default)]
+struct State {
+    engine: EngineState,
+    enabled: HashMap<String, bool>,
+}
+
+impl State {
+    fn apply(&mut self, features: ClientFeatures) {
+        self.enabled = features
+            .features
+            .iter()
+            .map(|feature| (feature.name.clone(), feature.enabled))
+            .collect();
+        if let Some(warnings) = self
+            .engine
+            .take_state(UpdateMessage::FullResponse(features))
+        {
+            for warning in warnings {
+                warn!(
+                    "Unleash toggle {} could not be compiled: {}",
+                    warning.toggle_name, warning.message
+                );
+            }
+        }
+    }
+}
+
+/// OpenFeature provider evaluating Unleash toggles locally.
+pub struct UnleashProvider {
+    metadata: ProviderMetadata,
+    app_name: String,
+    environment: Option<String>,
+    count_metrics: bool,
+    state: Arc<RwLock<State>>,
+    tasks: Vec<JoinHandle<()>>,
+}
+
+impl UnleashProvider {
+    /// Creates the provider, loading the bootstrap and performing the first fetch.
+    ///
+    /// A failing first fetch is only fatal when no bootstrap is configured.
+    pub async fn new(options: UnleashOptions) -> Result<Self, UnleashError> {
+        let mut state = State::default();
+        let bootstrapped = match &options.bootstrap {
+            Some(bootstrap) => {
+                state.apply(load_bootstrap(bootstrap).await?);
+                true
+            }
+            None => false,
+        };
+
+        let mut tasks = Vec::new();
+        let state = match &options.url {
+            Some(url) => {
+                let client = Arc::new(UnleashClient::new(url, &options)?);
+                match client.fetch_features().await {
+                    Ok(Some(features)) => state.apply(features),
+                    Ok(None) => {}
+                    Err(e) if bootstrapped => {
+                        warn!("Initial Unleash fetch failed, serving bootstrap: {e}")
+                    }
+                    Err(e) => return Err(e),
+                }
+
+                let state = Arc::new(RwLock::new(state));
+                tasks.push(spawn_refresh(
+                    client.clone(),
+                    state.clone(),
+                    options.refresh_interval,
+                ));
+                if !options.disable_metrics {
+                    tasks.push(spawn_metrics(
+                        client,
+                        state.clone(),
+                        options.metrics_interval,
+                    ));
+                }
+                state
+            }
+            None if bootstrapped => Arc::new(RwLock::new(state)),
+            None => return Err(UnleashError::MissingSource),
+        };
+
+        Ok(Self {
+            metadata: ProviderMetadata::new("unleash"),
+            app_name: options.app_name,
+            environment: options.environment,
+            count_metrics: options.url.is_some() && !options.disable_metrics,
+            state,
+            tasks,
+        })
+    }
+
+    async fn resolve(
+        &self,
+        flag_key: &str,
+        context: &EvaluationContext,
+    ) -> EvaluationResult<(ResolvedToggle, bool)> {
+        let context = to_unleash_context(context, &self.app_name, self.environment.as_deref());
+        let state = self.state.read().await;
+        let toggle = state
+            .engine
+            .resolve(flag_key, &context, &None)
+            .ok_or_else(|| {
+                EvaluationError::builder()
+                    .code(EvaluationErrorCode::FlagNotFound)
+                    .message(format!("Flag {flag_key} not found"))
+                    .build()
+            })?;
+
+        if self.count_metrics {
+            state.engine.count_toggle(flag_key, toggle.enabled);
+            if toggle.variant.enabled {
+                state.engine.count_variant(flag_key, &toggle.variant.name);
+            }
+        }
+        let globally_enabled = state.enabled.get(flag_key).copied().unwrap_or(true);
+        Ok((toggle, globally_enabled))
+    }
+
+    async fn resolve_variant<T>(
+        &self,
+        flag_key: &str,
+        context: &EvaluationContext,
+        parse: impl FnOnce(&ExtendedVariantDef) -> EvaluationResult<T>,
+    ) -> EvaluationResult<ResolutionDetails<T>> {
+        let (toggle, _) = self.resolve(flag_key, context).await?;
+        if !toggle.variant.enabled {
+            return Err(EvaluationError::builder()
+                .code(EvaluationErrorCode::General("Variant disabled".to_string()))
+                .message(format!("Flag {flag_key} has no enabled variant"))
+                .build());
+        }
+
+        Ok(ResolutionDetails {
+            value: parse(&toggle.variant)?,
+            variant: Some(toggle.variant.name.clone()),
+            reason: Some(EvaluationReason::Split),
+            flag_metadata: Some(flag_metadata(&toggle)),
+        })
+    }
+}
+
+impl Drop for UnleashProvider {
+    fn drop(&mut self) {
+        for task in &self.tasks {
+            task.abort();
+        }
+    }
+}
+
+#[async_trait]
+impl FeatureProvider for UnleashProvider {
+    fn metadata(&self) -> &ProviderMetadata {
+        &self.metadata
+    }
+
+    async fn resolve_bool_value(
+        &self,
+        flag_key: &str,
+        context: &EvaluationContext,
+    ) -> EvaluationResult<ResolutionDetails<bool>> {
+        let (toggle, globally_enabled) = self.resolve(flag_key, context).await?;
+        let reason = match (globally_enabled, toggle.enabled) {
+            (false, _) => EvaluationReason::Disabled,
+            (true, true) => EvaluationReason::TargetingMatch,
+            (true, false) => EvaluationReason::Default,
+        };
+
+        Ok(ResolutionDetails {
+            value: toggle.enabled,
+            variant: None,
+            reason: Some(reason),
+            flag_metadata: Some(flag_metadata(&toggle)),
+        })
+    }
+
+    async fn resolve_int_value(
+        &self,
+        flag_key: &str,
+        context: &EvaluationContext,
+    ) -> EvaluationResult<ResolutionDetails<i64>> {
+        self.resolve_variant(flag_key, context, |variant| {
+            let payload = payload_value(flag_key, variant)?;
+            payload.trim().parse().map_err(|_| {
+                type_mismatch(format!(
+                    "Payload {payload} of flag {flag_key} is not an integer"
+                ))
+            })
+        })
+        .await
+    }
+
+    async fn resolve_float_value(
+        &self,
+        flag_key: &str,
+        context: &EvaluationContext,
+    ) -> EvaluationResult<ResolutionDetails<f64>> {
+        self.resolve_variant(flag_key, context, |variant| {
+            let payload = payload_value(flag_key, variant)?;
+            payload.trim().parse().map_err(|_| {
+                type_mismatch(format!(
+                    "Payload {payload} of flag {flag_key} is not a float"
+                ))
+            })
+        })
+        .await
+    }
+
+    async fn resolve_string_value(
+        &self,
+        flag_key: &str,
+        context: &EvaluationContext,
+    ) -> EvaluationResult<ResolutionDetails<String>> {
+        self.resolve_variant(flag_key, context, |variant| {
+            Ok(variant
+                .payload
+                .as_ref()
+                .map_or_else(|| variant.name.clone(), |payload| payload.value.clone()))
+        })
+        .await
+    }
+
+    async fn resolve_struct_value(
+        &self,
+        flag_key: &str,
+        context: &EvaluationContext,
+    ) -> EvaluationResult<ResolutionDetails<StructValue>> {
+        self.resolve_variant(flag_key, context, |variant| {
+            let payload = payload_value(flag_key, variant)?;
+            let json: serde_json::Value = serde_json::from_str(payload).map_err(|e| {
+                EvaluationError::builder()
+                    .code(EvaluationErrorCode::ParseError)
+                    .message(format!("Payload of flag {flag_key} is not valid JSON: {e}"))
+                    .build()
+            })?;
+            match Value::try_from(json)? {
+                Value::Struct(value) => Ok(value),
+                _ => Err(type_mismatch(format!(
+                    "Payload of flag {flag_key} is not a JSON object"
+                ))),
+            }
+        })
+        .await
+    }
+}
+
+async fn load_bootstrap(bootstrap: &Bootstrap) -> Result<ClientFeatures, UnleashError> {
+    Ok(match bootstrap {
+        Bootstrap::File(path) => serde_json::from_slice(&tokio::fs::read(path).await?)?,
+        Bootstrap::Json(json) => serde_json::from_str(json)?,
+        Bootstrap::Features(features) => features.clone(),
+    })
+}
+
+fn spawn_refresh(
+    client: Arc<UnleashClient>,
+    state: Arc<RwLock<State>>,
+    interval: Duration,
+) -> JoinHandle<()> {
+    tokio::spawn(async move {
+        loop {
+            tokio::time::sleep(interval).await;
+            match client.fetch_features().await {
+                Ok(Some(features)) => {
+                    debug!("Refreshed {} Unleash toggles", features.features.len());
+                    state.write().await.apply(features);
+                }
+                Ok(None) => {}
+                Err(e) if e.is_retryable() => warn!("Failed to refresh Unleash toggles: {e}"),
+                Err(e) => error!("Failed to refresh Unleash toggles: {e}"),
+            }
+        }
+    })
+}
+
+fn spawn_metrics(
+    client: Arc<UnleashClient>,
+    state: Arc<RwLock<State>>,
+    interval: Duration,
+) -> JoinHandle<()> {
+    tokio::spawn(async move {
+        let interval_millis = u32::try_from(interval.as_millis()).unwrap_or(u32::MAX);
+        if let Err(e) = client.register(Utc::now(), interval_millis).await {
+            warn!("Failed to register Unleash client: {e}");
+        }
+
+        loop {
+            tokio::time::sleep(interval).await;
+            let bucket = state.write().await.engine.get_metrics(Utc::now());
+            if let Some(bucket) = bucket {
+                if let Err(e) = client.send_metrics(bucket).await {
+                    warn!("Failed to send Unleash metrics: {e}");
+                }
+            }
+        }
+    })
+}
+
+fn payload_value<'a>(flag_key: &str, variant: &'a ExtendedVariantDef) -> EvaluationResult<&'a str> {
+    variant
+        .payload
+        .as_ref()
+        .map(|payload| payload.value.as_str())
+        .ok_or_else(|| {
+            type_mismatch(format!(
+                "Variant {} of flag {flag_key} has no payload",
+                variant.name
+            ))
+        })
+}
+
+fn flag_metadata(toggle: &ResolvedToggle) -> FlagMetadata {
+    FlagMetadata::default()
+        .with_value("project", toggle.project.as_str())
+        .with_value("impressionData", toggle.impression_data)
+}
+
+fn type_mismatch(message: String) -> EvaluationError {
+    EvaluationError::builder()
+        .code(EvaluationErrorCode::TypeMismatch)
+        .message(message)
+        .build()
+}
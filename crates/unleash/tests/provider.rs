@@ -0,0 +1,257 @@
+use std::time::Duration;
+
+use open_feature::provider::FeatureProvider;
+use open_feature::{EvaluationContext, EvaluationErrorCode, EvaluationReason, StructValue};
+use open_feature_unleash::{Bootstrap, UnleashError, UnleashOptions, UnleashProvider};
+use openfeature_contrib_common::HttpError;
+use serde_json::json;
+use wiremock::matchers::{header, method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+fn toggle(name: &str, enabled: bool, payload: Option<(&str, &str)>) -> serde_json::Value {
+    let variants = match payload {
+        Some((kind, value)) => json!([{
+            "name": "variant",
+            "weight": 1000,
+            "stickiness": "default",
+            "payload": {"type": kind, "value": value}
+        }]),
+        None => json!([]),
+    };
+    json!({
+        "name": name,
+        "type": "release",
+        "project": "default",
+        "enabled": enabled,
+        "strategies": [{"name": "default", "parameters": {}}],
+        "variants": variants
+    })
+}
+
+fn features() -> serde_json::Value {
+    json!({
+        "version": 2,
+        "features": [
+            toggle("new-checkout", true, None),
+            toggle("old-checkout", false, None),
+            toggle("max-items", true, Some(("number", "25"))),
+            toggle("ratio", true, Some(("number", "0.25"))),
+            toggle("color", true, Some(("string", "blue"))),
+            toggle("banner", true, Some(("json", "{\"color\": \"blue\"}"))),
+            toggle("broken", true, Some(("json", "{"))),
+        ]
+    })
+}
+
+async fn offline() -> UnleashProvider {
+    UnleashProvider::new(UnleashOptions {
+        bootstrap: Some(Bootstrap::Json(features().to_string())),
+        ..Default::default()
+    })
+    .await
+    .unwrap()
+}
+
+fn user() -> EvaluationContext {
+    EvaluationContext::default().with_targeting_key("user-1")
+}
+
+#[tokio::test]
+async fn bootstrapped_toggles_are_served_offline() {
+    let provider = offline().await;
+
+    let details = provider
+        .resolve_bool_value("new-checkout", &user())
+        .await
+        .unwrap();
+    assert!(details.value);
+    assert_eq!(details.reason, Some(EvaluationReason::TargetingMatch));
+    let details = provider
+        .resolve_bool_value("old-checkout", &user())
+        .await
+        .unwrap();
+    assert!(!details.value);
+    assert_eq!(details.reason, Some(EvaluationReason::Disabled));
+}
+
+#[tokio::test]
+async fn variant_payloads_resolve_typed_flags() {
+    let provider = offline().await;
+
+    let details = provider
+        .resolve_int_value("max-items", &user())
+        .await
+        .unwrap();
+    assert_eq!(details.value, 25);
+    assert_eq!(details.variant.as_deref(), Some("variant"));
+    assert_eq!(details.reason, Some(EvaluationReason::Split));
+    let details = provider
+        .resolve_float_value("ratio", &user())
+        .await
+        .unwrap();
+    assert_eq!(details.value, 0.25);
+    let details = provider
+        .resolve_string_value("color", &user())
+        .await
+        .unwrap();
+    assert_eq!(details.value, "blue");
+    let details = provider
+        .resolve_struct_value("banner", &user())
+        .await
+        .unwrap();
+    assert_eq!(
+        details.value,
+        StructValue::default().with_field("color", "blue")
+    );
+}
+
+#[tokio::test]
+async fn mismatched_payloads_fail() {
+    let provider = offline().await;
+
+    let error = provider
+        .resolve_int_value("color", &user())
+        .await
+        .unwrap_err();
+    assert_eq!(error.code, EvaluationErrorCode::TypeMismatch);
+    let error = provider
+        .resolve_struct_value("max-items", &user())
+        .await
+        .unwrap_err();
+    assert_eq!(error.code, EvaluationErrorCode::TypeMismatch);
+    let error = provider
+        .resolve_struct_value("broken", &user())
+        .await
+        .unwrap_err();
+    assert_eq!(error.code, EvaluationErrorCode::ParseError);
+    let error = provider
+        .resolve_bool_value("missing", &user())
+        .await
+        .unwrap_err();
+    assert_eq!(error.code, EvaluationErrorCode::FlagNotFound);
+}
+
+#[tokio::test]
+async fn toggles_without_an_enabled_variant_fail_typed_flags() {
+    let provider = offline().await;
+
+    let error = provider
+        .resolve_string_value("new-checkout", &user())
+        .await
+        .unwrap_err();
+    assert_eq!(
+        error.code,
+        EvaluationErrorCode::General("Variant disabled".to_string())
+    );
+}
+
+#[tokio::test]
+async fn a_source_is_required() {
+    let result = UnleashProvider::new(UnleashOptions::default()).await;
+
+    assert!(matches!(result, Err(UnleashError::MissingSource)));
+}
+
+#[tokio::test]
+async fn bootstrap_files_are_read() {
+    let path = std::env::temp_dir().join(format!(
+        "open-feature-unleash-bootstrap-{}.json",
+        std::process::id()
+    ));
+    tokio::fs::write(&path, features().to_string())
+        .await
+        .unwrap();
+
+    let provider = UnleashProvider::new(UnleashOptions {
+        bootstrap: Some(Bootstrap::File(path.clone())),
+        ..Default::default()
+    })
+    .await;
+    tokio::fs::remove_file(&path).await.unwrap();
+
+    let details = provider
+        .unwrap()
+        .resolve_bool_value("new-checkout", &user())
+        .await
+        .unwrap();
+    assert!(details.value);
+}
+
+#[tokio::test]
+async fn unchanged_toggles_are_not_downloaded_again() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/api/client/features"))
+        .and(header("if-none-match", "\"v1\""))
+        .respond_with(ResponseTemplate::new(304))
+        .with_priority(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/api/client/features"))
+        .and(header("authorization", "secret"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .insert_header("etag", "\"v1\"")
+                .set_body_json(features()),
+        )
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let provider = UnleashProvider::new(UnleashOptions {
+        url: Some(format!("{}/api", server.uri())),
+        api_token: Some("secret".to_string()),
+        refresh_interval: Duration::from_millis(20),
+        disable_metrics: true,
+        ..Default::default()
+    })
+    .await
+    .unwrap();
+    tokio::time::timeout(Duration::from_secs(5), async {
+        while server.received_requests().await.unwrap().len() < 3 {
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+    })
+    .await
+    .expect("the toggles were not refreshed");
+
+    let details = provider
+        .resolve_bool_value("new-checkout", &user())
+        .await
+        .unwrap();
+    assert!(details.value);
+}
+
+#[tokio::test]
+async fn bootstrap_is_served_when_the_first_fetch_fails() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .respond_with(ResponseTemplate::new(503))
+        .mount(&server)
+        .await;
+
+    let provider = UnleashProvider::new(UnleashOptions {
+        url: Some(server.uri()),
+        bootstrap: Some(Bootstrap::Json(features().to_string())),
+        disable_metrics: true,
+        ..Default::default()
+    })
+    .await
+    .unwrap();
+    let details = provider
+        .resolve_bool_value("new-checkout", &user())
+        .await
+        .unwrap();
+    assert!(details.value);
+
+    let result = UnleashProvider::new(UnleashOptions {
+        url: Some(server.uri()),
+        disable_metrics: true,
+        ..Default::default()
+    })
+    .await;
+    assert!(
+        matches!(result, Err(UnleashError::Http(HttpError::Status { status, .. })) if status == 503)
+    );
+}